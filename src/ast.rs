@@ -1,10 +1,13 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::{
+    cache::{renew_file_id, renew_id, renew_id_keys},
+    diagnostics::ErrNo,
     lexer::IdProvider,
     typesystem::{DataTypeInformation, REAL_TYPE, VOID_TYPE},
 };
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display, Formatter, Result},
     iter,
     ops::Range,
@@ -14,13 +17,81 @@ mod pre_processor;
 
 pub type AstId = usize;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GenericBinding {
     pub name: String,
     pub nature: TypeNature,
 }
 
-#[derive(PartialEq)]
+/// the names of the pragmas understood by the compiler, other pragmas are reported as unknown
+pub const KNOWN_PRAGMAS: &[&str] = &["attribute", "text", "info", "warning", "error"];
+
+/// a pragma (e.g. `{attribute 'hide'}`) attached to the following POU, variable or statement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pragma {
+    /// the pragma's name (e.g. `attribute`)
+    pub name: String,
+    /// the pragma's arguments without quotes (e.g. `hide`)
+    pub arguments: Vec<String>,
+    pub location: SourceRange,
+}
+
+impl Pragma {
+    /// returns true if this is an `{attribute '<name>'}` pragma for the given attribute
+    pub fn is_attribute(&self, attribute: &str) -> bool {
+        self.name.eq_ignore_ascii_case("attribute")
+            && self
+                .arguments
+                .first()
+                .map_or(false, |it| it.eq_ignore_ascii_case(attribute))
+    }
+
+    /// returns the codes of a `{warning disable <code>...}` pragma
+    pub fn get_disabled_codes(&self) -> Option<&[String]> {
+        match self.arguments.split_first() {
+            Some((first, codes))
+                if self.name.eq_ignore_ascii_case("warning")
+                    && first.eq_ignore_ascii_case("disable") =>
+            {
+                Some(codes)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// the diagnostics disabled by a `{warning disable <code>...}` pragma
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub codes: Vec<ErrNo>,
+    /// the location of the POU or statement the pragma is attached to
+    pub location: SourceRange,
+}
+
+impl Suppression {
+    /// returns true if the given location lies within the suppressing POU or statement
+    pub fn contains(&self, location: &SourceRange) -> bool {
+        let same_file = match (self.location.get_file_id(), location.get_file_id()) {
+            (Some(file_id), Some(other)) => file_id == other,
+            _ => true,
+        };
+        same_file
+            && !location.is_undefined()
+            && self.location.get_start() <= location.get_start()
+            && location.get_end() <= self.location.get_end()
+    }
+}
+
+/// the name of the member that embeds the instance of a POU's super class (see `EXTENDS`)
+pub const SUPER_MEMBER_NAME: &str = "__SUPER";
+
+/// the name of an interface's member that points to the implementing POU's method table
+pub const VTABLE_MEMBER_NAME: &str = "__vtable";
+
+/// the name of an interface's member that points to the implementing POU's instance
+pub const INSTANCE_MEMBER_NAME: &str = "__instance";
+
+#[derive(PartialEq, Serialize, Deserialize)]
 pub struct Pou {
     pub name: String,
     pub variable_blocks: Vec<VariableBlock>,
@@ -33,9 +104,17 @@ pub struct Pou {
     pub poly_mode: Option<PolymorphismMode>,
     pub generics: Vec<GenericBinding>,
     pub linkage: LinkageType,
+    /// the name of the POU this POU EXTENDS (classes and function blocks only)
+    pub super_class: Option<String>,
+    /// the names of the interfaces this POU IMPLEMENTS (classes and function blocks only)
+    pub interfaces: Vec<String>,
+    /// the namespaces declared by the POU's own USING directives
+    pub usings: Vec<String>,
+    /// the pragmas attached to the POU
+    pub pragmas: Vec<Pragma>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum PolymorphismMode {
     None,
     Abstract,
@@ -60,7 +139,7 @@ pub enum DirectAccessType {
     Template,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum TypeNature {
     Any,
     Derived,
@@ -188,6 +267,18 @@ impl Debug for Pou {
         if !self.generics.is_empty() {
             str.field("generics", &self.generics);
         }
+        if let Some(super_class) = &self.super_class {
+            str.field("super_class", super_class);
+        }
+        if !self.interfaces.is_empty() {
+            str.field("interfaces", &self.interfaces);
+        }
+        if !self.usings.is_empty() {
+            str.field("usings", &self.usings);
+        }
+        if !self.pragmas.is_empty() {
+            str.field("pragmas", &self.pragmas);
+        }
         str.finish()
     }
 }
@@ -202,7 +293,7 @@ impl Pou {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Implementation {
     pub name: String,
     pub type_name: String,
@@ -215,14 +306,17 @@ pub struct Implementation {
     pub access: Option<AccessModifier>,
 }
 
-#[derive(Debug, Copy, PartialEq, Clone)]
+#[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
 pub enum LinkageType {
     Internal,
     External,
     BuiltIn,
+    /// defined by every output that uses it, the linker keeps one of the identical definitions
+    /// (e.g. the bundled standard library)
+    Weak,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum AccessModifier {
     Private,
     Public,
@@ -230,7 +324,7 @@ pub enum AccessModifier {
     Internal,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum PouType {
     Program,
     Function,
@@ -238,6 +332,7 @@ pub enum PouType {
     Action,
     Class,
     Method { owner_class: String },
+    Interface,
 }
 
 impl PouType {
@@ -251,12 +346,35 @@ impl PouType {
     }
 }
 
-#[derive(Debug, PartialEq, Default)]
+#[derive(PartialEq, Default, Serialize, Deserialize)]
 pub struct CompilationUnit {
     pub global_vars: Vec<VariableBlock>,
     pub units: Vec<Pou>,
     pub implementations: Vec<Implementation>,
     pub types: Vec<UserTypeDeclaration>,
+    /// the namespaces searched for unqualified names, in order, keyed by the lowercase qualified
+    /// name of every POU, type and global variable declared inside a NAMESPACE or with USING
+    pub usings: HashMap<String, Vec<String>>,
+    /// the pragmas attached to statements, keyed by the statement's id
+    #[serde(deserialize_with = "renew_id_keys")]
+    pub pragmas: HashMap<AstId, Vec<Pragma>>,
+    /// the diagnostics disabled by pragmas on the POUs and statements of this unit
+    pub suppressions: Vec<Suppression>,
+    /// the location of the file this unit was parsed from
+    pub file_name: String,
+    /// the line-breaks of the file this unit was parsed from
+    pub new_lines: NewLines,
+}
+
+impl Debug for CompilationUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("CompilationUnit")
+            .field("global_vars", &self.global_vars)
+            .field("units", &self.units)
+            .field("implementations", &self.implementations)
+            .field("types", &self.types)
+            .finish()
+    }
 }
 
 impl CompilationUnit {
@@ -271,10 +389,13 @@ impl CompilationUnit {
         self.units.extend(other.units);
         self.implementations.extend(other.implementations);
         self.types.extend(other.types);
+        self.usings.extend(other.usings);
+        self.pragmas.extend(other.pragmas);
+        self.suppressions.extend(other.suppressions);
     }
 }
 
-#[derive(Debug, Copy, PartialEq, Clone)]
+#[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
 pub enum VariableBlockType {
     Local,
     Temp,
@@ -284,17 +405,19 @@ pub enum VariableBlockType {
     InOut,
 }
 
-#[derive(Debug, Copy, PartialEq, Clone)]
+#[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
 pub enum ArgumentProperty {
     ByVal,
     ByRef,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Serialize, Deserialize)]
 pub struct VariableBlock {
     pub access: AccessModifier,
     pub constant: bool,
     pub retain: bool,
+    /// the block's variables are kept across a cold restart (`PERSISTENT`)
+    pub persistent: bool,
     pub variables: Vec<Variable>,
     pub variable_block_type: VariableBlockType,
     pub linkage: LinkageType,
@@ -310,13 +433,15 @@ impl Debug for VariableBlock {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Variable {
     pub name: String,
     pub data_type: DataTypeDeclaration,
     pub initializer: Option<AstStatement>,
     pub address: Option<AstStatement>,
     pub location: SourceRange,
+    /// the pragmas attached to the variable
+    pub pragmas: Vec<Pragma>,
 }
 
 impl Debug for Variable {
@@ -330,6 +455,9 @@ impl Debug for Variable {
         if self.address.is_some() {
             var.field("address", &self.address);
         }
+        if !self.pragmas.is_empty() {
+            var.field("pragmas", &self.pragmas);
+        }
         var.finish()
     }
 }
@@ -362,18 +490,40 @@ impl DiagnosticInfo for AstStatement {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceRange {
     range: core::ops::Range<usize>,
+    /// the id of the file this range points into, as registered with the `Diagnostician`
+    #[serde(deserialize_with = "renew_file_id")]
+    file_id: Option<usize>,
+}
+
+impl Debug for SourceRange {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let mut range = f.debug_struct("SourceRange");
+        range.field("range", &self.range);
+        if let Some(file_id) = self.file_id {
+            range.field("file_id", &file_id);
+        }
+        range.finish()
+    }
 }
 
 impl SourceRange {
     pub fn new(range: core::ops::Range<usize>) -> SourceRange {
-        SourceRange { range }
+        SourceRange {
+            range,
+            file_id: None,
+        }
+    }
+
+    /// creates a range pointing into the file with the given id
+    pub fn in_file(range: core::ops::Range<usize>, file_id: Option<usize>) -> SourceRange {
+        SourceRange { range, file_id }
     }
 
     pub fn undefined() -> SourceRange {
-        SourceRange { range: 0..0 }
+        SourceRange::new(0..0)
     }
 
     pub fn get_start(&self) -> usize {
@@ -384,13 +534,64 @@ impl SourceRange {
         self.range.end
     }
 
+    /// returns the id of the file this range points into, if known
+    pub fn get_file_id(&self) -> Option<usize> {
+        self.file_id
+    }
+
     pub fn sub_range(&self, start: usize, len: usize) -> SourceRange {
-        SourceRange::new((self.get_start() + start)..(self.get_start() + len))
+        self.with_range((self.get_start() + start)..(self.get_start() + len))
+    }
+
+    /// returns a range with the given offsets in the same file as this range
+    pub fn with_range(&self, range: core::ops::Range<usize>) -> SourceRange {
+        SourceRange::in_file(range, self.file_id)
+    }
+
+    /// returns the range from the start of this range to the end of the given range
+    pub fn span(&self, end: &SourceRange) -> SourceRange {
+        self.with_range(self.get_start()..end.get_end())
     }
 
     pub fn to_range(&self) -> Range<usize> {
         self.range.clone()
     }
+
+    /// returns true if this range does not point into a source file
+    pub fn is_undefined(&self) -> bool {
+        self.range == (0..0)
+    }
+}
+
+/// the offsets of all line-breaks in a source file, used to translate
+/// the offsets of a `SourceRange` into lines and columns
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewLines {
+    line_breaks: Vec<usize>,
+}
+
+impl NewLines {
+    pub fn build(source: &str) -> NewLines {
+        NewLines {
+            line_breaks: source
+                .match_indices('\n')
+                .map(|(offset, _)| offset)
+                .collect(),
+        }
+    }
+
+    /// returns the (1-based) line of the given offset
+    pub fn get_line_nr(&self, offset: usize) -> usize {
+        self.line_breaks.partition_point(|it| *it < offset) + 1
+    }
+
+    /// returns the (1-based) column of the given offset in the given line
+    pub fn get_column(&self, line: usize, offset: usize) -> usize {
+        match line.checked_sub(2).and_then(|it| self.line_breaks.get(it)) {
+            Some(line_break) => offset - line_break,
+            None => offset + 1,
+        }
+    }
 }
 
 impl From<std::ops::Range<usize>> for SourceRange {
@@ -399,7 +600,7 @@ impl From<std::ops::Range<usize>> for SourceRange {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataTypeDeclaration {
     DataTypeReference {
         referenced_type: String,
@@ -447,7 +648,7 @@ impl DataTypeDeclaration {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Serialize, Deserialize)]
 pub struct UserTypeDeclaration {
     pub data_type: DataType,
     pub initializer: Option<AstStatement>,
@@ -466,7 +667,7 @@ impl Debug for UserTypeDeclaration {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataType {
     StructType {
         name: Option<String>, //maybe None for inline structs
@@ -573,7 +774,7 @@ fn replace_reference(
     Some(*old_data_type)
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConditionalBlock {
     pub condition: Box<AstStatement>,
     pub body: Vec<AstStatement>,
@@ -588,16 +789,18 @@ impl Debug for ConditionalBlock {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum AstStatement {
     EmptyStatement {
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     // Literals
     LiteralInteger {
         value: i128,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralDate {
@@ -605,6 +808,7 @@ pub enum AstStatement {
         month: u32,
         day: u32,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralDateAndTime {
@@ -616,6 +820,7 @@ pub enum AstStatement {
         sec: u32,
         milli: u32,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralTimeOfDay {
@@ -624,6 +829,7 @@ pub enum AstStatement {
         sec: u32,
         milli: u32,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralTime {
@@ -636,64 +842,76 @@ pub enum AstStatement {
         nano: u32,
         negative: bool,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralReal {
         value: String,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralBool {
         value: bool,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralString {
         value: String,
         is_wide: bool,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralArray {
         elements: Option<Box<AstStatement>>, // expression-list
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     CastStatement {
         target: Box<AstStatement>,
         type_name: String,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     MultipliedStatement {
         multiplier: u32,
         element: Box<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     // Expressions
     QualifiedReference {
         elements: Vec<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     Reference {
         name: String,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ArrayAccess {
         reference: Box<AstStatement>,
         access: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     PointerAccess {
         reference: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     DirectAccess {
         access: DirectAccessType,
         index: Box<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     HardwareAccess {
@@ -701,39 +919,46 @@ pub enum AstStatement {
         access: DirectAccessType,
         address: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     BinaryExpression {
         operator: Operator,
         left: Box<AstStatement>,
         right: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     UnaryExpression {
         operator: Operator,
         value: Box<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ExpressionList {
         expressions: Vec<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     RangeStatement {
         start: Box<AstStatement>,
         end: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     // Assignment
     Assignment {
         left: Box<AstStatement>,
         right: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     // OutputAssignment
     OutputAssignment {
         left: Box<AstStatement>,
         right: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     //Call Statement
@@ -741,6 +966,7 @@ pub enum AstStatement {
         operator: Box<AstStatement>,
         parameters: Box<Option<AstStatement>>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     // Control Statements
@@ -748,6 +974,7 @@ pub enum AstStatement {
         blocks: Vec<ConditionalBlock>,
         else_block: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ForLoopStatement {
@@ -757,18 +984,21 @@ pub enum AstStatement {
         by_step: Option<Box<AstStatement>>,
         body: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     WhileLoopStatement {
         condition: Box<AstStatement>,
         body: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     RepeatLoopStatement {
         condition: Box<AstStatement>,
         body: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     CaseStatement {
@@ -776,26 +1006,32 @@ pub enum AstStatement {
         case_blocks: Vec<ConditionalBlock>,
         else_block: Vec<AstStatement>,
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     CaseCondition {
         condition: Box<AstStatement>,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ExitStatement {
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ContinueStatement {
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     ReturnStatement {
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
     LiteralNull {
         location: SourceRange,
+        #[serde(deserialize_with = "renew_id")]
         id: AstId,
     },
 }
@@ -1071,12 +1307,12 @@ impl AstStatement {
                 let last = elements
                     .last()
                     .map_or_else(SourceRange::undefined, |it| it.get_location());
-                SourceRange::new(first.get_start()..last.get_end())
+                first.span(&last)
             }
             AstStatement::BinaryExpression { left, right, .. } => {
                 let left_loc = left.get_location();
                 let right_loc = right.get_location();
-                SourceRange::new(left_loc.range.start..right_loc.range.end)
+                left_loc.span(&right_loc)
             }
             AstStatement::UnaryExpression { location, .. } => location.clone(),
             AstStatement::ExpressionList { expressions, .. } => {
@@ -1086,22 +1322,22 @@ impl AstStatement {
                 let last = expressions
                     .last()
                     .map_or_else(SourceRange::undefined, |it| it.get_location());
-                SourceRange::new(first.get_start()..last.get_end())
+                first.span(&last)
             }
             AstStatement::RangeStatement { start, end, .. } => {
                 let start_loc = start.get_location();
                 let end_loc = end.get_location();
-                SourceRange::new(start_loc.range.start..end_loc.range.end)
+                start_loc.span(&end_loc)
             }
             AstStatement::Assignment { left, right, .. } => {
                 let left_loc = left.get_location();
                 let right_loc = right.get_location();
-                SourceRange::new(left_loc.range.start..right_loc.range.end)
+                left_loc.span(&right_loc)
             }
             AstStatement::OutputAssignment { left, right, .. } => {
                 let left_loc = left.get_location();
                 let right_loc = right.get_location();
-                SourceRange::new(left_loc.range.start..right_loc.range.end)
+                left_loc.span(&right_loc)
             }
             AstStatement::CallStatement { location, .. } => location.clone(),
             AstStatement::IfStatement { location, .. } => location.clone(),
@@ -1114,7 +1350,7 @@ impl AstStatement {
             } => {
                 let reference_loc = reference.get_location();
                 let access_loc = access.get_location();
-                SourceRange::new(reference_loc.range.start..access_loc.range.end)
+                reference_loc.span(&access_loc)
             }
             AstStatement::PointerAccess { reference, .. } => reference.get_location(),
             AstStatement::DirectAccess { location, .. } => location.clone(),
@@ -1177,7 +1413,7 @@ impl AstStatement {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Operator {
     Plus,
     Minus,
@@ -22,12 +22,14 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
             .flat_map(|it| it.variables.iter_mut())
             .filter(|it| should_generate_implicit_type(it));
 
+        let first_new_type = unit.types.len();
         for var in all_variables {
             pre_process_variable_data_type(pou.name.as_str(), var, &mut unit.types)
         }
 
         //Generate implicit type for returns
         preprocess_return_type(pou, &mut unit.types);
+        inherit_scopes(&mut unit.usings, &pou.name, &unit.types[first_new_type..]);
     }
 
     //process all variables from GVLs
@@ -38,12 +40,15 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
         .filter(|it| should_generate_implicit_type(it));
 
     for var in all_variables {
-        pre_process_variable_data_type("global", var, &mut unit.types)
+        let first_new_type = unit.types.len();
+        pre_process_variable_data_type("global", var, &mut unit.types);
+        inherit_scopes(&mut unit.usings, &var.name, &unit.types[first_new_type..]);
     }
 
     //process all variables in dataTypes
     let mut new_types = vec![];
     for dt in unit.types.iter_mut() {
+        let first_new_type = new_types.len();
         {
             match &mut dt.data_type {
                 DataType::StructType {
@@ -160,10 +165,26 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
                 _ => {}
             }
         }
+        if let Some(name) = dt.data_type.get_name() {
+            inherit_scopes(&mut unit.usings, name, &new_types[first_new_type..]);
+        }
     }
     unit.types.append(&mut new_types);
 }
 
+/// lets the implicit types created for `container` look up names the same way the container does
+fn inherit_scopes(
+    usings: &mut HashMap<String, Vec<String>>,
+    container: &str,
+    new_types: &[UserTypeDeclaration],
+) {
+    if let Some(scopes) = usings.get(&container.to_lowercase()).cloned() {
+        for name in new_types.iter().filter_map(|it| it.data_type.get_name()) {
+            usings.insert(name.to_lowercase(), scopes.clone());
+        }
+    }
+}
+
 fn build_enum_initializer(
     last_name: &Option<String>,
     location: &SourceRange,
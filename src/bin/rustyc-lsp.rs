@@ -0,0 +1,13 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! A Structured Text language server
+//!
+//! Speaks the [`Language Server Protocol`] over stdio, editors start it as a child process.
+//!
+//! [`Language Server Protocol`]: https://microsoft.github.io/language-server-protocol/
+
+fn main() {
+    if let Err(err) = rusty::lsp::run() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
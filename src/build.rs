@@ -1,7 +1,9 @@
-use crate::diagnostics::Diagnostic;
-use crate::resolve_environment_variables;
-use crate::FormatOption;
+use crate::diagnostics::{Diagnostic, DiagnosticLevel, DiagnosticPolicy};
+use crate::{get_unresolved_offset, resolve_environment_variables};
+use crate::{ConfigFormat, ErrorFormat, FormatOption, OptimizationLevel, Target};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
@@ -17,7 +19,53 @@ pub struct Libraries {
     pub name: String,
     pub path: PathBuf,
     pub package: PackageFormat,
+    #[serde(default)]
     pub include_path: Vec<PathBuf>,
+    /// the library's interface file, it replaces the sources in `include_path`
+    #[serde(default)]
+    pub interface: Option<PathBuf>,
+}
+
+/// a target the project can be built for
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TargetDescription {
+    /// the name used to select the target with `--target`
+    pub name: String,
+    /// the target-triple, defaults to the name
+    #[serde(default)]
+    pub triple: Option<String>,
+    #[serde(default)]
+    pub sysroot: Option<PathBuf>,
+    #[serde(default)]
+    pub linker: Option<String>,
+}
+
+impl TargetDescription {
+    /// creates the target, the given sysroot and linker replace the described ones
+    pub fn to_target(&self, sysroot: Option<String>, linker: Option<String>) -> Target {
+        Target::Param {
+            triple: self.triple.clone().unwrap_or_else(|| self.name.clone()),
+            sysroot: sysroot.or_else(|| {
+                self.sysroot
+                    .as_ref()
+                    .map(|it| it.to_string_lossy().to_string())
+            }),
+            linker: linker.or_else(|| self.linker.clone()),
+        }
+    }
+}
+
+/// a named set of options, selected with `--profile`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub optimization: Option<OptimizationLevel>,
+    /// the names defined in addition to the project's defines
+    #[serde(default)]
+    pub defines: Vec<String>,
+    /// generate debug information
+    #[serde(default)]
+    pub debug: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -30,6 +78,44 @@ pub struct Project {
     pub libraries: Vec<Libraries>,
     #[serde(default)]
     pub package_commands: Vec<String>,
+    /// the names defined for the conditional compilation
+    #[serde(default)]
+    pub defines: Vec<String>,
+    /// additional files included for external declarations
+    #[serde(default)]
+    pub includes: Vec<PathBuf>,
+    #[serde(default)]
+    pub optimization: Option<OptimizationLevel>,
+    #[serde(default)]
+    pub error_format: Option<ErrorFormat>,
+    /// the levels of diagnostic codes (e.g. `"pou__empty_variable_block": "ignore"`)
+    #[serde(default)]
+    pub diagnostics: HashMap<String, DiagnosticLevel>,
+    /// report all warnings as errors
+    #[serde(default)]
+    pub warnings_as_errors: bool,
+    /// the location of the generated hardware configuration
+    #[serde(default)]
+    pub hardware_config: Option<PathBuf>,
+    /// the targets the project can be built for, all of them are built if none is selected
+    #[serde(default)]
+    pub targets: Vec<TargetDescription>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+    /// the build descriptions (or their directories) of the projects this project depends on
+    #[serde(default)]
+    pub dependencies: Vec<PathBuf>,
+}
+
+/// a project of a workspace, together with the location of its build description
+#[derive(Debug)]
+pub struct WorkspaceProject {
+    /// the canonical location of the build description
+    pub location: PathBuf,
+    pub root: PathBuf,
+    pub project: Project,
+    /// the canonical locations of the direct dependencies' build descriptions
+    pub dependencies: Vec<PathBuf>,
 }
 
 impl Project {
@@ -55,40 +141,220 @@ impl Project {
                         .into_iter()
                         .map(|it| if it.is_absolute() { it } else { root.join(it) })
                         .collect(),
+                    interface: it
+                        .interface
+                        .map(|it| if it.is_absolute() { it } else { root.join(it) }),
+                    ..it
+                })
+                .collect(),
+            includes: self
+                .includes
+                .into_iter()
+                .map(|it| if it.is_absolute() { it } else { root.join(it) })
+                .collect(),
+            hardware_config: self.hardware_config.map(|it| {
+                if it.is_absolute() {
+                    it
+                } else {
+                    root.join(it)
+                }
+            }),
+            targets: self
+                .targets
+                .into_iter()
+                .map(|it| TargetDescription {
+                    sysroot: it
+                        .sysroot
+                        .map(|it| if it.is_absolute() { it } else { root.join(it) }),
                     ..it
                 })
                 .collect(),
+            dependencies: self
+                .dependencies
+                .into_iter()
+                .map(|it| if it.is_absolute() { it } else { root.join(it) })
+                .collect(),
             ..self
         }
     }
 
+    /// returns the profile with the given name
+    pub fn get_profile(&self, name: &str) -> Result<&Profile, Diagnostic> {
+        self.profiles.get(name).ok_or_else(|| {
+            let mut known = self.profiles.keys().map(String::as_str).collect::<Vec<_>>();
+            known.sort_unstable();
+            Diagnostic::param_error(&format!(
+                "Unknown profile '{}', the build description declares: {}",
+                name,
+                known.join(", ")
+            ))
+        })
+    }
+
+    /// returns the diagnostic policy of the project, unknown codes are reported
+    pub fn get_diagnostic_policy(&self) -> Result<DiagnosticPolicy, Diagnostic> {
+        let mut policy = DiagnosticPolicy {
+            warnings_as_errors: self.warnings_as_errors,
+            ..Default::default()
+        };
+        for (code, level) in &self.diagnostics {
+            policy.set_level(code, *level)?;
+        }
+        Ok(policy)
+    }
+
+    /// returns the declared target with the given name
+    pub fn get_target(&self, name: &str) -> Option<&TargetDescription> {
+        self.targets.iter().find(|it| it.name == name)
+    }
+
     /// Retuns a project from the given string (in json format)
     /// All environment variables (marked with `$VAR_NAME`) that can be resovled at this time are resolved before the conversion
     pub fn try_parse(content: &str) -> Result<Self, Diagnostic> {
-        let content = resolve_environment_variables(content)?;
-        serde_json::from_str(&content).map_err(Into::into)
+        Project::try_parse_as(content, ConfigFormat::JSON)
+    }
+
+    /// Retuns a project from the given string in the given format
+    /// All environment variables (marked with `$VAR_NAME`) that can be resovled at this time are resolved before the conversion
+    pub fn try_parse_as(content: &str, format: ConfigFormat) -> Result<Self, Diagnostic> {
+        let resolved = resolve_environment_variables(content)?;
+        let invalid = |reason: String, line: usize, column: usize| {
+            //the parsers append the position to their message, report it only once
+            let suffix = format!(" at line {} column {}", line, column);
+            let reason = reason.strip_suffix(&suffix).unwrap_or(&reason);
+            let (line, column) = get_unresolved_position(content, &resolved, line, column);
+            Diagnostic::invalid_build_description(reason, line, column)
+        };
+        match format {
+            ConfigFormat::JSON => serde_json::from_str(&resolved)
+                .map_err(|err| invalid(err.to_string(), err.line(), err.column())),
+            ConfigFormat::TOML => toml::from_str(&resolved).map_err(|err| {
+                //the toml parser counts lines and columns starting with 0
+                let (line, column) = err.line_col().map_or((0, 0), |(l, c)| (l + 1, c + 1));
+                invalid(err.to_string(), line, column)
+            }),
+        }
+    }
+}
+
+/// maps the given (1-based) line and column within the `resolved` content back to the
+/// `unresolved` content, resolving the environment variables shifts the positions
+fn get_unresolved_position(
+    unresolved: &str,
+    resolved: &str,
+    line: usize,
+    column: usize,
+) -> (usize, usize) {
+    if line == 0 {
+        return (line, column);
     }
+    let line_start = resolved
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum::<usize>();
+    let resolved_offset = line_start + column.saturating_sub(1);
+    let offset = get_unresolved_offset(unresolved, resolved_offset)
+        .unwrap_or(resolved_offset)
+        .min(unresolved.len());
+    let before = unresolved.get(..offset).unwrap_or(unresolved);
+    let line_start = before.rfind('\n').map_or(0, |it| it + 1);
+    (
+        before.matches('\n').count() + 1,
+        before.len() - line_start + 1,
+    )
 }
 
 pub fn get_project_from_file(build_config: &Path) -> Result<Project, Diagnostic> {
     //read from file
     let content = fs::read_to_string(build_config)?;
 
-    //convert file to Object
-    let project = Project::try_parse(&content)?;
+    //convert file to Object, the format is derived from the file's extension
+    let format = crate::cli::get_config_format(&build_config.to_string_lossy())
+        .unwrap_or(ConfigFormat::JSON);
+    let project = Project::try_parse_as(&content, format)?;
 
     Ok(project)
 }
 
+/// returns the build description at the given location, a directory is searched for a
+/// `plc.json` or a `plc.toml`
+pub fn get_build_description(location: &Path) -> PathBuf {
+    if location.is_dir() {
+        let json = location.join("plc.json");
+        let toml = location.join("plc.toml");
+        if !json.exists() && toml.exists() {
+            toml
+        } else {
+            json
+        }
+    } else {
+        location.to_path_buf()
+    }
+}
+
+/// collects the project of the given build description and all of its dependencies,
+/// every dependency comes before the projects depending on it
+pub fn collect_workspace(build_config: &Path) -> Result<Vec<WorkspaceProject>, Diagnostic> {
+    let mut projects = vec![];
+    collect_workspace_project(build_config, &mut vec![], &mut projects)?;
+    Ok(projects)
+}
+
+fn collect_workspace_project(
+    build_config: &Path,
+    visiting: &mut Vec<PathBuf>,
+    projects: &mut Vec<WorkspaceProject>,
+) -> Result<PathBuf, Diagnostic> {
+    let location = build_config.canonicalize().map_err(|err| {
+        Diagnostic::io_read_error(&build_config.to_string_lossy(), &err.to_string())
+    })?;
+    if projects.iter().any(|it| it.location == location) {
+        return Ok(location);
+    }
+    if let Some(start) = visiting.iter().position(|it| *it == location) {
+        let cycle = visiting[start..]
+            .iter()
+            .chain(std::iter::once(&location))
+            .map(|it| it.to_string_lossy())
+            .collect::<Vec<_>>();
+        return Err(Diagnostic::param_error(&format!(
+            "Cyclic dependency between the projects: {}",
+            cycle.join(" -> ")
+        )));
+    }
+
+    let root = location.parent().map(Path::to_path_buf).unwrap_or_default();
+    env::set_var("PROJECT_ROOT", &root);
+    let project = get_project_from_file(&location).map(|it| it.to_resolved(&root))?;
+
+    visiting.push(location.clone());
+    let dependencies = project
+        .dependencies
+        .iter()
+        .map(|it| collect_workspace_project(&get_build_description(it), visiting, projects))
+        .collect::<Result<Vec<_>, _>>()?;
+    visiting.pop();
+
+    projects.push(WorkspaceProject {
+        location: location.clone(),
+        root,
+        project,
+        dependencies,
+    });
+    Ok(location)
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
     use std::{env, vec};
 
-    use crate::FormatOption;
+    use crate::diagnostics::{DiagnosticLevel, ErrNo};
+    use crate::{ConfigFormat, FormatOption, OptimizationLevel};
 
     use super::Libraries;
-    use super::{PackageFormat, Project};
+    use super::{collect_workspace, PackageFormat, Project};
 
     #[test]
     fn check_build_struct_from_file() {
@@ -102,15 +368,27 @@ mod tests {
                     path: PathBuf::from("libs/"),
                     package: PackageFormat::Copy,
                     include_path: vec![PathBuf::from("simple_program.st")],
+                    interface: None,
                 },
                 Libraries {
                     name: String::from("nocopy"),
                     path: PathBuf::from("libs/"),
                     package: PackageFormat::System,
                     include_path: vec![PathBuf::from("simple_program.st")],
+                    interface: None,
                 },
             ],
             package_commands: vec![],
+            defines: vec![],
+            includes: vec![],
+            optimization: None,
+            error_format: None,
+            diagnostics: Default::default(),
+            warnings_as_errors: false,
+            hardware_config: None,
+            targets: vec![],
+            profiles: Default::default(),
+            dependencies: vec![],
         };
         let proj = Project::try_parse(
             r#"
@@ -195,4 +473,290 @@ mod tests {
 
         assert_eq!(root.join("simple_program.st"), proj.files[0]);
     }
+
+    #[test]
+    fn libraries_can_be_included_with_their_interface() {
+        let root = PathBuf::from("root");
+        let proj = Project::try_parse(
+            r#"
+            {
+                "files" : [
+                    "simple_program.st"
+                ],
+                "libraries" : [
+                    {
+                        "name" : "vendor",
+                        "path" : "libs/",
+                        "package" : "System",
+                        "interface" : "libs/vendor.stif"
+                    }
+                ]
+            }
+        "#,
+        )
+        .unwrap()
+        .to_resolved(&root);
+
+        assert!(proj.libraries[0].include_path.is_empty());
+        assert_eq!(
+            proj.libraries[0].interface,
+            Some(root.join("libs/vendor.stif"))
+        );
+    }
+
+    #[test]
+    fn project_can_be_parsed_from_toml() {
+        let proj = Project::try_parse_as(
+            r#"
+            files = ["simple_program.st"]
+            compile_type = "Shared"
+            output = "proj.so"
+
+            [[libraries]]
+            name = "copy"
+            path = "libs/"
+            package = "Copy"
+            include_path = ["simple_program.st"]
+        "#,
+            ConfigFormat::TOML,
+        )
+        .unwrap();
+
+        assert_eq!(vec![PathBuf::from("simple_program.st")], proj.files);
+        assert_eq!(FormatOption::Shared, proj.compile_type);
+        assert_eq!(Some("proj.so"), proj.output.as_deref());
+        assert_eq!("copy", proj.libraries[0].name);
+        assert_eq!(PathBuf::from("libs/"), proj.libraries[0].path);
+        assert_eq!(PackageFormat::Copy, proj.libraries[0].package);
+        assert_eq!(
+            vec![PathBuf::from("simple_program.st")],
+            proj.libraries[0].include_path
+        );
+    }
+
+    #[test]
+    fn toml_project_creation_resolves_environment_vars() {
+        env::set_var("toml_test_var", "toml_value");
+        let proj = Project::try_parse_as(
+            r#"
+            files = ["simple_program.st"]
+            output = "$toml_test_var.so"
+        "#,
+            ConfigFormat::TOML,
+        )
+        .unwrap();
+
+        assert_eq!("toml_value.so", &proj.output.unwrap());
+    }
+
+    #[test]
+    fn toml_errors_report_line_and_column() {
+        let err = Project::try_parse_as(
+            "files = [\"simple_program.st\"]\noutput = proj.so\n",
+            ConfigFormat::TOML,
+        )
+        .unwrap_err();
+
+        let message = err.get_message();
+        assert!(
+            message.starts_with("Invalid build description at line 2, column "),
+            "{}",
+            message
+        );
+        assert_eq!(message.matches(" at line").count(), 1, "{}", message);
+    }
+
+    #[test]
+    fn errors_report_the_position_before_resolving_environment_vars() {
+        env::set_var("position_test_var", "a_much_longer_value");
+        env::remove_var("position_test_vaz");
+        let parse = |var: &str| {
+            let content = format!(
+                "files = [\"simple_program.st\"]\noutput = \"${}\" proj.so\n",
+                var
+            );
+            Project::try_parse_as(&content, ConfigFormat::TOML)
+                .unwrap_err()
+                .get_message()
+                .to_string()
+        };
+
+        //the resolved variable is reported like the unresolved one of the same length
+        let message = parse("position_test_var");
+        assert!(
+            message.starts_with("Invalid build description at line 2, column "),
+            "{}",
+            message
+        );
+        assert_eq!(message, parse("position_test_vaz"));
+    }
+
+    #[test]
+    fn json_errors_report_line_and_column() {
+        let err =
+            Project::try_parse("{\n  \"files\" : [\"simple_program.st\"],\n  \"output\" 1\n}")
+                .unwrap_err();
+
+        assert!(err
+            .get_message()
+            .starts_with("Invalid build description at line 3, column"));
+    }
+
+    #[test]
+    fn targets_and_profiles_are_parsed() {
+        let root = PathBuf::from("root");
+        let proj = Project::try_parse_as(
+            r#"
+            files = ["simple_program.st"]
+            optimization = "Less"
+
+            [[targets]]
+            name = "arm"
+            triple = "aarch64-linux-gnu"
+            sysroot = "sysroots/arm"
+            linker = "aarch64-linux-gnu-gcc"
+
+            [[targets]]
+            name = "x86_64-linux-gnu"
+
+            [profiles.release]
+            optimization = "Aggressive"
+            defines = ["RELEASE"]
+
+            [profiles.debug]
+            debug = true
+        "#,
+            ConfigFormat::TOML,
+        )
+        .unwrap()
+        .to_resolved(&root);
+
+        assert_eq!(Some(OptimizationLevel::Less), proj.optimization);
+        let release = proj.get_profile("release").unwrap();
+        assert_eq!(Some(OptimizationLevel::Aggressive), release.optimization);
+        assert_eq!(vec!["RELEASE".to_string()], release.defines);
+        assert!(!release.debug);
+        assert!(proj.get_profile("debug").unwrap().debug);
+
+        let arm = proj.get_target("arm").unwrap().to_target(None, None);
+        assert_eq!(Some("aarch64-linux-gnu"), arm.try_get_name());
+        let sysroot = root.join("sysroots/arm");
+        assert_eq!(Some(sysroot.to_str().unwrap()), arm.get_sysroot());
+        assert_eq!(Some("aarch64-linux-gnu-gcc"), arm.get_linker());
+
+        let x86 = proj
+            .get_target("x86_64-linux-gnu")
+            .unwrap()
+            .to_target(Some("sysroot".to_string()), Some("cc".to_string()));
+        assert_eq!(Some("x86_64-linux-gnu"), x86.try_get_name());
+        assert_eq!(Some("sysroot"), x86.get_sysroot());
+        assert_eq!(Some("cc"), x86.get_linker());
+    }
+
+    #[test]
+    fn diagnostic_levels_are_parsed() {
+        let proj = Project::try_parse_as(
+            r#"
+            files = ["simple_program.st"]
+            warnings_as_errors = true
+
+            [diagnostics]
+            pragma__unattached = "ignore"
+            syntax__non_standard_keyword = "info"
+        "#,
+            ConfigFormat::TOML,
+        )
+        .unwrap();
+
+        let policy = proj.get_diagnostic_policy().unwrap();
+        assert!(policy.warnings_as_errors);
+        assert_eq!(
+            policy.levels.get(&ErrNo::pragma__unattached),
+            Some(&DiagnosticLevel::Ignore)
+        );
+        assert_eq!(
+            policy.levels.get(&ErrNo::syntax__non_standard_keyword),
+            Some(&DiagnosticLevel::Info)
+        );
+
+        let proj = Project::try_parse(
+            r#"{ "files" : ["simple_program.st"], "diagnostics" : { "no_such_code" : "error" } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            proj.get_diagnostic_policy().unwrap_err().get_message(),
+            "Unknown diagnostic code 'no_such_code'"
+        );
+    }
+
+    #[test]
+    fn unknown_profiles_are_reported() {
+        let proj = Project::try_parse(
+            r#"
+            {
+                "files" : ["simple_program.st"],
+                "profiles" : {
+                    "release" : {},
+                    "debug" : { "debug" : true }
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            proj.get_profile("fast").unwrap_err().get_message(),
+            "Unknown profile 'fast', the build description declares: debug, release"
+        );
+    }
+
+    #[test]
+    fn workspace_dependencies_are_collected_before_their_dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        let write_project = |name: &str, dependencies: &[&str]| {
+            let location = dir.path().join(name);
+            std::fs::create_dir_all(&location).unwrap();
+            let project = serde_json::json!({ "files": [], "dependencies": dependencies });
+            std::fs::write(location.join("plc.json"), project.to_string()).unwrap();
+        };
+        write_project("machine", &["../common", "../base"]);
+        write_project("common", &["../base/plc.json"]);
+        write_project("base", &[]);
+
+        let workspace = collect_workspace(&dir.path().join("machine/plc.json")).unwrap();
+
+        let location = |name: &str| dir.path().join(name).canonicalize().unwrap();
+        assert_eq!(
+            vec![
+                location("base/plc.json"),
+                location("common/plc.json"),
+                location("machine/plc.json")
+            ],
+            workspace
+                .iter()
+                .map(|it| it.location.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![location("common/plc.json"), location("base/plc.json")],
+            workspace[2].dependencies
+        );
+    }
+
+    #[test]
+    fn cyclic_workspace_dependencies_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, dependency) in [("a", "../b"), ("b", "../a")] {
+            let location = dir.path().join(name);
+            std::fs::create_dir_all(&location).unwrap();
+            let project = serde_json::json!({ "files": [], "dependencies": [dependency] });
+            std::fs::write(location.join("plc.json"), project.to_string()).unwrap();
+        }
+
+        let err = collect_workspace(&dir.path().join("a/plc.json")).unwrap_err();
+
+        assert!(err
+            .get_message()
+            .starts_with("Cyclic dependency between the projects: "));
+    }
 }
@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use inkwell::{
     basic_block::BasicBlock,
-    values::{BasicValue, BasicValueEnum, IntValue},
+    intrinsics::Intrinsic,
+    values::{BasicValue, BasicValueEnum, FloatValue, IntValue, PointerValue},
+    FloatPredicate, IntPredicate,
 };
 use lazy_static::lazy_static;
 
@@ -11,19 +13,25 @@ use crate::{
         flatten_expression_list, AstStatement, CompilationUnit, GenericBinding, LinkageType,
         SourceRange,
     },
-    codegen::generators::expression_generator::{self, ExpressionCodeGenerator},
+    codegen::{
+        generators::expression_generator::{self, ExpressionCodeGenerator},
+        llvm_typesystem,
+    },
     diagnostics::Diagnostic,
     lexer::{self, IdProvider},
     parser,
     resolver::{
         generics::{generic_name_resolver, no_generic_name_resolver},
-        AnnotationMap, TypeAnnotator, VisitorContext,
+        AnnotationMap, StatementAnnotation, TypeAnnotator, VisitorContext,
     },
     typesystem::{
-        get_bigger_type, DataTypeInformation, DINT_SIZE, DINT_TYPE, REAL_TYPE, UDINT_TYPE,
+        get_bigger_type, DataType, DataTypeInformation, DINT_SIZE, DINT_TYPE, REAL_TYPE, UDINT_TYPE,
     },
 };
 
+mod conversions;
+mod strings;
+
 // Defines a set of functions that are always included in a compiled application
 lazy_static! {
     static ref BUILTIN: HashMap<&'static str, BuiltIn> = HashMap::from([
@@ -35,10 +43,10 @@ lazy_static! {
                     in : U;
                 END_VAR
                 END_FUNCTION
-            ",
+            ".into(),
                 annotation: None,
                 generic_name_resolver: no_generic_name_resolver,
-                code: |generator, params, location| {
+                code: |generator, _, params, location| {
                     if let [reference] = params {
                         generator
                             .generate_element_pointer(reference)
@@ -60,10 +68,10 @@ lazy_static! {
                     in : U;
                 END_VAR
                 END_FUNCTION
-                ",
+                ".into(),
                 annotation: None,
                 generic_name_resolver: no_generic_name_resolver,
-                code: |generator, params, location| {
+                code: |generator, _, params, location| {
                     if let [reference] = params {
                         generator
                             .generate_element_pointer(reference)
@@ -86,10 +94,10 @@ lazy_static! {
                     args : {sized} U...;
                 END_VAR
                 END_FUNCTION
-                ",
+                ".into(),
                 annotation : None,
                 generic_name_resolver: no_generic_name_resolver,
-                code: |generator, params, location| {
+                code: |generator, _, params, location| {
                     let llvm = generator.llvm;
                     let context = llvm.context;
                     let builder = &llvm.builder;
@@ -143,10 +151,10 @@ lazy_static! {
                     IN1 : U;
                 END_VAR
                 END_FUNCTION
-                ",
+                ".into(),
                 annotation: None,
                 generic_name_resolver: no_generic_name_resolver,
-                code: |generator, params, location| {
+                code: |generator, _, params, location| {
                     if let &[g,in0,in1] = params {
                         //Evaluate the parameters
                         let cond = expression_generator::to_i1(generator.generate_expression(g)?.into_int_value(), &generator.llvm.builder);
@@ -168,10 +176,10 @@ lazy_static! {
                 VAR_INPUT
                     in : U;
                 END_VAR
-                END_FUNCTION",
+                END_FUNCTION".into(),
                 annotation: None,
                 generic_name_resolver: no_generic_name_resolver,
-                code : |generator, params, location| {
+                code : |generator, _, params, location| {
                     if params.len() == 1 {
                         generator.generate_expression(params[0])
                     } else {
@@ -189,7 +197,7 @@ lazy_static! {
                     EXPONENT: V;
                 END_VAR
                 END_FUNCTION
-                ",
+                ".into(),
                 annotation: Some(|annotator, operator , parameters, ctx| {
                     let params = parameters.ok_or_else(|| Diagnostic::codegen_error("EXPT requires parameters", operator.get_location()))?;
                     if let [element, exponant] = flatten_expression_list(params)[..] {
@@ -249,12 +257,524 @@ lazy_static! {
                     Ok(())
                 }),
                 generic_name_resolver,
-                code : |_,_,_| {
+                code : |_, _, _, _| {
                     unreachable!("Expt will always end up calling the real functions by the resolver magic")
                 }
             }
+        ),
+        (
+            "ABS",
+            BuiltIn {
+                decl: "FUNCTION ABS<U: ANY_NUM> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    if let &[value] = params {
+                        let signed = get_intrinsic_type_info(generator, value)?.is_signed_int();
+                        match generator.generate_expression(value)? {
+                            BasicValueEnum::FloatValue(value) => generate_intrinsic_call(generator, operator, "llvm.fabs", &[value.into()]),
+                            BasicValueEnum::IntValue(value) if signed => {
+                                let builder = &generator.llvm.builder;
+                                let is_negative = builder.build_int_compare(IntPredicate::SLT, value, value.get_type().const_zero(), "");
+                                Ok(builder.build_select(is_negative, builder.build_int_neg(value, ""), value, ""))
+                            }
+                            value => Ok(value),
+                        }
+                    } else {
+                        Err(Diagnostic::codegen_error("ABS expects exactly one parameter", location))
+                    }
+                }
+            }
+        ),
+        (
+            "SQRT",
+            BuiltIn {
+                decl: "FUNCTION SQRT<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.sqrt"), location)
+                }
+            }
+        ),
+        (
+            "LN",
+            BuiltIn {
+                decl: "FUNCTION LN<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.log"), location)
+                }
+            }
+        ),
+        (
+            "LOG",
+            BuiltIn {
+                decl: "FUNCTION LOG<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.log10"), location)
+                }
+            }
+        ),
+        (
+            "EXP",
+            BuiltIn {
+                decl: "FUNCTION EXP<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.exp"), location)
+                }
+            }
+        ),
+        (
+            "SIN",
+            BuiltIn {
+                decl: "FUNCTION SIN<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.sin"), location)
+                }
+            }
+        ),
+        (
+            "COS",
+            BuiltIn {
+                decl: "FUNCTION COS<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::Intrinsic("llvm.cos"), location)
+                }
+            }
+        ),
+        (
+            "TAN",
+            BuiltIn {
+                decl: "FUNCTION TAN<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::MathLibrary("tan"), location)
+                }
+            }
+        ),
+        (
+            "ASIN",
+            BuiltIn {
+                decl: "FUNCTION ASIN<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::MathLibrary("asin"), location)
+                }
+            }
+        ),
+        (
+            "ACOS",
+            BuiltIn {
+                decl: "FUNCTION ACOS<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::MathLibrary("acos"), location)
+                }
+            }
+        ),
+        (
+            "ATAN",
+            BuiltIn {
+                decl: "FUNCTION ATAN<U: ANY_REAL> : U
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_real_function(generator, operator, params, RealFunction::MathLibrary("atan"), location)
+                }
+            }
+        ),
+        (
+            "MIN",
+            BuiltIn {
+                decl: "FUNCTION MIN<U: ANY_ELEMENTARY> : U
+                VAR_INPUT
+                    args : U...;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    let signed = is_signed_return_type(generator, operator)?;
+                    let values = generate_generic_arguments(generator, operator, params)?;
+                    if let Some((first, rest)) = values.split_first() {
+                        rest.iter().try_fold(*first, |min, value| {
+                            let is_smaller = generate_greater_than(generator, min, *value, signed, &location)?;
+                            Ok(generator.llvm.builder.build_select(is_smaller, *value, min, ""))
+                        })
+                    } else {
+                        Err(Diagnostic::codegen_error("MIN expects at least one parameter", location))
+                    }
+                }
+            }
+        ),
+        (
+            "MAX",
+            BuiltIn {
+                decl: "FUNCTION MAX<U: ANY_ELEMENTARY> : U
+                VAR_INPUT
+                    args : U...;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    let signed = is_signed_return_type(generator, operator)?;
+                    let values = generate_generic_arguments(generator, operator, params)?;
+                    if let Some((first, rest)) = values.split_first() {
+                        rest.iter().try_fold(*first, |max, value| {
+                            let is_bigger = generate_greater_than(generator, *value, max, signed, &location)?;
+                            Ok(generator.llvm.builder.build_select(is_bigger, *value, max, ""))
+                        })
+                    } else {
+                        Err(Diagnostic::codegen_error("MAX expects at least one parameter", location))
+                    }
+                }
+            }
+        ),
+        (
+            "LIMIT",
+            BuiltIn {
+                decl: "FUNCTION LIMIT<U: ANY_ELEMENTARY> : U
+                VAR_INPUT
+                    MN : U;
+                    IN : U;
+                    MX : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    let signed = is_signed_return_type(generator, operator)?;
+                    if let [mn, value, mx] = generate_generic_arguments(generator, operator, params)?[..] {
+                        let builder = &generator.llvm.builder;
+                        //MIN(MAX(IN, MN), MX)
+                        let is_below = generate_greater_than(generator, mn, value, signed, &location)?;
+                        let value = builder.build_select(is_below, mn, value, "");
+                        let is_above = generate_greater_than(generator, value, mx, signed, &location)?;
+                        Ok(builder.build_select(is_above, mx, value, ""))
+                    } else {
+                        Err(Diagnostic::codegen_error("Invalid signature for LIMIT", location))
+                    }
+                }
+            }
+        ),
+        (
+            "SHL",
+            BuiltIn {
+                decl: "FUNCTION SHL<U: ANY_BIT, V: ANY_INT> : U
+                VAR_INPUT
+                    IN : U;
+                    N : V;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_shift(generator, operator, params, Shift::Left, location)
+                }
+            }
+        ),
+        (
+            "SHR",
+            BuiltIn {
+                decl: "FUNCTION SHR<U: ANY_BIT, V: ANY_INT> : U
+                VAR_INPUT
+                    IN : U;
+                    N : V;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_shift(generator, operator, params, Shift::Right, location)
+                }
+            }
+        ),
+        (
+            "ROL",
+            BuiltIn {
+                decl: "FUNCTION ROL<U: ANY_BIT, V: ANY_INT> : U
+                VAR_INPUT
+                    IN : U;
+                    N : V;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_shift(generator, operator, params, Shift::RotateLeft, location)
+                }
+            }
+        ),
+        (
+            "ROR",
+            BuiltIn {
+                decl: "FUNCTION ROR<U: ANY_BIT, V: ANY_INT> : U
+                VAR_INPUT
+                    IN : U;
+                    N : V;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    generate_shift(generator, operator, params, Shift::RotateRight, location)
+                }
+            }
+        ),
+        (
+            "LEN",
+            BuiltIn {
+                decl: "FUNCTION LEN<U: ANY_STRING> : DINT
+                VAR_INPUT
+                    IN : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, None)
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_len(params)
+                }
+            }
+        ),
+        (
+            "LEFT",
+            BuiltIn {
+                decl: "FUNCTION LEFT<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN : U;
+                    L : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.first().copied().unwrap_or_default()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_left(params)
+                }
+            }
+        ),
+        (
+            "RIGHT",
+            BuiltIn {
+                decl: "FUNCTION RIGHT<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN : U;
+                    L : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.first().copied().unwrap_or_default()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_right(params)
+                }
+            }
+        ),
+        (
+            "MID",
+            BuiltIn {
+                decl: "FUNCTION MID<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN : U;
+                    L : DINT;
+                    P : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.first().copied().unwrap_or_default()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_mid(params)
+                }
+            }
+        ),
+        (
+            "CONCAT",
+            BuiltIn {
+                decl: "FUNCTION CONCAT<U: ANY_STRING> : U
+                VAR_INPUT
+                    args : U...;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.iter().sum()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_concat(params)
+                }
+            }
+        ),
+        (
+            "INSERT",
+            BuiltIn {
+                decl: "FUNCTION INSERT<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN1 : U;
+                    IN2 : U;
+                    P : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.iter().sum()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_insert(params)
+                }
+            }
+        ),
+        (
+            "DELETE",
+            BuiltIn {
+                decl: "FUNCTION DELETE<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN : U;
+                    L : DINT;
+                    P : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.first().copied().unwrap_or_default()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_delete(params)
+                }
+            }
+        ),
+        (
+            "REPLACE",
+            BuiltIn {
+                decl: "FUNCTION REPLACE<U: ANY_STRING> : U
+                VAR_INPUT
+                    IN1 : U;
+                    IN2 : U;
+                    L : DINT;
+                    P : DINT;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, Some(|lengths| lengths.iter().sum()))
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_replace(params)
+                }
+            }
+        ),
+        (
+            "FIND",
+            BuiltIn {
+                decl: "FUNCTION FIND<U: ANY_STRING> : DINT
+                VAR_INPUT
+                    IN1 : U;
+                    IN2 : U;
+                END_VAR
+                END_FUNCTION
+                ".into(),
+                annotation: Some(|annotator, operator, parameters, _| {
+                    strings::annotate_string_function(annotator, operator, parameters, None)
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code: |generator, operator, params, location| {
+                    strings::StringFunction::new(generator, operator, location).generate_find(params)
+                }
+            }
         )
     ]);
+
+    // The type conversion functions (e.g. `INT_TO_REAL`, `TO_DINT`, `TRUNC`) generated for all elementary types
+    static ref CONVERSIONS: HashMap<String, BuiltIn> = conversions::create_conversions();
 }
 
 type AnnotationFunction = fn(
@@ -266,11 +786,12 @@ type AnnotationFunction = fn(
 type GenericNameResolver = fn(&str, &[GenericBinding], &HashMap<String, String>) -> String;
 type CodegenFunction = for<'ink, 'b> fn(
     &'b ExpressionCodeGenerator<'ink, 'b>,
+    &AstStatement,
     &[&AstStatement],
     SourceRange,
 ) -> Result<BasicValueEnum<'ink>, Diagnostic>;
 pub struct BuiltIn {
-    decl: &'static str,
+    decl: Cow<'static, str>,
     annotation: Option<AnnotationFunction>,
     generic_name_resolver: GenericNameResolver,
     code: CodegenFunction,
@@ -280,10 +801,11 @@ impl BuiltIn {
     pub fn codegen<'ink, 'b>(
         &self,
         generator: &'b ExpressionCodeGenerator<'ink, 'b>,
+        operator: &AstStatement,
         params: &[&AstStatement],
         location: SourceRange,
     ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
-        (self.code)(generator, params, location)
+        (self.code)(generator, operator, params, location)
     }
     pub(crate) fn get_annotation(&self) -> Option<AnnotationFunction> {
         self.annotation
@@ -294,10 +816,288 @@ impl BuiltIn {
     }
 }
 
+/// the way a function on real numbers is generated
+enum RealFunction {
+    /// calls the given llvm intrinsic
+    Intrinsic(&'static str),
+    /// calls the given function of the c math library (e.g. `tan` or `tanf`)
+    MathLibrary(&'static str),
+}
+
+/// the shift and rotate operations of the ANY_BIT functions
+enum Shift {
+    Left,
+    Right,
+    RotateLeft,
+    RotateRight,
+}
+
+/// returns the intrinsic type of the given parameter's type hint
+fn get_intrinsic_type_info<'g>(
+    generator: &'g ExpressionCodeGenerator,
+    statement: &AstStatement,
+) -> Result<&'g DataTypeInformation, Diagnostic> {
+    generator
+        .get_type_hint_info_for(statement)
+        .map(|it| generator.index.find_intrinsic_type(it))
+}
+
+/// returns the return type of the function called by the given operator
+fn get_return_type<'g>(
+    generator: &'g ExpressionCodeGenerator,
+    operator: &AstStatement,
+) -> Result<&'g DataType, Diagnostic> {
+    if let Some(StatementAnnotation::Function { return_type, .. }) =
+        generator.annotations.get(operator)
+    {
+        Ok(generator.index.get_type_or_panic(return_type))
+    } else {
+        Err(Diagnostic::codegen_error(
+            "Cannot resolve the return type of the builtin call",
+            operator.get_location(),
+        ))
+    }
+}
+
+/// returns a pointer to the given string parameter, values that are not
+/// available as a pointer are stored into a temporary variable
+fn get_string_pointer<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    param: &AstStatement,
+) -> Result<PointerValue<'ink>, Diagnostic> {
+    match param {
+        AstStatement::QualifiedReference { .. } | AstStatement::Reference { .. } => {
+            generator.generate_element_pointer(param)
+        }
+        _ => match generator.generate_expression(param)? {
+            BasicValueEnum::PointerValue(pointer) => Ok(pointer),
+            value => {
+                let pointer = generator.llvm.create_local_variable("", &value.get_type());
+                generator.llvm.builder.build_store(pointer, value);
+                Ok(pointer)
+            }
+        },
+    }
+}
+
+/// generates a call to the llvm intrinsic with the given name, overloaded by the type of the first argument
+fn generate_intrinsic_call<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    name: &str,
+    args: &[BasicValueEnum<'ink>],
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    let module = generator.get_module(operator)?;
+    let function = Intrinsic::find(name)
+        .zip(args.first())
+        .and_then(|(intrinsic, first)| intrinsic.get_declaration(module, &[first.get_type()]))
+        .ok_or_else(|| {
+            Diagnostic::codegen_error(
+                &format!("Cannot find intrinsic {}", name),
+                operator.get_location(),
+            )
+        })?;
+    let args = args.iter().map(|it| (*it).into()).collect::<Vec<_>>();
+    generator
+        .llvm
+        .builder
+        .build_call(function, &args, "")
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| {
+            Diagnostic::codegen_error("Intrinsic returned no value", operator.get_location())
+        })
+}
+
+/// generates a call to the given function of the c math library,
+/// the `f`-suffixed variant is used for 32 bit floats
+fn generate_math_library_call<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    name: &str,
+    value: FloatValue<'ink>,
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    let module = generator.get_module(operator)?;
+    let float_type = value.get_type();
+    let name = if float_type == generator.llvm.context.f32_type() {
+        format!("{}f", name)
+    } else {
+        name.to_string()
+    };
+    let function = module.get_function(&name).unwrap_or_else(|| {
+        module.add_function(&name, float_type.fn_type(&[float_type.into()], false), None)
+    });
+    generator
+        .llvm
+        .builder
+        .build_call(function, &[value.into()], "")
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| {
+            Diagnostic::codegen_error("Function returned no value", operator.get_location())
+        })
+}
+
+/// generates a function with a single ANY_REAL parameter
+fn generate_real_function<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    params: &[&AstStatement],
+    function: RealFunction,
+    location: SourceRange,
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    if let &[value] = params {
+        let value = generator.generate_expression(value)?;
+        match function {
+            RealFunction::Intrinsic(name) => {
+                generate_intrinsic_call(generator, operator, name, &[value])
+            }
+            RealFunction::MathLibrary(name) => {
+                generate_math_library_call(generator, operator, name, value.into_float_value())
+            }
+        }
+    } else {
+        Err(Diagnostic::codegen_error(
+            "Expected exactly one parameter",
+            location,
+        ))
+    }
+}
+
+/// returns true if the return type of the function called by the given operator is a signed integer
+fn is_signed_return_type(
+    generator: &ExpressionCodeGenerator,
+    operator: &AstStatement,
+) -> Result<bool, Diagnostic> {
+    let return_type = get_return_type(generator, operator)?;
+    Ok(generator
+        .index
+        .find_intrinsic_type(return_type.get_type_information())
+        .is_signed_int())
+}
+
+/// generates the parameters of a generic function and casts them into the function's resolved
+/// return type, so arguments of different sizes (e.g. `MIN(INT#1, LINT#2)`) can be compared
+fn generate_generic_arguments<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    params: &[&AstStatement],
+) -> Result<Vec<BasicValueEnum<'ink>>, Diagnostic> {
+    let index = generator.index;
+    let target_type = get_return_type(generator, operator)?;
+    params
+        .iter()
+        .map(|param| {
+            let value = generator.generate_expression(param)?;
+            //the expression was already cast into its type hint unless the hint is still generic
+            let value_type = generator
+                .annotations
+                .get_type_hint(param, index)
+                .filter(|it| !it.get_type_information().is_generic(index))
+                .unwrap_or_else(|| generator.annotations.get_type_or_void(param, index));
+            llvm_typesystem::cast_if_needed(
+                generator.llvm,
+                index,
+                generator.llvm_index,
+                target_type,
+                value,
+                value_type,
+                param,
+            )
+        })
+        .collect()
+}
+
+/// generates an `left > right` comparison for numeric values
+fn generate_greater_than<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    left: BasicValueEnum<'ink>,
+    right: BasicValueEnum<'ink>,
+    signed: bool,
+    location: &SourceRange,
+) -> Result<IntValue<'ink>, Diagnostic> {
+    let builder = &generator.llvm.builder;
+    match (left, right) {
+        (BasicValueEnum::IntValue(left), BasicValueEnum::IntValue(right)) => {
+            let predicate = if signed {
+                IntPredicate::SGT
+            } else {
+                IntPredicate::UGT
+            };
+            Ok(builder.build_int_compare(predicate, left, right, ""))
+        }
+        (BasicValueEnum::FloatValue(left), BasicValueEnum::FloatValue(right)) => {
+            Ok(builder.build_float_compare(FloatPredicate::OGT, left, right, ""))
+        }
+        _ => Err(Diagnostic::codegen_error(
+            "Only numeric values can be compared",
+            location.clone(),
+        )),
+    }
+}
+
+/// generates the shift and rotate functions, shifting by more than the bit-width results in 0,
+/// rotating by more than the bit-width rotates by the remainder
+fn generate_shift<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    params: &[&AstStatement],
+    shift: Shift,
+    location: SourceRange,
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    if let &[value, n] = params {
+        let signed = get_intrinsic_type_info(generator, n)?.is_signed_int();
+        let builder = &generator.llvm.builder;
+        let value = generator.generate_expression(value)?.into_int_value();
+        let n = generator.generate_expression(n)?.into_int_value();
+        let value_type = value.get_type();
+        let width = value_type.get_bit_width() as u64;
+        let n_width = n.get_type().const_int(width, false);
+        let n = match shift {
+            Shift::RotateLeft | Shift::RotateRight if signed => {
+                builder.build_int_signed_rem(n, n_width, "")
+            }
+            Shift::RotateLeft | Shift::RotateRight => {
+                builder.build_int_unsigned_rem(n, n_width, "")
+            }
+            _ => n,
+        };
+        let in_range = builder.build_int_compare(IntPredicate::ULT, n, n_width, "");
+        let n = builder.build_int_cast(n, value_type, "");
+        let result = match shift {
+            Shift::Left => builder.build_left_shift(value, n, ""),
+            Shift::Right => builder.build_right_shift(value, n, false, ""),
+            Shift::RotateLeft => {
+                return generate_intrinsic_call(
+                    generator,
+                    operator,
+                    "llvm.fshl",
+                    &[value.into(), value.into(), n.into()],
+                )
+            }
+            Shift::RotateRight => {
+                return generate_intrinsic_call(
+                    generator,
+                    operator,
+                    "llvm.fshr",
+                    &[value.into(), value.into(), n.into()],
+                )
+            }
+        };
+        Ok(builder.build_select(in_range, result, value_type.const_zero(), ""))
+    } else {
+        Err(Diagnostic::codegen_error(
+            "Expected exactly two parameters",
+            location,
+        ))
+    }
+}
+
 pub fn parse_built_ins(id_provider: IdProvider) -> CompilationUnit {
     let src = BUILTIN
-        .iter()
-        .map(|(_, it)| it.decl)
+        .values()
+        .chain(CONVERSIONS.values())
+        .map(|it| it.decl.as_ref())
         .collect::<Vec<&str>>()
         .join(" ");
     let mut unit = parser::parse(
@@ -311,5 +1111,8 @@ pub fn parse_built_ins(id_provider: IdProvider) -> CompilationUnit {
 
 /// Returns the requested functio from the builtin index or None
 pub fn get_builtin(name: &str) -> Option<&'static BuiltIn> {
-    BUILTIN.get(name.to_uppercase().as_str())
+    let name = name.to_uppercase();
+    BUILTIN
+        .get(name.as_str())
+        .or_else(|| CONVERSIONS.get(name.as_str()))
 }
@@ -0,0 +1,443 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::collections::HashMap;
+
+use inkwell::{
+    values::{BasicValueEnum, IntValue},
+    AddressSpace, FloatPredicate, IntPredicate,
+};
+
+use crate::{
+    ast::{AstStatement, SourceRange},
+    codegen::{generators::expression_generator::ExpressionCodeGenerator, llvm_typesystem},
+    diagnostics::Diagnostic,
+    resolver::generics::no_generic_name_resolver,
+    typesystem::{
+        DataTypeInformation, StringEncoding, BOOL_TYPE, DATE_AND_TIME_TYPE, DATE_TYPE,
+        TIME_OF_DAY_TYPE,
+    },
+};
+
+use super::{generate_intrinsic_call, get_return_type, get_string_pointer, BuiltIn};
+
+/// nanoseconds per day, used to split a DATE_AND_TIME into its DATE and TIME_OF_DAY
+const NANOSECONDS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// the groups of elementary types that can be converted into each other
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Numeric,
+    Char,
+    Duration,
+    Date,
+    TimeOfDay,
+    DateAndTime,
+}
+
+const ELEMENTARY_TYPES: &[(&str, Kind)] = &[
+    ("BOOL", Kind::Numeric),
+    ("BYTE", Kind::Numeric),
+    ("WORD", Kind::Numeric),
+    ("DWORD", Kind::Numeric),
+    ("LWORD", Kind::Numeric),
+    ("SINT", Kind::Numeric),
+    ("USINT", Kind::Numeric),
+    ("INT", Kind::Numeric),
+    ("UINT", Kind::Numeric),
+    ("DINT", Kind::Numeric),
+    ("UDINT", Kind::Numeric),
+    ("LINT", Kind::Numeric),
+    ("ULINT", Kind::Numeric),
+    ("REAL", Kind::Numeric),
+    ("LREAL", Kind::Numeric),
+    ("CHAR", Kind::Char),
+    ("WCHAR", Kind::Char),
+    ("TIME", Kind::Duration),
+    ("LTIME", Kind::Duration),
+    ("DATE", Kind::Date),
+    ("LDATE", Kind::Date),
+    ("TOD", Kind::TimeOfDay),
+    ("LTOD", Kind::TimeOfDay),
+    ("TIME_OF_DAY", Kind::TimeOfDay),
+    ("DT", Kind::DateAndTime),
+    ("LDT", Kind::DateAndTime),
+    ("DATE_AND_TIME", Kind::DateAndTime),
+];
+
+const INTEGER_TYPES: &[&str] = &[
+    "SINT", "USINT", "INT", "UINT", "DINT", "UDINT", "LINT", "ULINT",
+];
+
+/// the bit types and the unsigned integer types of the same size, used for the BCD conversions
+const BCD_TYPES: &[(&str, &str)] = &[
+    ("BYTE", "USINT"),
+    ("WORD", "UINT"),
+    ("DWORD", "UDINT"),
+    ("LWORD", "ULINT"),
+];
+
+/// returns true if a value of the `from` kind can be converted into the `to` kind
+fn is_convertible(from: Kind, to: Kind) -> bool {
+    from == to
+        || from == Kind::Numeric
+        || to == Kind::Numeric
+        || (from == Kind::DateAndTime && matches!(to, Kind::Date | Kind::TimeOfDay))
+}
+
+fn create_function(name: &str, generics: &str, return_type: &str, input: &str) -> String {
+    format!(
+        "FUNCTION {name}{generics} : {return_type}
+        VAR_INPUT
+            in : {input};
+        END_VAR
+        END_FUNCTION
+        "
+    )
+}
+
+fn conversion(decl: String) -> BuiltIn {
+    BuiltIn {
+        decl: decl.into(),
+        annotation: None,
+        generic_name_resolver: no_generic_name_resolver,
+        code: |generator, operator, params, location| {
+            convert(generator, operator, params, location, false)
+        },
+    }
+}
+
+fn truncating_conversion(decl: String) -> BuiltIn {
+    BuiltIn {
+        decl: decl.into(),
+        annotation: None,
+        generic_name_resolver: no_generic_name_resolver,
+        code: |generator, operator, params, location| {
+            convert(generator, operator, params, location, true)
+        },
+    }
+}
+
+fn bcd_decoding(decl: String) -> BuiltIn {
+    BuiltIn {
+        decl: decl.into(),
+        annotation: None,
+        generic_name_resolver: no_generic_name_resolver,
+        code: |generator, operator, params, location| {
+            convert_bcd(generator, operator, params, location, decode_bcd)
+        },
+    }
+}
+
+fn bcd_encoding(decl: String) -> BuiltIn {
+    BuiltIn {
+        decl: decl.into(),
+        annotation: None,
+        generic_name_resolver: no_generic_name_resolver,
+        code: |generator, operator, params, location| {
+            convert_bcd(generator, operator, params, location, encode_bcd)
+        },
+    }
+}
+
+/// creates the conversion functions between all elementary types:
+/// - `X_TO_Y` for every pair of convertible types
+/// - `TO_Y` accepting any elementary type
+/// - `STRING_TO_Y` and `WSTRING_TO_Y` for integer types
+/// - `TRUNC` and `X_TRUNC_Y` truncating a real number into an integer
+/// - the BCD conversions `BCD_TO_Y`, `X_BCD_TO_Y`, `X_TO_BCD_Y` and `X_TO_BCD`
+///
+/// the conversions into strings (`X_TO_STRING`, `X_TO_WSTRING`, `TO_STRING`, `TO_WSTRING`) and
+/// from strings into real numbers (`STRING_TO_REAL`, `STRING_TO_LREAL` and their `WSTRING`
+/// variants) are not supported, calls to them are reported as unresolved references
+pub fn create_conversions() -> HashMap<String, BuiltIn> {
+    let mut conversions = HashMap::new();
+    for (target, target_kind) in ELEMENTARY_TYPES {
+        for (source, source_kind) in ELEMENTARY_TYPES {
+            if source != target && is_convertible(*source_kind, *target_kind) {
+                let name = format!("{source}_TO_{target}");
+                let decl = create_function(&name, "", target, source);
+                conversions.insert(name, conversion(decl));
+            }
+        }
+        let name = format!("TO_{target}");
+        let decl = create_function(&name, "<U: ANY_ELEMENTARY>", target, "U");
+        conversions.insert(name, conversion(decl));
+    }
+
+    for target in INTEGER_TYPES {
+        for source in ["STRING", "WSTRING"] {
+            let name = format!("{source}_TO_{target}");
+            let decl = create_function(&name, "", target, source);
+            conversions.insert(name, conversion(decl));
+        }
+        for source in ["REAL", "LREAL"] {
+            let name = format!("{source}_TRUNC_{target}");
+            let decl = create_function(&name, "", target, source);
+            conversions.insert(name, truncating_conversion(decl));
+        }
+        let name = format!("BCD_TO_{target}");
+        let decl = create_function(&name, "<U: ANY_BIT>", target, "U");
+        conversions.insert(name, bcd_decoding(decl));
+    }
+    let decl = create_function("TRUNC", "<U: ANY_REAL>", "DINT", "U");
+    conversions.insert("TRUNC".to_string(), truncating_conversion(decl));
+
+    for (bit_type, int_type) in BCD_TYPES {
+        let name = format!("{bit_type}_BCD_TO_{int_type}");
+        let decl = create_function(&name, "", int_type, bit_type);
+        conversions.insert(name, bcd_decoding(decl));
+
+        let name = format!("{int_type}_TO_BCD_{bit_type}");
+        let decl = create_function(&name, "", bit_type, int_type);
+        conversions.insert(name, bcd_encoding(decl));
+
+        let name = format!("{int_type}_TO_BCD");
+        let decl = create_function(&name, "", bit_type, int_type);
+        conversions.insert(name, bcd_encoding(decl));
+    }
+    conversions
+}
+
+/// converts the single parameter into the return type of the called conversion function,
+/// real numbers are rounded to the nearest integer unless `truncate` is set
+fn convert<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    params: &[&AstStatement],
+    location: SourceRange,
+    truncate: bool,
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    let param = if let &[param] = params {
+        param
+    } else {
+        return Err(Diagnostic::codegen_error(
+            "Conversion functions expect exactly one parameter",
+            location,
+        ));
+    };
+    let llvm = generator.llvm;
+    let builder = &llvm.builder;
+    let index = generator.index;
+    let source_type = generator.get_type_hint_for(param)?;
+    let target_type = get_return_type(generator, operator)?;
+    let source_name = index
+        .get_intrinsic_type_by_name(source_type.get_name())
+        .get_name();
+    let target_name = index
+        .get_intrinsic_type_by_name(target_type.get_name())
+        .get_name();
+    let target_llvm_type = generator.llvm_index.get_associated_type(target_name)?;
+
+    if let DataTypeInformation::String { encoding, .. } =
+        index.find_intrinsic_type(source_type.get_type_information())
+    {
+        let value = parse_integer(generator, operator, param, encoding)?;
+        return Ok(builder
+            .build_int_cast(value, target_llvm_type.into_int_type(), "")
+            .into());
+    }
+
+    let value = generator.generate_expression(param)?;
+    if target_type.get_type_information().is_bool() {
+        let is_true = match value {
+            BasicValueEnum::FloatValue(value) => builder.build_float_compare(
+                FloatPredicate::ONE,
+                value,
+                value.get_type().const_zero(),
+                "",
+            ),
+            value => {
+                let value = value.into_int_value();
+                builder.build_int_compare(
+                    IntPredicate::NE,
+                    value,
+                    value.get_type().const_zero(),
+                    "",
+                )
+            }
+        };
+        let bool_type = generator.llvm_index.get_associated_type(BOOL_TYPE)?;
+        return Ok(builder
+            .build_int_z_extend(is_true, bool_type.into_int_type(), "")
+            .into());
+    }
+
+    if source_name == DATE_AND_TIME_TYPE
+        && (target_name == DATE_TYPE || target_name == TIME_OF_DAY_TYPE)
+    {
+        let value = value.into_int_value();
+        let day = value.get_type().const_int(NANOSECONDS_PER_DAY, false);
+        let time_of_day = builder.build_int_signed_rem(value, day, "");
+        return if target_name == DATE_TYPE {
+            Ok(builder.build_int_sub(value, time_of_day, "").into())
+        } else {
+            Ok(time_of_day.into())
+        };
+    }
+
+    let value = if !truncate && value.is_float_value() && target_llvm_type.is_int_type() {
+        generate_intrinsic_call(generator, operator, "llvm.round", &[value])?
+    } else {
+        value
+    };
+    llvm_typesystem::cast_if_needed(
+        llvm,
+        index,
+        generator.llvm_index,
+        target_type,
+        value,
+        source_type,
+        param,
+    )
+}
+
+/// parses an optionally signed decimal integer from the start of the given string,
+/// parsing stops at the first character that is not a digit
+fn parse_integer<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    param: &AstStatement,
+    encoding: &StringEncoding,
+) -> Result<IntValue<'ink>, Diagnostic> {
+    let context = generator.llvm.context;
+    let builder = &generator.llvm.builder;
+    let function = generator.get_function_context(operator)?.function;
+    let char_type = match encoding {
+        StringEncoding::Utf8 => context.i8_type(),
+        StringEncoding::Utf16 => context.i16_type(),
+    };
+    let i64_type = context.i64_type();
+
+    let string = builder.build_pointer_cast(
+        get_string_pointer(generator, param)?,
+        char_type.ptr_type(AddressSpace::Generic),
+        "",
+    );
+    let first = builder.build_load(string, "").into_int_value();
+    let is_minus = builder.build_int_compare(
+        IntPredicate::EQ,
+        first,
+        char_type.const_int('-' as u64, false),
+        "",
+    );
+    let is_plus = builder.build_int_compare(
+        IntPredicate::EQ,
+        first,
+        char_type.const_int('+' as u64, false),
+        "",
+    );
+    let start = builder.build_int_z_extend(builder.build_or(is_minus, is_plus, ""), i64_type, "");
+    let entry_block = builder
+        .get_insert_block()
+        .expect("Builder should have a block at this point");
+    let loop_block = context.append_basic_block(function, "parse_digit");
+    let body_block = context.append_basic_block(function, "add_digit");
+    let end_block = context.append_basic_block(function, "parse_end");
+    builder.build_unconditional_branch(loop_block);
+
+    builder.position_at_end(loop_block);
+    let position = builder.build_phi(i64_type, "");
+    let accumulator = builder.build_phi(i64_type, "");
+    let position_value = position.as_basic_value().into_int_value();
+    let accumulator_value = accumulator.as_basic_value().into_int_value();
+    let character = generator
+        .llvm
+        .load_array_element(string, &[position_value], "")
+        .map(|it| builder.build_load(it, "").into_int_value())?;
+    let digit = builder.build_int_sub(character, char_type.const_int('0' as u64, false), "");
+    let is_digit =
+        builder.build_int_compare(IntPredicate::ULT, digit, char_type.const_int(10, false), "");
+    builder.build_conditional_branch(is_digit, body_block, end_block);
+
+    builder.position_at_end(body_block);
+    let next_accumulator = builder.build_int_add(
+        builder.build_int_mul(accumulator_value, i64_type.const_int(10, false), ""),
+        builder.build_int_z_extend(digit, i64_type, ""),
+        "",
+    );
+    let next_position = builder.build_int_add(position_value, i64_type.const_int(1, false), "");
+    builder.build_unconditional_branch(loop_block);
+
+    position.add_incoming(&[(&start, entry_block), (&next_position, body_block)]);
+    accumulator.add_incoming(&[
+        (&i64_type.const_zero(), entry_block),
+        (&next_accumulator, body_block),
+    ]);
+
+    builder.position_at_end(end_block);
+    let negated = builder.build_int_neg(accumulator_value, "");
+    Ok(builder
+        .build_select(is_minus, negated, accumulator_value, "")
+        .into_int_value())
+}
+
+/// converts the single parameter with the given BCD function and casts the result into the
+/// return type of the called function
+fn convert_bcd<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    operator: &AstStatement,
+    params: &[&AstStatement],
+    location: SourceRange,
+    bcd_function: fn(&ExpressionCodeGenerator<'ink, '_>, IntValue<'ink>) -> IntValue<'ink>,
+) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    if let &[param] = params {
+        let target_type = get_return_type(generator, operator)?;
+        let target_llvm_type = generator
+            .llvm_index
+            .get_associated_type(target_type.get_name())?
+            .into_int_type();
+        let builder = &generator.llvm.builder;
+        let value = generator.generate_expression(param)?.into_int_value();
+        let value =
+            builder.build_int_z_extend_or_bit_cast(value, generator.llvm.context.i64_type(), "");
+        let result = bcd_function(generator, value);
+        Ok(builder
+            .build_int_truncate_or_bit_cast(result, target_llvm_type, "")
+            .into())
+    } else {
+        Err(Diagnostic::codegen_error(
+            "BCD conversions expect exactly one parameter",
+            location,
+        ))
+    }
+}
+
+/// decodes the 16 BCD digits of the given i64 value
+fn decode_bcd<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    value: IntValue<'ink>,
+) -> IntValue<'ink> {
+    let builder = &generator.llvm.builder;
+    let i64_type = generator.llvm.context.i64_type();
+    (0..16).rev().fold(i64_type.const_zero(), |result, digit| {
+        let nibble = builder.build_and(
+            builder.build_right_shift(value, i64_type.const_int(digit * 4, false), false, ""),
+            i64_type.const_int(0xF, false),
+            "",
+        );
+        builder.build_int_add(
+            builder.build_int_mul(result, i64_type.const_int(10, false), ""),
+            nibble,
+            "",
+        )
+    })
+}
+
+/// encodes the given i64 value into 16 BCD digits
+fn encode_bcd<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    value: IntValue<'ink>,
+) -> IntValue<'ink> {
+    let builder = &generator.llvm.builder;
+    let i64_type = generator.llvm.context.i64_type();
+    let ten = i64_type.const_int(10, false);
+    let (result, _) = (0..16).fold((i64_type.const_zero(), value), |(result, rest), digit| {
+        let nibble = builder.build_int_unsigned_rem(rest, ten, "");
+        let result = builder.build_or(
+            result,
+            builder.build_left_shift(nibble, i64_type.const_int(digit * 4, false), ""),
+            "",
+        );
+        (result, builder.build_int_unsigned_div(rest, ten, ""))
+    });
+    result
+}
@@ -0,0 +1,578 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use inkwell::{
+    basic_block::BasicBlock,
+    types::IntType,
+    values::{BasicValueEnum, IntValue, PointerValue},
+    AddressSpace, IntPredicate,
+};
+
+use crate::{
+    ast::{flatten_expression_list, AstStatement, SourceRange},
+    codegen::generators::expression_generator::ExpressionCodeGenerator,
+    diagnostics::Diagnostic,
+    resolver::{self, AnnotationMap, StatementAnnotation, TypeAnnotator},
+    typesystem::{DataTypeInformation, StringEncoding, DEFAULT_STRING_LEN, DINT_TYPE},
+};
+
+use super::{get_return_type, get_string_pointer};
+
+/// calculates the length of a string function's result from the lengths of its string parameters
+pub type ResultLength = fn(&[i64]) -> i64;
+
+/// annotates a call to a string function. The string parameters keep their own type, the remaining
+/// parameters are passed as DINT. If a `result_length` is given, the call returns a string with the
+/// calculated length in the encoding of the first parameter
+pub fn annotate_string_function(
+    annotator: &mut TypeAnnotator,
+    operator: &AstStatement,
+    parameters: Option<&AstStatement>,
+    result_length: Option<ResultLength>,
+) -> Result<(), Diagnostic> {
+    let mut lengths = vec![];
+    let mut is_wide = None;
+    for parameter in parameters.map(flatten_expression_list).unwrap_or_default() {
+        let string_type = annotator
+            .annotation_map
+            .get_type(parameter, annotator.index)
+            .and_then(|it| {
+                if let DataTypeInformation::String { size, encoding } = annotator
+                    .index
+                    .find_intrinsic_type(it.get_type_information())
+                {
+                    let size = size
+                        .as_int_value(annotator.index)
+                        .unwrap_or(DEFAULT_STRING_LEN as i64 + 1);
+                    Some((
+                        it.get_name().to_string(),
+                        size - 1,
+                        matches!(encoding, StringEncoding::Utf16),
+                    ))
+                } else {
+                    None
+                }
+            });
+        if let Some((type_name, length, wide)) = string_type {
+            annotator
+                .annotation_map
+                .annotate_type_hint(parameter, StatementAnnotation::new_value(type_name));
+            lengths.push(length);
+            is_wide.get_or_insert(wide);
+        } else {
+            annotator.annotate_parameters(parameter, DINT_TYPE);
+        }
+    }
+
+    if let Some(result_length) = result_length {
+        let return_type = resolver::register_string_type(
+            &mut annotator.annotation_map.new_index,
+            is_wide.unwrap_or_default(),
+            result_length(&lengths) as usize,
+        );
+        if let Some(StatementAnnotation::Function { qualified_name, .. }) =
+            annotator.annotation_map.get(operator)
+        {
+            let annotation = StatementAnnotation::Function {
+                return_type,
+                qualified_name: qualified_name.clone(),
+                call_name: None,
+            };
+            annotator.annotation_map.annotate(operator, annotation);
+        }
+    }
+    Ok(())
+}
+
+/// a string parameter of a string function
+struct StringParameter<'ink> {
+    /// the pointer to the string's first character
+    pointer: PointerValue<'ink>,
+    /// the number of characters before the terminating null
+    length: IntValue<'ink>,
+    /// the type of the string's characters
+    char_type: IntType<'ink>,
+}
+
+/// the string returned by a string function
+struct StringResult<'ink> {
+    /// the pointer to the result's (zero initialized) array
+    array: PointerValue<'ink>,
+    /// the pointer to the result's first character
+    pointer: PointerValue<'ink>,
+}
+
+/// the builder for the inline implementation of a string function
+pub struct StringFunction<'ink, 'g, 'b> {
+    generator: &'g ExpressionCodeGenerator<'ink, 'b>,
+    operator: &'g AstStatement,
+    location: SourceRange,
+}
+
+impl<'ink, 'g, 'b> StringFunction<'ink, 'g, 'b> {
+    pub fn new(
+        generator: &'g ExpressionCodeGenerator<'ink, 'b>,
+        operator: &'g AstStatement,
+        location: SourceRange,
+    ) -> Self {
+        StringFunction {
+            generator,
+            operator,
+            location,
+        }
+    }
+
+    /// `LEN(IN)`: the number of characters in IN
+    pub fn generate_len(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[value] = params {
+            Ok(self.generate_string_parameter(value)?.length.into())
+        } else {
+            Err(self.invalid_signature("LEN"))
+        }
+    }
+
+    /// `LEFT(IN, L)`: the leftmost L characters of IN
+    pub fn generate_left(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[value, l] = params {
+            let value = self.generate_string_parameter(value)?;
+            let count = self.clamp(self.generate_int(l)?, value.length);
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &value, self.zero(), count)?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("LEFT"))
+        }
+    }
+
+    /// `RIGHT(IN, L)`: the rightmost L characters of IN
+    pub fn generate_right(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[value, l] = params {
+            let value = self.generate_string_parameter(value)?;
+            let count = self.clamp(self.generate_int(l)?, value.length);
+            let start = self.sub(value.length, count);
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &value, start, count)?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("RIGHT"))
+        }
+    }
+
+    /// `MID(IN, L, P)`: L characters of IN beginning at the P-th character
+    pub fn generate_mid(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[value, l, p] = params {
+            let value = self.generate_string_parameter(value)?;
+            let (start, count) = self.generate_range(&value, l, p)?;
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &value, start, count)?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("MID"))
+        }
+    }
+
+    /// `CONCAT(IN1, IN2, ...)`: the concatenation of all parameters
+    pub fn generate_concat(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        let result = self.create_result()?;
+        params.iter().try_fold(self.zero(), |offset, it| {
+            let value = self.generate_string_parameter(it)?;
+            self.copy(&result, offset, &value, self.zero(), value.length)?;
+            Ok::<_, Diagnostic>(self.add(offset, value.length))
+        })?;
+        Ok(self.load_result(result))
+    }
+
+    /// `INSERT(IN1, IN2, P)`: IN2 inserted into IN1 after the P-th character
+    pub fn generate_insert(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[in1, in2, p] = params {
+            let in1 = self.generate_string_parameter(in1)?;
+            let in2 = self.generate_string_parameter(in2)?;
+            let position = self.clamp(self.generate_int(p)?, in1.length);
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &in1, self.zero(), position)?;
+            self.copy(&result, position, &in2, self.zero(), in2.length)?;
+            let rest = self.sub(in1.length, position);
+            self.copy(
+                &result,
+                self.add(position, in2.length),
+                &in1,
+                position,
+                rest,
+            )?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("INSERT"))
+        }
+    }
+
+    /// `DELETE(IN, L, P)`: IN without the L characters beginning at the P-th character
+    pub fn generate_delete(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[value, l, p] = params {
+            let value = self.generate_string_parameter(value)?;
+            let (start, count) = self.generate_range(&value, l, p)?;
+            let end = self.add(start, count);
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &value, self.zero(), start)?;
+            let rest = self.sub(value.length, end);
+            self.copy(&result, start, &value, end, rest)?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("DELETE"))
+        }
+    }
+
+    /// `REPLACE(IN1, IN2, L, P)`: IN1 with the L characters beginning at the P-th character replaced by IN2
+    pub fn generate_replace(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[in1, in2, l, p] = params {
+            let in1 = self.generate_string_parameter(in1)?;
+            let in2 = self.generate_string_parameter(in2)?;
+            let (start, count) = self.generate_range(&in1, l, p)?;
+            let end = self.add(start, count);
+            let result = self.create_result()?;
+            self.copy(&result, self.zero(), &in1, self.zero(), start)?;
+            self.copy(&result, start, &in2, self.zero(), in2.length)?;
+            let rest = self.sub(in1.length, end);
+            self.copy(&result, self.add(start, in2.length), &in1, end, rest)?;
+            Ok(self.load_result(result))
+        } else {
+            Err(self.invalid_signature("REPLACE"))
+        }
+    }
+
+    /// `FIND(IN1, IN2)`: the position of the first occurrence of IN2 in IN1, 0 if there is none
+    pub fn generate_find(
+        &self,
+        params: &[&AstStatement],
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        if let &[in1, in2] = params {
+            let in1 = self.generate_string_parameter(in1)?;
+            let in2 = self.generate_string_parameter(in2)?;
+            let builder = &self.generator.llvm.builder;
+            let last_start = self.sub(in1.length, in2.length);
+            let has_pattern =
+                builder.build_int_compare(IntPredicate::SGT, in2.length, self.zero(), "");
+
+            let entry_block = self.current_block();
+            let outer_block = self.append_block("find_start")?;
+            let inner_block = self.append_block("find_compare")?;
+            let compare_block = self.append_block("find_compare_char")?;
+            let inner_next_block = self.append_block("find_next_char")?;
+            let outer_next_block = self.append_block("find_next_start")?;
+            let found_block = self.append_block("find_found")?;
+            let not_found_block = self.append_block("find_not_found")?;
+            let end_block = self.append_block("find_end")?;
+            builder.build_unconditional_branch(outer_block);
+
+            //for each possible start i in IN1
+            builder.position_at_end(outer_block);
+            let start = builder.build_phi(self.int_type(), "");
+            let start_value = start.as_basic_value().into_int_value();
+            let next_start = self.add(start_value, self.one());
+            let in_range =
+                builder.build_int_compare(IntPredicate::SLE, start_value, last_start, "");
+            let in_range = builder.build_and(in_range, has_pattern, "");
+            builder.build_conditional_branch(in_range, inner_block, not_found_block);
+
+            //compare each character j of IN2 with IN1[i + j]
+            builder.position_at_end(inner_block);
+            let offset = builder.build_phi(self.int_type(), "");
+            let offset_value = offset.as_basic_value().into_int_value();
+            let next_offset = self.add(offset_value, self.one());
+            let is_done =
+                builder.build_int_compare(IntPredicate::SGE, offset_value, in2.length, "");
+            builder.build_conditional_branch(is_done, found_block, compare_block);
+
+            builder.position_at_end(compare_block);
+            let left = self.load_char(&in1, self.add(start_value, offset_value))?;
+            let right = self.load_char(&in2, offset_value)?;
+            let is_equal = builder.build_int_compare(IntPredicate::EQ, left, right, "");
+            builder.build_conditional_branch(is_equal, inner_next_block, outer_next_block);
+
+            builder.position_at_end(inner_next_block);
+            builder.build_unconditional_branch(inner_block);
+
+            builder.position_at_end(outer_next_block);
+            builder.build_unconditional_branch(outer_block);
+
+            builder.position_at_end(found_block);
+            builder.build_unconditional_branch(end_block);
+
+            builder.position_at_end(not_found_block);
+            builder.build_unconditional_branch(end_block);
+
+            start.add_incoming(&[(&self.zero(), entry_block), (&next_start, outer_next_block)]);
+            offset.add_incoming(&[
+                (&self.zero(), outer_block),
+                (&next_offset, inner_next_block),
+            ]);
+
+            builder.position_at_end(end_block);
+            let position = builder.build_phi(self.int_type(), "");
+            position.add_incoming(&[(&next_start, found_block), (&self.zero(), not_found_block)]);
+            Ok(position.as_basic_value())
+        } else {
+            Err(self.invalid_signature("FIND"))
+        }
+    }
+
+    /// generates the pointer to and the length of the given string parameter
+    fn generate_string_parameter(
+        &self,
+        param: &AstStatement,
+    ) -> Result<StringParameter<'ink>, Diagnostic> {
+        let generator = self.generator;
+        let (size, encoding) = if let DataTypeInformation::String { size, encoding } = generator
+            .index
+            .find_intrinsic_type(generator.get_type_hint_info_for(param)?)
+        {
+            let size = size
+                .as_int_value(generator.index)
+                .map_err(|err| Diagnostic::codegen_error(&err, param.get_location()))?;
+            (size, encoding)
+        } else {
+            return Err(Diagnostic::codegen_error(
+                "Expected a STRING or WSTRING parameter",
+                param.get_location(),
+            ));
+        };
+        let char_type = get_char_type(generator, encoding);
+        let pointer = generator.llvm.builder.build_pointer_cast(
+            get_string_pointer(generator, param)?,
+            char_type.ptr_type(AddressSpace::Generic),
+            "",
+        );
+        let parameter = StringParameter {
+            pointer,
+            length: self.zero(),
+            char_type,
+        };
+        let length = self.generate_length(&parameter, size)?;
+        Ok(StringParameter {
+            length,
+            ..parameter
+        })
+    }
+
+    /// counts the characters before the terminating null, but at most `size` characters
+    fn generate_length(
+        &self,
+        string: &StringParameter<'ink>,
+        size: i64,
+    ) -> Result<IntValue<'ink>, Diagnostic> {
+        let builder = &self.generator.llvm.builder;
+        let entry_block = self.current_block();
+        let loop_block = self.append_block("length_check")?;
+        let char_block = self.append_block("length_check_char")?;
+        let next_block = self.append_block("length_next")?;
+        let end_block = self.append_block("length_end")?;
+        builder.build_unconditional_branch(loop_block);
+
+        builder.position_at_end(loop_block);
+        let length = builder.build_phi(self.int_type(), "");
+        let length_value = length.as_basic_value().into_int_value();
+        let is_full = builder.build_int_compare(
+            IntPredicate::SGE,
+            length_value,
+            self.int_type().const_int(size as u64, true),
+            "",
+        );
+        builder.build_conditional_branch(is_full, end_block, char_block);
+
+        builder.position_at_end(char_block);
+        let character = self.load_char(string, length_value)?;
+        let is_null = builder.build_int_compare(
+            IntPredicate::EQ,
+            character,
+            string.char_type.const_zero(),
+            "",
+        );
+        builder.build_conditional_branch(is_null, end_block, next_block);
+
+        builder.position_at_end(next_block);
+        let next_length = self.add(length_value, self.one());
+        builder.build_unconditional_branch(loop_block);
+        length.add_incoming(&[(&self.zero(), entry_block), (&next_length, next_block)]);
+
+        builder.position_at_end(end_block);
+        Ok(length_value)
+    }
+
+    /// returns the start and the number of characters selected by the 1-based position `p`
+    /// and the length `l`, both limited to the given string
+    fn generate_range(
+        &self,
+        string: &StringParameter<'ink>,
+        l: &AstStatement,
+        p: &AstStatement,
+    ) -> Result<(IntValue<'ink>, IntValue<'ink>), Diagnostic> {
+        let start = self.sub(self.generate_int(p)?, self.one());
+        let start = self.clamp(start, string.length);
+        let count = self.clamp(self.generate_int(l)?, self.sub(string.length, start));
+        Ok((start, count))
+    }
+
+    /// creates the zero initialized string returned by the function
+    fn create_result(&self) -> Result<StringResult<'ink>, Diagnostic> {
+        let generator = self.generator;
+        let return_type = get_return_type(generator, self.operator)?;
+        let encoding = if let DataTypeInformation::String { encoding, .. } = generator
+            .index
+            .find_intrinsic_type(return_type.get_type_information())
+        {
+            encoding
+        } else {
+            return Err(self.invalid_signature(return_type.get_name()));
+        };
+        let llvm_type = generator
+            .llvm_index
+            .get_associated_type(return_type.get_name())?;
+        let array = generator.llvm.create_local_variable("", &llvm_type);
+        generator
+            .llvm
+            .builder
+            .build_store(array, llvm_type.into_array_type().const_zero());
+        let pointer = generator.llvm.builder.build_pointer_cast(
+            array,
+            get_char_type(generator, encoding).ptr_type(AddressSpace::Generic),
+            "",
+        );
+        Ok(StringResult { array, pointer })
+    }
+
+    fn load_result(&self, result: StringResult<'ink>) -> BasicValueEnum<'ink> {
+        self.generator.llvm.builder.build_load(result.array, "")
+    }
+
+    /// copies `count` characters beginning at `source_offset` of the source
+    /// into the result beginning at `offset`
+    fn copy(
+        &self,
+        result: &StringResult<'ink>,
+        offset: IntValue<'ink>,
+        source: &StringParameter<'ink>,
+        source_offset: IntValue<'ink>,
+        count: IntValue<'ink>,
+    ) -> Result<(), Diagnostic> {
+        let llvm = self.generator.llvm;
+        let char_size = source.char_type.get_bit_width() / 8;
+        let target = llvm.load_array_element(result.pointer, &[offset], "")?;
+        let source = llvm.load_array_element(source.pointer, &[source_offset], "")?;
+        let size = llvm.builder.build_int_mul(
+            count,
+            self.int_type().const_int(char_size as u64, false),
+            "",
+        );
+        llvm.builder
+            .build_memcpy(target, char_size, source, char_size, size)
+            .map_err(|err| Diagnostic::codegen_error(err, self.location.clone()))?;
+        Ok(())
+    }
+
+    fn load_char(
+        &self,
+        string: &StringParameter<'ink>,
+        position: IntValue<'ink>,
+    ) -> Result<IntValue<'ink>, Diagnostic> {
+        let llvm = self.generator.llvm;
+        llvm.load_array_element(string.pointer, &[position], "")
+            .map(|it| llvm.builder.build_load(it, "").into_int_value())
+    }
+
+    fn generate_int(&self, param: &AstStatement) -> Result<IntValue<'ink>, Diagnostic> {
+        let value = self.generator.generate_expression(param)?.into_int_value();
+        Ok(self
+            .generator
+            .llvm
+            .builder
+            .build_int_cast(value, self.int_type(), ""))
+    }
+
+    /// limits the given value to the range `0..=max`
+    fn clamp(&self, value: IntValue<'ink>, max: IntValue<'ink>) -> IntValue<'ink> {
+        let builder = &self.generator.llvm.builder;
+        let is_negative = builder.build_int_compare(IntPredicate::SLT, value, self.zero(), "");
+        let value = builder
+            .build_select(is_negative, self.zero(), value, "")
+            .into_int_value();
+        let is_too_big = builder.build_int_compare(IntPredicate::SGT, value, max, "");
+        builder
+            .build_select(is_too_big, max, value, "")
+            .into_int_value()
+    }
+
+    fn add(&self, left: IntValue<'ink>, right: IntValue<'ink>) -> IntValue<'ink> {
+        self.generator.llvm.builder.build_int_add(left, right, "")
+    }
+
+    fn sub(&self, left: IntValue<'ink>, right: IntValue<'ink>) -> IntValue<'ink> {
+        self.generator.llvm.builder.build_int_sub(left, right, "")
+    }
+
+    fn int_type(&self) -> IntType<'ink> {
+        self.generator.llvm.context.i32_type()
+    }
+
+    fn zero(&self) -> IntValue<'ink> {
+        self.int_type().const_zero()
+    }
+
+    fn one(&self) -> IntValue<'ink> {
+        self.int_type().const_int(1, false)
+    }
+
+    fn current_block(&self) -> BasicBlock<'ink> {
+        self.generator
+            .llvm
+            .builder
+            .get_insert_block()
+            .expect("Builder should have a block at this point")
+    }
+
+    fn append_block(&self, name: &str) -> Result<BasicBlock<'ink>, Diagnostic> {
+        let function = self.generator.get_function_context(self.operator)?.function;
+        Ok(self
+            .generator
+            .llvm
+            .context
+            .append_basic_block(function, name))
+    }
+
+    fn invalid_signature(&self, function: &str) -> Diagnostic {
+        Diagnostic::codegen_error(
+            &format!("Invalid signature for {}", function),
+            self.location.clone(),
+        )
+    }
+}
+
+fn get_char_type<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    encoding: &StringEncoding,
+) -> IntType<'ink> {
+    match encoding {
+        StringEncoding::Utf8 => generator.llvm.context.i8_type(),
+        StringEncoding::Utf16 => generator.llvm.context.i16_type(),
+    }
+}
@@ -0,0 +1,1069 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! Import of C headers as external ST declarations (`rustyc import-c`).
+//!
+//! The reverse of the `header` module: the declarations of a C header are translated into an
+//! ST file that is included (`-i`) to call into the C code. Prototypes become `{external}`
+//! functions, structs and enums become ST types, typedefs become aliases and extern variables
+//! become external globals. Pointer parameters are passed `{ref}` (`char*` as `STRING`),
+//! variadic functions receive `...` and the `<name>_count` and pointer pairs written by the
+//! `header` module become `{sized}` variadic arguments.
+//!
+//! The header is not preprocessed, its directives are skipped. Declarations that cannot be
+//! represented in ST (unions, bit fields, function pointers, ...) are listed in a comment
+//! instead. The sizes of `long` and of the pointer sized types (e.g. `size_t`) are taken from
+//! the data model of the target (see `DataModel`).
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    diagnostics::Diagnostic,
+    lexer::{self, IdProvider, Token},
+    typesystem::{
+        self, BOOL_TYPE, BYTE_TYPE, CHAR_TYPE, DINT_TYPE, INT_TYPE, LINT_TYPE, LREAL_TYPE,
+        REAL_TYPE, SINT_TYPE, STRING_TYPE, UDINT_TYPE, UINT_TYPE, ULINT_TYPE, USINT_TYPE,
+    },
+    DataModel,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// the specifiers that do not change the imported type
+const IGNORED_SPECIFIERS: &[&str] = &[
+    "extern",
+    "inline",
+    "__inline",
+    "__inline__",
+    "const",
+    "volatile",
+    "register",
+    "auto",
+    "restrict",
+    "__restrict",
+    "__restrict__",
+    "__extension__",
+];
+
+/// the keywords an arithmetic type is made of, e.g. `unsigned long int`
+const ARITHMETIC_KEYWORDS: &[&str] = &[
+    "void", "char", "short", "int", "long", "float", "double", "signed", "unsigned", "_Bool",
+    "bool",
+];
+
+/// the attributes and similar extensions that are skipped with their arguments
+const ATTRIBUTES: &[&str] = &["__attribute__", "__declspec", "__asm__", "__asm", "asm"];
+
+/// translates the declarations of the given C header into ST, the C types get the sizes of
+/// the given data model
+pub fn import_header(source: &str, data_model: DataModel) -> String {
+    let tokens = tokenize(source);
+    let mut importer = Importer {
+        tags: find_defined_tags(&tokens),
+        tokens,
+        position: 0,
+        data_model,
+        types: HashMap::new(),
+        constants: HashMap::new(),
+        declarations: vec![],
+    };
+    importer.import();
+
+    let mut result = vec!["(* generated by rustyc *)".to_string()];
+    result.extend(importer.declarations);
+    result.join("\n\n") + "\n"
+}
+
+/// imports the header at the given location and writes the ST declarations to the output
+pub fn import_header_file(
+    header: &Path,
+    output: &Path,
+    data_model: DataModel,
+) -> Result<(), Diagnostic> {
+    let source = fs::read_to_string(header)
+        .map_err(|err| Diagnostic::io_read_error(&header.to_string_lossy(), &err.to_string()))?;
+    fs::write(output, import_header(&source, data_model))
+        .map_err(|err| Diagnostic::io_write_error(&output.to_string_lossy(), &err.to_string()))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum CToken {
+    Identifier(String),
+    Number(String),
+    /// a string or character literal
+    Literal,
+    Ellipsis,
+    Symbol(char),
+}
+
+/// a C type, as far as it can be represented in ST
+#[derive(Clone, Debug, PartialEq)]
+enum CType {
+    Void,
+    /// `char`, pointers to it are strings
+    Char,
+    /// a struct (or union) the header does not define, it can only be pointed to
+    Opaque(String),
+    /// an ST type
+    Named(String),
+    Pointer(Box<CType>),
+    /// an array of the given length, `None` for `[]`
+    Array(Box<CType>, Option<i64>),
+}
+
+impl CType {
+    /// returns the ST type of a variable of this type
+    fn to_st(&self) -> Result<String, String> {
+        match self {
+            CType::Void => Err("void is no type of a variable".to_string()),
+            CType::Char => Ok(CHAR_TYPE.to_string()),
+            CType::Opaque(name) => Err(format!("{} is not defined", name)),
+            CType::Named(name) => Ok(name.clone()),
+            CType::Pointer(inner) => Ok(match inner.as_ref() {
+                //the values behind pointers to unknown types are untyped
+                CType::Void | CType::Opaque(_) => format!("REF_TO {}", BYTE_TYPE),
+                CType::Char => format!("REF_TO {}", STRING_TYPE),
+                inner => format!("REF_TO {}", inner.to_st()?),
+            }),
+            CType::Array(inner, length) => {
+                let length = get_array_length(*length)?;
+                if **inner == CType::Char {
+                    //the length of a string does not include its terminating character
+                    return Ok(format!("{}[{}]", STRING_TYPE, length - 1));
+                }
+                //`[2][3]` is a single array with two dimensions
+                let mut dimensions = vec![format!("0..{}", length - 1)];
+                let mut element = inner.as_ref();
+                while let CType::Array(inner, length) = element {
+                    if **inner == CType::Char {
+                        break;
+                    }
+                    dimensions.push(format!("0..{}", get_array_length(*length)? - 1));
+                    element = inner;
+                }
+                Ok(format!(
+                    "ARRAY[{}] OF {}",
+                    dimensions.join(", "),
+                    element.to_st()?
+                ))
+            }
+        }
+    }
+}
+
+fn get_array_length(length: Option<i64>) -> Result<i64, String> {
+    length
+        .filter(|it| *it > 0)
+        .ok_or_else(|| "arrays without a length are not supported".to_string())
+}
+
+/// the storage class and type of a declaration, shared by all its declarators
+struct Specifiers {
+    data_type: CType,
+    is_typedef: bool,
+    is_static: bool,
+}
+
+/// a declarator of a declaration, e.g. `*names[10]` or `sum(int a, int b)`
+struct Declarator {
+    name: Option<String>,
+    data_type: CType,
+    /// the parameters if this declares a function
+    parameters: Option<Parameters>,
+}
+
+struct Parameters {
+    parameters: Vec<(Option<String>, CType)>,
+    is_variadic: bool,
+}
+
+struct Importer {
+    tokens: Vec<CToken>,
+    position: usize,
+    /// the sizes of the C types on the target
+    data_model: DataModel,
+    /// the ST names of the structs and enums defined in the header, see `find_defined_tags`
+    tags: HashMap<String, String>,
+    /// the types declared by typedefs, by their C name
+    types: HashMap<String, CType>,
+    /// the values of the enum elements
+    constants: HashMap<String, i64>,
+    /// the imported declarations in the order of the header
+    declarations: Vec<String>,
+}
+
+impl Importer {
+    fn import(&mut self) {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                //`extern "C" {` and its closing brace
+                CToken::Identifier(keyword)
+                    if keyword == "extern" && self.peek_at(1) == Some(&CToken::Literal) =>
+                {
+                    self.position += 2;
+                    self.allow_symbol('{');
+                }
+                CToken::Symbol(';') | CToken::Symbol('}') => self.position += 1,
+                _ => {
+                    let start = self.position;
+                    if let Err(reason) = self.import_declaration() {
+                        self.skip_declaration(start);
+                        let name = find_declared_name(&self.tokens[start..self.position])
+                            .unwrap_or("a declaration");
+                        self.declarations
+                            .push(format!("(* {} cannot be imported, {} *)", name, reason));
+                    }
+                }
+            }
+        }
+    }
+
+    fn import_declaration(&mut self) -> Result<(), String> {
+        let specifiers = self.parse_specifiers()?;
+        //a struct or enum definition without declarators
+        if self.allow_symbol(';') {
+            return Ok(());
+        }
+        loop {
+            let declarator = self.parse_declarator(specifiers.data_type.clone())?;
+            let name = declarator
+                .name
+                .ok_or_else(|| "the declaration has no name".to_string())?;
+            if specifiers.is_static {
+                return Err("it is static".to_string());
+            }
+            if specifiers.is_typedef {
+                if declarator.parameters.is_some() {
+                    return Err("function types are not supported".to_string());
+                }
+                self.import_typedef(&name, declarator.data_type)?;
+            } else if let Some(parameters) = declarator.parameters {
+                let function = import_function(&name, &declarator.data_type, &parameters)?;
+                self.declarations.push(function);
+                //a definition in the header is declared like a prototype
+                if self.peek() == Some(&CToken::Symbol('{')) {
+                    self.skip_block();
+                    return Ok(());
+                }
+            } else {
+                check_name(&name)?;
+                self.declarations.push(format!(
+                    "{{external}}\nVAR_GLOBAL\n    {} : {};\nEND_VAR",
+                    name,
+                    declarator.data_type.to_st()?
+                ));
+            }
+            if !self.allow_symbol(',') {
+                break;
+            }
+        }
+        self.expect_symbol(';')
+    }
+
+    /// parses the storage class, qualifiers and type of a declaration, the structs and enums
+    /// defined in it are imported
+    fn parse_specifiers(&mut self) -> Result<Specifiers, String> {
+        let mut is_typedef = false;
+        let mut is_static = false;
+        let mut data_type = None;
+        let mut keywords = vec![];
+        while let Some(CToken::Identifier(word)) = self.peek().cloned() {
+            match word.as_str() {
+                "typedef" => is_typedef = true,
+                "static" => is_static = true,
+                _ if IGNORED_SPECIFIERS.contains(&word.as_str()) => {}
+                _ if ATTRIBUTES.contains(&word.as_str()) => {
+                    self.skip_attributes();
+                    continue;
+                }
+                _ if ARITHMETIC_KEYWORDS.contains(&word.as_str()) => keywords.push(word),
+                "struct" | "union" | "enum" if data_type.is_none() && keywords.is_empty() => {
+                    self.position += 1;
+                    data_type = Some(self.parse_tagged_type(&word, is_typedef)?);
+                    continue;
+                }
+                _ if data_type.is_none() && keywords.is_empty() => {
+                    data_type = Some(self.resolve_type_name(&word)?);
+                }
+                //the name of the first declarator
+                _ => break,
+            }
+            self.position += 1;
+        }
+        let data_type = match data_type {
+            Some(data_type) => data_type,
+            None => get_arithmetic_type(&keywords, self.data_model)?,
+        };
+        Ok(Specifiers {
+            data_type,
+            is_typedef,
+            is_static,
+        })
+    }
+
+    fn resolve_type_name(&self, name: &str) -> Result<CType, String> {
+        if let Some(data_type) = self.types.get(name) {
+            return Ok(data_type.clone());
+        }
+        let st_type = match name {
+            "int8_t" => SINT_TYPE,
+            "uint8_t" => USINT_TYPE,
+            "int16_t" => INT_TYPE,
+            "uint16_t" => UINT_TYPE,
+            "int32_t" => DINT_TYPE,
+            "uint32_t" => UDINT_TYPE,
+            "int64_t" => LINT_TYPE,
+            "uint64_t" => ULINT_TYPE,
+            "intptr_t" | "ptrdiff_t" | "ssize_t" if self.data_model == DataModel::Ilp32 => {
+                DINT_TYPE
+            }
+            "uintptr_t" | "size_t" if self.data_model == DataModel::Ilp32 => UDINT_TYPE,
+            "intptr_t" | "ptrdiff_t" | "ssize_t" => LINT_TYPE,
+            "uintptr_t" | "size_t" => ULINT_TYPE,
+            _ => return Err(format!("its type {} is unknown", name)),
+        };
+        Ok(CType::Named(st_type.to_string()))
+    }
+
+    /// parses the type following `struct`, `union` or `enum` and imports its definition
+    fn parse_tagged_type(&mut self, keyword: &str, is_typedef: bool) -> Result<CType, String> {
+        let tag = self.allow_identifier();
+        let key = tag.as_ref().map(|tag| format!("{} {}", keyword, tag));
+        let defined_name = key.as_ref().and_then(|key| self.tags.get(key)).cloned();
+        if self.peek() != Some(&CToken::Symbol('{')) {
+            return Ok(match defined_name {
+                Some(name) => CType::Named(name),
+                None => CType::Opaque(key.unwrap_or_else(|| keyword.to_string())),
+            });
+        }
+        if keyword == "union" {
+            return Err("unions are not supported".to_string());
+        }
+
+        //anonymous types are named after their typedef
+        let name = match defined_name {
+            Some(name) => Some(name),
+            None if is_typedef => {
+                match self
+                    .tokens
+                    .get(find_closing_brace(&self.tokens, self.position) + 1)
+                {
+                    Some(CToken::Identifier(name)) => Some(name.clone()),
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        self.position += 1;
+
+        if keyword == "enum" {
+            let elements = self.parse_enum_elements()?;
+            for (element, _) in &elements {
+                if !is_identifier(element) {
+                    return Err(format!("its element {} is a keyword in ST", element));
+                }
+            }
+            return match name {
+                Some(name) => {
+                    check_name(&name)?;
+                    let elements = elements
+                        .iter()
+                        .map(|(element, value)| format!("    {} := {}", element, value))
+                        .collect::<Vec<_>>();
+                    self.declarations.push(format!(
+                        "TYPE {} : (\n{}\n);\nEND_TYPE",
+                        name,
+                        elements.join(",\n")
+                    ));
+                    Ok(CType::Named(name))
+                }
+                //the elements of an anonymous enum are constants
+                None => {
+                    let elements = elements
+                        .iter()
+                        .map(|(element, value)| {
+                            format!("    {} : {} := {};", element, DINT_TYPE, value)
+                        })
+                        .collect::<Vec<_>>();
+                    self.declarations.push(format!(
+                        "VAR_GLOBAL CONSTANT\n{}\nEND_VAR",
+                        elements.join("\n")
+                    ));
+                    Ok(CType::Named(DINT_TYPE.to_string()))
+                }
+            };
+        }
+
+        let name = name.ok_or_else(|| "anonymous structs are not supported".to_string())?;
+        let members = match self.parse_members() {
+            Ok(members) => members,
+            Err(reason) => {
+                //the struct cannot be used, it is not imported
+                if let Some(key) = key {
+                    self.tags.remove(&key);
+                }
+                return Err(reason);
+            }
+        };
+        check_name(&name)?;
+        self.declarations.push(format!(
+            "TYPE {} :\nSTRUCT\n{}\nEND_STRUCT\nEND_TYPE",
+            name,
+            members.join("\n")
+        ));
+        Ok(CType::Named(name))
+    }
+
+    /// parses the members of a struct up to its closing brace
+    fn parse_members(&mut self) -> Result<Vec<String>, String> {
+        let mut members = vec![];
+        while !self.allow_symbol('}') {
+            if self.peek().is_none() {
+                return Err("the struct is not closed".to_string());
+            }
+            let specifiers = self.parse_specifiers()?;
+            loop {
+                let declarator = self.parse_declarator(specifiers.data_type.clone())?;
+                if self.peek() == Some(&CToken::Symbol(':')) {
+                    return Err("bit fields are not supported".to_string());
+                }
+                let name = declarator
+                    .name
+                    .ok_or_else(|| "anonymous members are not supported".to_string())?;
+                members.push(format!(
+                    "    {} : {};",
+                    to_variable_name(&name),
+                    declarator.data_type.to_st()?
+                ));
+                if !self.allow_symbol(',') {
+                    break;
+                }
+            }
+            self.expect_symbol(';')?;
+        }
+        Ok(members)
+    }
+
+    /// parses the elements of an enum up to its closing brace, elements without a value
+    /// follow their predecessor
+    fn parse_enum_elements(&mut self) -> Result<Vec<(String, i64)>, String> {
+        let mut elements = vec![];
+        let mut next_value = 0;
+        while !self.allow_symbol('}') {
+            let element = self
+                .allow_identifier()
+                .ok_or_else(|| "its elements cannot be read".to_string())?;
+            let value = if self.allow_symbol('=') {
+                self.parse_constant(0)?
+            } else {
+                next_value
+            };
+            //the enum is a DINT, unsigned values keep their bits
+            let value = match value {
+                _ if i32::try_from(value).is_ok() => value,
+                _ if u32::try_from(value).is_ok() => value as u32 as i32 as i64,
+                _ => return Err(format!("the value of {} exceeds a DINT", element)),
+            };
+            self.constants.insert(element.clone(), value);
+            elements.push((element, value));
+            next_value = value + 1;
+            if !self.allow_symbol(',') {
+                self.expect_symbol('}')?;
+                break;
+            }
+        }
+        Ok(elements)
+    }
+
+    /// evaluates an integer constant expression of literals and enum elements, only
+    /// operators binding at least as strong as the given precedence are evaluated
+    fn parse_constant(&mut self, precedence: u8) -> Result<i64, String> {
+        let mut value = match self.next() {
+            Some(CToken::Symbol('-')) => self.parse_constant(u8::MAX)?.wrapping_neg(),
+            Some(CToken::Symbol('~')) => !self.parse_constant(u8::MAX)?,
+            Some(CToken::Symbol('+')) => self.parse_constant(u8::MAX)?,
+            Some(CToken::Symbol('(')) => {
+                let value = self.parse_constant(0)?;
+                self.expect_symbol(')')?;
+                value
+            }
+            Some(CToken::Number(number)) => parse_integer(&number)?,
+            Some(CToken::Identifier(name)) => *self
+                .constants
+                .get(&name)
+                .ok_or_else(|| format!("the value of {} is unknown", name))?,
+            _ => return Err("it uses an unsupported constant expression".to_string()),
+        };
+        while let Some((operator, operator_precedence)) = self.peek_operator() {
+            if operator_precedence < precedence {
+                break;
+            }
+            self.position += operator.len();
+            let right = self.parse_constant(operator_precedence + 1)?;
+            value = match operator {
+                "|" => value | right,
+                "^" => value ^ right,
+                "&" => value & right,
+                "<<" => value.wrapping_shl(right as u32),
+                ">>" => value.wrapping_shr(right as u32),
+                "+" => value.wrapping_add(right),
+                "-" => value.wrapping_sub(right),
+                "*" => value.wrapping_mul(right),
+                _ if right == 0 => return Err("it divides by zero".to_string()),
+                "/" => value.wrapping_div(right),
+                _ => value.wrapping_rem(right),
+            };
+        }
+        Ok(value)
+    }
+
+    /// returns the binary operator at the current position and its precedence
+    fn peek_operator(&self) -> Option<(&'static str, u8)> {
+        match (self.peek(), self.peek_at(1)) {
+            (Some(CToken::Symbol('<')), Some(CToken::Symbol('<'))) => Some(("<<", 4)),
+            (Some(CToken::Symbol('>')), Some(CToken::Symbol('>'))) => Some((">>", 4)),
+            (Some(CToken::Symbol(symbol)), _) => match symbol {
+                '|' => Some(("|", 1)),
+                '^' => Some(("^", 2)),
+                '&' => Some(("&", 3)),
+                '+' => Some(("+", 5)),
+                '-' => Some(("-", 5)),
+                '*' => Some(("*", 6)),
+                '/' => Some(("/", 6)),
+                '%' => Some(("%", 6)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn parse_declarator(&mut self, base_type: CType) -> Result<Declarator, String> {
+        let mut data_type = base_type;
+        while self.allow_symbol('*') {
+            data_type = CType::Pointer(Box::new(data_type));
+            self.skip_qualifiers();
+        }
+        if self.peek() == Some(&CToken::Symbol('(')) {
+            return Err("function pointers are not supported".to_string());
+        }
+        let name = self.allow_identifier();
+        let parameters = if self.allow_symbol('(') {
+            Some(self.parse_parameters()?)
+        } else {
+            None
+        };
+        let mut lengths = vec![];
+        while self.allow_symbol('[') {
+            if self.allow_symbol(']') {
+                lengths.push(None);
+            } else {
+                lengths.push(Some(self.parse_constant(0)?));
+                self.expect_symbol(']')?;
+            }
+        }
+        //`int m[2][3]` is an array of two arrays of three ints
+        for length in lengths.into_iter().rev() {
+            data_type = CType::Array(Box::new(data_type), length);
+        }
+        self.skip_attributes();
+        Ok(Declarator {
+            name,
+            data_type,
+            parameters,
+        })
+    }
+
+    /// parses the parameters of a function after the opening parenthesis
+    fn parse_parameters(&mut self) -> Result<Parameters, String> {
+        let mut parameters = vec![];
+        let mut is_variadic = false;
+        //`f(void)` has no parameters
+        if self.peek() == Some(&CToken::Identifier("void".to_string()))
+            && self.peek_at(1) == Some(&CToken::Symbol(')'))
+        {
+            self.position += 1;
+        }
+        while !self.allow_symbol(')') {
+            if self.peek() == Some(&CToken::Ellipsis) {
+                self.position += 1;
+                is_variadic = true;
+            } else {
+                let specifiers = self.parse_specifiers()?;
+                let declarator = self.parse_declarator(specifiers.data_type)?;
+                if declarator.parameters.is_some() {
+                    return Err("function pointers are not supported".to_string());
+                }
+                parameters.push((declarator.name, declarator.data_type));
+            }
+            if !self.allow_symbol(',') {
+                self.expect_symbol(')')?;
+                break;
+            }
+        }
+        Ok(Parameters {
+            parameters,
+            is_variadic,
+        })
+    }
+
+    fn import_typedef(&mut self, name: &str, data_type: CType) -> Result<(), String> {
+        match &data_type {
+            //`typedef struct point point;`
+            CType::Named(type_name) if type_name == name => {}
+            //pointers to opaque types are untyped
+            CType::Opaque(_) => {
+                self.types.insert(name.to_string(), data_type);
+                return Ok(());
+            }
+            //e.g. `typedef uint16_t WORD;`, the name refers to the type it stands for
+            _ if check_name(name).is_err() => {
+                self.types.insert(name.to_string(), data_type);
+                return Ok(());
+            }
+            _ => {
+                self.declarations
+                    .push(format!("TYPE {} : {}; END_TYPE", name, data_type.to_st()?))
+            }
+        }
+        self.types
+            .insert(name.to_string(), CType::Named(name.to_string()));
+        Ok(())
+    }
+
+    /// skips the declaration starting at the given position
+    fn skip_declaration(&mut self, start: usize) {
+        self.position = start;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                CToken::Symbol(';') if depth == 0 => {
+                    self.position += 1;
+                    return;
+                }
+                //the body of a function definition
+                CToken::Symbol('{')
+                    if depth == 0
+                        && self.position > start
+                        && self.tokens[self.position - 1] == CToken::Symbol(')') =>
+                {
+                    self.skip_block();
+                    return;
+                }
+                CToken::Symbol('{') => depth += 1,
+                //the closing brace of `extern "C" {`
+                CToken::Symbol('}') if depth == 0 => break,
+                CToken::Symbol('}') => depth -= 1,
+                _ => {}
+            }
+            self.position += 1;
+        }
+        if self.position == start {
+            self.position += 1;
+        }
+    }
+
+    /// skips the block starting at the current position
+    fn skip_block(&mut self) {
+        self.position =
+            (find_closing_brace(&self.tokens, self.position) + 1).min(self.tokens.len());
+    }
+
+    fn skip_qualifiers(&mut self) {
+        while let Some(CToken::Identifier(word)) = self.peek() {
+            if !IGNORED_SPECIFIERS.contains(&word.as_str()) {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    /// skips attributes like `__attribute__((packed))` with their arguments
+    fn skip_attributes(&mut self) {
+        while let Some(CToken::Identifier(word)) = self.peek() {
+            if !ATTRIBUTES.contains(&word.as_str()) {
+                break;
+            }
+            self.position += 1;
+            if self.peek() == Some(&CToken::Symbol('(')) {
+                let mut depth = 0;
+                while let Some(token) = self.next() {
+                    match token {
+                        CToken::Symbol('(') => depth += 1,
+                        CToken::Symbol(')') if depth == 1 => break,
+                        CToken::Symbol(')') => depth -= 1,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<&CToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&CToken> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<CToken> {
+        let token = self.tokens.get(self.position).cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn allow_symbol(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&CToken::Symbol(symbol));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn allow_identifier(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(CToken::Identifier(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), String> {
+        if self.allow_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("'{}' is expected", symbol))
+        }
+    }
+}
+
+/// returns the `{external}` ST function for the given prototype
+fn import_function(
+    name: &str,
+    return_type: &CType,
+    parameters: &Parameters,
+) -> Result<String, String> {
+    check_name(name)?;
+    let mut function = match return_type {
+        CType::Void => format!("{{external}}\nFUNCTION {}", name),
+        _ => format!("{{external}}\nFUNCTION {} : {}", name, return_type.to_st()?),
+    };
+
+    //the variables of the parameter blocks and whether they are passed by reference,
+    //consecutive parameters passed the same way share a block
+    let mut blocks: Vec<(bool, Vec<String>)> = vec![];
+    let mut add = |by_ref: bool, variable: String| match blocks.last_mut() {
+        Some((block_by_ref, variables)) if *block_by_ref == by_ref => variables.push(variable),
+        _ => blocks.push((by_ref, vec![variable])),
+    };
+    let sized = find_sized_variadic(parameters);
+    let fixed_parameters = if sized.is_some() {
+        &parameters.parameters[..parameters.parameters.len() - 2]
+    } else {
+        &parameters.parameters[..]
+    };
+    for (index, (parameter, data_type)) in fixed_parameters.iter().enumerate() {
+        let parameter = match parameter {
+            //the function's name is its result variable
+            Some(parameter) if parameter.eq_ignore_ascii_case(name) => format!("{}_", parameter),
+            Some(parameter) => to_variable_name(parameter),
+            None => format!("arg{}", index + 1),
+        };
+        let data_type = match data_type {
+            CType::Array(inner, None) => CType::Pointer(inner.clone()),
+            _ => data_type.clone(),
+        };
+        match data_type {
+            //the values behind typed pointers are passed by reference, `char*` as a string
+            CType::Pointer(inner)
+                if !matches!(
+                    inner.as_ref(),
+                    CType::Void | CType::Opaque(_) | CType::Pointer(_)
+                ) =>
+            {
+                let inner = match inner.as_ref() {
+                    CType::Char => STRING_TYPE.to_string(),
+                    inner => inner.to_st()?,
+                };
+                add(true, format!("    {} : {};", parameter, inner));
+            }
+            //arrays are passed as a pointer to their first element
+            CType::Array(..) => add(true, format!("    {} : {};", parameter, data_type.to_st()?)),
+            _ => add(
+                false,
+                format!("    {} : {};", parameter, data_type.to_st()?),
+            ),
+        }
+    }
+    if let Some((parameter, element_type)) = sized {
+        add(
+            false,
+            format!("    {} : {{sized}} {}...;", parameter, element_type),
+        );
+    } else if parameters.is_variadic {
+        add(false, "    args : ...;".to_string());
+    }
+
+    for (by_ref, variables) in blocks {
+        function.push_str(if by_ref {
+            "\nVAR_INPUT {ref}\n"
+        } else {
+            "\nVAR_INPUT\n"
+        });
+        function.push_str(&variables.join("\n"));
+        function.push_str("\nEND_VAR");
+    }
+    function.push_str("\nEND_FUNCTION");
+    Ok(function)
+}
+
+/// returns the name and element type of sized variadic arguments, they are passed as the
+/// last two parameters `int32_t <name>_count, <type>* <name>` (see the `header` module)
+fn find_sized_variadic(parameters: &Parameters) -> Option<(String, String)> {
+    if parameters.is_variadic {
+        return None;
+    }
+    match parameters.parameters.as_slice() {
+        [.., (Some(count), CType::Named(count_type)), (Some(name), CType::Pointer(element))]
+            if count_type == DINT_TYPE && *count == format!("{}_count", name) =>
+        {
+            let element = match element.as_ref() {
+                CType::Char => return None,
+                element => element.to_st().ok()?,
+            };
+            Some((to_variable_name(name), element))
+        }
+        _ => None,
+    }
+}
+
+/// returns the ST type for the keywords of an arithmetic type, `long` has 64 bits on `Lp64`
+/// targets and 32 bits otherwise, `long long` always has 64 bits
+fn get_arithmetic_type(keywords: &[String], data_model: DataModel) -> Result<CType, String> {
+    let has = |keyword: &str| keywords.iter().any(|it| it == keyword);
+    let is_long_long = keywords.iter().filter(|it| *it == "long").count() > 1;
+    let unsigned = has("unsigned");
+    let st_type = if keywords.is_empty() {
+        return Err("its type is missing".to_string());
+    } else if has("void") {
+        return Ok(CType::Void);
+    } else if has("_Bool") || has("bool") {
+        BOOL_TYPE
+    } else if has("char") {
+        if unsigned {
+            USINT_TYPE
+        } else if has("signed") {
+            SINT_TYPE
+        } else {
+            return Ok(CType::Char);
+        }
+    } else if has("float") {
+        REAL_TYPE
+    } else if has("double") {
+        if has("long") {
+            return Err("long double is not supported".to_string());
+        }
+        LREAL_TYPE
+    } else if has("short") {
+        if unsigned {
+            UINT_TYPE
+        } else {
+            INT_TYPE
+        }
+    } else if has("long") && (is_long_long || data_model == DataModel::Lp64) {
+        if unsigned {
+            ULINT_TYPE
+        } else {
+            LINT_TYPE
+        }
+    } else if unsigned {
+        UDINT_TYPE
+    } else {
+        DINT_TYPE
+    };
+    Ok(CType::Named(st_type.to_string()))
+}
+
+/// parses a C integer literal (decimal, hexadecimal or octal, with an optional suffix)
+fn parse_integer(literal: &str) -> Result<i64, String> {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8)
+    } else {
+        digits.parse()
+    };
+    value
+        .map(|it| it as i64)
+        .map_err(|_| format!("{} is no integer", literal))
+}
+
+/// returns an error if the given name cannot be used for a POU, type or global in ST
+fn check_name(name: &str) -> Result<(), String> {
+    if !is_identifier(name) {
+        Err(format!("{} is a keyword in ST", name))
+    } else if typesystem::get_builtin_types()
+        .iter()
+        .any(|it| it.get_name().eq_ignore_ascii_case(name))
+    {
+        Err(format!("{} is a builtin type in ST", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// returns the name of a variable, the names of ST keywords get an underscore appended
+fn to_variable_name(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        format!("{}_", name)
+    }
+}
+
+/// returns true if the ST lexer reads the given name as a single identifier
+fn is_identifier(name: &str) -> bool {
+    let lexer = lexer::lex_with_ids(name, IdProvider::default());
+    lexer.token == Token::Identifier && lexer.range() == (0..name.len())
+}
+
+/// splits the header into tokens, comments and preprocessor directives are skipped
+fn tokenize(source: &str) -> Vec<CToken> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let at = |index: usize| chars.get(index).copied().unwrap_or_default();
+    let mut tokens = vec![];
+    let mut index = 0;
+    //directives start a line
+    let mut line_start = true;
+    while index < chars.len() {
+        let current = chars[index];
+        if current == '\n' {
+            line_start = true;
+            index += 1;
+        } else if current.is_whitespace() {
+            index += 1;
+        } else if current == '#' && line_start {
+            //a directive ends with its line, unless the line ends with a backslash
+            while index < chars.len() && chars[index] != '\n' {
+                index += if chars[index] == '\\' { 2 } else { 1 };
+            }
+        } else if current == '/' && at(index + 1) == '/' {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+        } else if current == '/' && at(index + 1) == '*' {
+            index += 2;
+            while index < chars.len() && !(chars[index] == '*' && at(index + 1) == '/') {
+                index += 1;
+            }
+            index += 2;
+        } else {
+            line_start = false;
+            if current == '"' || current == '\'' {
+                index += 1;
+                while index < chars.len() && chars[index] != current {
+                    index += if chars[index] == '\\' { 2 } else { 1 };
+                }
+                index += 1;
+                tokens.push(CToken::Literal);
+            } else if current.is_ascii_alphanumeric() || current == '_' {
+                let start = index;
+                while index < chars.len()
+                    && (chars[index].is_ascii_alphanumeric()
+                        || chars[index] == '_'
+                        || (chars[index] == '.' && current.is_ascii_digit()))
+                {
+                    index += 1;
+                }
+                let text = chars[start..index].iter().collect::<String>();
+                tokens.push(if current.is_ascii_digit() {
+                    CToken::Number(text)
+                } else {
+                    CToken::Identifier(text)
+                });
+            } else if current == '.' && at(index + 1) == '.' && at(index + 2) == '.' {
+                index += 3;
+                tokens.push(CToken::Ellipsis);
+            } else {
+                index += 1;
+                tokens.push(CToken::Symbol(current));
+            }
+        }
+    }
+    tokens
+}
+
+/// returns the index of the brace closing the one at the given index
+fn find_closing_brace(tokens: &[CToken], open: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            CToken::Symbol('{') => depth += 1,
+            CToken::Symbol('}') if depth == 1 => return index,
+            CToken::Symbol('}') => depth -= 1,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// returns the ST names of the structs and enums the header defines by their tag
+/// (e.g. `struct point`). A struct defined in a typedef is named after the typedef
+fn find_defined_tags(tokens: &[CToken]) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    for (index, window) in tokens.windows(3).enumerate() {
+        if let [CToken::Identifier(keyword), CToken::Identifier(tag), CToken::Symbol('{')] = window
+        {
+            if keyword != "struct" && keyword != "enum" {
+                continue;
+            }
+            let is_typedef =
+                index > 0 && tokens[index - 1] == CToken::Identifier("typedef".to_string());
+            let name = match tokens.get(find_closing_brace(tokens, index + 2) + 1) {
+                Some(CToken::Identifier(name)) if is_typedef => name.clone(),
+                _ => tag.clone(),
+            };
+            tags.insert(format!("{} {}", keyword, tag), name);
+        }
+    }
+    tags
+}
+
+/// returns the name declared by the given tokens of a declaration, used to report
+/// declarations that cannot be imported
+fn find_declared_name(tokens: &[CToken]) -> Option<&str> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            CToken::Symbol('(') | CToken::Symbol('[') | CToken::Symbol('{') => depth += 1,
+            CToken::Symbol(')') | CToken::Symbol(']') | CToken::Symbol('}') => depth -= 1,
+            CToken::Identifier(name) => {
+                let is_specifier = IGNORED_SPECIFIERS.contains(&name.as_str())
+                    || ARITHMETIC_KEYWORDS.contains(&name.as_str())
+                    || ATTRIBUTES.contains(&name.as_str())
+                    || ["typedef", "static", "struct", "union", "enum"].contains(&name.as_str());
+                //the name of a function pointer follows `(*`
+                let is_function_pointer = index > 1
+                    && tokens[index - 1] == CToken::Symbol('*')
+                    && tokens[index - 2] == CToken::Symbol('(');
+                let is_declarator = depth == 0
+                    && matches!(
+                        tokens.get(index + 1),
+                        None | Some(CToken::Symbol('(' | '[' | '{' | ';' | ',' | '=' | ':'))
+                    );
+                if !is_specifier && (is_declarator || is_function_pointer) {
+                    return Some(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,236 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use inkwell::context::Context;
+
+use crate::{
+    c_import::import_header, diagnostics::Diagnostician, header, test_utils::tests::parse,
+    DataModel, SourceCode,
+};
+
+/// imports the given header and makes sure the result can be parsed
+fn import(header: &str) -> String {
+    let declarations = import_header(header, DataModel::Lp64);
+    let (_, diagnostics) = parse(&declarations);
+    assert_eq!(diagnostics, vec![], "{}", declarations);
+    declarations
+}
+
+#[test]
+fn functions_are_imported_with_their_parameter_conventions() {
+    let declarations = import(
+        "
+        #include <stdint.h>
+        #define LIMIT 10
+
+        int min(int a, int b);
+        void reset(void);
+        double scale(float value, unsigned long factor, int64_t* total);
+        int printf(const char* format, ...);
+        int32_t largest(int32_t values_count, int32_t* values);
+        ",
+    );
+
+    assert!(
+        declarations.contains(
+            "{external}\nFUNCTION min : DINT\nVAR_INPUT\n    a : DINT;\n    b : DINT;\nEND_VAR\nEND_FUNCTION"
+        ),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains("{external}\nFUNCTION reset\nEND_FUNCTION"),
+        "{}",
+        declarations
+    );
+    //pointers are passed by reference, consecutive parameters share a block
+    assert!(
+        declarations.contains(
+            "FUNCTION scale : LREAL\nVAR_INPUT\n    value : REAL;\n    factor : ULINT;\nEND_VAR\nVAR_INPUT {ref}\n    total : LINT;\nEND_VAR"
+        ),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains(
+            "FUNCTION printf : DINT\nVAR_INPUT {ref}\n    format : STRING;\nEND_VAR\nVAR_INPUT\n    args : ...;\nEND_VAR"
+        ),
+        "{}",
+        declarations
+    );
+    //the count and pointer of sized variadic arguments, as written by `--emit-header`
+    assert!(
+        declarations
+            .contains("FUNCTION largest : DINT\nVAR_INPUT\n    values : {sized} DINT...;\nEND_VAR"),
+        "{}",
+        declarations
+    );
+}
+
+#[test]
+fn long_and_pointer_sized_types_follow_the_data_model() {
+    let header = "
+        #include <stdint.h>
+        long count(unsigned long mask, long long total, size_t length, intptr_t offset);
+        ";
+    let signature = |data_model| {
+        let declarations = import_header(header, data_model);
+        let start = declarations.find("FUNCTION count").unwrap();
+        declarations[start..].to_string()
+    };
+
+    assert!(signature(DataModel::Lp64).starts_with(
+        "FUNCTION count : LINT\nVAR_INPUT\n    mask : ULINT;\n    total : LINT;\n    length : ULINT;\n    offset : LINT;\nEND_VAR"
+    ), "{}", signature(DataModel::Lp64));
+    assert!(signature(DataModel::Llp64).starts_with(
+        "FUNCTION count : DINT\nVAR_INPUT\n    mask : UDINT;\n    total : LINT;\n    length : ULINT;\n    offset : LINT;\nEND_VAR"
+    ), "{}", signature(DataModel::Llp64));
+    assert!(signature(DataModel::Ilp32).starts_with(
+        "FUNCTION count : DINT\nVAR_INPUT\n    mask : UDINT;\n    total : LINT;\n    length : UDINT;\n    offset : DINT;\nEND_VAR"
+    ), "{}", signature(DataModel::Ilp32));
+}
+
+#[test]
+fn structs_enums_and_typedefs_are_imported() {
+    let declarations = import(
+        "
+        typedef struct node {
+            struct node* next;
+            char name[16];
+            unsigned char data[2][4];
+        } Node;
+
+        typedef enum { MODE_A = 1 << 0, MODE_B = 1 << 1, MODE_AB = MODE_A | MODE_B } Mode;
+        enum { IDLE, RUNNING = 5, DONE };
+
+        typedef uint32_t Handle;
+        typedef uint16_t WORD;
+        typedef double Matrix[3][3];
+        typedef struct device_s* device_t;
+
+        extern Node first;
+        WORD read_word(device_t device, Mode mode);
+        ",
+    );
+
+    assert!(
+        declarations.contains(
+            "TYPE Node :\nSTRUCT\n    next : REF_TO Node;\n    name : STRING[15];\n    data : ARRAY[0..1, 0..3] OF USINT;\nEND_STRUCT\nEND_TYPE"
+        ),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations
+            .contains("TYPE Mode : (\n    MODE_A := 1,\n    MODE_B := 2,\n    MODE_AB := 3\n);"),
+        "{}",
+        declarations
+    );
+    //anonymous enums declare constants
+    assert!(
+        declarations.contains(
+            "VAR_GLOBAL CONSTANT\n    IDLE : DINT := 0;\n    RUNNING : DINT := 5;\n    DONE : DINT := 6;\nEND_VAR"
+        ),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains("TYPE Handle : UDINT; END_TYPE"),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains("TYPE Matrix : ARRAY[0..2, 0..2] OF LREAL; END_TYPE"),
+        "{}",
+        declarations
+    );
+    //pointers to undefined structs are untyped
+    assert!(
+        declarations.contains("TYPE device_t : REF_TO BYTE; END_TYPE"),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains("{external}\nVAR_GLOBAL\n    first : Node;\nEND_VAR"),
+        "{}",
+        declarations
+    );
+    //WORD is an ST type, the typedef is not declared
+    assert!(!declarations.contains("TYPE WORD"), "{}", declarations);
+    assert!(
+        declarations.contains(
+            "FUNCTION read_word : UINT\nVAR_INPUT\n    device : device_t;\n    mode : Mode;\nEND_VAR"
+        ),
+        "{}",
+        declarations
+    );
+}
+
+#[test]
+fn unsupported_declarations_are_listed_in_comments() {
+    let declarations = import(
+        "
+        typedef union { int a; float b; } Value;
+        struct Flags { unsigned enabled : 1; };
+        typedef void (*callback_t)(int);
+        int on_change(void (*callback)(int), void* user);
+        static inline int add(int a, int b) { return a + b; }
+        FILE* open_file(const char* path);
+        long double precise(void);
+        int still_imported(void);
+        ",
+    );
+
+    for comment in [
+        "(* Value cannot be imported, unions are not supported *)",
+        "(* Flags cannot be imported, bit fields are not supported *)",
+        "(* callback_t cannot be imported, function pointers are not supported *)",
+        "(* on_change cannot be imported, function pointers are not supported *)",
+        "(* add cannot be imported, it is static *)",
+        "(* open_file cannot be imported, its type FILE is unknown *)",
+        "(* precise cannot be imported, long double is not supported *)",
+    ] {
+        assert!(declarations.contains(comment), "{}", declarations);
+    }
+    assert!(
+        declarations.contains("FUNCTION still_imported : DINT"),
+        "{}",
+        declarations
+    );
+}
+
+#[test]
+fn generated_headers_can_be_imported() {
+    let context = Context::create();
+    let (index, _) = crate::compile_module(
+        &context,
+        vec![SourceCode {
+            path: "lib.st".into(),
+            source: "
+            TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+
+            FUNCTION distance : LREAL
+            VAR_INPUT p : Point; END_VAR
+            VAR_IN_OUT total : DINT; END_VAR
+            END_FUNCTION
+            "
+            .into(),
+        }],
+        vec![],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap();
+    let declarations = import(&header::generate_header(&index, "lib.h").unwrap());
+
+    assert!(
+        declarations.contains("TYPE Point :\nSTRUCT\n    x : INT;\n    y : INT;\nEND_STRUCT"),
+        "{}",
+        declarations
+    );
+    assert!(
+        declarations.contains(
+            "FUNCTION distance : LREAL\nVAR_INPUT\n    p : Point;\nEND_VAR\nVAR_INPUT {ref}\n    total : DINT;\nEND_VAR"
+        ),
+        "{}",
+        declarations
+    );
+}
@@ -0,0 +1,396 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! The cache of incremental builds (`--cache-dir`).
+//!
+//! For every source file the cache stores the hash of its content together with its
+//! pre-processed `CompilationUnit` and its contribution to the `Index`, so an unchanged file
+//! is neither parsed nor indexed again. It also remembers the object files generated for a
+//! file, they are reused until the file or one of the files it depends on changes.
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ast::{AstId, CompilationUnit},
+    diagnostics::Diagnostic,
+    index::Index,
+    lexer::IdProvider,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// the state of every file of the last build
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, FileState>,
+}
+
+/// what the cache knows about a source file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileState {
+    /// the hash of the file's content
+    pub hash: u64,
+    /// the hash of the file's contribution to the index (without ids and locations)
+    pub interface: u64,
+    /// the lowercase names of the symbols declared by the file
+    pub declared: Vec<String>,
+    /// all lowercase strings of the file's unit, a superset of the names it uses
+    pub referenced: Vec<String>,
+    /// the configurations (see `object_key`) an up-to-date object file exists for
+    pub objects: Vec<String>,
+}
+
+/// the cached unit and index contribution of a file
+#[derive(Serialize, Deserialize)]
+struct CachedUnit {
+    unit: Value,
+    index: Value,
+}
+
+pub struct BuildCache {
+    /// the directory of the cache entries for the current compiler configuration
+    location: PathBuf,
+    manifest: Manifest,
+}
+
+impl BuildCache {
+    /// opens the cache in the given directory. Every `fingerprint` (the hash of everything
+    /// besides the sources that influences the cached results) gets its own entries
+    pub fn open(directory: &Path, fingerprint: u64) -> Result<BuildCache, Diagnostic> {
+        let location = directory.join(format!("{:016x}", fingerprint));
+        fs::create_dir_all(location.join("units")).map_err(|err| {
+            Diagnostic::io_write_error(&location.to_string_lossy(), &err.to_string())
+        })?;
+        //a damaged manifest only costs a full rebuild
+        let manifest = fs::read_to_string(location.join("manifest.json"))
+            .ok()
+            .and_then(|it| serde_json::from_str(&it).ok())
+            .unwrap_or_default();
+        Ok(BuildCache { location, manifest })
+    }
+
+    /// returns the state of the given file in the last build
+    pub fn get_state(&self, path: &str) -> Option<&FileState> {
+        self.manifest.files.get(path)
+    }
+
+    /// returns the states of all files of the last build
+    pub fn get_states(&self) -> &BTreeMap<String, FileState> {
+        &self.manifest.files
+    }
+
+    /// loads the cached unit and index contribution of the given file. The ids of the cached
+    /// AST are replaced by new ones of the given `id_provider` and its ranges point into the
+    /// file with the given `file_id`
+    pub fn load(
+        &self,
+        path: &str,
+        file_id: usize,
+        id_provider: &IdProvider,
+    ) -> Option<(CompilationUnit, Index)> {
+        let content = fs::read_to_string(self.get_unit_path(path)).ok()?;
+        let cached: CachedUnit = serde_json::from_str(&content).ok()?;
+        //the unit and its index contribution share the same ids
+        with_renewed_ids(Some(file_id), id_provider, || {
+            Some((
+                serde_json::from_value(cached.unit).ok()?,
+                serde_json::from_value(cached.index).ok()?,
+            ))
+        })
+    }
+
+    /// stores the given unit and index contribution of a file together with its new state
+    pub fn store(
+        &mut self,
+        path: &str,
+        state: FileState,
+        unit: Value,
+        index: Value,
+    ) -> Result<(), Diagnostic> {
+        let unit_path = self.get_unit_path(path);
+        let content = serde_json::to_string(&CachedUnit { unit, index })?;
+        fs::write(&unit_path, content).map_err(|err| {
+            Diagnostic::io_write_error(&unit_path.to_string_lossy(), &err.to_string())
+        })?;
+        self.manifest.files.insert(path.to_string(), state);
+        Ok(())
+    }
+
+    /// forgets the given file, e.g. because it cannot be cached
+    pub fn remove(&mut self, path: &str) {
+        if self.manifest.files.remove(path).is_some() {
+            let _ = fs::remove_file(self.get_unit_path(path));
+        }
+    }
+
+    /// forgets the object files of the given file, they need to be generated again
+    pub fn invalidate_objects(&mut self, path: &str) {
+        if let Some(state) = self.manifest.files.get_mut(path) {
+            state.objects.clear();
+        }
+    }
+
+    /// returns true if an up-to-date object file of the given file exists for the given
+    /// configuration
+    pub fn has_object(&self, path: &str, key: &str) -> bool {
+        self.get_state(path)
+            .map_or(false, |it| it.objects.iter().any(|it| it == key))
+            && self.get_object_path(path, key).exists()
+    }
+
+    /// remembers that an up-to-date object file of the given file exists for the given
+    /// configuration
+    pub fn add_object(&mut self, path: &str, key: &str) {
+        if let Some(state) = self.manifest.files.get_mut(path) {
+            if !state.objects.iter().any(|it| it == key) {
+                state.objects.push(key.to_string());
+            }
+        }
+    }
+
+    /// returns the location of the object file of the given file and configuration
+    pub fn get_object_path(&self, path: &str, key: &str) -> PathBuf {
+        self.location
+            .join("objects")
+            .join(key)
+            .join(format!("{:016x}.o", hash(&[path])))
+    }
+
+    /// writes the state of all files, so the next build can use it
+    pub fn save(&self) -> Result<(), Diagnostic> {
+        let manifest_path = self.location.join("manifest.json");
+        let content = serde_json::to_string(&self.manifest)?;
+        fs::write(&manifest_path, content).map_err(|err| {
+            Diagnostic::io_write_error(&manifest_path.to_string_lossy(), &err.to_string())
+        })
+    }
+
+    fn get_unit_path(&self, path: &str) -> PathBuf {
+        self.location
+            .join("units")
+            .join(format!("{:016x}.json", hash(&[path])))
+    }
+}
+
+/// hashes the given parts with FNV-1a, the hash is stable across runs and platforms
+pub fn hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        //the separator keeps ["ab", "c"] and ["a", "bc"] apart
+        for byte in part.bytes().chain(std::iter::once(0xff)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// returns the key of the object files generated with the given configuration
+pub fn object_key(parts: &[&str]) -> String {
+    format!("{:016x}", hash(parts))
+}
+
+/// hashes the given index contribution without its ids and locations, so only a change of
+/// the declarations themselves changes the hash
+pub fn interface_hash(index: &Value) -> u64 {
+    fn strip(value: &Value) -> Value {
+        match value {
+            Value::Object(map) if is_source_range(map) => Value::Null,
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(key, _)| key.as_str() != "id")
+                    .map(|(key, value)| (key.clone(), strip(value)))
+                    .collect(),
+            ),
+            Value::Array(values) => Value::Array(values.iter().map(strip).collect()),
+            _ => value.clone(),
+        }
+    }
+    hash(&[&strip(index).to_string()])
+}
+
+/// returns the lowercase names of all symbols declared by the given index contribution
+pub fn declared_names(index: &Index) -> Vec<String> {
+    let mut names = index
+        .get_globals()
+        .keys()
+        .chain(index.get_pous().keys())
+        .chain(index.get_implementations().keys())
+        .chain(index.get_types().keys())
+        .chain(index.get_pou_types().keys())
+        .chain(index.get_global_initializers().keys())
+        .map(|it| it.to_lowercase())
+        .collect::<Vec<_>>();
+    //enum elements may be used without their type's name
+    for name in index.get_global_qualified_enums().keys() {
+        names.push(name.to_lowercase());
+        names.extend(name.rsplit('.').next().map(str::to_lowercase));
+    }
+    //a symbol declared in a NAMESPACE may be used without (a part of) its namespace
+    let suffixes = names
+        .iter()
+        .flat_map(|name| {
+            name.match_indices('.')
+                .map(move |(i, _)| name[i + 1..].to_string())
+        })
+        .collect::<Vec<_>>();
+    names.extend(suffixes);
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// returns all lowercase strings of the given unit. They contain the names of every symbol
+/// the unit uses (and some more)
+pub fn referenced_names(unit: &Value) -> Vec<String> {
+    fn collect(value: &Value, names: &mut HashSet<String>) {
+        match value {
+            Value::String(it) => {
+                names.insert(it.to_lowercase());
+            }
+            Value::Array(values) => values.iter().for_each(|it| collect(it, names)),
+            Value::Object(map) => map.values().for_each(|it| collect(it, names)),
+            _ => {}
+        }
+    }
+    let mut names = HashSet::new();
+    collect(unit, &mut names);
+    let mut names = names.into_iter().collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// returns the files that need to be annotated and generated again: the changed files and
+/// every file that uses a symbol of a file whose declarations changed, or of one of those
+/// files in turn.
+///
+/// - `previous` the states of the last build
+/// - `current` the states of the current build
+/// - `changed` the files whose content changed since the last build (including removed files)
+pub fn find_dirty_files(
+    previous: &BTreeMap<String, FileState>,
+    current: &BTreeMap<String, FileState>,
+    changed: &HashSet<String>,
+) -> HashSet<String> {
+    let mut affected = HashSet::new();
+    for file in changed {
+        let before = previous.get(file);
+        let after = current.get(file);
+        if before.map(|it| it.interface) != after.map(|it| it.interface) {
+            affected.extend(before.iter().flat_map(|it| it.declared.iter().cloned()));
+            affected.extend(after.iter().flat_map(|it| it.declared.iter().cloned()));
+        }
+    }
+
+    let mut dirty = changed
+        .iter()
+        .filter(|it| current.contains_key(*it))
+        .cloned()
+        .collect::<HashSet<_>>();
+    loop {
+        let dependents = current
+            .iter()
+            .filter(|(file, _)| !dirty.contains(*file))
+            .filter(|(_, state)| state.referenced.iter().any(|it| affected.contains(it)))
+            .map(|(file, _)| file.clone())
+            .collect::<Vec<_>>();
+        if dependents.is_empty() {
+            return dirty;
+        }
+        //a dependent's own declarations may change with the declarations it uses
+        //(e.g. the size of a struct), so its dependents are affected as well
+        for file in dependents {
+            affected.extend(current[&file].declared.iter().cloned());
+            dirty.insert(file);
+        }
+    }
+}
+
+/// the new ids and file id given to the AST deserialized by `with_renewed_ids`
+struct Renewal {
+    file_id: Option<usize>,
+    /// the new id of every old id, so references between a unit and its index contribution
+    /// stay intact
+    ids: HashMap<AstId, AstId>,
+    id_provider: IdProvider,
+}
+
+thread_local! {
+    static RENEWAL: RefCell<Option<Renewal>> = RefCell::new(None);
+}
+
+/// runs the given deserialization of a cached AST or index. Its ids are replaced by new ones of
+/// the given `id_provider`, so they do not collide with the ids of the current compilation, and
+/// its ranges point into the given file (if any). The same old id always gets the same new id
+pub fn with_renewed_ids<T>(
+    file_id: Option<usize>,
+    id_provider: &IdProvider,
+    deserialize: impl FnOnce() -> T,
+) -> T {
+    let renewal = Renewal {
+        file_id,
+        ids: HashMap::new(),
+        id_provider: id_provider.clone(),
+    };
+    let previous = RENEWAL.with(|it| it.replace(Some(renewal)));
+    let result = deserialize();
+    RENEWAL.with(|it| *it.borrow_mut() = previous);
+    result
+}
+
+/// returns the new id of the given id, the id is kept outside of `with_renewed_ids`
+fn renew(id: AstId) -> AstId {
+    RENEWAL.with(|it| match it.borrow_mut().as_mut() {
+        Some(renewal) => {
+            let id_provider = &mut renewal.id_provider;
+            *renewal
+                .ids
+                .entry(id)
+                .or_insert_with(|| id_provider.next_id())
+        }
+        None => id,
+    })
+}
+
+/// deserializes an `AstId`, see `with_renewed_ids`
+pub fn renew_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AstId, D::Error> {
+    AstId::deserialize(deserializer).map(renew)
+}
+
+/// deserializes a map keyed by `AstId`s (e.g. the pragmas of a unit's statements), see
+/// `with_renewed_ids`
+pub fn renew_id_keys<'de, D, V>(deserializer: D) -> Result<HashMap<AstId, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let map = HashMap::<AstId, V>::deserialize(deserializer)?;
+    Ok(map.into_iter().map(|(id, it)| (renew(id), it)).collect())
+}
+
+/// deserializes the file id of a `SourceRange`, see `with_renewed_ids`. Ranges that do not point
+/// into a file keep pointing nowhere
+pub fn renew_file_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<usize>, D::Error> {
+    let file_id = Option::<usize>::deserialize(deserializer)?;
+    Ok(file_id.map(|file_id| {
+        RENEWAL.with(|it| {
+            it.borrow()
+                .as_ref()
+                .and_then(|it| it.file_id)
+                .unwrap_or(file_id)
+        })
+    }))
+}
+
+/// returns true if the given object is a serialized `SourceRange`
+fn is_source_range(map: &serde_json::Map<String, Value>) -> bool {
+    map.len() == 2 && map.contains_key("range") && map.contains_key("file_id")
+}
@@ -0,0 +1,166 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    ast::{self, LinkageType},
+    cache::{self, BuildCache, FileState},
+    index, lexer,
+    lexer::IdProvider,
+    parser,
+};
+
+fn state(interface: u64, declared: &[&str], referenced: &[&str]) -> FileState {
+    FileState {
+        hash: 0,
+        interface,
+        declared: declared.iter().map(|it| it.to_string()).collect(),
+        referenced: referenced.iter().map(|it| it.to_string()).collect(),
+        objects: vec![],
+    }
+}
+
+fn states(states: Vec<(&str, FileState)>) -> BTreeMap<String, FileState> {
+    states
+        .into_iter()
+        .map(|(path, state)| (path.to_string(), state))
+        .collect()
+}
+
+#[test]
+fn hashes_are_stable_and_separate_their_parts() {
+    assert_eq!(cache::hash(&["ab", "c"]), cache::hash(&["ab", "c"]));
+    assert_ne!(cache::hash(&["ab", "c"]), cache::hash(&["a", "bc"]));
+    assert_ne!(cache::hash(&["abc"]), cache::hash(&["ab", "c"]));
+}
+
+#[test]
+fn loaded_units_get_new_ids_and_point_into_the_current_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let id_provider = IdProvider::default();
+    let (mut unit, _) = parser::parse(
+        lexer::lex_file(
+            "FUNCTION foo : INT {warning 'x'} foo := 1; END_FUNCTION",
+            3,
+            id_provider.clone(),
+            &[],
+        ),
+        LinkageType::Internal,
+    );
+    ast::pre_process(&mut unit, id_provider.clone());
+    let index = index::visitor::visit(&unit, id_provider);
+    let statement_id = unit.implementations[0].statements[0].get_id();
+    assert!(unit.pragmas.contains_key(&statement_id));
+
+    let mut cache = BuildCache::open(dir.path(), 0).unwrap();
+    cache
+        .store(
+            "foo.st",
+            state(0, &["foo"], &[]),
+            serde_json::to_value(&unit).unwrap(),
+            serde_json::to_value(&index).unwrap(),
+        )
+        .unwrap();
+    cache.save().unwrap();
+
+    //a new build continues with fresh ids
+    let id_provider = IdProvider::default();
+    let first_free_id = id_provider.clone().next_id();
+    let cache = BuildCache::open(dir.path(), 0).unwrap();
+    assert_eq!(cache.get_state("foo.st"), Some(&state(0, &["foo"], &[])));
+    let (unit, index) = cache.load("foo.st", 7, &id_provider).unwrap();
+
+    let loaded_id = unit.implementations[0].statements[0].get_id();
+    assert_ne!(loaded_id, statement_id);
+    assert!(loaded_id > first_free_id);
+    //the pragmas follow their statement
+    assert_eq!(unit.pragmas.keys().collect::<Vec<_>>(), vec![&loaded_id]);
+    assert_eq!(unit.units[0].location.get_file_id(), Some(7));
+    assert_eq!(
+        index.find_pou("foo").unwrap().get_location().get_file_id(),
+        Some(7)
+    );
+
+    //outside of a renewal the ids are kept
+    let value = serde_json::to_value(&unit).unwrap();
+    let kept: ast::CompilationUnit = serde_json::from_value(value).unwrap();
+    assert_eq!(kept.implementations[0].statements[0].get_id(), loaded_id);
+
+    //another configuration has its own entries
+    assert!(BuildCache::open(dir.path(), 1)
+        .unwrap()
+        .get_state("foo.st")
+        .is_none());
+}
+
+#[test]
+fn interface_hash_ignores_ids_and_locations() {
+    let index = |src: &str| {
+        let (_, index) = crate::test_utils::tests::index(src);
+        serde_json::to_value(&index).unwrap()
+    };
+    let before = index("FUNCTION foo : INT foo := 1; END_FUNCTION");
+    let moved = index("\n\nFUNCTION foo : INT\n x := 2; END_FUNCTION");
+    let changed = index("FUNCTION foo : DINT foo := 1; END_FUNCTION");
+
+    assert_eq!(
+        cache::interface_hash(&before),
+        cache::interface_hash(&moved)
+    );
+    assert_ne!(
+        cache::interface_hash(&before),
+        cache::interface_hash(&changed)
+    );
+}
+
+#[test]
+fn namespaced_symbols_are_declared_with_their_shorter_names() {
+    let (_, index) = crate::test_utils::tests::index(
+        "NAMESPACE Vendor.Motion FUNCTION Move : INT END_FUNCTION END_NAMESPACE",
+    );
+    let declared = cache::declared_names(&index);
+    for name in ["vendor.motion.move", "motion.move", "move"] {
+        assert!(
+            declared.contains(&name.to_string()),
+            "{} is not declared",
+            name
+        );
+    }
+}
+
+#[test]
+fn files_using_changed_declarations_are_dirty() {
+    let previous = states(vec![
+        ("a.st", state(1, &["a"], &[])),
+        ("b.st", state(2, &["b"], &["a"])),
+        ("c.st", state(3, &["c"], &["b"])),
+        ("d.st", state(4, &["d"], &[])),
+    ]);
+
+    //a body change only affects the changed file
+    let current = states(vec![
+        ("a.st", state(1, &["a"], &[])),
+        ("b.st", state(2, &["b"], &["a"])),
+        ("c.st", state(3, &["c"], &["b"])),
+        ("d.st", state(4, &["d"], &[])),
+    ]);
+    let changed = HashSet::from(["a.st".to_string()]);
+    assert_eq!(
+        cache::find_dirty_files(&previous, &current, &changed),
+        HashSet::from(["a.st".to_string()])
+    );
+
+    //a changed interface affects its users and their users
+    let mut current = current;
+    current.insert("a.st".to_string(), state(5, &["a"], &[]));
+    assert_eq!(
+        cache::find_dirty_files(&previous, &current, &changed),
+        HashSet::from(["a.st".to_string(), "b.st".to_string(), "c.st".to_string()])
+    );
+
+    //the users of a removed file are dirty, the removed file itself is gone
+    current.remove("a.st");
+    assert_eq!(
+        cache::find_dirty_files(&previous, &current, &changed),
+        HashSet::from(["b.st".to_string(), "c.st".to_string()])
+    );
+}
@@ -3,7 +3,10 @@ use clap::{ArgGroup, CommandFactory, ErrorKind, Parser, Subcommand};
 use encoding_rs::Encoding;
 use std::{ffi::OsStr, path::Path};
 
-use crate::{ConfigFormat, ErrorFormat, FormatOption};
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticPolicy},
+    ConfigFormat, DebugLevel, ErrorFormat, FormatOption,
+};
 
 pub type ParameterError = clap::Error;
 
@@ -96,12 +99,20 @@ pub struct CompileParameters {
     #[clap(
         name = "input-files",
         help = "Read input from <input-files>, may be a glob expression like 'src/**/*' or a sequence of files",
-        required = true,
+        required_unless_present = "explain",
         min_values = 1
     )]
     // having a vec allows bash to resolve *.st itself
     pub input: Vec<String>,
 
+    #[clap(
+        long,
+        name = "explain",
+        value_name = "code",
+        help = "Print the explanation of the given diagnostic code (e.g. E031)"
+    )]
+    pub explain: Option<String>,
+
     #[clap(
         name = "library-path",
         long,
@@ -129,6 +140,23 @@ pub struct CompileParameters {
     )]
     pub includes: Vec<String>,
 
+    #[clap(
+        name = "define",
+        long,
+        short = 'D',
+        global = true,
+        help = "Define a name for the conditional compilation ({IF defined(<name>)})"
+    )]
+    pub defines: Vec<String>,
+
+    #[clap(
+        short = 'g',
+        long = "debug",
+        global = true,
+        help = "Generate source-level debug information (DWARF)"
+    )]
+    pub generate_debug: bool,
+
     #[clap(
         name = "hardware-conf",
         long,
@@ -140,26 +168,88 @@ pub struct CompileParameters {
     ) ]
     pub hardware_config: Option<String>,
 
+    #[clap(
+        name = "cache-dir",
+        long,
+        global = true,
+        help = "Cache the parsed files and their objects in the given directory, only changed files and their dependents are compiled again"
+    )]
+    pub cache_dir: Option<String>,
+
+    #[clap(
+        name = "emit-interface",
+        long,
+        global = true,
+        help = "Write the interface of the compiled library to the given file (*.stif). Consumers include it with -i instead of the library's sources"
+    )]
+    pub emit_interface: Option<String>,
+
+    #[clap(
+        name = "emit-header",
+        long,
+        global = true,
+        help = "Write a C header declaring the compiled POUs, their types and the global variables to the given file"
+    )]
+    pub emit_header: Option<String>,
+
+    #[clap(
+        name = "retain-map",
+        long,
+        global = true,
+        help = "Write the offset, size and type of every RETAIN and PERSISTENT variable in the retain image to the given json file"
+    )]
+    pub retain_map: Option<String>,
+
+    #[clap(
+        long = "separate-objects",
+        global = true,
+        help = "Generate a separate module for every input file. Unless the output is linked, every module is written into the output's directory and named after its file"
+    )]
+    pub separate_objects: bool,
+
+    #[clap(
+        long = "bounds-check",
+        global = true,
+        help = "Check the indices of array accesses against the array's bounds at runtime. An index out of bounds calls CheckBounds if it is defined, otherwise it traps"
+    )]
+    pub bounds_check: bool,
+
+    #[clap(
+        name = "jobs",
+        long,
+        short = 'j',
+        help = "The number of threads used to parse, annotate and generate the files, 0 uses all available cores",
+        default_value = "1",
+        global = true
+    )]
+    pub jobs: usize,
+
     #[clap(
         name = "optimization",
         long,
         short = 'O',
-        help = "Optimization level",
+        help = "Optimization level [default: default]",
         arg_enum,
-        default_value = "default",
         global = true
     )]
-    pub optimization: crate::OptimizationLevel,
+    pub optimization: Option<crate::OptimizationLevel>,
 
     #[clap(
         name = "error-format",
         long,
-        help = "Set format for error reporting",
+        help = "Set format for error reporting [default: rich]",
         arg_enum,
-        default_value = "rich",
         global = true
     )]
-    pub error_format: ErrorFormat,
+    pub error_format: Option<ErrorFormat>,
+
+    #[clap(
+        name = "warning",
+        short = 'W',
+        global = true,
+        help = "Configure the severity of diagnostics: -Werror reports all warnings as errors, -W<code> reports <code> as a warning, -Wno-<code> ignores it, -Werror=<code> and -Winfo=<code> report it as an error or an info"
+    )]
+    pub warnings: Vec<String>,
 
     #[clap(
         name = "linker",
@@ -180,9 +270,9 @@ pub enum SubCommands {
     /// build
     ///
     /// Options:
-    /// --build-location <path> --lib-location <path>
+    /// --build-location <path> --lib-location <path> --profile <name>
     ///
-    /// Supported format: json
+    /// Supported format: json, toml
     ///
     Build {
         #[clap(
@@ -195,6 +285,29 @@ pub enum SubCommands {
 
         #[clap(name = "lib-location", long)]
         lib_location: Option<String>,
+
+        #[clap(
+            long,
+            help = "Build with the options of the given profile from the build description"
+        )]
+        profile: Option<String>,
+    },
+    /// Imports the declarations of a C header as external ST declarations.
+    ///
+    /// import-c <header> [-o <output-file>]
+    ///
+    /// Writes an ST file with the header's functions, structs, enums and globals
+    /// that can be included (-i) to call into the C code
+    ///
+    ImportC {
+        header: String,
+
+        #[clap(
+            short,
+            long,
+            help = "Write the ST declarations to <output>, defaults to the header's name with the extension .st"
+        )]
+        output: Option<String>,
     },
 }
 
@@ -284,15 +397,38 @@ impl CompileParameters {
         )
     }
 
+    /// returns the debug information to generate
+    pub fn debug_level(&self) -> DebugLevel {
+        if self.generate_debug {
+            DebugLevel::Full
+        } else {
+            DebugLevel::None
+        }
+    }
+
     pub fn config_format(&self) -> Option<ConfigFormat> {
         self.hardware_config.as_deref().and_then(get_config_format)
     }
+
+    /// applies the `-W` options to the given policy, they replace the configured levels
+    pub fn apply_warning_options(
+        &self,
+        mut policy: DiagnosticPolicy,
+    ) -> Result<DiagnosticPolicy, Diagnostic> {
+        for option in &self.warnings {
+            policy.apply_option(option)?;
+        }
+        Ok(policy)
+    }
 }
 
 #[cfg(test)]
 mod cli_tests {
     use super::{CompileParameters, SubCommands};
-    use crate::{ConfigFormat, ErrorFormat, FormatOption, OptimizationLevel};
+    use crate::{
+        diagnostics::{DiagnosticLevel, DiagnosticPolicy, ErrNo},
+        ConfigFormat, DebugLevel, ErrorFormat, FormatOption, OptimizationLevel,
+    };
     use clap::{CommandFactory, ErrorKind};
     use pretty_assertions::assert_eq;
     use std::ffi::OsStr;
@@ -429,38 +565,38 @@ mod cli_tests {
     fn test_optimization_levels() {
         let parameters = CompileParameters::parse(vec_of_strings!("alpha.st")).unwrap();
 
-        assert_eq!(parameters.optimization, OptimizationLevel::Default);
+        assert_eq!(parameters.optimization, None);
         let parameters = CompileParameters::parse(vec_of_strings!("alpha.st", "-Onone")).unwrap();
 
-        assert_eq!(parameters.optimization, OptimizationLevel::None);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::None));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "--optimization", "none"))
                 .unwrap();
-        assert_eq!(parameters.optimization, OptimizationLevel::None);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::None));
 
         let parameters = CompileParameters::parse(vec_of_strings!("alpha.st", "-Oless")).unwrap();
 
-        assert_eq!(parameters.optimization, OptimizationLevel::Less);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Less));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "--optimization", "less"))
                 .unwrap();
-        assert_eq!(parameters.optimization, OptimizationLevel::Less);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Less));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "-Odefault")).unwrap();
 
-        assert_eq!(parameters.optimization, OptimizationLevel::Default);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Default));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "--optimization", "default"))
                 .unwrap();
-        assert_eq!(parameters.optimization, OptimizationLevel::Default);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Default));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "-Oaggressive")).unwrap();
 
-        assert_eq!(parameters.optimization, OptimizationLevel::Aggressive);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Aggressive));
         let parameters =
             CompileParameters::parse(vec_of_strings!("alpha.st", "--optimization", "aggressive"))
                 .unwrap();
-        assert_eq!(parameters.optimization, OptimizationLevel::Aggressive);
+        assert_eq!(parameters.optimization, Some(OptimizationLevel::Aggressive));
     }
 
     #[test]
@@ -654,6 +790,7 @@ mod cli_tests {
                     assert_eq!(build_location, Some("bin/build".to_string()));
                     assert_eq!(lib_location, Some("bin/build/libs".to_string()));
                 }
+                _ => panic!("expected the build subcommand"),
             };
             assert_eq!(
                 parameters.sysroot,
@@ -698,6 +835,32 @@ mod cli_tests {
         );
     }
 
+    #[test]
+    fn debug_information_is_generated_with_g() {
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
+        assert_eq!(parameters.debug_level(), DebugLevel::None);
+
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st", "-g")).unwrap();
+        assert_eq!(parameters.debug_level(), DebugLevel::Full);
+
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st", "--debug")).unwrap();
+        assert_eq!(parameters.debug_level(), DebugLevel::Full);
+    }
+
+    #[test]
+    fn defines_added() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "-D",
+            "PLC_A",
+            "-DDEBUG",
+            "--define",
+            "SIMULATION"
+        ))
+        .unwrap();
+        assert_eq!(parameters.defines, vec!["PLC_A", "DEBUG", "SIMULATION"]);
+    }
+
     #[test]
     fn config_option_set() {
         let parameters =
@@ -725,9 +888,9 @@ mod cli_tests {
 
     #[test]
     fn error_format_default_set() {
-        // make sure the default error format is set
+        // no error format is set, the project's or the default one is used
         let params = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
-        assert_eq!(params.error_format, ErrorFormat::Rich);
+        assert_eq!(params.error_format, None);
     }
 
     #[test]
@@ -735,7 +898,14 @@ mod cli_tests {
         // set clang as error format
         let params =
             CompileParameters::parse(vec_of_strings!("input.st", "--error-format=clang")).unwrap();
-        assert_eq!(params.error_format, ErrorFormat::Clang);
+        assert_eq!(params.error_format, Some(ErrorFormat::Clang));
+        // machine-readable formats
+        let params =
+            CompileParameters::parse(vec_of_strings!("input.st", "--error-format=json")).unwrap();
+        assert_eq!(params.error_format, Some(ErrorFormat::Json));
+        let params =
+            CompileParameters::parse(vec_of_strings!("input.st", "--error-format=sarif")).unwrap();
+        assert_eq!(params.error_format, Some(ErrorFormat::Sarif));
         // set invalid error format
         expect_argument_error(
             vec_of_strings!("input.st", "--error-format=none"),
@@ -769,4 +939,124 @@ mod cli_tests {
                 .to_string()
         )
     }
+
+    #[test]
+    fn build_subcommand_with_profile() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "build",
+            "plc.toml",
+            "--profile",
+            "release",
+            "--target",
+            "arm"
+        ))
+        .unwrap();
+
+        match parameters.commands {
+            Some(SubCommands::Build {
+                build_config,
+                profile,
+                ..
+            }) => {
+                assert_eq!(build_config, Some("plc.toml".to_string()));
+                assert_eq!(profile, Some("release".to_string()));
+            }
+            _ => panic!("expected the build subcommand"),
+        }
+        assert_eq!(parameters.target, vec!["arm".to_string()]);
+    }
+
+    #[test]
+    fn emit_header_is_parsed() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "--emit-header", "out.h"))
+                .unwrap();
+        assert_eq!(parameters.emit_header, Some("out.h".to_string()));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
+        assert_eq!(parameters.emit_header, None);
+    }
+
+    #[test]
+    fn bounds_check_is_parsed() {
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
+        assert!(!parameters.bounds_check);
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "--bounds-check")).unwrap();
+        assert!(parameters.bounds_check);
+    }
+
+    #[test]
+    fn retain_map_is_parsed() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "--retain-map", "retain.json"))
+                .unwrap();
+        assert_eq!(parameters.retain_map, Some("retain.json".to_string()));
+    }
+
+    #[test]
+    fn warning_options_configure_the_diagnostic_policy() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "-Werror",
+            "-Wno-pragma__unattached",
+            "-Werror=syntax__unusual_parentheses",
+            "-Winfo=E049"
+        ))
+        .unwrap();
+        let policy = parameters
+            .apply_warning_options(DiagnosticPolicy::default())
+            .unwrap();
+
+        assert!(policy.warnings_as_errors);
+        assert_eq!(
+            policy.levels.get(&ErrNo::pragma__unattached),
+            Some(&DiagnosticLevel::Ignore)
+        );
+        assert_eq!(
+            policy.levels.get(&ErrNo::syntax__unusual_parentheses),
+            Some(&DiagnosticLevel::Error)
+        );
+        assert_eq!(
+            policy.levels.get(&ErrNo::pragma__unknown),
+            Some(&DiagnosticLevel::Info)
+        );
+    }
+
+    #[test]
+    fn unknown_warning_codes_are_reported() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "-Wno-unknown_code")).unwrap();
+        let err = parameters
+            .apply_warning_options(DiagnosticPolicy::default())
+            .unwrap_err();
+        assert_eq!(err.get_message(), "Unknown diagnostic code 'unknown_code'");
+    }
+
+    #[test]
+    fn explain_does_not_require_input_files() {
+        let parameters = CompileParameters::parse(vec_of_strings!("--explain", "E031")).unwrap();
+        assert_eq!(parameters.explain, Some("E031".to_string()));
+        assert!(parameters.input.is_empty());
+    }
+
+    #[test]
+    fn import_c_subcommand() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("import-c", "vendor/io.h", "-o", "io.st"))
+                .unwrap();
+        match parameters.commands {
+            Some(SubCommands::ImportC { header, output }) => {
+                assert_eq!(header, "vendor/io.h".to_string());
+                assert_eq!(output, Some("io.st".to_string()));
+            }
+            _ => panic!("expected the import-c subcommand"),
+        }
+
+        let parameters = CompileParameters::parse(vec_of_strings!("import-c", "io.h")).unwrap();
+        match parameters.commands {
+            Some(SubCommands::ImportC { output, .. }) => assert_eq!(output, None),
+            _ => panic!("expected the import-c subcommand"),
+        }
+    }
 }
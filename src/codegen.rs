@@ -2,30 +2,109 @@
 
 /// module to generate llvm intermediate representation for a CompilationUnit
 use self::{
+    debug::DebugBuilder,
     generators::{
         data_type_generator,
         llvm::{GlobalValueExt, Llvm},
         pou_generator::{self, PouGenerator},
-        variable_generator,
+        retain_generator, variable_generator,
     },
     llvm_index::LlvmTypedIndex,
 };
 use crate::{
     diagnostics::Diagnostic,
     resolver::{AstAnnotations, StringLiterals},
+    DebugLevel,
 };
 
 use super::ast::*;
 use super::index::*;
 use inkwell::module::Module;
+use inkwell::values::GlobalValue;
 use inkwell::{context::Context, types::BasicType};
 
+pub(crate) mod debug;
 pub(crate) mod generators;
 mod llvm_index;
-mod llvm_typesystem;
+pub(crate) mod llvm_typesystem;
 #[cfg(test)]
 mod tests;
 
+/// the global symbols a generated module defines, the others are only declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinedSymbols {
+    /// the symbols of all files
+    All,
+    /// the symbols of the file with the given id and the compiler-generated symbols it uses,
+    /// the module is linked with the modules of the other files
+    File(usize),
+    /// no symbols, the module only uses the symbols of the other modules
+    None,
+}
+
+impl DefinedSymbols {
+    /// returns true if the symbols declared in the given file are defined, compiler-generated
+    /// symbols belong to no file
+    pub fn defines(&self, file_id: Option<usize>) -> bool {
+        match (self, file_id) {
+            (DefinedSymbols::All, _) | (DefinedSymbols::File(_), None) => true,
+            (DefinedSymbols::File(id), Some(file_id)) => *id == file_id,
+            (DefinedSymbols::None, _) => false,
+        }
+    }
+
+    /// returns true if the module is linked with other modules, every module gets its own
+    /// copy of a helper-symbol
+    pub fn is_partial(&self) -> bool {
+        *self != DefinedSymbols::All
+    }
+}
+
+/// the options a module is generated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeGenOptions {
+    /// the debug information generated into the module
+    pub debug_level: DebugLevel,
+    /// the global symbols defined in the module, the others are only declared
+    pub symbols: DefinedSymbols,
+    /// check the indices of array accesses against the array's bounds at runtime
+    pub bounds_check: bool,
+    /// generate the entry points to save & restore the retained variables (see `retain`), a
+    /// library leaves them to the project linking it
+    pub retain_functions: bool,
+}
+
+impl Default for CodeGenOptions {
+    fn default() -> Self {
+        CodeGenOptions {
+            debug_level: DebugLevel::None,
+            symbols: DefinedSymbols::All,
+            bounds_check: false,
+            retain_functions: true,
+        }
+    }
+}
+
+impl CodeGenOptions {
+    /// the options for the module of a single file, it only defines the global variables of
+    /// the given file and keeps its helper-symbols (e.g. literals) private
+    pub fn for_file(self, file_id: usize) -> Self {
+        CodeGenOptions {
+            symbols: DefinedSymbols::File(file_id),
+            ..self
+        }
+    }
+
+    /// the options for a module that only declares the global symbols, e.g. for the entry
+    /// points to save & restore the retained variables of all files
+    pub fn for_declarations() -> Self {
+        CodeGenOptions {
+            symbols: DefinedSymbols::None,
+            ..CodeGenOptions::default()
+        }
+    }
+}
+
 /// the codegen struct carries all dependencies required to generate
 /// the IR code for a compilation unit
 pub struct CodeGen<'ink> {
@@ -33,13 +112,26 @@ pub struct CodeGen<'ink> {
     pub context: &'ink Context,
     /// the module represents a llvm compilation unit
     pub module: Module<'ink>,
+    /// the options the module is generated with
+    options: CodeGenOptions,
 }
 
 impl<'ink> CodeGen<'ink> {
     /// constructs a new code-generator that generates CompilationUnits into a module with the given module_name
-    pub fn new(context: &'ink Context, module_name: &str) -> CodeGen<'ink> {
+    pub fn new(
+        context: &'ink Context,
+        module_name: &str,
+        options: CodeGenOptions,
+    ) -> CodeGen<'ink> {
         let module = context.create_module(module_name);
-        CodeGen { context, module }
+        if options.debug_level != DebugLevel::None {
+            debug::add_debug_module_flags(context, &module);
+        }
+        CodeGen {
+            context,
+            module,
+            options,
+        }
     }
 
     pub fn generate_llvm_index(
@@ -62,6 +154,7 @@ impl<'ink> CodeGen<'ink> {
             global_index,
             annotations,
             &index,
+            self.options.symbols,
         )?;
         index.merge(llvm_gv_index);
 
@@ -76,12 +169,22 @@ impl<'ink> CodeGen<'ink> {
         )?;
         let llvm = Llvm::new(self.context, self.context.create_builder());
         index.merge(llvm_impl_index);
+        //Generate the method tables for pous implementing interfaces
+        let llvm_vtable_index = pou_generator::generate_interface_tables(
+            &self.module,
+            &llvm,
+            global_index,
+            &index,
+            self.options.symbols.is_partial(),
+        )?;
+        index.merge(llvm_vtable_index);
         let llvm_values_index = pou_generator::generate_global_constants_for_pou_members(
             &self.module,
             &llvm,
             global_index,
             annotations,
             &index,
+            self.options.symbols.is_partial(),
         )?;
         index.merge(llvm_values_index);
 
@@ -98,8 +201,7 @@ impl<'ink> CodeGen<'ink> {
                 data_type.as_basic_type_enum(),
             );
             let initializer = llvm.create_const_utf8_string(literal.as_str(), len)?;
-            literal_variable
-                .make_constant()
+            self.make_module_local(literal_variable.make_constant())
                 .set_initializer(&initializer);
 
             index.associate_utf08_literal(literal, literal_variable);
@@ -117,16 +219,36 @@ impl<'ink> CodeGen<'ink> {
             );
             let initializer =
                 llvm.create_const_utf16_string(literal.as_str(), literal.len() + 1)?;
-            literal_variable
-                .make_constant()
+            self.make_module_local(literal_variable.make_constant())
                 .set_initializer(&initializer);
 
             index.associate_utf16_literal(literal, literal_variable);
         }
 
+        //the module of a single file does not know the variables retained by other files
+        if self.options.symbols == DefinedSymbols::All && self.options.retain_functions {
+            self.generate_retain_functions(global_index)?;
+        }
+
         Ok(index)
     }
 
+    /// generates the entry points to save & restore the retained variables of the given index
+    /// (see `retain`), their symbols need to be declared in the module
+    pub fn generate_retain_functions(&self, global_index: &Index) -> Result<(), Diagnostic> {
+        let llvm = Llvm::new(self.context, self.context.create_builder());
+        retain_generator::generate_retain_functions(&self.module, &llvm, global_index)
+    }
+
+    /// every module of a single file gets its own copy of a helper-symbol
+    fn make_module_local(&self, global: GlobalValue<'ink>) -> GlobalValue<'ink> {
+        if self.options.symbols.is_partial() {
+            global.make_private()
+        } else {
+            global
+        }
+    }
+
     /// generates all TYPEs, GLOBAL-sections and POUs of the given CompilationUnit
     pub fn generate(
         &self,
@@ -135,20 +257,37 @@ impl<'ink> CodeGen<'ink> {
         global_index: &Index,
         llvm_index: &LlvmTypedIndex,
     ) -> Result<(), Diagnostic> {
+        //every file gets its own debug compile unit
+        let debug = if self.options.debug_level == DebugLevel::Full {
+            Some(DebugBuilder::new(self.context, &self.module, unit))
+        } else {
+            None
+        };
+
         //generate all pous
         let llvm = Llvm::new(self.context, self.context.create_builder());
-        let pou_generator = PouGenerator::new(llvm, global_index, annotations, llvm_index);
+        let pou_generator = PouGenerator::new(
+            llvm,
+            global_index,
+            annotations,
+            llvm_index,
+            debug.as_ref(),
+            self.options,
+        );
 
         //Generate the POU stubs in the first go to make sure they can be referenced.
         for implementation in &unit.implementations {
             //Don't generate external or generic functions
             if let Some(entry) = global_index.find_pou(implementation.name.as_str()) {
                 if !entry.is_generic() && entry.get_linkage() != &LinkageType::External {
-                    pou_generator.generate_implementation(implementation)?;
+                    pou_generator.generate_implementation(implementation, &self.module)?;
                 }
             }
         }
 
+        if let Some(debug) = &debug {
+            debug.finalize();
+        }
         Ok(())
     }
 }
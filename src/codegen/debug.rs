@@ -0,0 +1,490 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DIType, DWARFEmissionKind,
+        DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::{FlagBehavior, Module},
+    values::{FunctionValue, PointerValue},
+    AddressSpace,
+};
+
+use crate::{
+    ast::{CompilationUnit, Implementation, NewLines, PouType, SourceRange},
+    index::{Index, VariableIndexEntry},
+    typesystem::{DataType, DataTypeInformation, StringEncoding, POINTER_SIZE},
+};
+
+const DEBUG_INFO_VERSION: u64 = 3;
+const DWARF_VERSION: u64 = 4;
+
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+const DW_ATE_UNSIGNED_CHAR: u32 = 0x08;
+const DW_ATE_UTF: u32 = 0x10;
+
+/// adds the module flags required by llvm to emit debug information
+pub fn add_debug_module_flags(context: &Context, module: &Module) {
+    module.add_basic_value_flag(
+        "Debug Info Version",
+        FlagBehavior::Warning,
+        context.i32_type().const_int(DEBUG_INFO_VERSION, false),
+    );
+    module.add_basic_value_flag(
+        "Dwarf Version",
+        FlagBehavior::Warning,
+        context.i32_type().const_int(DWARF_VERSION, false),
+    );
+}
+
+/// a debug type with the size and alignment (in bits) of the described type
+#[derive(Clone, Copy)]
+struct DebugType<'ink> {
+    di_type: DIType<'ink>,
+    size: u64,
+    alignment: u32,
+}
+
+/// generates the DWARF debug information of a single compilation unit:
+/// a compile unit for the file, a subprogram for every POU, method and action,
+/// the line locations of the generated statements and the POUs' variables
+pub struct DebugBuilder<'ink, 'b> {
+    context: &'ink Context,
+    debug_info: DebugInfoBuilder<'ink>,
+    compile_unit: DICompileUnit<'ink>,
+    file: DIFile<'ink>,
+    new_lines: &'b NewLines,
+    /// the already described types, by their lowercase name
+    types: RefCell<HashMap<String, DebugType<'ink>>>,
+    /// the structs currently described, used to break up recursive pointers
+    incomplete_types: RefCell<HashSet<String>>,
+}
+
+impl<'ink, 'b> DebugBuilder<'ink, 'b> {
+    /// creates a new DebugBuilder with a compile unit for the given unit's file
+    pub fn new(
+        context: &'ink Context,
+        module: &Module<'ink>,
+        unit: &'b CompilationUnit,
+    ) -> DebugBuilder<'ink, 'b> {
+        let path = Path::new(&unit.file_name);
+        let file_name = path
+            .file_name()
+            .and_then(|it| it.to_str())
+            .unwrap_or(&unit.file_name);
+        let directory = path.parent().and_then(|it| it.to_str()).unwrap_or_default();
+        let (debug_info, compile_unit) = module.create_debug_info_builder(
+            true,
+            //there is no DWARF language for ST, C is understood by all debuggers
+            DWARFSourceLanguage::C,
+            file_name,
+            directory,
+            "RuSTy Structured text Compiler",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        let file = compile_unit.get_file();
+        DebugBuilder {
+            context,
+            debug_info,
+            compile_unit,
+            file,
+            new_lines: &unit.new_lines,
+            types: RefCell::new(HashMap::new()),
+            incomplete_types: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// creates the subprogram of the given implementation and attaches it to the given function.
+    /// The builder's location is moved to the start of the implementation.
+    pub fn register_function(
+        &self,
+        index: &Index,
+        builder: &Builder<'ink>,
+        implementation: &Implementation,
+        function: FunctionValue<'ink>,
+    ) {
+        let return_type = index
+            .find_return_type(&implementation.type_name)
+            .and_then(|it| self.get_debug_type(index, it));
+        let parameter_types = if implementation.pou_type == PouType::Function {
+            index
+                .get_declared_parameters(&implementation.type_name)
+                .into_iter()
+                .filter_map(|it| self.get_variable_type(index, it))
+                .collect::<Vec<_>>()
+        } else {
+            index
+                .find_effective_type(&implementation.type_name)
+                .and_then(|it| self.get_debug_type(index, it))
+                .map(|it| self.create_pointer_type(&implementation.type_name, it))
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        let subroutine_type = self.debug_info.create_subroutine_type(
+            self.file,
+            return_type.map(|it| it.di_type),
+            &parameter_types
+                .iter()
+                .map(|it| it.di_type)
+                .collect::<Vec<_>>(),
+            DIFlags::PUBLIC,
+        );
+
+        let line = self.get_line(&implementation.location);
+        let subprogram = self.debug_info.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            &implementation.name,
+            None,
+            self.file,
+            line,
+            subroutine_type,
+            false,
+            true,
+            line,
+            DIFlags::PUBLIC,
+            false,
+        );
+        function.set_subprogram(subprogram);
+
+        let debug_location = self.debug_info.create_debug_location(
+            self.context,
+            line,
+            0,
+            subprogram.as_debug_info_scope(),
+            None,
+        );
+        builder.set_current_debug_location(self.context, debug_location);
+    }
+
+    /// sets the location of the next generated instructions to the given location inside
+    /// the given function. Undefined locations (e.g. of generated statements) are ignored.
+    pub fn set_location(
+        &self,
+        builder: &Builder<'ink>,
+        function: FunctionValue<'ink>,
+        location: &SourceRange,
+    ) {
+        if location.is_undefined() {
+            return;
+        }
+        if let Some(subprogram) = function.get_subprogram() {
+            let line = self.get_line(location);
+            let column = self.get_column(location);
+            let debug_location = self.debug_info.create_debug_location(
+                self.context,
+                line,
+                column,
+                subprogram.as_debug_info_scope(),
+                None,
+            );
+            builder.set_current_debug_location(self.context, debug_location);
+        }
+    }
+
+    /// declares the given variable, stored at the given pointer, as a local variable of the
+    /// given function
+    pub fn declare_variable(
+        &self,
+        index: &Index,
+        function: FunctionValue<'ink>,
+        variable: &VariableIndexEntry,
+        storage: PointerValue<'ink>,
+        block: BasicBlock<'ink>,
+    ) {
+        let subprogram = function.get_subprogram();
+        let debug_type = self.get_variable_type(index, variable);
+        if let (Some(subprogram), Some(debug_type)) = (subprogram, debug_type) {
+            let line = self.get_line(&variable.source_location);
+            let column = self.get_column(&variable.source_location);
+            let scope = subprogram.as_debug_info_scope();
+            let local_variable = self.debug_info.create_auto_variable(
+                scope,
+                variable.get_name(),
+                self.file,
+                line,
+                debug_type.di_type,
+                true,
+                DIFlags::ZERO,
+                debug_type.alignment,
+            );
+            let debug_location =
+                self.debug_info
+                    .create_debug_location(self.context, line, column, scope, None);
+            self.debug_info.insert_declare_at_end(
+                storage,
+                Some(local_variable),
+                None,
+                debug_location,
+                block,
+            );
+        }
+    }
+
+    /// resolves all temporary debug information, must be called after the unit was generated
+    pub fn finalize(&self) {
+        self.debug_info.finalize();
+    }
+
+    fn get_line(&self, location: &SourceRange) -> u32 {
+        self.new_lines.get_line_nr(location.get_start()) as u32
+    }
+
+    fn get_column(&self, location: &SourceRange) -> u32 {
+        let line = self.new_lines.get_line_nr(location.get_start());
+        self.new_lines.get_column(line, location.get_start()) as u32
+    }
+
+    fn get_variable_type(
+        &self,
+        index: &Index,
+        variable: &VariableIndexEntry,
+    ) -> Option<DebugType<'ink>> {
+        index
+            .find_effective_type(variable.get_type_name())
+            .and_then(|it| self.get_debug_type(index, it))
+    }
+
+    /// returns the debug type describing the given data type
+    fn get_debug_type(&self, index: &Index, data_type: &DataType) -> Option<DebugType<'ink>> {
+        let key = data_type.get_name().to_lowercase();
+        if let Some(debug_type) = self.types.borrow().get(&key) {
+            return Some(*debug_type);
+        }
+        let debug_type = self.create_debug_type(index, data_type)?;
+        self.types.borrow_mut().insert(key, debug_type);
+        Some(debug_type)
+    }
+
+    fn create_debug_type(&self, index: &Index, data_type: &DataType) -> Option<DebugType<'ink>> {
+        let name = data_type.get_name();
+        match data_type.get_type_information() {
+            DataTypeInformation::Integer {
+                signed,
+                size,
+                semantic_size,
+                ..
+            } => {
+                let encoding = match (semantic_size, signed) {
+                    (Some(1), _) => DW_ATE_BOOLEAN,
+                    (_, true) => DW_ATE_SIGNED,
+                    (_, false) => DW_ATE_UNSIGNED,
+                };
+                self.create_basic_type(name, *size as u64, encoding)
+            }
+            DataTypeInformation::Float { size, .. } => {
+                self.create_basic_type(name, *size as u64, DW_ATE_FLOAT)
+            }
+            DataTypeInformation::String { size, encoding } => {
+                let length = size.as_int_value(index).ok()?;
+                let character = match encoding {
+                    StringEncoding::Utf8 => self.create_basic_type("CHAR", 8, DW_ATE_UNSIGNED_CHAR),
+                    StringEncoding::Utf16 => self.create_basic_type("WCHAR", 16, DW_ATE_UTF),
+                }?;
+                let array = self.create_array_type(character, &[0..length]);
+                Some(self.create_typedef(name, array))
+            }
+            DataTypeInformation::Array {
+                inner_type_name,
+                dimensions,
+                ..
+            } => {
+                let inner_type = index
+                    .find_effective_type(inner_type_name)
+                    .and_then(|it| self.get_debug_type(index, it))?;
+                //IEC arrays keep their declared bounds, e.g. ARRAY[1..5]
+                let bounds = dimensions
+                    .iter()
+                    .map(|it| it.get_range_inclusive(index).ok())
+                    .map(|it| it.map(|range| *range.start()..*range.end() + 1))
+                    .collect::<Option<Vec<_>>>()?;
+                let array = self.create_array_type(inner_type, &bounds);
+                Some(self.create_typedef(name, array))
+            }
+            DataTypeInformation::Pointer {
+                inner_type_name, ..
+            } => {
+                let inner_type = if self
+                    .incomplete_types
+                    .borrow()
+                    .contains(&inner_type_name.to_lowercase())
+                {
+                    //a pointer to a struct that is not yet complete, describe it as a byte pointer
+                    self.create_basic_type("BYTE", 8, DW_ATE_UNSIGNED)?
+                } else {
+                    index
+                        .find_effective_type(inner_type_name)
+                        .and_then(|it| self.get_debug_type(index, it))?
+                };
+                Some(self.create_pointer_type(name, inner_type))
+            }
+            DataTypeInformation::Struct { .. } => self.create_struct_type(index, data_type),
+            //enums are described by their numeric type
+            DataTypeInformation::Enum {
+                referenced_type, ..
+            }
+            | DataTypeInformation::SubRange {
+                referenced_type, ..
+            } => {
+                let referenced_type = index
+                    .find_effective_type(referenced_type)
+                    .and_then(|it| self.get_debug_type(index, it))?;
+                Some(self.create_typedef(name, referenced_type))
+            }
+            DataTypeInformation::Alias { .. }
+            | DataTypeInformation::Generic { .. }
+            | DataTypeInformation::Void => None,
+        }
+    }
+
+    fn create_basic_type(&self, name: &str, size: u64, encoding: u32) -> Option<DebugType<'ink>> {
+        self.debug_info
+            .create_basic_type(name, size, encoding, DIFlags::PUBLIC)
+            .ok()
+            .map(|it| DebugType {
+                di_type: it.as_type(),
+                size,
+                alignment: size as u32,
+            })
+    }
+
+    fn create_array_type(
+        &self,
+        inner_type: DebugType<'ink>,
+        bounds: &[std::ops::Range<i64>],
+    ) -> DebugType<'ink> {
+        let length = bounds
+            .iter()
+            .map(|it| (it.end - it.start).max(0) as u64)
+            .product::<u64>();
+        let size = inner_type.size * length;
+        let array = self.debug_info.create_array_type(
+            inner_type.di_type,
+            size,
+            inner_type.alignment,
+            bounds,
+        );
+        DebugType {
+            di_type: array.as_type(),
+            size,
+            alignment: inner_type.alignment,
+        }
+    }
+
+    fn create_pointer_type(&self, name: &str, inner_type: DebugType<'ink>) -> DebugType<'ink> {
+        let pointer = self.debug_info.create_pointer_type(
+            name,
+            inner_type.di_type,
+            POINTER_SIZE as u64,
+            POINTER_SIZE,
+            AddressSpace::Generic,
+        );
+        DebugType {
+            di_type: pointer.as_type(),
+            size: POINTER_SIZE as u64,
+            alignment: POINTER_SIZE,
+        }
+    }
+
+    fn create_typedef(&self, name: &str, referenced_type: DebugType<'ink>) -> DebugType<'ink> {
+        let typedef = self.debug_info.create_typedef(
+            referenced_type.di_type,
+            name,
+            self.file,
+            0,
+            self.file.as_debug_info_scope(),
+            referenced_type.alignment,
+        );
+        DebugType {
+            di_type: typedef.as_type(),
+            ..referenced_type
+        }
+    }
+
+    /// describes a struct or the instance-struct of a POU, the members are laid out
+    /// with their natural alignment like the generated llvm struct
+    fn create_struct_type(&self, index: &Index, data_type: &DataType) -> Option<DebugType<'ink>> {
+        let name = data_type.get_name();
+        self.incomplete_types
+            .borrow_mut()
+            .insert(name.to_lowercase());
+        let members = index
+            .get_container_members(name)
+            .into_iter()
+            .filter(|it| !it.is_temp() && !it.is_return())
+            .map(|it| {
+                self.get_variable_type(index, it)
+                    .map(|debug_type| (it, debug_type))
+            })
+            .collect::<Option<Vec<_>>>();
+        self.incomplete_types
+            .borrow_mut()
+            .remove(&name.to_lowercase());
+
+        let mut offset = 0;
+        let mut alignment = 8;
+        let mut elements = vec![];
+        for (member, member_type) in members? {
+            offset = align_to(offset, member_type.alignment);
+            alignment = alignment.max(member_type.alignment);
+            let element = self.debug_info.create_member_type(
+                self.file.as_debug_info_scope(),
+                member.get_name(),
+                self.file,
+                self.get_line(&member.source_location),
+                member_type.size,
+                member_type.alignment,
+                offset,
+                DIFlags::PUBLIC,
+                member_type.di_type,
+            );
+            elements.push(element.as_type());
+            offset += member_type.size;
+        }
+        let size = align_to(offset, alignment);
+        let struct_type = self.debug_info.create_struct_type(
+            self.file.as_debug_info_scope(),
+            name,
+            self.file,
+            0,
+            size,
+            alignment,
+            DIFlags::PUBLIC,
+            None,
+            &elements,
+            0,
+            None,
+            name,
+        );
+        Some(DebugType {
+            di_type: struct_type.as_type(),
+            size,
+            alignment,
+        })
+    }
+}
+
+/// returns the given offset (in bits) rounded up to the given alignment
+fn align_to(offset: u64, alignment: u32) -> u64 {
+    let alignment = alignment.max(1) as u64;
+    (offset + alignment - 1) / alignment * alignment
+}
@@ -3,5 +3,6 @@ pub mod date_time_util;
 pub mod expression_generator;
 pub mod llvm;
 pub mod pou_generator;
+pub mod retain_generator;
 pub mod statement_generator;
 pub mod variable_generator;
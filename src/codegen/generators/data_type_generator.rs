@@ -7,7 +7,7 @@ use std::convert::TryInto;
 /// - SubRange types
 /// - Alias types
 /// - sized Strings
-use crate::ast::SourceRange;
+use crate::ast::{LinkageType, SourceRange};
 use crate::index::{Index, VariableIndexEntry, VariableType};
 use crate::resolver::AstAnnotations;
 use crate::typesystem::{Dimension, StringEncoding, StructSource};
@@ -67,6 +67,7 @@ pub fn generate_data_types<'ink>(
         .get_pous()
         .values()
         .filter(|pou| !pou.is_generic() && !pou.is_action()) //actions dont get an own datatype, they use the one from their parent
+        .filter(|pou| pou.get_linkage() != &LinkageType::BuiltIn) //builtins are generated inline at the call site
         .map(|pou| pou.get_instance_struct_type(generator.index))
         .map(|it| (it.get_name(), it))
         .collect::<Vec<(&str, &DataType)>>();
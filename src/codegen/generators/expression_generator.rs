@@ -3,19 +3,20 @@ use crate::{
     ast::{self, DirectAccessType, SourceRange},
     codegen::llvm_typesystem,
     diagnostics::{Diagnostic, INTERNAL_LLVM_ERROR},
-    index::{ImplementationIndexEntry, Index, PouIndexEntry, VariableIndexEntry},
+    index::{self, ImplementationIndexEntry, Index, PouIndexEntry, VariableIndexEntry},
     resolver::{AnnotationMap, AstAnnotations, StatementAnnotation},
     typesystem::{
-        is_same_type_class, Dimension, StringEncoding, VarArgs, DINT_TYPE, INT_SIZE, INT_TYPE,
-        LINT_TYPE,
+        is_same_type_class, Dimension, StringEncoding, VarArgs, BOUNDS_CHECK_FN, DINT_TYPE,
+        INT_SIZE, INT_TYPE, LINT_TYPE,
     },
 };
 use inkwell::{
     builder::Builder,
-    types::{BasicType, BasicTypeEnum},
+    module::Module,
+    types::{AnyType, AnyTypeEnum, BasicType, BasicTypeEnum},
     values::{
-        ArrayValue, BasicMetadataValueEnum, BasicValue, BasicValueEnum, FloatValue, GlobalValue,
-        IntValue, PointerValue, StructValue, VectorValue,
+        ArrayValue, BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue,
+        CallableValue, FloatValue, GlobalValue, IntValue, PointerValue, StructValue, VectorValue,
     },
     AddressSpace, FloatPredicate, IntPredicate,
 };
@@ -26,11 +27,12 @@ use crate::{
     codegen::{
         llvm_index::LlvmTypedIndex,
         llvm_typesystem::{cast_if_needed, get_llvm_int_type},
+        CodeGenOptions,
     },
     typesystem::{DataType, DataTypeInformation},
 };
 
-use super::{llvm::Llvm, statement_generator::FunctionContext};
+use super::{llvm::Llvm, pou_generator, statement_generator::FunctionContext};
 
 /// the generator for expressions
 pub struct ExpressionCodeGenerator<'a, 'b> {
@@ -40,6 +42,8 @@ pub struct ExpressionCodeGenerator<'a, 'b> {
     pub llvm_index: &'b LlvmTypedIndex<'a>,
     /// the current function to create blocks in
     pub function_context: Option<&'b FunctionContext<'a>>,
+    /// the module to declare called intrinsics in
+    module: Option<&'b Module<'a>>,
 
     /// the string-prefix to use for temporary variables
     pub temp_variable_prefix: String,
@@ -48,6 +52,9 @@ pub struct ExpressionCodeGenerator<'a, 'b> {
 
     // the function on how to obtain the the length to use for the string
     string_len_provider: fn(type_length_declaration: usize, actual_length: usize) -> usize,
+
+    /// the options the module is generated with
+    options: CodeGenOptions,
 }
 
 /// context information to generate a parameter
@@ -71,12 +78,16 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
     /// - `index` the index / global symbol table
     /// - `type_hint` an optional type hint for generating literals
     /// - `function_context` the current function to create blocks
+    /// - `module` the module to declare called intrinsics in
+    /// - `options` the options the module is generated with
     pub fn new(
         llvm: &'b Llvm<'ink>,
         index: &'b Index,
         annotations: &'b AstAnnotations,
         llvm_index: &'b LlvmTypedIndex<'ink>,
         function_context: &'b FunctionContext<'ink>,
+        module: &'b Module<'ink>,
+        options: CodeGenOptions,
     ) -> ExpressionCodeGenerator<'ink, 'b> {
         ExpressionCodeGenerator {
             llvm,
@@ -84,9 +95,11 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             llvm_index,
             annotations,
             function_context: Some(function_context),
+            module: Some(module),
             temp_variable_prefix: "load_".to_string(),
             temp_variable_suffix: "".to_string(),
             string_len_provider: |_, actual_length| actual_length, //when generating string-literals in a body, use the actual length
+            options,
         }
     }
 
@@ -109,9 +122,11 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             llvm_index,
             annotations,
             function_context: None,
+            module: None,
             temp_variable_prefix: "load_".to_string(),
             temp_variable_suffix: "".to_string(),
             string_len_provider: |type_length_declaration, _| type_length_declaration, //when generating string-literals in declarations, use the declared length
+            options: CodeGenOptions::default(),
         }
     }
 
@@ -124,6 +139,12 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             .ok_or_else(|| Diagnostic::missing_function(statement.get_location()))
     }
 
+    /// returns the module or returns a Compile-Error
+    pub fn get_module(&self, statement: &AstStatement) -> Result<&'b Module<'ink>, Diagnostic> {
+        self.module
+            .ok_or_else(|| Diagnostic::missing_function(statement.get_location()))
+    }
+
     /// entry point into the expression generator.
     /// generates the given expression and returns the resulting BasicValueEnum
     pub fn generate_expression(
@@ -490,6 +511,11 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                 })
             .ok_or_else(|| Diagnostic::cannot_generate_call_statement(operator))?;
 
+        //calls to an interface's method are dispatched through the assigned pou's method table
+        if self.index.is_interface(pou.get_container()) {
+            return self.generate_interface_method_call(pou, operator, parameters);
+        }
+
         // find corresponding implementation
         let implementation = pou
             .find_implementation(self.index)
@@ -503,6 +529,7 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             //adr, ref, etc.
             return builtin.codegen(
                 self,
+                operator,
                 parameters
                     .as_ref()
                     .map(ast::flatten_expression_list)
@@ -537,21 +564,96 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             })?;
         //If the target is a function, declare the struct locally
         //Assign all parameters into the struct values
-        let call_result = builder
-            .build_call(function, &arguments_list, "call")
-            .try_as_basic_value();
+        let call_result = builder.build_call(function, &arguments_list, "call");
+        self.get_call_result_value(call_result)
+    }
+
+    /// generates a call to the given method of an interface. The call is dispatched through
+    /// the method table of the pou that was assigned to the interface
+    fn generate_interface_method_call(
+        &self,
+        method: &PouIndexEntry,
+        operator: &AstStatement,
+        parameters: &Option<AstStatement>,
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        let method_name = method.get_name();
+        let slot = self
+            .index
+            .get_interface_methods(method.get_container())
+            .into_iter()
+            .position(|it| it.get_name().eq_ignore_ascii_case(method_name))
+            .ok_or_else(|| Diagnostic::cannot_generate_call_statement(operator))?;
 
+        let builder = &self.llvm.builder;
+        let interface_ptr = self.generate_element_pointer(operator)?;
+        let vtable_ptr = builder
+            .build_struct_gep(interface_ptr, 0, ast::VTABLE_MEMBER_NAME)
+            .expect(INTERNAL_LLVM_ERROR);
+        let instance_ptr = builder
+            .build_struct_gep(interface_ptr, 1, ast::INSTANCE_MEMBER_NAME)
+            .expect(INTERNAL_LLVM_ERROR);
+        let instance = builder
+            .build_load(instance_ptr, ast::INSTANCE_MEMBER_NAME)
+            .into_pointer_value();
+
+        //look up the method in the table
+        let byte_pointer_type = self.llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+        let table = builder.build_pointer_cast(
+            builder
+                .build_load(vtable_ptr, ast::VTABLE_MEMBER_NAME)
+                .into_pointer_value(),
+            byte_pointer_type.ptr_type(AddressSpace::Generic),
+            "table",
+        );
+        let slot_ptr = unsafe {
+            builder.build_in_bounds_gep(
+                table,
+                &[self.llvm.context.i32_type().const_int(slot as u64, false)],
+                "slot",
+            )
+        };
+        let function_type = pou_generator::get_interface_method_type(
+            self.llvm,
+            self.index,
+            self.llvm_index,
+            method_name,
+        )?;
+        let function_ptr = builder.build_pointer_cast(
+            builder
+                .build_load(slot_ptr, method_name)
+                .into_pointer_value(),
+            function_type.ptr_type(AddressSpace::Generic),
+            method_name,
+        );
+        let function = CallableValue::try_from(function_ptr)
+            .map_err(|_| Diagnostic::cannot_generate_call_statement(operator))?;
+
+        let call_ptr = self.allocate_function_struct_instance(method_name, operator)?;
+        let arguments_list = self.generate_stateful_pou_call_parameters(
+            method_name,
+            Some(instance),
+            call_ptr,
+            parameters,
+        )?;
+        let call_result = builder.build_call(function, &arguments_list, "call");
+        self.get_call_result_value(call_result)
+    }
+
+    /// returns the value of the given call or a null-pointer if the called pou does not
+    /// return a value
+    fn get_call_result_value(
+        &self,
+        call_result: CallSiteValue<'ink>,
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
         // we return an uninitialized int pointer for void methods :-/
         // dont deref it!!
-        let value = call_result.either(Ok, |_| {
+        call_result.try_as_basic_value().either(Ok, |_| {
             get_llvm_int_type(self.llvm.context, INT_SIZE, INT_TYPE).map(|int| {
                 int.ptr_type(AddressSpace::Const)
                     .const_null()
                     .as_basic_value_enum()
             })
-        })?;
-
-        Ok(value)
+        })
     }
 
     /// generates the argument list for a call to a pou
@@ -685,12 +787,29 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         type_name: &str,
         param_statement: &AstStatement,
     ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
-        Ok(match self.index.find_effective_type(type_name) {
-            Some(type_info) if type_info.information.is_string() => {
-                self.generate_string_argument(type_info, param_statement)?
-            }
-            _ => self.generate_expression(param_statement)?,
-        })
+        let parameter_type = self.index.find_effective_type(type_name);
+        if let Some(type_info) = parameter_type.filter(|it| it.information.is_string()) {
+            return self.generate_string_argument(type_info, param_statement);
+        }
+        let value = self.generate_expression(param_statement)?;
+        //the arguments of synthesized calls (e.g. to CheckBounds) have no type hint, they are cast
+        //to the parameter's type
+        match (
+            parameter_type,
+            self.annotations.get_hint(param_statement),
+            self.annotations.get_type(param_statement, self.index),
+        ) {
+            (Some(parameter_type), None, Some(actual_type)) => cast_if_needed(
+                self.llvm,
+                self.index,
+                self.llvm_index,
+                parameter_type,
+                value,
+                actual_type,
+                param_statement,
+            ),
+            _ => Ok(value),
+        }
     }
 
     /// Before passing a string to a function, it is copied to a new string with the
@@ -973,12 +1092,16 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         right: &AstStatement,
     ) -> Result<(), Diagnostic> {
         let function_name = param_context.function_name;
-        let parameter_struct = param_context.parameter_struct;
         if let AstStatement::Reference { name, .. } = left {
             let parameter = self
                 .index
                 .find_member(function_name, name)
                 .ok_or_else(|| Diagnostic::unresolved_reference(name, left.get_location()))?;
+            //inherited parameters live in the embedded instance of the super class
+            let parameter_struct = self.upcast_to_pou(
+                param_context.parameter_struct,
+                parameter.get_container_name(),
+            );
             let index = parameter.get_location_in_parent();
             let param_type = self.index.find_effective_type(parameter.get_type_name());
             self.generate_call_struct_argument_assignment(&CallParameterAssignment {
@@ -1016,6 +1139,13 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
     ) -> Result<PointerValue<'ink>, Diagnostic> {
         let mut qualifier: Option<PointerValue> = None;
         for e in elements {
+            //the namespace of a POU or global variable is part of its name
+            if matches!(
+                self.annotations.get(e),
+                Some(StatementAnnotation::Namespace { .. })
+            ) {
+                continue;
+            }
             qualifier = Some(self.do_generate_element_pointer(qualifier, e)?);
         }
         qualifier.ok_or_else(|| {
@@ -1077,21 +1207,25 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             match self.annotations.get(context) {
                 Some(StatementAnnotation::Function { qualified_name, .. })
                 | Some(StatementAnnotation::Program { qualified_name, .. }) => {
-                    if self.index.find_pou_implementation(qualified_name).is_some() {
-                        return Ok(qualifier.to_owned());
+                    if let Some(pou) = self.index.find_pou(qualified_name) {
+                        if pou.find_implementation(self.index).is_some()
+                            || self.index.is_interface(pou.get_container())
+                        {
+                            //an inherited method needs the embedded instance of its class
+                            return Ok(self.upcast_to_pou(*qualifier, pou.get_container()));
+                        }
                     }
                 }
                 Some(StatementAnnotation::Variable { qualified_name, .. }) => {
-                    let member_location = self
+                    let member = self
                         .index
                         .find_fully_qualified_variable(qualified_name)
-                        .map(VariableIndexEntry::get_location_in_parent)
                         .ok_or_else(|| {
                             Diagnostic::unresolved_reference(qualified_name, offset.clone())
                         })?;
                     let gep = self.llvm.get_member_pointer_from_struct(
-                        *qualifier,
-                        member_location,
+                        self.upcast_to_pou(*qualifier, member.get_container_name()),
+                        member.get_location_in_parent(),
                         name,
                         offset,
                     )?;
@@ -1120,6 +1254,44 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         }
     }
 
+    /// walks down the embedded super class instances of the given struct-pointer until it
+    /// points to an instance of the given pou. Derived pous embed the instance of their super class
+    /// as their first member.
+    ///
+    /// returns the given pointer if the pou is not a super class of the pointer's struct
+    fn upcast_to_pou(&self, pointer: PointerValue<'ink>, pou_name: &str) -> PointerValue<'ink> {
+        let target_type =
+            if let Some(target_type) = self.llvm_index.find_associated_pou_type(pou_name) {
+                target_type.as_any_type_enum()
+            } else {
+                return pointer;
+            };
+
+        //find the depth of the embedded instance first, so no gep is built if there is none
+        let mut depth = 0;
+        let mut current_type = pointer.get_type().get_element_type();
+        while current_type != target_type {
+            match current_type {
+                AnyTypeEnum::StructType(struct_type) => {
+                    match struct_type.get_field_type_at_index(0) {
+                        Some(BasicTypeEnum::StructType(super_type)) => {
+                            current_type = super_type.as_any_type_enum();
+                            depth += 1;
+                        }
+                        _ => return pointer,
+                    }
+                }
+                _ => return pointer,
+            }
+        }
+        (0..depth).fold(pointer, |current, _| {
+            self.llvm
+                .builder
+                .build_struct_gep(current, 0, ast::SUPER_MEMBER_NAME)
+                .expect(INTERNAL_LLVM_ERROR)
+        })
+    }
+
     fn deref(&self, accessor_ptr: PointerValue<'ink>) -> PointerValue<'ink> {
         self.llvm
             .load_pointer(&accessor_ptr, "deref")
@@ -1172,7 +1344,34 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             .as_int_value(self.index)
             .map_err(|it| Diagnostic::codegen_error(&it, access_expression.get_location()))?;
 
+        let is_bounds_checked = self.is_bounds_checked(access_expression)?;
+        if is_bounds_checked
+            && self
+                .index
+                .find_pou_implementation(BOUNDS_CHECK_FN)
+                .is_some()
+        {
+            //CheckBounds returns the (DINT) index to access
+            let checked_value = self
+                .generate_check_bounds_call(dimension, access_expression)?
+                .into_int_value();
+            return Ok(self
+                .llvm
+                .builder
+                .build_int_sub(
+                    checked_value,
+                    checked_value
+                        .get_type()
+                        .const_int(start_offset as u64, true),
+                    "",
+                )
+                .as_basic_value_enum());
+        }
+
         let access_value = self.generate_expression(access_expression)?;
+        if is_bounds_checked {
+            self.generate_bounds_trap(dimension, access_value.into_int_value(), access_expression)?;
+        }
         //If start offset is not 0, adjust the current statement with an add operation
         let result = if start_offset != 0 {
             let access_int_value = access_value.into_int_value();
@@ -1197,6 +1396,138 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         )
     }
 
+    /// returns true if the indices of the array accesses in the current function are checked
+    /// against the array's bounds. The accesses in `CheckBounds` itself are not checked, it would
+    /// call itself
+    fn is_bounds_checked(&self, access_expression: &AstStatement) -> Result<bool, Diagnostic> {
+        if !self.options.bounds_check {
+            return Ok(false);
+        }
+        let function_context = self.get_function_context(access_expression)?;
+        Ok(!function_context
+            .linking_context
+            .get_call_name()
+            .eq_ignore_ascii_case(BOUNDS_CHECK_FN))
+    }
+
+    /// generates a call to the user defined `CheckBounds(index, lower, upper : DINT) : DINT`
+    /// with the given index and the bounds of the given dimension, it returns the index to access
+    ///
+    /// - `dimension` the array's dimension
+    /// - `access_expression` the expression inside the array-statement
+    fn generate_check_bounds_call(
+        &self,
+        dimension: &Dimension,
+        access_expression: &AstStatement,
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        let range = dimension
+            .get_range_inclusive(self.index)
+            .map_err(|it| Diagnostic::codegen_error(&it, access_expression.get_location()))?;
+        let location = access_expression.get_location();
+        let dint_id = self.annotations.get_dint_id();
+        let bound = |value: i64| AstStatement::LiteralInteger {
+            value: value as i128,
+            location: location.clone(),
+            id: dint_id,
+        };
+        let call = ast::create_call_to(
+            BOUNDS_CHECK_FN.to_string(),
+            vec![
+                access_expression.clone(),
+                bound(*range.start()),
+                bound(*range.end()),
+            ],
+            dint_id,
+            dint_id,
+            &location,
+        );
+        self.generate_expression(&call)
+    }
+
+    /// traps if the given index is outside of the bounds of the given dimension. The index is
+    /// compared in its own width, a bound that is beyond the range of the index's type is not
+    /// compared
+    ///
+    /// - `dimension` the array's dimension
+    /// - `access_value` the index of the access
+    /// - `access_expression` the expression inside the array-statement
+    fn generate_bounds_trap(
+        &self,
+        dimension: &Dimension,
+        access_value: IntValue<'ink>,
+        access_expression: &AstStatement,
+    ) -> Result<(), Diagnostic> {
+        let range = dimension
+            .get_range_inclusive(self.index)
+            .map_err(|it| Diagnostic::codegen_error(&it, access_expression.get_location()))?;
+        let (lower, upper) = (*range.start() as i128, *range.end() as i128);
+
+        //enums are represented as DINTs
+        let signed = !matches!(
+            self.get_type_hint_for(access_expression)?
+                .get_type_information(),
+            DataTypeInformation::Integer { signed: false, .. }
+        );
+        let int_type = access_value.get_type();
+        let bits = int_type.get_bit_width();
+        let (min, max) = if signed {
+            (-(1_i128 << (bits - 1)), (1_i128 << (bits - 1)) - 1)
+        } else {
+            (0, (1_i128 << bits) - 1)
+        };
+
+        let builder = &self.llvm.builder;
+        let out_of_bounds = if lower > max || upper < min {
+            //no value of the index's type is within the bounds
+            Some(self.llvm.context.bool_type().const_int(1, false))
+        } else {
+            let (less, greater) = if signed {
+                (IntPredicate::SLT, IntPredicate::SGT)
+            } else {
+                (IntPredicate::ULT, IntPredicate::UGT)
+            };
+            let below = (lower > min).then(|| {
+                let lower = int_type.const_int(lower as u64, signed);
+                builder.build_int_compare(less, access_value, lower, "")
+            });
+            let above = (upper < max).then(|| {
+                let upper = int_type.const_int(upper as u64, signed);
+                builder.build_int_compare(greater, access_value, upper, "")
+            });
+            match (below, above) {
+                (Some(below), Some(above)) => Some(builder.build_or(below, above, "")),
+                (below, above) => below.or(above),
+            }
+        };
+        //every value of the index's type is within the bounds
+        let out_of_bounds = if let Some(out_of_bounds) = out_of_bounds {
+            out_of_bounds
+        } else {
+            return Ok(());
+        };
+
+        let function = self.get_function_context(access_expression)?.function;
+        let module = self.get_module(access_expression)?;
+        let trap = module.get_function("llvm.trap").unwrap_or_else(|| {
+            module.add_function(
+                "llvm.trap",
+                self.llvm.context.void_type().fn_type(&[], false),
+                None,
+            )
+        });
+        let trap_block = self
+            .llvm
+            .context
+            .append_basic_block(function, "out_of_bounds");
+        let continue_block = self.llvm.context.append_basic_block(function, "in_bounds");
+        builder.build_conditional_branch(out_of_bounds, trap_block, continue_block);
+        builder.position_at_end(trap_block);
+        builder.build_call(trap, &[], "");
+        builder.build_unreachable();
+        builder.position_at_end(continue_block);
+        Ok(())
+    }
+
     /// generates a gep statement for a array-reference with an optional qualifier
     ///
     /// - `qualifier` an optional qualifier for a reference (e.g. myStruct.x[2] where myStruct is the qualifier for x)
@@ -2133,7 +2464,7 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                 .as_basic_value_enum()),
             _ => Err(Diagnostic::codegen_error(
                 format!("illegal boolean expresspion for operator {:}", operator).as_str(),
-                (left.get_location().get_start()..right.get_location().get_end()).into(),
+                left.get_location().span(&right.get_location()),
             )),
         }
     }
@@ -2359,6 +2690,8 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                 .builder
                 .build_memcpy(left, align_left, right, align_right, size)
                 .map_err(|err| Diagnostic::codegen_error(err, right_statement.get_location()))?;
+        } else if left_type.is_interface() && !right_type.is_interface() {
+            self.generate_interface_store(left_type, right_statement, left)?;
         } else {
             let expression = self.generate_expression(right_statement)?;
             self.llvm.builder.build_store(left, expression);
@@ -2366,6 +2699,60 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         Ok(())
     }
 
+    /// stores the method table and the instance of the pou `right_statement` into the
+    /// interface pointed to by `left`
+    fn generate_interface_store(
+        &self,
+        left_type: &DataTypeInformation,
+        right_statement: &AstStatement,
+        left: PointerValue<'ink>,
+    ) -> Result<(), Diagnostic> {
+        let pou_name = self
+            .annotations
+            .get_type_or_void(right_statement, self.index)
+            .get_name();
+        //the right side is hinted with the interface it is assigned to
+        let interface = self
+            .annotations
+            .get_type_hint(right_statement, self.index)
+            .map(DataType::get_name)
+            .filter(|it| self.index.is_interface(it))
+            .ok_or_else(|| {
+                Diagnostic::codegen_error(
+                    &format!("Cannot assign '{}' to {}", pou_name, left_type.get_name()),
+                    right_statement.get_location(),
+                )
+            })?;
+        let vtable = self
+            .llvm_index
+            .find_global_value(&index::get_vtable_name(pou_name, interface))
+            .ok_or_else(|| {
+                Diagnostic::codegen_error(
+                    &format!("'{}' does not implement '{}'", pou_name, interface),
+                    right_statement.get_location(),
+                )
+            })?;
+
+        let byte_pointer_type = self.llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+        let builder = &self.llvm.builder;
+        let vtable_ptr = builder
+            .build_struct_gep(left, 0, ast::VTABLE_MEMBER_NAME)
+            .expect(INTERNAL_LLVM_ERROR);
+        builder.build_store(
+            vtable_ptr,
+            builder.build_pointer_cast(vtable.as_pointer_value(), byte_pointer_type, ""),
+        );
+        let instance_ptr = builder
+            .build_struct_gep(left, 1, ast::INSTANCE_MEMBER_NAME)
+            .expect(INTERNAL_LLVM_ERROR);
+        let instance = self.generate_element_pointer(right_statement)?;
+        builder.build_store(
+            instance_ptr,
+            builder.build_pointer_cast(instance, byte_pointer_type, ""),
+        );
+        Ok(())
+    }
+
     fn get_string_size(
         &self,
         datatype: &DataTypeInformation,
@@ -21,6 +21,7 @@ pub struct Llvm<'a> {
 pub trait GlobalValueExt {
     fn make_constant(self) -> Self;
     fn make_external(self) -> Self;
+    fn make_private(self) -> Self;
     fn set_initial_value(
         self,
         initial_value: Option<BasicValueEnum>,
@@ -40,6 +41,11 @@ impl<'ink> GlobalValueExt for GlobalValue<'ink> {
         self
     }
 
+    fn make_private(self) -> Self {
+        self.set_linkage(Linkage::Private);
+        self
+    }
+
     fn set_initial_value(
         self,
         initial_value: Option<BasicValueEnum>,
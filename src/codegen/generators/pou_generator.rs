@@ -6,10 +6,10 @@ use super::{
     statement_generator::{FunctionContext, StatementCodeGenerator},
 };
 use crate::{
-    ast::{AstStatement, Pou},
-    codegen::llvm_index::LlvmTypedIndex,
+    ast::{AstStatement, LinkageType, Pou, SUPER_MEMBER_NAME},
+    codegen::{debug::DebugBuilder, llvm_index::LlvmTypedIndex, CodeGenOptions},
     diagnostics::{Diagnostic, INTERNAL_LLVM_ERROR},
-    index::{self, ImplementationType},
+    index::{self, ImplementationType, PouIndexEntry, VariableType},
     resolver::AstAnnotations,
     typesystem::{self, VarArgs},
 };
@@ -26,7 +26,7 @@ use crate::{
     index::Index,
 };
 use inkwell::{
-    module::Module,
+    module::{Linkage, Module},
     types::{BasicMetadataTypeEnum, BasicTypeEnum, FunctionType},
     values::{BasicValue, BasicValueEnum, FunctionValue},
     AddressSpace,
@@ -41,6 +41,10 @@ pub struct PouGenerator<'ink, 'cg> {
     index: &'cg Index,
     annotations: &'cg AstAnnotations,
     llvm_index: &'cg LlvmTypedIndex<'ink>,
+    /// generates the debug information if it was requested
+    debug: Option<&'cg DebugBuilder<'ink, 'cg>>,
+    /// the options the module is generated with
+    options: CodeGenOptions,
 }
 
 /// Creates opaque implementations for all callable items in the index
@@ -53,9 +57,17 @@ pub fn generate_implementation_stubs<'ink>(
     types_index: &LlvmTypedIndex<'ink>,
 ) -> Result<LlvmTypedIndex<'ink>, Diagnostic> {
     let mut llvm_index = LlvmTypedIndex::default();
-    let pou_generator = PouGenerator::new(llvm, index, annotations, types_index);
+    let pou_generator = PouGenerator::new(
+        llvm,
+        index,
+        annotations,
+        types_index,
+        None,
+        CodeGenOptions::default(),
+    );
     for (name, implementation) in index.get_implementations() {
-        if !implementation.is_generic() {
+        //builtins are generated inline at the call site
+        if !implementation.is_generic() && index.get_builtin_function(name).is_none() {
             let curr_f = pou_generator.generate_implementation_stub(implementation, module)?;
             llvm_index.associate_implementation(name, curr_f)?;
         }
@@ -67,6 +79,7 @@ pub fn generate_implementation_stubs<'ink>(
 ///Generates a global constant for each initialized pou member
 /// The given constant can then be used to initialize the variable using memcpy without re-evaluating the expression
 /// Retrieves the POUs from the index (implementation)
+/// The constants are private to the module if `module_local` is set
 /// Returns a new LLVM index to be merged with the parent codegen index.
 pub fn generate_global_constants_for_pou_members<'ink>(
     module: &Module<'ink>,
@@ -74,6 +87,7 @@ pub fn generate_global_constants_for_pou_members<'ink>(
     index: &Index,
     annotations: &AstAnnotations,
     llvm_index: &LlvmTypedIndex<'ink>,
+    module_local: bool,
 ) -> Result<LlvmTypedIndex<'ink>, Diagnostic> {
     let mut local_llvm_index = LlvmTypedIndex::default();
     for (_, implementation) in index.get_implementations() {
@@ -90,6 +104,9 @@ pub fn generate_global_constants_for_pou_members<'ink>(
             });
         let exp_gen =
             ExpressionCodeGenerator::new_context_free(llvm, index, annotations, llvm_index);
+        let is_weak = index
+            .find_pou(type_name)
+            .map_or(false, |it| it.get_linkage() == &LinkageType::Weak);
         for variable in variables {
             let name = index::get_initializer_name(variable.get_qualified_name());
             let right_stmt = match variable.initial_value {
@@ -118,10 +135,19 @@ pub fn generate_global_constants_for_pou_members<'ink>(
                         exp_gen.generate_expression(stmt)?
                     };
                     let variable_type = llvm_index.get_associated_type(variable.get_type_name())?;
-                    let global_value = llvm
-                        .create_global_variable(module, &name, variable_type)
+                    let mut global_value = llvm
+                        .create_global_variable(
+                            module,
+                            &index::get_symbol_name(&name),
+                            variable_type,
+                        )
                         .make_constant()
                         .set_initial_value(Some(value), variable_type);
+                    if module_local {
+                        global_value = global_value.make_private();
+                    } else if is_weak {
+                        global_value.set_linkage(Linkage::WeakODR);
+                    }
                     local_llvm_index.associate_global(&name, global_value)?;
                 }
             }
@@ -130,6 +156,212 @@ pub fn generate_global_constants_for_pou_members<'ink>(
     Ok(local_llvm_index)
 }
 
+/// Generates a method table for every pou that implements an interface. The table holds one
+/// function per interface method that forwards the call to the pou's implementation of it.
+/// The tables and their functions are private to the module if `module_local` is set
+/// Returns a new LLVM index to be merged with the parent codegen index.
+pub fn generate_interface_tables<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    module_local: bool,
+) -> Result<LlvmTypedIndex<'ink>, Diagnostic> {
+    let mut local_llvm_index = LlvmTypedIndex::default();
+    let byte_pointer_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+    let implementing_pous = index.get_pous().values().filter(|it| {
+        matches!(
+            it,
+            PouIndexEntry::Class { .. } | PouIndexEntry::FunctionBlock { .. }
+        )
+    });
+    for pou in implementing_pous {
+        for interface in index.get_implemented_interfaces(pou.get_name()) {
+            let methods = index
+                .get_interface_methods(interface)
+                .into_iter()
+                .map(|method| {
+                    generate_interface_method_adapter(
+                        module,
+                        llvm,
+                        index,
+                        llvm_index,
+                        pou.get_name(),
+                        method,
+                    )
+                    .map(|it| {
+                        if module_local {
+                            it.set_linkage(Linkage::Private);
+                        }
+                        it.as_global_value()
+                            .as_pointer_value()
+                            .const_cast(byte_pointer_type)
+                    })
+                })
+                .collect::<Result<Vec<PointerValue>, Diagnostic>>()?;
+
+            let table = byte_pointer_type.const_array(&methods);
+            let name = index::get_vtable_name(pou.get_name(), interface);
+            let mut global_value = llvm
+                .create_global_variable(module, &name, table.get_type().as_basic_type_enum())
+                .make_constant()
+                .set_initial_value(
+                    Some(table.as_basic_value_enum()),
+                    table.get_type().as_basic_type_enum(),
+                );
+            if module_local {
+                global_value = global_value.make_private();
+            }
+            local_llvm_index.associate_global(&name, global_value)?;
+        }
+    }
+    Ok(local_llvm_index)
+}
+
+/// returns the type of the functions stored in an interface's method table for the given
+/// interface method: `fn(i8* instance, method_struct* parameters) -> return_type`
+pub fn get_interface_method_type<'ink>(
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    method_name: &str,
+) -> Result<FunctionType<'ink>, Diagnostic> {
+    let parameters: [BasicMetadataTypeEnum; 2] = [
+        llvm.context
+            .i8_type()
+            .ptr_type(AddressSpace::Generic)
+            .into(),
+        llvm_index
+            .get_associated_pou_type(method_name)?
+            .ptr_type(AddressSpace::Generic)
+            .into(),
+    ];
+    Ok(match index.find_return_type(method_name) {
+        Some(return_type) => llvm_index
+            .get_associated_type(return_type.get_name())?
+            .fn_type(&parameters, false),
+        None => llvm.context.void_type().fn_type(&parameters, false),
+    })
+}
+
+/// generates the function that forwards a call through an interface's method table to the
+/// given pou's (possibly inherited) implementation of the given interface method
+fn generate_interface_method_adapter<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    pou_name: &str,
+    interface_method: &PouIndexEntry,
+) -> Result<FunctionValue<'ink>, Diagnostic> {
+    let interface_method_name = interface_method.get_name();
+    let method = index
+        .find_method(pou_name, interface_method.get_unqualified_name())
+        .ok_or_else(|| {
+            Diagnostic::codegen_error(
+                &format!(
+                    "'{}' does not implement '{}'",
+                    pou_name, interface_method_name
+                ),
+                SourceRange::undefined(),
+            )
+        })?;
+    let target = llvm_index
+        .find_associated_implementation(method.get_name())
+        .ok_or_else(|| Diagnostic::missing_function(SourceRange::undefined()))?;
+
+    let function_type = get_interface_method_type(llvm, index, llvm_index, interface_method_name)?;
+    let function = module.add_function(
+        &format!("__{}_{}", pou_name, interface_method_name),
+        function_type,
+        None,
+    );
+    let block = llvm.context.append_basic_block(function, "entry");
+    llvm.builder.position_at_end(block);
+
+    //an inherited method needs the embedded instance of its super class
+    let mut instance = llvm.builder.build_pointer_cast(
+        function
+            .get_nth_param(0)
+            .map(BasicValueEnum::into_pointer_value)
+            .ok_or_else(|| Diagnostic::missing_function(SourceRange::undefined()))?,
+        llvm_index
+            .get_associated_pou_type(pou_name)?
+            .ptr_type(AddressSpace::Generic),
+        "instance",
+    );
+    let depth = std::iter::once(pou_name)
+        .chain(index.get_super_classes(pou_name))
+        .position(|it| it.eq_ignore_ascii_case(method.get_container()))
+        .unwrap_or_default();
+    for _ in 0..depth {
+        instance = llvm
+            .builder
+            .build_struct_gep(instance, 0, SUPER_MEMBER_NAME)
+            .expect(INTERNAL_LLVM_ERROR);
+    }
+
+    //copy the parameters into the implementation's parameter-struct
+    let parameters = function
+        .get_nth_param(1)
+        .map(BasicValueEnum::into_pointer_value)
+        .ok_or_else(|| Diagnostic::missing_function(SourceRange::undefined()))?;
+    let arguments = llvm.create_local_variable(
+        method.get_name(),
+        &llvm_index.get_associated_pou_type(method.get_name())?,
+    );
+    let declared_parameters = index
+        .get_declared_parameters(interface_method_name)
+        .into_iter()
+        .zip(index.get_declared_parameters(method.get_name()))
+        .collect::<Vec<_>>();
+    let get_parameter_pointers = |source: &VariableIndexEntry, target: &VariableIndexEntry| {
+        Ok::<_, Diagnostic>((
+            llvm.get_member_pointer_from_struct(
+                parameters,
+                source.get_location_in_parent(),
+                source.get_name(),
+                &source.source_location,
+            )?,
+            llvm.get_member_pointer_from_struct(
+                arguments,
+                target.get_location_in_parent(),
+                target.get_name(),
+                &target.source_location,
+            )?,
+        ))
+    };
+    for (source, target) in &declared_parameters {
+        let (source_ptr, target_ptr) = get_parameter_pointers(source, target)?;
+        llvm.builder.build_store(
+            target_ptr,
+            llvm.load_pointer(&source_ptr, source.get_name()),
+        );
+    }
+
+    let call_result = llvm
+        .builder
+        .build_call(target, &[instance.into(), arguments.into()], "call")
+        .try_as_basic_value();
+
+    //copy the outputs back into the interface method's parameter-struct
+    for (source, target) in declared_parameters
+        .iter()
+        .filter(|(source, _)| source.get_variable_type() == VariableType::Output)
+    {
+        let (source_ptr, target_ptr) = get_parameter_pointers(source, target)?;
+        llvm.builder.build_store(
+            source_ptr,
+            llvm.load_pointer(&target_ptr, target.get_name()),
+        );
+    }
+    match call_result.left() {
+        Some(value) => llvm.builder.build_return(Some(&value)),
+        None => llvm.builder.build_return(None),
+    };
+    Ok(function)
+}
+
 impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
     /// creates a new PouGenerator
     ///
@@ -139,12 +371,29 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         index: &'cg Index,
         annotations: &'cg AstAnnotations,
         llvm_index: &'cg LlvmTypedIndex<'ink>,
+        debug: Option<&'cg DebugBuilder<'ink, 'cg>>,
+        options: CodeGenOptions,
     ) -> PouGenerator<'ink, 'cg> {
         PouGenerator {
             llvm,
             index,
             annotations,
             llvm_index,
+            debug,
+            options,
+        }
+    }
+
+    /// returns the options the module is generated with
+    pub fn get_options(&self) -> &CodeGenOptions {
+        &self.options
+    }
+
+    /// sets the source location of the following instructions of the given function,
+    /// if debug information is generated
+    pub fn set_debug_location(&self, function: FunctionValue<'ink>, location: &SourceRange) {
+        if let Some(debug) = self.debug {
+            debug.set_location(&self.llvm.builder, function, location);
         }
     }
 
@@ -156,8 +405,6 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
     ) -> Result<FunctionValue<'ink>, Diagnostic> {
         let global_index = self.index;
         //generate a function that takes a instance-struct parameter
-        let pou_name = implementation.get_call_name();
-
         let parameters = self.create_parameters_for_implementation(implementation)?;
 
         let return_type = match global_index.find_return_type(implementation.get_type_name()) {
@@ -172,7 +419,11 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         let function_declaration =
             self.create_llvm_function_type(parameters, variadic, return_type)?;
 
-        let curr_f = module.add_function(pou_name, function_declaration, None);
+        let curr_f = module.add_function(
+            &implementation.get_symbol_name(),
+            function_declaration,
+            None,
+        );
         Ok(curr_f)
     }
 
@@ -223,6 +474,7 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
     pub fn generate_implementation(
         &self,
         implementation: &Implementation,
+        module: &Module<'ink>,
     ) -> Result<(), Diagnostic> {
         let context = self.llvm.context;
         let mut local_index = LlvmTypedIndex::create_child(self.llvm_index);
@@ -238,15 +490,27 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                     implementation.location.clone(),
                 )
             })?;
+        //weak_odr instead of linkonce_odr, so unreferenced definitions are not discarded
+        if implementation.linkage == LinkageType::Weak {
+            current_function.set_linkage(Linkage::WeakODR);
+        }
 
         //generate the body
         let block = context.append_basic_block(current_function, "entry");
         self.llvm.builder.position_at_end(block);
+        if let Some(debug) = self.debug {
+            debug.register_function(
+                self.index,
+                &self.llvm.builder,
+                implementation,
+                current_function,
+            );
+        }
 
         let mut param_index = 0;
 
-        if let PouType::Method { .. } = implementation.pou_type {
-            let class_name = implementation.type_name.split('.').collect::<Vec<&str>>()[0];
+        if let PouType::Method { owner_class } = &implementation.pou_type {
+            let class_name = owner_class.as_str();
             let class_members = self.index.get_container_members(class_name);
             self.generate_local_struct_variable_accessors(
                 param_index,
@@ -255,6 +519,12 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                 current_function,
                 &class_members,
             )?;
+            self.generate_inherited_struct_variable_accessors(
+                param_index,
+                &mut local_index,
+                class_name,
+                current_function,
+            )?;
             param_index += 1;
         }
 
@@ -276,6 +546,22 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                 current_function,
                 &pou_members,
             )?;
+            self.generate_inherited_struct_variable_accessors(
+                param_index,
+                &mut local_index,
+                &implementation.type_name,
+                current_function,
+            )?;
+        }
+
+        if let Some(debug) = self.debug {
+            for member in &pou_members {
+                if let Some(storage) =
+                    local_index.find_loaded_associated_variable_value(member.get_qualified_name())
+                {
+                    debug.declare_variable(self.index, current_function, member, storage, block);
+                }
+            }
         }
 
         let function_context = FunctionContext {
@@ -304,11 +590,14 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                 self,
                 &local_index,
                 &function_context,
+                module,
             );
             statement_gen.generate_body(&implementation.statements)?
         }
 
         // generate return statement
+        let end = implementation.location.get_end();
+        self.set_debug_location(current_function, &SourceRange::new(end..end));
         self.generate_return_statement(&function_context, &local_index)?;
 
         Ok(())
@@ -448,6 +737,49 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         Ok(())
     }
 
+    /// generates a load-statement for the members inherited from the super classes of the
+    /// given pou. Every derived pou embeds the instance of its super class as its first member.
+    fn generate_inherited_struct_variable_accessors(
+        &self,
+        arg_index: u32,
+        index: &mut LlvmTypedIndex<'ink>,
+        type_name: &str,
+        current_function: FunctionValue<'ink>,
+    ) -> Result<(), Diagnostic> {
+        let super_classes = self.index.get_super_classes(type_name);
+        if super_classes.is_empty() {
+            return Ok(());
+        }
+
+        let mut struct_ptr = current_function
+            .get_nth_param(arg_index)
+            .map(BasicValueEnum::into_pointer_value)
+            .ok_or_else(|| Diagnostic::missing_function(SourceRange::undefined()))?;
+        for super_class in super_classes {
+            struct_ptr = self
+                .llvm
+                .builder
+                .build_struct_gep(struct_ptr, 0, SUPER_MEMBER_NAME)
+                .expect(INTERNAL_LLVM_ERROR);
+
+            let members = self
+                .index
+                .get_container_members(super_class)
+                .into_iter()
+                .filter(|it| !it.is_temp() && !it.is_return());
+            for m in members {
+                let ptr = self
+                    .llvm
+                    .builder
+                    .build_struct_gep(struct_ptr, m.get_location_in_parent(), m.get_name())
+                    .expect(INTERNAL_LLVM_ERROR);
+                index.associate_loaded_local_variable(super_class, m.get_name(), ptr)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// generates assignment statements for initialized variables in the VAR-block
     ///
     /// - `blocks` - all declaration blocks of the current pou
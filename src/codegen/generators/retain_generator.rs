@@ -0,0 +1,134 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+
+/// generates the entry points to save & restore the retained variables (see `retain`)
+use inkwell::{
+    module::Module,
+    types::BasicTypeEnum,
+    values::{IntValue, PointerValue},
+    AddressSpace,
+};
+
+use crate::{
+    ast::SourceRange,
+    diagnostics::{Diagnostic, INTERNAL_LLVM_ERROR},
+    index::Index,
+    retain::{self, RetainedVariable},
+};
+
+use super::llvm::Llvm;
+
+/// generates `__retain_size`, `__retain_save` and `__retain_restore` into the given module,
+/// nothing is generated if there are no retained variables. The symbols of the retained
+/// variables need to be declared in the module
+pub fn generate_retain_functions<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+) -> Result<(), Diagnostic> {
+    let variables = retain::collect_retained_variables(index);
+    if variables.is_empty() {
+        return Ok(());
+    }
+
+    let size_type = llvm.context.i64_type();
+    let image_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+    let size_function =
+        module.add_function(retain::SIZE_FUNCTION, size_type.fn_type(&[], false), None);
+    let save_function = module.add_function(
+        retain::SAVE_FUNCTION,
+        llvm.context
+            .void_type()
+            .fn_type(&[image_type.into()], false),
+        None,
+    );
+    let restore_function = module.add_function(
+        retain::RESTORE_FUNCTION,
+        llvm.context
+            .void_type()
+            .fn_type(&[image_type.into()], false),
+        None,
+    );
+
+    let save_block = llvm.context.append_basic_block(save_function, "entry");
+    let restore_block = llvm.context.append_basic_block(restore_function, "entry");
+    let save_image = save_function
+        .get_nth_param(0)
+        .map(|it| it.into_pointer_value())
+        .expect(INTERNAL_LLVM_ERROR);
+    let restore_image = restore_function
+        .get_nth_param(0)
+        .map(|it| it.into_pointer_value())
+        .expect(INTERNAL_LLVM_ERROR);
+
+    //the variables are copied one after the other, the image has no padding
+    let mut offset = size_type.const_zero();
+    for variable in &variables {
+        llvm.builder.position_at_end(save_block);
+        let (pointer, size) = get_variable_pointer(module, llvm, variable)?;
+        let target = unsafe { llvm.builder.build_in_bounds_gep(save_image, &[offset], "") };
+        llvm.builder
+            .build_memcpy(target, 1, pointer, 1, size)
+            .map_err(|err| Diagnostic::codegen_error(err, SourceRange::undefined()))?;
+
+        llvm.builder.position_at_end(restore_block);
+        let (pointer, size) = get_variable_pointer(module, llvm, variable)?;
+        let source = unsafe {
+            llvm.builder
+                .build_in_bounds_gep(restore_image, &[offset], "")
+        };
+        llvm.builder
+            .build_memcpy(pointer, 1, source, 1, size)
+            .map_err(|err| Diagnostic::codegen_error(err, SourceRange::undefined()))?;
+
+        offset = offset.const_add(size);
+    }
+    llvm.builder.position_at_end(save_block);
+    llvm.builder.build_return(None);
+    llvm.builder.position_at_end(restore_block);
+    llvm.builder.build_return(None);
+
+    let size_block = llvm.context.append_basic_block(size_function, "entry");
+    llvm.builder.position_at_end(size_block);
+    llvm.builder.build_return(Some(&offset));
+    Ok(())
+}
+
+/// returns the pointer to the given retained variable and its size, a member of a program
+/// instance is addressed at the builder's current position
+fn get_variable_pointer<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    variable: &RetainedVariable,
+) -> Result<(PointerValue<'ink>, IntValue<'ink>), Diagnostic> {
+    let global = module
+        .get_global(&variable.get_symbol())
+        .ok_or_else(|| {
+            Diagnostic::codegen_error(
+                &format!(
+                    "Cannot find the symbol of {}",
+                    variable.get_qualified_name()
+                ),
+                variable.global.source_location.clone(),
+            )
+        })?
+        .as_pointer_value();
+    let pointer = match variable.member {
+        Some((member, member_index)) => llvm.get_member_pointer_from_struct(
+            global,
+            member_index,
+            member.get_name(),
+            &member.source_location,
+        )?,
+        None => global,
+    };
+    let size = BasicTypeEnum::try_from(pointer.get_type().get_element_type())
+        .ok()
+        .and_then(|it| it.size_of())
+        .ok_or_else(|| {
+            Diagnostic::codegen_error(
+                "Couldn't determine type size",
+                variable.global.source_location.clone(),
+            )
+        })?;
+    Ok((pointer, size))
+}
@@ -20,6 +20,7 @@ use inkwell::{
     basic_block::BasicBlock,
     builder::Builder,
     context::Context,
+    module::Module,
     values::{BasicValueEnum, FunctionValue},
 };
 use std::ops::Range;
@@ -40,6 +41,7 @@ pub struct StatementCodeGenerator<'a, 'b> {
     pou_generator: &'b PouGenerator<'a, 'b>,
     llvm_index: &'b LlvmTypedIndex<'a>,
     function_context: &'b FunctionContext<'a>,
+    module: &'b Module<'a>,
 
     pub load_prefix: String,
     pub load_suffix: String,
@@ -59,6 +61,7 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         pou_generator: &'b PouGenerator<'a, 'b>,
         llvm_index: &'b LlvmTypedIndex<'a>,
         linking_context: &'b FunctionContext<'a>,
+        module: &'b Module<'a>,
     ) -> StatementCodeGenerator<'a, 'b> {
         StatementCodeGenerator {
             llvm,
@@ -67,6 +70,7 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
             pou_generator,
             llvm_index,
             function_context: linking_context,
+            module,
             load_prefix: "load_".to_string(),
             load_suffix: "".to_string(),
             current_loop_exit: None,
@@ -82,6 +86,8 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
             self.annotations,
             self.llvm_index,
             self.function_context,
+            self.module,
+            *self.pou_generator.get_options(),
         )
     }
 
@@ -110,6 +116,8 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
     ///
     /// - `statement` the statement to be generated
     pub fn generate_statement(&self, statement: &AstStatement) -> Result<(), Diagnostic> {
+        self.pou_generator
+            .set_debug_location(self.function_context.function, &statement.get_location());
         match statement {
             AstStatement::EmptyStatement { .. } => {
                 //nothing to generate
@@ -6,10 +6,17 @@ use crate::{
     diagnostics::{Diagnostic, ErrNo},
     index::{Index, PouIndexEntry},
     resolver::AstAnnotations,
+    retain,
+};
+use inkwell::{
+    module::{Linkage, Module},
+    values::GlobalValue,
 };
-use inkwell::{module::Module, values::GlobalValue};
 
-use crate::{codegen::llvm_index::LlvmTypedIndex, index::VariableIndexEntry};
+use crate::{
+    codegen::{llvm_index::LlvmTypedIndex, DefinedSymbols},
+    index::VariableIndexEntry,
+};
 
 use super::{
     data_type_generator::get_default_for,
@@ -23,6 +30,7 @@ pub fn generate_global_variables<'ctx, 'b>(
     global_index: &'b Index,
     annotations: &'b AstAnnotations,
     types_index: &'b LlvmTypedIndex<'ctx>,
+    symbols: DefinedSymbols,
 ) -> Result<LlvmTypedIndex<'ctx>, Diagnostic> {
     let mut index = LlvmTypedIndex::default();
 
@@ -59,6 +67,7 @@ pub fn generate_global_variables<'ctx, 'b>(
             annotations,
             types_index,
             variable,
+            symbols,
         )
         .map_err(|err| match err.get_type() {
             ErrNo::codegen__missing_function | ErrNo::reference__unresolved => {
@@ -77,6 +86,7 @@ pub fn generate_global_variables<'ctx, 'b>(
 /// - `llvm` the struct used to generate IR-code
 /// - `index` the global symbol table, the global variable will be registerd as a new symbol
 /// - `global_variable` the variable to generate
+/// - `symbols` the variables that are defined, the other variables are only declared
 pub fn generate_global_variable<'ctx, 'b>(
     module: &'b Module<'ctx>,
     llvm: &'b Llvm<'ctx>,
@@ -84,6 +94,7 @@ pub fn generate_global_variable<'ctx, 'b>(
     annotations: &'b AstAnnotations,
     index: &'b LlvmTypedIndex<'ctx>,
     global_variable: &VariableIndexEntry,
+    symbols: DefinedSymbols,
 ) -> Result<GlobalValue<'ctx>, Diagnostic> {
     let type_name = global_variable.get_type_name();
     let variable_type = index.get_associated_type(type_name)?;
@@ -106,10 +117,17 @@ pub fn generate_global_variable<'ctx, 'b>(
         None
     };
 
-    let mut global_ir_variable =
-        llvm.create_global_variable(module, global_variable.get_name(), variable_type);
+    let mut global_ir_variable = llvm.create_global_variable(
+        module,
+        &crate::index::get_symbol_name(global_variable.get_name()),
+        variable_type,
+    );
+    let declaring_file = global_variable.source_location.get_file_id();
     if global_variable.is_external() {
         global_ir_variable = global_ir_variable.make_external();
+    } else if !symbols.defines(declaring_file) {
+        //the variable is defined in another module
+        return Ok(global_ir_variable);
     } else {
         let initial_value = initial_value
             // 2nd try: find an associated default value for the declared type
@@ -126,6 +144,16 @@ pub fn generate_global_variable<'ctx, 'b>(
                 ));
             }
         }
+        if symbols.is_partial() && declaring_file.is_none() {
+            //compiler-generated variables are part of every module that uses them
+            global_ir_variable = global_ir_variable.make_private();
+        }
+        if global_variable.is_weak() {
+            global_ir_variable.set_linkage(Linkage::WeakODR);
+        }
+        if let Some(section) = retain::get_section(global_index, global_variable) {
+            global_ir_variable.set_section(section);
+        }
     }
     Ok(global_ir_variable)
 }
@@ -1,5 +1,5 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
-use crate::test_utils::tests::{codegen, generate_with_empty_program};
+use crate::test_utils::tests::{codegen, codegen_with_bounds_check, generate_with_empty_program};
 
 #[test]
 fn program_with_variables_and_references_generates_void_function_and_struct_and_body() {
@@ -988,6 +988,77 @@ fn class_member_access_from_method() {
     insta::assert_snapshot!(result)
 }
 
+#[test]
+fn inherited_members_are_accessed_through_the_super_instance() {
+    let result = codegen(
+        "
+    FUNCTION_BLOCK base
+        VAR x : INT; END_VAR
+    END_FUNCTION_BLOCK
+
+    FUNCTION_BLOCK derived EXTENDS base
+        VAR y : INT; END_VAR
+    END_FUNCTION_BLOCK
+
+    PROGRAM main
+        VAR d : derived; END_VAR
+        d.y := 1;
+        d.x := 2;
+        d(x := 3);
+    END_PROGRAM
+        ",
+    );
+
+    //only the accesses to the inherited x need the embedded instance of base
+    let super_geps = result
+        .lines()
+        .filter(|it| it.trim_start().starts_with("%__SUPER") && it.contains("getelementptr"))
+        .count();
+    assert_eq!(super_geps, 2, "{}", result);
+}
+
+#[test]
+fn interface_method_adapter_copies_the_outputs_back() {
+    let result = codegen(
+        "
+    INTERFACE ICounter
+        METHOD next
+            VAR_INPUT step : INT; END_VAR
+            VAR_OUTPUT value : INT; END_VAR
+        END_METHOD
+    END_INTERFACE
+
+    CLASS Counter IMPLEMENTS ICounter
+        VAR total : INT; END_VAR
+        METHOD next
+            VAR_INPUT step : INT; END_VAR
+            VAR_OUTPUT value : INT; END_VAR
+            total := total + step;
+            value := total;
+        END_METHOD
+    END_CLASS
+
+    PROGRAM main
+        VAR c : Counter; counter : ICounter; v : INT; END_VAR
+        counter := c;
+        counter.next(step := 2, value => v);
+    END_PROGRAM
+        ",
+    );
+
+    let adapter = result
+        .split("define void @__Counter_ICounter.next(")
+        .nth(1)
+        .and_then(|it| it.split("\n}\n").next())
+        .unwrap_or_else(|| panic!("{}", result));
+    let (before_call, after_call) = adapter
+        .split_once("call void @Counter.next(")
+        .unwrap_or_else(|| panic!("{}", adapter));
+    //the input is copied into the implementation's parameters, the output back out of them
+    assert_eq!(before_call.matches("store i16").count(), 2, "{}", adapter);
+    assert_eq!(after_call.matches("store i16").count(), 1, "{}", adapter);
+}
+
 #[test]
 fn while_loop_with_if_exit() {
     let result = codegen(
@@ -2631,6 +2702,115 @@ fn sub_range_type_calls_check_function_on_assigment() {
     insta::assert_snapshot!(result);
 }
 
+#[test]
+fn array_access_is_not_checked_without_bounds_check() {
+    let result = codegen(
+        "
+        PROGRAM Main
+        VAR
+            arr : ARRAY[1..10] OF INT;
+            i : INT;
+        END_VAR
+        arr[i] := 7;
+        END_PROGRAM
+        ",
+    );
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn array_access_traps_outside_of_its_bounds_with_bounds_check() {
+    let result = codegen_with_bounds_check(
+        "
+        PROGRAM Main
+        VAR
+            arr : ARRAY[1..10] OF INT;
+            i : INT;
+        END_VAR
+        arr[i] := 7;
+        END_PROGRAM
+        ",
+    )
+    .unwrap();
+
+    //the INT index is compared with the bounds before it is cast to a DINT
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn every_dimension_of_an_array_access_is_checked_with_bounds_check() {
+    let result = codegen_with_bounds_check(
+        "
+        PROGRAM Main
+        VAR
+            arr : ARRAY[0..3, -2..2] OF INT;
+            i : USINT;
+            j : DINT;
+        END_VAR
+        arr[i, j] := 7;
+        END_PROGRAM
+        ",
+    )
+    .unwrap();
+
+    //an unsigned index cannot be below 0, only its upper bound is compared
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn array_access_calls_check_bounds_with_bounds_check() {
+    let result = codegen_with_bounds_check(
+        "
+        FUNCTION CheckBounds : DINT
+        VAR_INPUT
+            index : DINT;
+            lower : DINT;
+            upper : DINT;
+        END_VAR
+        CheckBounds := index;
+        END_FUNCTION
+
+        PROGRAM Main
+        VAR
+            arr : ARRAY[1..10] OF INT;
+            i : INT;
+        END_VAR
+        arr[i] := 7;
+        END_PROGRAM
+        ",
+    )
+    .unwrap();
+
+    //the returned index is accessed
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn array_accesses_in_check_bounds_are_not_checked() {
+    let result = codegen_with_bounds_check(
+        "
+        VAR_GLOBAL
+            calls : ARRAY[1..10] OF DINT;
+        END_VAR
+
+        FUNCTION CheckBounds : DINT
+        VAR_INPUT
+            index : DINT;
+            lower : DINT;
+            upper : DINT;
+        END_VAR
+        calls[index] := calls[index] + 1;
+        CheckBounds := index;
+        END_FUNCTION
+        ",
+    )
+    .unwrap();
+
+    //neither a recursive call nor a trap
+    insta::assert_snapshot!(result);
+}
+
 #[test]
 fn using_global_consts_in_expressions() {
     //GIVEN some constants used in an expression
@@ -2934,3 +3114,56 @@ fn constant_expressions_in_ranged_type_declaration_are_propagated() {
 
     insta::assert_snapshot!(result);
 }
+
+#[test]
+fn namespaced_symbols_are_valid_c_identifiers() {
+    let result = codegen(
+        "
+        NAMESPACE Vendor.Motion
+            VAR_GLOBAL gSpeed : INT; END_VAR
+
+            FUNCTION scale : INT
+            VAR_INPUT x : INT; END_VAR
+                scale := x * gSpeed;
+            END_FUNCTION
+
+            FUNCTION_BLOCK FB_Axis
+                METHOD move : INT
+                END_METHOD
+            END_FUNCTION_BLOCK
+        END_NAMESPACE
+
+        PROGRAM main
+        VAR a : Vendor.Motion.FB_Axis; r : INT; END_VAR
+            r := Vendor.Motion.scale(Vendor.Motion.gSpeed);
+        END_PROGRAM
+        ",
+    );
+
+    assert!(
+        result.contains("@Vendor__Motion__gSpeed = global i16 0"),
+        "{}",
+        result
+    );
+    assert!(
+        result.contains("define i16 @Vendor__Motion__scale("),
+        "{}",
+        result
+    );
+    assert!(
+        result.contains("define void @Vendor__Motion__FB_Axis("),
+        "{}",
+        result
+    );
+    //methods keep the dot separating them from their function block
+    assert!(
+        result.contains("define i16 @Vendor__Motion__FB_Axis.move("),
+        "{}",
+        result
+    );
+    assert!(
+        result.contains("call i16 @Vendor__Motion__scale("),
+        "{}",
+        result
+    );
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use crate::test_utils::tests::{codegen, codegen_with_debug};
+
+#[test]
+fn no_debug_information_without_debug_level() {
+    let result = codegen(
+        "
+        PROGRAM prg
+        VAR
+            x : DINT;
+        END_VAR
+            x := 1;
+        END_PROGRAM
+        ",
+    );
+
+    assert!(!result.contains("!dbg"));
+    assert!(!result.contains("DICompileUnit"));
+}
+
+#[test]
+fn compile_unit_and_subprograms_are_generated() {
+    let result = codegen_with_debug(
+        "
+        FUNCTION foo : DINT
+        VAR_INPUT
+            a : DINT;
+        END_VAR
+            foo := a;
+        END_FUNCTION
+
+        PROGRAM prg
+        VAR
+            x : DINT;
+        END_VAR
+            x := foo(1);
+        END_PROGRAM
+
+        ACTIONS prg
+        ACTION reset
+            x := 0;
+        END_ACTION
+        END_ACTIONS
+        ",
+    );
+
+    assert!(result.contains("\"Debug Info Version\", i32 3"));
+    assert!(result.contains("!DICompileUnit(language: DW_LANG_C"));
+    assert!(result.contains("!DIFile(filename: \"main.st\""));
+    assert!(result.contains("!DISubprogram(name: \"foo\""));
+    assert!(result.contains("!DISubprogram(name: \"prg\""));
+    assert!(result.contains("!DISubprogram(name: \"prg.reset\""));
+}
+
+#[test]
+fn statements_get_the_line_of_their_source_location() {
+    let result = codegen_with_debug(
+        "PROGRAM prg
+VAR
+    x : DINT;
+END_VAR
+    x := 1;
+
+    x := x + 2;
+END_PROGRAM
+",
+    );
+
+    assert!(result.contains("!DILocation(line: 5, column: 5"));
+    assert!(result.contains("!DILocation(line: 7, column: 5"));
+}
+
+#[test]
+fn variables_are_described_by_their_types() {
+    let result = codegen_with_debug(
+        "
+        TYPE Point : STRUCT
+            x : REAL;
+            y : REAL;
+        END_STRUCT
+        END_TYPE
+
+        TYPE Color : (red, green, blue);
+        END_TYPE
+
+        PROGRAM prg
+        VAR
+            flag : BOOL;
+            counter : INT;
+            values : ARRAY[1..5] OF LINT;
+            text : STRING[10];
+            point : Point;
+            color : Color;
+            point_ref : REF_TO Point;
+        END_VAR
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("call void @llvm.dbg.declare"));
+    assert!(result.contains("!DILocalVariable(name: \"flag\""));
+    assert!(result.contains("!DIBasicType(name: \"BOOL\", size: 8, encoding: DW_ATE_boolean"));
+    assert!(result.contains("!DIBasicType(name: \"INT\", size: 16, encoding: DW_ATE_signed"));
+    assert!(result.contains("!DISubrange(count: 5, lowerBound: 1)"));
+    assert!(result.contains("!DISubrange(count: 11"));
+    assert!(result.contains("!DICompositeType(tag: DW_TAG_structure_type, name: \"Point\""));
+    assert!(result.contains("!DIDerivedType(tag: DW_TAG_member, name: \"y\""));
+    assert!(result.contains("!DIDerivedType(tag: DW_TAG_typedef, name: \"Color\""));
+    assert!(result.contains("!DIDerivedType(tag: DW_TAG_pointer_type"));
+}
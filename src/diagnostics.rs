@@ -1,7 +1,10 @@
 use std::{
+    collections::HashMap,
     error::Error,
     fmt::{self, Display},
     ops::Range,
+    str::FromStr,
+    sync::{Arc, Mutex},
 };
 
 use codespan_reporting::{
@@ -10,8 +13,10 @@ use codespan_reporting::{
     term::termcolor::{ColorChoice, StandardStream},
 };
 use inkwell::support::LLVMString;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use crate::ast::{DataTypeDeclaration, DiagnosticInfo, PouType, SourceRange};
+use crate::ast::{DataTypeDeclaration, DiagnosticInfo, PouType, SourceRange, Suppression};
 
 pub const INTERNAL_LLVM_ERROR: &str = "internal llvm codegen error";
 
@@ -21,6 +26,8 @@ pub enum Diagnostic {
         message: String,
         range: SourceRange,
         err_no: ErrNo,
+        /// other locations that help to understand the error (e.g. a declaration)
+        related: Vec<RelatedLocation>,
     },
     GeneralError {
         message: String,
@@ -29,67 +36,160 @@ pub enum Diagnostic {
     ImprovementSuggestion {
         message: String,
         range: SourceRange,
+        err_no: ErrNo,
     },
 }
 
-#[allow(non_camel_case_types)]
+/// a location related to a diagnostic, reported as a note next to the diagnostic
 #[derive(PartialEq, Debug, Clone)]
-pub enum ErrNo {
-    undefined,
+pub struct RelatedLocation {
+    pub message: String,
+    pub range: SourceRange,
+}
+
+/// declares the `ErrNo`s together with their stable code, the explanation of a code is read
+/// from `diagnostics/explanations/<code>.md`
+macro_rules! error_codes {
+    ($($err_no:ident => $code:literal,)*) => {
+        #[allow(non_camel_case_types)]
+        #[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
+        pub enum ErrNo {
+            $($err_no,)*
+        }
+
+        impl ErrNo {
+            /// all codes, in the order of their declaration
+            pub const ALL: &'static [ErrNo] = &[$(ErrNo::$err_no,)*];
+
+            /// returns the stable code reported with the diagnostics (e.g. `E010`)
+            pub fn get_code(&self) -> &'static str {
+                match self {
+                    $(ErrNo::$err_no => $code,)*
+                }
+            }
+
+            /// returns the explanation of this code printed by `--explain`
+            pub fn get_explanation(&self) -> &'static str {
+                match self {
+                    $(ErrNo::$err_no => {
+                        include_str!(concat!("diagnostics/explanations/", $code, ".md"))
+                    })*
+                }
+            }
+        }
+    };
+}
+
+//codes are never reused or renumbered, new codes get the next free number
+error_codes! {
+    undefined => "E000",
 
     //general
-    general__io_err,
-    general__param_err,
+    general__io_err => "E001",
+    general__param_err => "E002",
 
     //syntax
-    syntax__generic_error,
-    syntax__missing_token,
-    syntax__unexpected_token,
+    syntax__generic_error => "E004",
+    syntax__missing_token => "E005",
+    syntax__unexpected_token => "E006",
+    syntax__missing_word_separator => "E044",
+    syntax__non_standard_keyword => "E045",
+    syntax__mismatched_parentheses => "E046",
+    syntax__unusual_parentheses => "E047",
+
+    //pragmas
+    pragma__invalid_location => "E048",
+    pragma__unknown => "E049",
+    pragma__unattached => "E050",
+    pragma__unknown_diagnostic_code => "E051",
 
     //semantic
     // pou related
-    pou__missing_return_type,
-    pou__unexpected_return_type,
-    pou__unsupported_return_type,
-    pou__empty_variable_block,
-    pou__missing_action_container,
+    pou__missing_return_type => "E007",
+    pou__unexpected_return_type => "E008",
+    pou__unsupported_return_type => "E009",
+    pou__empty_variable_block => "E010",
+    pou__missing_action_container => "E011",
+    pou__invalid_super_class => "E012",
+    pou__invalid_interface => "E013",
+    pou__missing_interface_method => "E014",
+    pou__interface_method_mismatch => "E015",
+    pou__mixed_retain => "E043",
+    pou__invalid_check_function => "E052",
 
     //variable related
-    var__unresolved_constant,
-    var__invalid_constant_block,
-    var__invalid_constant,
-    var__cannot_assign_to_const,
-    var__invalid_assignment,
-    var__missing_type,
+    var__unresolved_constant => "E016",
+    var__invalid_constant_block => "E017",
+    var__invalid_constant => "E018",
+    var__cannot_assign_to_const => "E019",
+    var__invalid_assignment => "E020",
+    var__missing_type => "E021",
 
     //reference related
-    reference__unresolved,
-    reference__illegal_access,
+    reference__unresolved => "E022",
+    reference__illegal_access => "E023",
 
     //type related
-    type__cast_error,
-    type__unknown_type,
-    type__literal_out_of_range,
-    type__incompatible_literal_cast,
-    type__incompatible_directaccess,
-    type__incompatible_directaccess_variable,
-    type__incompatible_directaccess_range,
-    type__incompatible_arrayaccess_range,
-    type__incompatible_arrayaccess_variable,
-    type__incompatible_arrayaccess_type,
-    type__expected_literal,
-    type__invalid_nature,
-    type__unknown_nature,
-    type__unresolved_generic,
-    type__incompatible_size,
+    type__cast_error => "E024",
+    type__unknown_type => "E025",
+    type__literal_out_of_range => "E026",
+    type__incompatible_literal_cast => "E027",
+    type__incompatible_directaccess => "E028",
+    type__incompatible_directaccess_variable => "E029",
+    type__incompatible_directaccess_range => "E030",
+    type__incompatible_arrayaccess_range => "E031",
+    type__incompatible_arrayaccess_variable => "E032",
+    type__incompatible_arrayaccess_type => "E033",
+    type__expected_literal => "E034",
+    type__invalid_nature => "E035",
+    type__unknown_nature => "E036",
+    type__unresolved_generic => "E037",
+    type__incompatible_size => "E038",
 
     //codegen related
-    codegen__general,
-    codegen__missing_function,
-    codegen__missing_compare_function,
+    codegen__general => "E039",
+    codegen__missing_function => "E040",
+    codegen__missing_compare_function => "E041",
 
     //linker
-    linker__generic_error,
+    linker__generic_error => "E042",
+}
+
+impl ErrNo {
+    /// returns the name used to refer to this code (e.g. `pou__empty_variable_block`)
+    pub fn get_name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// returns true if the diagnostics of this code are reported as warnings by default, only
+    /// the levels of these codes can be configured or disabled
+    pub fn is_warning_by_default(&self) -> bool {
+        matches!(
+            self,
+            ErrNo::syntax__missing_word_separator
+                | ErrNo::syntax__non_standard_keyword
+                | ErrNo::syntax__mismatched_parentheses
+                | ErrNo::syntax__unusual_parentheses
+                | ErrNo::pragma__invalid_location
+                | ErrNo::pragma__unknown
+                | ErrNo::pragma__unattached
+                | ErrNo::pragma__unknown_diagnostic_code
+        )
+    }
+}
+
+impl FromStr for ErrNo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrNo::ALL
+            .iter()
+            .find(|it| {
+                it.get_code().eq_ignore_ascii_case(s) || it.get_name().eq_ignore_ascii_case(s)
+            })
+            .cloned()
+            .ok_or_else(|| format!("Unknown diagnostic code '{}'", s))
+    }
 }
 
 impl<T: Error> From<T> for Diagnostic {
@@ -102,69 +202,84 @@ impl<T: Error> From<T> for Diagnostic {
 }
 
 impl Diagnostic {
-    pub fn syntax_error(message: &str, range: SourceRange) -> Diagnostic {
+    /// creates an error with the given code, related locations can be added with `with_related`
+    pub fn error(message: String, range: SourceRange, err_no: ErrNo) -> Diagnostic {
         Diagnostic::SyntaxError {
-            message: message.to_string(),
+            message,
             range,
-            err_no: ErrNo::syntax__generic_error,
+            err_no,
+            related: vec![],
         }
     }
 
+    /// creates a suggestion that is reported as a warning unless the policy says otherwise
+    pub fn improvement(message: String, range: SourceRange, err_no: ErrNo) -> Diagnostic {
+        Diagnostic::ImprovementSuggestion {
+            message,
+            range,
+            err_no,
+        }
+    }
+
+    pub fn syntax_error(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::error(message.to_string(), range, ErrNo::syntax__generic_error)
+    }
+
     pub fn unexpected_token_found(expected: &str, found: &str, range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Unexpected token: expected {} but found {}",
                 expected, found
             ),
             range,
-            err_no: ErrNo::syntax__unexpected_token,
-        }
+            ErrNo::syntax__unexpected_token,
+        )
     }
 
     pub fn unexpected_initializer_on_function_return(range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Return types cannot have a default value".into(),
+        Diagnostic::error(
+            "Return types cannot have a default value".into(),
             range,
-            err_no: ErrNo::syntax__unexpected_token,
-        }
+            ErrNo::syntax__unexpected_token,
+        )
     }
 
     pub fn return_type_not_supported(pou_type: &PouType, range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "POU Type {:?} does not support a return type. Did you mean Function?",
                 pou_type
             ),
             range,
-            err_no: ErrNo::pou__unexpected_return_type,
-        }
+            ErrNo::pou__unexpected_return_type,
+        )
     }
 
     pub fn function_unsupported_return_type(data_type: &DataTypeDeclaration) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Data Type {:?} not supported as a function return type!",
                 data_type
             ),
-            range: data_type.get_location(),
-            err_no: ErrNo::pou__unsupported_return_type,
-        }
+            data_type.get_location(),
+            ErrNo::pou__unsupported_return_type,
+        )
     }
 
     pub fn function_return_missing(range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Function Return type missing".into(),
+        Diagnostic::error(
+            "Function Return type missing".into(),
             range,
-            err_no: ErrNo::pou__missing_return_type,
-        }
+            ErrNo::pou__missing_return_type,
+        )
     }
 
     pub fn missing_function(location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Cannot generate code outside of function context.".into(),
-            range: location,
-            err_no: ErrNo::codegen__missing_function,
-        }
+        Diagnostic::error(
+            "Cannot generate code outside of function context.".into(),
+            location,
+            ErrNo::codegen__missing_function,
+        )
     }
 
     pub fn missing_compare_function(
@@ -172,46 +287,118 @@ impl Diagnostic {
         data_type: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Missing compare function 'FUNCTION {} : BOOL VAR_INPUT a,b : {}; END_VAR ...'.",
                 function_name, data_type
             ),
-            range: location,
-            err_no: ErrNo::codegen__missing_compare_function,
-        }
+            location,
+            ErrNo::codegen__missing_compare_function,
+        )
     }
 
     pub fn missing_token(epxected_token: &str, range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Missing expected Token {}", epxected_token),
+        Diagnostic::error(
+            format!("Missing expected Token {}", epxected_token),
             range,
-            err_no: ErrNo::syntax__missing_token,
-        }
+            ErrNo::syntax__missing_token,
+        )
     }
 
     pub fn missing_action_container(range: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Missing Actions Container Name".to_string(),
+        Diagnostic::error(
+            "Missing Actions Container Name".to_string(),
             range,
-            err_no: ErrNo::pou__missing_action_container,
-        }
+            ErrNo::pou__missing_action_container,
+        )
+    }
+
+    pub fn invalid_super_class(super_class: &str, reason: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::error(
+            format!("Invalid super class '{}': {}", super_class, reason),
+            range,
+            ErrNo::pou__invalid_super_class,
+        )
+    }
+
+    pub fn invalid_interface(interface: &str, reason: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::error(
+            format!("Invalid interface '{}': {}", interface, reason),
+            range,
+            ErrNo::pou__invalid_interface,
+        )
+    }
+
+    pub fn missing_interface_method(
+        pou: &str,
+        interface_method: &str,
+        range: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::error(
+            format!(
+                "'{}' does not implement interface method '{}'",
+                pou, interface_method
+            ),
+            range,
+            ErrNo::pou__missing_interface_method,
+        )
+    }
+
+    pub fn interface_method_mismatch(
+        method: &str,
+        interface_method: &str,
+        range: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::error(
+            format!(
+                "Method '{}' does not match the signature of interface method '{}'",
+                method, interface_method
+            ),
+            range,
+            ErrNo::pou__interface_method_mismatch,
+        )
+    }
+
+    pub fn mixed_retain(pou: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::error(
+            format!(
+                "The members of program '{}' must all be retained the same way, its instance is retained as a whole",
+                pou
+            ),
+            range,
+            ErrNo::pou__mixed_retain,
+        )
+    }
+
+    pub fn invalid_check_function(
+        function: &str,
+        signature: &str,
+        range: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::error(
+            format!(
+                "The check function '{}' must be declared as '{}'",
+                function, signature
+            ),
+            range,
+            ErrNo::pou__invalid_check_function,
+        )
     }
 
     pub fn unresolved_reference(reference: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Could not resolve reference to {:}", reference),
-            range: location,
-            err_no: ErrNo::reference__unresolved,
-        }
+        Diagnostic::error(
+            format!("Could not resolve reference to {:}", reference),
+            location,
+            ErrNo::reference__unresolved,
+        )
     }
 
     pub fn illegal_access(reference: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Illegal access to private member {:}", reference),
-            range: location,
-            err_no: ErrNo::reference__illegal_access,
-        }
+        Diagnostic::error(
+            format!("Illegal access to private member {:}", reference),
+            location,
+            ErrNo::reference__illegal_access,
+        )
     }
 
     pub fn unresolved_generic_type(
@@ -219,30 +406,30 @@ impl Diagnostic {
         nature: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Could not resolve generic type {} with nature {}",
                 symbol, nature
             ),
-            range: location,
-            err_no: ErrNo::type__unresolved_generic,
-        }
+            location,
+            ErrNo::type__unresolved_generic,
+        )
     }
 
     pub fn unknown_type(type_name: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Unknown type: {:}", type_name),
-            range: location,
-            err_no: ErrNo::type__unknown_type,
-        }
+        Diagnostic::error(
+            format!("Unknown type: {:}", type_name),
+            location,
+            ErrNo::type__unknown_type,
+        )
     }
 
     pub fn casting_error(type_name: &str, target_type: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Cannot cast from {:} to {:}", type_name, target_type),
-            range: location,
-            err_no: ErrNo::type__cast_error,
-        }
+        Diagnostic::error(
+            format!("Cannot cast from {:} to {:}", type_name, target_type),
+            location,
+            ErrNo::type__cast_error,
+        )
     }
 
     pub fn incompatible_directaccess(
@@ -250,14 +437,14 @@ impl Diagnostic {
         access_size: u64,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "{}-Wise access requires a Numerical type larger than {} bits",
                 access_type, access_size
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_directaccess,
-        }
+            location,
+            ErrNo::type__incompatible_directaccess,
+        )
     }
 
     pub fn incompatible_directaccess_range(
@@ -266,67 +453,59 @@ impl Diagnostic {
         access_range: Range<u64>,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "{}-Wise access for type {} must be in the range {}..{}",
                 access_type, target_type, access_range.start, access_range.end
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_directaccess_range,
-        }
+            location,
+            ErrNo::type__incompatible_directaccess_range,
+        )
     }
 
     pub fn incompatible_directaccess_variable(
         access_type: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(format!(
                 "Invalid type {} for direct variable access. Only variables of Integer types are allowed",
                 access_type
-            ),
-            range: location,
-            err_no: ErrNo::type__incompatible_directaccess_variable,
-        }
+            ), location, ErrNo::type__incompatible_directaccess_variable)
     }
 
     pub fn incompatible_array_access_range(
         range: Range<i128>,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Array access must be in the range {}..{}",
                 range.start, range.end
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_arrayaccess_range,
-        }
+            location,
+            ErrNo::type__incompatible_arrayaccess_range,
+        )
     }
 
     pub fn incompatible_array_access_variable(
         access_type: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Invalid type {} for array access. Only variables of Array types are allowed",
                 access_type
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_arrayaccess_variable,
-        }
+            location,
+            ErrNo::type__incompatible_arrayaccess_variable,
+        )
     }
 
     pub fn incompatible_array_access_type(access_type: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(format!(
                 "Invalid type {} for array access. Only variables of Integer types are allowed to access an array",
                 access_type
-            ),
-            range: location,
-            err_no: ErrNo::type__incompatible_arrayaccess_variable,
-        }
+            ), location, ErrNo::type__incompatible_arrayaccess_type)
     }
 
     pub fn incompatible_literal_cast(
@@ -334,22 +513,22 @@ impl Diagnostic {
         literal_type: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Literal {:} is not campatible to {:}",
                 literal_type, cast_type
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_literal_cast,
-        }
+            location,
+            ErrNo::type__incompatible_literal_cast,
+        )
     }
 
     pub fn literal_expected(location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Expected literal".into(),
-            range: location,
-            err_no: ErrNo::type__expected_literal,
-        }
+        Diagnostic::error(
+            "Expected literal".into(),
+            location,
+            ErrNo::type__expected_literal,
+        )
     }
 
     pub fn literal_out_of_range(
@@ -357,19 +536,19 @@ impl Diagnostic {
         range_hint: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Literal {:} out of range ({})", literal, range_hint),
-            range: location,
-            err_no: ErrNo::type__literal_out_of_range,
-        }
+        Diagnostic::error(
+            format!("Literal {:} out of range ({})", literal, range_hint),
+            location,
+            ErrNo::type__literal_out_of_range,
+        )
     }
 
     pub fn empty_variable_block(location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "Variable block is empty".into(),
-            range: location,
-            err_no: ErrNo::pou__empty_variable_block,
-        }
+        Diagnostic::error(
+            "Variable block is empty".into(),
+            location,
+            ErrNo::pou__empty_variable_block,
+        )
     }
 
     pub fn unresolved_constant(
@@ -377,41 +556,37 @@ impl Diagnostic {
         reason: Option<&str>,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Unresolved constant '{:}' variable{:}",
                 constant_name,
                 reason
                     .map(|it| format!(": {:}", it))
                     .unwrap_or_else(|| "".into()),
             ),
-            range: location,
-            err_no: ErrNo::pou__empty_variable_block,
-        }
+            location,
+            ErrNo::var__unresolved_constant,
+        )
     }
 
     pub fn invalid_constant_block(location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: "This variable block does not support the CONSTANT modifier".to_string(),
-            range: location,
-            err_no: ErrNo::var__invalid_constant_block,
-        }
+        Diagnostic::error(
+            "This variable block does not support the CONSTANT modifier".to_string(),
+            location,
+            ErrNo::var__invalid_constant_block,
+        )
     }
 
     pub fn invalid_constant(constant_name: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Invalid constant {:} - Functionblock- and Class-instances cannot be delcared constant", constant_name),
-            range: location,
-            err_no: ErrNo::var__invalid_constant,
-        }
+        Diagnostic::error(format!("Invalid constant {:} - Functionblock- and Class-instances cannot be delcared constant", constant_name), location, ErrNo::var__invalid_constant)
     }
 
     pub fn cannot_assign_to_constant(qualified_name: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Cannot assign to CONSTANT '{:}'", qualified_name),
-            range: location,
-            err_no: ErrNo::var__cannot_assign_to_const,
-        }
+        Diagnostic::error(
+            format!("Cannot assign to CONSTANT '{:}'", qualified_name),
+            location,
+            ErrNo::var__cannot_assign_to_const,
+        )
     }
 
     pub fn cannot_generate_initializer(variable_name: &str, location: SourceRange) -> Diagnostic {
@@ -425,11 +600,7 @@ impl Diagnostic {
     }
 
     pub fn codegen_error(message: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: message.into(),
-            range: location,
-            err_no: ErrNo::codegen__general,
-        }
+        Diagnostic::error(message.into(), location, ErrNo::codegen__general)
     }
 
     pub fn cannot_generate_call_statement<T: DiagnosticInfo>(operator: &T) -> Diagnostic {
@@ -463,6 +634,22 @@ impl Diagnostic {
         }
     }
 
+    /// an error in the build description, `line` and `column` start at 1 (0 if unknown)
+    pub fn invalid_build_description(reason: &str, line: usize, column: usize) -> Diagnostic {
+        let message = if line > 0 {
+            format!(
+                "Invalid build description at line {}, column {}: {}",
+                line, column, reason
+            )
+        } else {
+            format!("Invalid build description: {}", reason)
+        };
+        Diagnostic::GeneralError {
+            message,
+            err_no: ErrNo::general__param_err,
+        }
+    }
+
     pub fn llvm_error(file: &str, llvm_error: &LLVMString) -> Diagnostic {
         Diagnostic::GeneralError {
             message: format!(
@@ -496,41 +683,41 @@ impl Diagnostic {
         left_type: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Invalid assignment: cannot assign '{:}' to '{:}'",
                 right_type, left_type
             ),
-            range: location,
-            err_no: ErrNo::var__invalid_assignment,
-        }
+            location,
+            ErrNo::var__invalid_assignment,
+        )
     }
 
     pub fn invalid_type_nature(type_name: &str, nature: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "Invalid type nature for generic argument. {} is no {}.",
                 type_name, nature
             ),
-            range: location,
-            err_no: ErrNo::type__invalid_nature,
-        }
+            location,
+            ErrNo::type__invalid_nature,
+        )
     }
 
     pub fn unknown_type_nature(nature: &str, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Unknown type nature {}.", nature),
-            range: location,
-            err_no: ErrNo::type__unknown_nature,
-        }
+        Diagnostic::error(
+            format!("Unknown type nature {}.", nature),
+            location,
+            ErrNo::type__unknown_nature,
+        )
     }
 
     pub fn missing_datatype(reason: Option<&str>, location: SourceRange) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!("Missing datatype {}", reason.unwrap_or("")),
-            range: location,
-            err_no: ErrNo::var__missing_type,
-        }
+        Diagnostic::error(
+            format!("Missing datatype {}", reason.unwrap_or("")),
+            location,
+            ErrNo::var__missing_type,
+        )
     }
 
     pub fn incompatible_type_size(
@@ -539,14 +726,14 @@ impl Diagnostic {
         error: &str,
         location: SourceRange,
     ) -> Diagnostic {
-        Diagnostic::SyntaxError {
-            message: format!(
+        Diagnostic::error(
+            format!(
                 "The type {} {} is too small to {} Pointer",
                 nature, size, error
             ),
-            range: location,
-            err_no: ErrNo::type__incompatible_size,
-        }
+            location,
+            ErrNo::type__incompatible_size,
+        )
     }
 
     pub fn link_error(error: &str) -> Diagnostic {
@@ -572,12 +759,36 @@ impl Diagnostic {
         }
     }
 
+    /// returns the locations related to this diagnostic
+    pub fn get_related(&self) -> &[RelatedLocation] {
+        match self {
+            Diagnostic::SyntaxError { related, .. } => related.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// adds a note pointing to the given related location (e.g. "'x' is declared here"),
+    /// only errors with a location carry notes
+    pub fn with_related(mut self, message: &str, range: SourceRange) -> Diagnostic {
+        if let Diagnostic::SyntaxError { related, .. } = &mut self {
+            related.push(RelatedLocation {
+                message: message.to_string(),
+                range,
+            });
+        }
+        self
+    }
+
+    /// adds a note pointing to the declaration of the given symbol
+    pub fn with_declaration(self, name: &str, range: SourceRange) -> Diagnostic {
+        self.with_related(&format!("'{}' is declared here", name), range)
+    }
+
     pub fn get_type(&self) -> &ErrNo {
         match self {
-            Diagnostic::SyntaxError { err_no, .. } | Diagnostic::GeneralError { err_no, .. } => {
-                err_no
-            }
-            Diagnostic::ImprovementSuggestion { .. } => &ErrNo::undefined,
+            Diagnostic::SyntaxError { err_no, .. }
+            | Diagnostic::GeneralError { err_no, .. }
+            | Diagnostic::ImprovementSuggestion { err_no, .. } => err_no,
         }
     }
 
@@ -587,35 +798,120 @@ impl Diagnostic {
     pub fn relocate(it: Diagnostic, new_location: SourceRange) -> Diagnostic {
         match it {
             Diagnostic::SyntaxError {
-                message, err_no, ..
+                message,
+                err_no,
+                related,
+                ..
             } => Diagnostic::SyntaxError {
                 message,
                 range: new_location,
                 err_no,
+                related,
+            },
+            Diagnostic::ImprovementSuggestion {
+                message, err_no, ..
+            } => Diagnostic::ImprovementSuggestion {
+                message,
+                range: new_location,
+                err_no,
             },
-            Diagnostic::ImprovementSuggestion { message, .. } => {
-                Diagnostic::ImprovementSuggestion {
-                    message,
-                    range: new_location,
-                }
-            }
             _ => it,
         }
     }
 
     pub fn invalid_pragma_location(message: &str, range: SourceRange) -> Diagnostic {
-        Diagnostic::ImprovementSuggestion {
-            message: format!("Invalid pragma location: {}", message),
+        Diagnostic::improvement(
+            format!("Invalid pragma location: {}", message),
             range,
-        }
+            ErrNo::pragma__invalid_location,
+        )
+    }
+
+    pub fn unknown_pragma(name: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            format!("Unknown pragma '{}' will be ignored", name),
+            range,
+            ErrNo::pragma__unknown,
+        )
+    }
+
+    pub fn unattached_pragma(name: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            format!(
+                "Pragma '{}' is not attached to any element and will be ignored",
+                name
+            ),
+            range,
+            ErrNo::pragma__unattached,
+        )
+    }
+
+    pub fn unknown_diagnostic_code(code: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            format!("Unknown diagnostic code '{}' cannot be disabled", code),
+            range,
+            ErrNo::pragma__unknown_diagnostic_code,
+        )
+    }
+
+    pub fn undisableable_diagnostic_code(code: &ErrNo, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            format!(
+                "'{}' is not a warning and cannot be disabled",
+                code.get_name()
+            ),
+            range,
+            ErrNo::pragma__unknown_diagnostic_code,
+        )
+    }
+
+    pub fn missing_word_separator(word: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            format!("the words in {} should be separated by a '_'", word),
+            range,
+            ErrNo::syntax__missing_word_separator,
+        )
+    }
+
+    pub fn non_standard_keyword(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            message.to_string(),
+            range,
+            ErrNo::syntax__non_standard_keyword,
+        )
+    }
+
+    pub fn mismatched_parentheses(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            message.to_string(),
+            range,
+            ErrNo::syntax__mismatched_parentheses,
+        )
+    }
+
+    pub fn unusual_parentheses(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::improvement(
+            message.to_string(),
+            range,
+            ErrNo::syntax__unusual_parentheses,
+        )
+    }
+
+    pub fn invalid_conditional_pragma(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::error(
+            format!("Invalid conditional compilation: {}", message),
+            range,
+            ErrNo::syntax__generic_error,
+        )
     }
 }
 
 /// a diagnostics severity
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Severity {
     Error,
     Warning,
-    _Info,
+    Info,
 }
 
 impl Display for Severity {
@@ -623,27 +919,159 @@ impl Display for Severity {
         let severity = match self {
             Severity::Error => "error",
             Severity::Warning => "warning",
-            Severity::_Info => "info",
+            Severity::Info => "info",
         };
         write!(f, "{}", severity)
     }
 }
 
+/// the level a `DiagnosticPolicy` assigns to the diagnostics of a code
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+    Info,
+    /// the diagnostics are not reported
+    Ignore,
+}
+
+impl DiagnosticLevel {
+    /// returns the severity of this level, `None` if the diagnostics are ignored
+    pub fn get_severity(&self) -> Option<Severity> {
+        match self {
+            DiagnosticLevel::Error => Some(Severity::Error),
+            DiagnosticLevel::Warning => Some(Severity::Warning),
+            DiagnosticLevel::Info => Some(Severity::Info),
+            DiagnosticLevel::Ignore => None,
+        }
+    }
+}
+
+/// the severities of the diagnostics, configured with `-W` options or in the build description
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticPolicy {
+    /// the levels of the configured codes, all other codes keep their default severity
+    pub levels: HashMap<ErrNo, DiagnosticLevel>,
+    /// report all warnings as errors (`-Werror`)
+    pub warnings_as_errors: bool,
+}
+
+impl DiagnosticPolicy {
+    /// applies the given `-W` option, without the leading `-W`:
+    /// * `error` reports all warnings as errors
+    /// * `<code>` reports the code as a warning
+    /// * `no-<code>` ignores the code
+    /// * `error=<code>` and `info=<code>` report the code as an error or an info
+    pub fn apply_option(&mut self, option: &str) -> Result<(), Diagnostic> {
+        if option == "error" {
+            self.warnings_as_errors = true;
+            return Ok(());
+        }
+        let (level, code) = if let Some(code) = option.strip_prefix("no-") {
+            (DiagnosticLevel::Ignore, code)
+        } else if let Some(code) = option.strip_prefix("error=") {
+            (DiagnosticLevel::Error, code)
+        } else if let Some(code) = option.strip_prefix("info=") {
+            (DiagnosticLevel::Info, code)
+        } else {
+            (DiagnosticLevel::Warning, option)
+        };
+        self.set_level(code, level)
+    }
+
+    /// sets the level of the code with the given name, only the levels of codes that are
+    /// warnings by default can be changed
+    pub fn set_level(&mut self, code: &str, level: DiagnosticLevel) -> Result<(), Diagnostic> {
+        let code = code
+            .parse::<ErrNo>()
+            .map_err(|err| Diagnostic::param_error(&err))?;
+        if !code.is_warning_by_default() {
+            return Err(Diagnostic::param_error(&format!(
+                "The level of '{}' cannot be changed, only the levels of warnings can be configured",
+                code.get_name()
+            )));
+        }
+        self.levels.insert(code, level);
+        Ok(())
+    }
+
+    /// returns the severity of the given diagnostic, `None` if it is ignored
+    pub fn get_severity(&self, diagnostic: &Diagnostic) -> Option<Severity> {
+        let severity = match self.levels.get(diagnostic.get_type()) {
+            Some(level) => level.get_severity()?,
+            None => get_default_severity(diagnostic),
+        };
+        if self.warnings_as_errors && severity == Severity::Warning {
+            Some(Severity::Error)
+        } else {
+            Some(severity)
+        }
+    }
+}
+
+/// returns the severity of the given diagnostic if no policy is configured:
+/// ImprovementSuggestions are warnings and everything else is an error
+fn get_default_severity(diagnostic: &Diagnostic) -> Severity {
+    match diagnostic {
+        // improvements become warnings
+        Diagnostic::ImprovementSuggestion { .. } => Severity::Warning,
+        // everything else becomes an error
+        _ => Severity::Error,
+    }
+}
+
+/// removes the diagnostics disabled by the given `{warning disable <code>}` suppressions,
+/// a diagnostic is disabled if it lies within the suppressing POU or statement
+pub fn remove_suppressed(
+    diagnostics: Vec<Diagnostic>,
+    suppressions: &[Suppression],
+) -> Vec<Diagnostic> {
+    if suppressions.is_empty() {
+        return diagnostics;
+    }
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let location = diagnostic.get_location();
+            !suppressions
+                .iter()
+                .any(|it| it.codes.contains(diagnostic.get_type()) && it.contains(&location))
+        })
+        .collect()
+}
+
 /// the assessor determins the severity of a diagnostic
 /// this trait allows for different implementations for different usecases
 /// (e.g. default, compiler-settings, tests)
 pub trait DiagnosticAssessor {
     /// determines the severity of the given diagnostic
     fn assess(&self, d: Diagnostic) -> AssessedDiagnostic;
+    /// returns true if the given diagnostic should not be reported at all
+    fn is_ignored(&self, _d: &Diagnostic) -> bool {
+        false
+    }
     fn assess_all(&self, d: Vec<Diagnostic>) -> Vec<AssessedDiagnostic> {
-        d.into_iter().map(|it| self.assess(it)).collect()
+        d.into_iter()
+            .filter(|it| !self.is_ignored(it))
+            .map(|it| self.assess(it))
+            .collect()
     }
 }
 
 /// the default assessor will treat ImprovementSuggestions as warnings
-/// and everything else as errors
+/// and everything else as errors, unless its policy says otherwise
 #[derive(Default)]
-pub struct DefaultDiagnosticAssessor {}
+pub struct DefaultDiagnosticAssessor {
+    policy: DiagnosticPolicy,
+}
+
+impl DefaultDiagnosticAssessor {
+    /// creates an assessor that determines the severities with the given policy
+    pub fn new(policy: DiagnosticPolicy) -> Self {
+        DefaultDiagnosticAssessor { policy }
+    }
+}
 
 pub struct AssessedDiagnostic {
     pub diagnostic: Diagnostic,
@@ -652,18 +1080,21 @@ pub struct AssessedDiagnostic {
 
 impl DiagnosticAssessor for DefaultDiagnosticAssessor {
     fn assess(&self, d: Diagnostic) -> AssessedDiagnostic {
-        let severity = match d {
-            // improvements become warnings
-            Diagnostic::ImprovementSuggestion { .. } => Severity::Warning,
-            // everything else becomes an error
-            _ => Severity::Error,
-        };
+        //ignored diagnostics are filtered before, report them with their default severity
+        let severity = self
+            .policy
+            .get_severity(&d)
+            .unwrap_or_else(|| get_default_severity(&d));
 
         AssessedDiagnostic {
             diagnostic: d,
             severity,
         }
     }
+
+    fn is_ignored(&self, d: &Diagnostic) -> bool {
+        self.policy.get_severity(d).is_none()
+    }
 }
 
 /// the DiagnosticReporter decides on the format and where to report the diagnostic to.
@@ -721,15 +1152,32 @@ impl DiagnosticReporter for CodeSpanDiagnosticReporter {
             let diagnostic_factory = match ad.severity {
                 Severity::Error => codespan_reporting::diagnostic::Diagnostic::error(),
                 Severity::Warning => codespan_reporting::diagnostic::Diagnostic::warning(),
-                Severity::_Info => codespan_reporting::diagnostic::Diagnostic::note(),
+                Severity::Info => codespan_reporting::diagnostic::Diagnostic::note(),
             };
 
+            //ranges that know their file point into it, others into the reported file,
+            //diagnostics without a location (e.g. fatal errors) are reported without a label
+            let location_file_id = location.get_file_id().unwrap_or(file_id);
+            let mut labels = if location.is_undefined() || self.files.get(location_file_id).is_err()
+            {
+                vec![]
+            } else {
+                vec![Label::primary(
+                    location_file_id,
+                    location.get_start()..location.get_end(),
+                )]
+            };
+            labels.extend(d.get_related().iter().filter_map(|related| {
+                related.range.get_file_id().map(|related_file_id| {
+                    Label::secondary(related_file_id, related.range.to_range())
+                        .with_message(related.message.as_str())
+                })
+            }));
+
             let diag = diagnostic_factory
+                .with_code(d.get_type().get_code())
                 .with_message(d.get_message())
-                .with_labels(vec![Label::primary(
-                    file_id,
-                    location.get_start()..location.get_end(),
-                )]);
+                .with_labels(labels);
             let result = codespan_reporting::term::emit(
                 &mut self.writer.lock(),
                 &self.config,
@@ -769,22 +1217,32 @@ impl Default for ClangFormatDiagnosticReporter {
 impl DiagnosticReporter for ClangFormatDiagnosticReporter {
     fn report(&self, diagnostics: &[AssessedDiagnostic], file_id: usize) {
         for ad in diagnostics {
-            let file = self.files.get(file_id).ok();
-
             let diagnostic = &ad.diagnostic;
             let location = &diagnostic.get_location();
-            let start = self.files.location(file_id, location.get_start());
-            let end = self.files.location(file_id, location.get_end());
-
-            let res = self.build_diagnostic_msg(
-                file,
-                start.as_ref().ok(),
-                end.as_ref().ok(),
+            //ranges that know their file point into it, others into the reported file
+            let res = self.build_located_msg(
+                location.get_file_id().unwrap_or(file_id),
+                location,
                 &ad.severity,
-                diagnostic.get_message(),
+                &format!(
+                    "{} [{}]",
+                    diagnostic.get_message(),
+                    diagnostic.get_type().get_code()
+                ),
             );
-
             eprintln!("{}", res);
+
+            for related in diagnostic.get_related() {
+                if let Some(related_file_id) = related.range.get_file_id() {
+                    let res = self.build_located_msg(
+                        related_file_id,
+                        &related.range,
+                        &"note",
+                        &related.message,
+                    );
+                    eprintln!("{}", res);
+                }
+            }
         }
     }
     fn register(&mut self, path: String, src: String) -> usize {
@@ -793,6 +1251,20 @@ impl DiagnosticReporter for ClangFormatDiagnosticReporter {
 }
 
 impl ClangFormatDiagnosticReporter {
+    /// returns the diagnostic message in clang format for the given range of the given file
+    fn build_located_msg(
+        &self,
+        file_id: usize,
+        location: &SourceRange,
+        severity: &dyn Display,
+        msg: &str,
+    ) -> String {
+        let file = self.files.get(file_id).ok();
+        let start = self.files.location(file_id, location.get_start());
+        let end = self.files.location(file_id, location.get_end());
+        self.build_diagnostic_msg(file, start.as_ref().ok(), end.as_ref().ok(), severity, msg)
+    }
+
     /// returns diagnostic message in clang format
     /// file-name:{range}: severity: message
     /// optional parameters that are none will not be included
@@ -801,7 +1273,7 @@ impl ClangFormatDiagnosticReporter {
         file: Option<&SimpleFile<String, String>>,
         start: Option<&Location>,
         end: Option<&Location>,
-        severity: &Severity,
+        severity: &dyn Display,
         msg: &str,
     ) -> String {
         let mut str = String::new();
@@ -842,6 +1314,268 @@ impl ClangFormatDiagnosticReporter {
     }
 }
 
+/// a position in a source file, lines and columns start at 1
+#[derive(Debug, PartialEq, Serialize)]
+struct LineColumn {
+    line: usize,
+    column: usize,
+}
+
+impl From<Location> for LineColumn {
+    fn from(location: Location) -> Self {
+        LineColumn {
+            line: location.line_number,
+            column: location.column_number,
+        }
+    }
+}
+
+/// the location of a diagnostic in the machine-readable formats
+#[derive(Debug, PartialEq, Serialize)]
+struct ReportedLocation {
+    file: String,
+    /// the byte offsets of the location in the file
+    range: Range<usize>,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+/// returns the location of the given range in the given file,
+/// `None` if the range does not point into a registered file
+fn get_reported_location(
+    files: &SimpleFiles<String, String>,
+    file_id: usize,
+    range: &SourceRange,
+) -> Option<ReportedLocation> {
+    if range.is_undefined() {
+        return None;
+    }
+    let file = files.get(file_id).ok()?;
+    let start = files.location(file_id, range.get_start()).ok()?;
+    let end = files.location(file_id, range.get_end()).ok()?;
+    Some(ReportedLocation {
+        file: file.name().clone(),
+        range: range.to_range(),
+        start: start.into(),
+        end: end.into(),
+    })
+}
+
+/// a diagnostic as reported by the `JsonDiagnosticReporter`
+#[derive(Debug, PartialEq, Serialize)]
+struct JsonDiagnostic {
+    /// the stable code of the diagnostic (e.g. `E031`)
+    code: String,
+    /// the name of the code (e.g. `type__incompatible_arrayaccess_range`)
+    name: String,
+    severity: String,
+    message: String,
+    location: Option<ReportedLocation>,
+    related: Vec<JsonRelatedLocation>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct JsonRelatedLocation {
+    message: String,
+    location: Option<ReportedLocation>,
+}
+
+/// a DiagnosticReporter that reports every diagnostic as a json object on its own line
+pub struct JsonDiagnosticReporter {
+    files: SimpleFiles<String, String>,
+}
+
+impl Default for JsonDiagnosticReporter {
+    fn default() -> Self {
+        JsonDiagnosticReporter {
+            files: SimpleFiles::new(),
+        }
+    }
+}
+
+impl JsonDiagnosticReporter {
+    /// returns the given diagnostic as a json object, ranges without a file point into
+    /// the reported file
+    fn build_json(&self, diagnostic: &AssessedDiagnostic, file_id: usize) -> JsonDiagnostic {
+        let d = &diagnostic.diagnostic;
+        let location = d.get_location();
+        JsonDiagnostic {
+            code: d.get_type().get_code().to_string(),
+            name: d.get_type().get_name(),
+            severity: diagnostic.severity.to_string(),
+            message: d.get_message().to_string(),
+            location: get_reported_location(
+                &self.files,
+                location.get_file_id().unwrap_or(file_id),
+                &location,
+            ),
+            related: d
+                .get_related()
+                .iter()
+                .map(|related| JsonRelatedLocation {
+                    message: related.message.clone(),
+                    location: get_reported_location(
+                        &self.files,
+                        related.range.get_file_id().unwrap_or(file_id),
+                        &related.range,
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DiagnosticReporter for JsonDiagnosticReporter {
+    fn report(&self, diagnostics: &[AssessedDiagnostic], file_id: usize) {
+        for ad in diagnostics {
+            match serde_json::to_string(&self.build_json(ad, file_id)) {
+                Ok(json) => eprintln!("{}", json),
+                Err(err) => eprintln!("Unable to report diagnostics: {}", err),
+            }
+        }
+    }
+
+    fn register(&mut self, path: String, src: String) -> usize {
+        self.files.add(path, src)
+    }
+}
+
+/// the SARIF log (https://sarifweb.azurewebsites.net) of a build, the SARIF reporters of all
+/// diagnosticians of the build add their results to it, it is written once the build is finished
+#[derive(Debug, Clone, Default)]
+pub struct SarifLog {
+    results: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl SarifLog {
+    fn add(&self, results: impl Iterator<Item = serde_json::Value>) {
+        if let Ok(mut it) = self.results.lock() {
+            it.extend(results);
+        }
+    }
+
+    /// returns the log of all added results, with a rule for every reported code
+    pub fn build_log(&self) -> serde_json::Value {
+        let results = self.results.lock().map(|it| it.clone()).unwrap_or_default();
+        let rules = ErrNo::ALL
+            .iter()
+            .filter(|code| results.iter().any(|it| it["ruleId"] == code.get_code()))
+            .map(|code| {
+                json!({
+                    "id": code.get_code(),
+                    "name": code.get_name(),
+                    "fullDescription": { "text": code.get_explanation() },
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rustyc",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
+
+    /// writes the log to the standard error
+    pub fn write(&self) {
+        eprintln!("{}", self.build_log());
+    }
+}
+
+/// a DiagnosticReporter that adds all diagnostics to the SARIF log of the build
+pub struct SarifDiagnosticReporter {
+    files: SimpleFiles<String, String>,
+    log: SarifLog,
+}
+
+impl SarifDiagnosticReporter {
+    pub fn new(log: SarifLog) -> Self {
+        SarifDiagnosticReporter {
+            files: SimpleFiles::new(),
+            log,
+        }
+    }
+
+    /// returns the SARIF result of the given diagnostic
+    fn build_result(&self, diagnostic: &AssessedDiagnostic, file_id: usize) -> serde_json::Value {
+        let d = &diagnostic.diagnostic;
+        let location = d.get_location();
+        let level = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        };
+        let locations = get_reported_location(
+            &self.files,
+            location.get_file_id().unwrap_or(file_id),
+            &location,
+        )
+        .map(|it| build_sarif_location(&it));
+        let related = d
+            .get_related()
+            .iter()
+            .filter_map(|related| {
+                get_reported_location(
+                    &self.files,
+                    related.range.get_file_id().unwrap_or(file_id),
+                    &related.range,
+                )
+                .map(|it| (related, build_sarif_location(&it)))
+            })
+            .zip(0..)
+            .map(|((related, mut location), id)| {
+                location["id"] = json!(id);
+                location["message"] = json!({ "text": related.message });
+                location
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "ruleId": d.get_type().get_code(),
+            "level": level,
+            "message": { "text": d.get_message() },
+            "locations": locations.into_iter().collect::<Vec<_>>(),
+            "relatedLocations": related,
+        })
+    }
+}
+
+/// returns the SARIF location of the given location
+fn build_sarif_location(location: &ReportedLocation) -> serde_json::Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": location.file },
+            "region": {
+                "startLine": location.start.line,
+                "startColumn": location.start.column,
+                "endLine": location.end.line,
+                "endColumn": location.end.column,
+                "byteOffset": location.range.start,
+                "byteLength": location.range.end - location.range.start,
+            }
+        }
+    })
+}
+
+impl DiagnosticReporter for SarifDiagnosticReporter {
+    fn report(&self, diagnostics: &[AssessedDiagnostic], file_id: usize) {
+        self.log
+            .add(diagnostics.iter().map(|it| self.build_result(it, file_id)));
+    }
+
+    fn register(&mut self, path: String, src: String) -> usize {
+        self.files.add(path, src)
+    }
+}
+
 /// a DiagnosticReporter that swallows all diagnostics
 #[derive(Default)]
 pub struct NullDiagnosticReporter {
@@ -891,9 +1625,49 @@ impl Diagnostician {
         }
     }
 
-    /// assess and reports the given diagnostics
-    pub fn handle(&self, diagnostics: Vec<Diagnostic>, file_id: usize) {
-        self.report(&self.assess_all(diagnostics), file_id);
+    /// creates a diagnostician that reports every diagnostic as a json object
+    pub fn json_diagnostician() -> Diagnostician {
+        Diagnostician {
+            reporter: Box::new(JsonDiagnosticReporter::default()),
+            assessor: Box::new(DefaultDiagnosticAssessor::default()),
+        }
+    }
+
+    /// creates a diagnostician that adds all diagnostics to the given SARIF log
+    pub fn sarif_diagnostician(log: SarifLog) -> Diagnostician {
+        Diagnostician {
+            reporter: Box::new(SarifDiagnosticReporter::new(log)),
+            assessor: Box::new(DefaultDiagnosticAssessor::default()),
+        }
+    }
+
+    /// replaces the assessor with one that determines the severities with the given policy
+    pub fn with_policy(self, policy: DiagnosticPolicy) -> Diagnostician {
+        Diagnostician {
+            assessor: Box::new(DefaultDiagnosticAssessor::new(policy)),
+            ..self
+        }
+    }
+
+    /// assess and reports the given diagnostics, returns true if one of them is an error
+    pub fn handle(&self, diagnostics: Vec<Diagnostic>, file_id: usize) -> bool {
+        let diagnostics = self.assess_all(diagnostics);
+        self.report(&diagnostics, file_id);
+        diagnostics
+            .iter()
+            .any(|it| matches!(it.severity, Severity::Error))
+    }
+
+    /// reports an error that stopped the build, regardless of the policy
+    pub fn report_fatal(&self, error: Diagnostic) {
+        let file_id = error.get_location().get_file_id().unwrap_or_default();
+        self.report(
+            &[AssessedDiagnostic {
+                diagnostic: error,
+                severity: Severity::Error,
+            }],
+            file_id,
+        );
     }
 }
 
@@ -914,6 +1688,10 @@ impl DiagnosticAssessor for Diagnostician {
         //delegate to assesor
         self.assessor.assess(d)
     }
+
+    fn is_ignored(&self, d: &Diagnostic) -> bool {
+        self.assessor.is_ignored(d)
+    }
 }
 
 impl Default for Diagnostician {
@@ -928,8 +1706,16 @@ impl Default for Diagnostician {
 #[cfg(test)]
 mod diagnostics_tests {
     use codespan_reporting::files::{Location, SimpleFile};
+    use serde_json::json;
 
-    use super::ClangFormatDiagnosticReporter;
+    use crate::ast::{SourceRange, Suppression};
+
+    use super::{
+        remove_suppressed, AssessedDiagnostic, ClangFormatDiagnosticReporter,
+        DefaultDiagnosticAssessor, Diagnostic, DiagnosticAssessor, DiagnosticLevel,
+        DiagnosticPolicy, DiagnosticReporter, ErrNo, JsonDiagnosticReporter,
+        SarifDiagnosticReporter, SarifLog, Severity,
+    };
 
     #[test]
     fn test_build_diagnostic_msg() {
@@ -1027,4 +1813,268 @@ mod diagnostics_tests {
 
         assert_eq!(res, "error: This is an error");
     }
+
+    #[test]
+    fn test_build_located_msg_in_other_file() {
+        let mut reporter = ClangFormatDiagnosticReporter::default();
+        reporter.register(
+            "main.st".to_string(),
+            "PROGRAM main\nEND_PROGRAM".to_string(),
+        );
+        let globals = reporter.register(
+            "globals.st".to_string(),
+            "VAR_GLOBAL\n  x : INT;\nEND_VAR".to_string(),
+        );
+
+        let res = reporter.build_located_msg(
+            globals,
+            &SourceRange::in_file(13..14, Some(globals)),
+            &"note",
+            "'x' is declared here",
+        );
+
+        assert_eq!(res, "globals.st:2:3:{2:3-2:4}: note: 'x' is declared here");
+    }
+
+    #[test]
+    fn the_policy_determines_the_severity_of_diagnostics() {
+        let unresolved = Diagnostic::unresolved_reference("x", SourceRange::new(1..2));
+        let separator = Diagnostic::missing_word_separator("ENDIF", SourceRange::new(3..4));
+        let suggestion = Diagnostic::unknown_pragma("foo", SourceRange::new(5..6));
+        let unattached = Diagnostic::unattached_pragma("bar", SourceRange::new(7..8));
+        let diagnostics = vec![
+            unresolved.clone(),
+            separator.clone(),
+            suggestion.clone(),
+            unattached.clone(),
+        ];
+
+        let severities = |policy: DiagnosticPolicy| {
+            DefaultDiagnosticAssessor::new(policy)
+                .assess_all(diagnostics.clone())
+                .into_iter()
+                .map(|it| (it.diagnostic, it.severity))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            severities(DiagnosticPolicy::default()),
+            vec![
+                (unresolved.clone(), Severity::Error),
+                (separator.clone(), Severity::Warning),
+                (suggestion.clone(), Severity::Warning),
+                (unattached.clone(), Severity::Warning),
+            ]
+        );
+
+        let mut policy = DiagnosticPolicy::default();
+        policy
+            .apply_option("no-syntax__missing_word_separator")
+            .unwrap();
+        policy.apply_option("error=E050").unwrap();
+        policy.apply_option("info=pragma__unknown").unwrap();
+        assert_eq!(
+            severities(policy.clone()),
+            vec![
+                (unresolved.clone(), Severity::Error),
+                (suggestion.clone(), Severity::Info),
+                (unattached.clone(), Severity::Error),
+            ]
+        );
+
+        //-Werror turns all warnings into errors
+        policy.apply_option("pragma__unattached").unwrap();
+        policy.apply_option("error").unwrap();
+        assert_eq!(
+            severities(policy),
+            vec![
+                (unresolved, Severity::Error),
+                (suggestion, Severity::Info),
+                (unattached, Severity::Error),
+            ]
+        );
+        assert_eq!(
+            "Reference__Unresolved".parse::<ErrNo>(),
+            Ok(ErrNo::reference__unresolved)
+        );
+        assert_eq!(DiagnosticLevel::Ignore.get_severity(), None);
+    }
+
+    #[test]
+    fn the_levels_of_errors_cannot_be_changed() {
+        let mut policy = DiagnosticPolicy::default();
+
+        let err = policy.apply_option("no-reference__unresolved").unwrap_err();
+        assert_eq!(
+            err.get_message(),
+            "The level of 'reference__unresolved' cannot be changed, only the levels of warnings can be configured"
+        );
+        assert!(policy.apply_option("E004").is_err());
+        assert!(policy
+            .set_level("type__cast_error", DiagnosticLevel::Info)
+            .is_err());
+        assert!(policy.levels.is_empty());
+    }
+
+    #[test]
+    fn suppressed_diagnostics_are_removed() {
+        let suppressions = vec![Suppression {
+            codes: vec![ErrNo::reference__unresolved],
+            location: SourceRange::in_file(10..20, Some(1)),
+        }];
+        let inside = Diagnostic::unresolved_reference("x", SourceRange::in_file(12..13, Some(1)));
+        let outside = Diagnostic::unresolved_reference("y", SourceRange::in_file(22..23, Some(1)));
+        let other_file =
+            Diagnostic::unresolved_reference("z", SourceRange::in_file(12..13, Some(2)));
+        let other_code = Diagnostic::casting_error("INT", "STRING", SourceRange::new(12..13));
+
+        assert_eq!(
+            remove_suppressed(
+                vec![
+                    inside,
+                    outside.clone(),
+                    other_file.clone(),
+                    other_code.clone()
+                ],
+                &suppressions
+            ),
+            vec![outside, other_file, other_code]
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_reported_as_json() {
+        let mut reporter = JsonDiagnosticReporter::default();
+        let globals = reporter.register(
+            "globals.st".to_string(),
+            "VAR_GLOBAL\n  x : INT;\nEND_VAR".to_string(),
+        );
+        let main = reporter.register(
+            "main.st".to_string(),
+            "PROGRAM main\n  x := 'a';\nEND_PROGRAM".to_string(),
+        );
+        let diagnostic = AssessedDiagnostic {
+            diagnostic: Diagnostic::casting_error("STRING", "INT", SourceRange::new(20..23))
+                .with_declaration("x", SourceRange::in_file(13..14, Some(globals))),
+            severity: Severity::Error,
+        };
+
+        let json = serde_json::to_value(reporter.build_json(&diagnostic, main)).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "code": "E024",
+                "name": "type__cast_error",
+                "severity": "error",
+                "message": "Cannot cast from STRING to INT",
+                "location": {
+                    "file": "main.st",
+                    "range": { "start": 20, "end": 23 },
+                    "start": { "line": 2, "column": 8 },
+                    "end": { "line": 2, "column": 11 }
+                },
+                "related": [{
+                    "message": "'x' is declared here",
+                    "location": {
+                        "file": "globals.st",
+                        "range": { "start": 13, "end": 14 },
+                        "start": { "line": 2, "column": 3 },
+                        "end": { "line": 2, "column": 4 }
+                    }
+                }]
+            })
+        );
+
+        let general = AssessedDiagnostic {
+            diagnostic: Diagnostic::param_error("Unknown profile"),
+            severity: Severity::Error,
+        };
+        let json = serde_json::to_value(reporter.build_json(&general, main)).unwrap();
+        assert_eq!(json["code"], "E002");
+        assert_eq!(json["location"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn diagnostics_are_collected_into_a_sarif_log() {
+        let log = SarifLog::default();
+        let mut reporter = SarifDiagnosticReporter::new(log.clone());
+        let main = reporter.register(
+            "main.st".to_string(),
+            "PROGRAM main\n  x := 'a';\nEND_PROGRAM".to_string(),
+        );
+        reporter.report(
+            &[AssessedDiagnostic {
+                diagnostic: Diagnostic::unresolved_reference("x", SourceRange::new(15..16)),
+                severity: Severity::Error,
+            }],
+            main,
+        );
+        //the diagnosticians of a build share the log
+        let mut other = SarifDiagnosticReporter::new(log.clone());
+        let other_file = other.register("other.st".to_string(), "{foo}".to_string());
+        other.report(
+            &[AssessedDiagnostic {
+                diagnostic: Diagnostic::unknown_pragma("foo", SourceRange::new(0..5)),
+                severity: Severity::Info,
+            }],
+            other_file,
+        );
+
+        let log = log.build_log();
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"][0]["tool"]["driver"]["name"], "rustyc");
+        let results = &log["runs"][0]["results"];
+        assert_eq!(
+            results[0],
+            json!({
+                "ruleId": "E022",
+                "level": "error",
+                "message": { "text": "Could not resolve reference to x" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "main.st" },
+                        "region": {
+                            "startLine": 2,
+                            "startColumn": 3,
+                            "endLine": 2,
+                            "endColumn": 4,
+                            "byteOffset": 15,
+                            "byteLength": 1
+                        }
+                    }
+                }],
+                "relatedLocations": []
+            })
+        );
+        assert_eq!(results[1]["ruleId"], "E049");
+        assert_eq!(results[1]["level"], "note");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "other.st"
+        );
+        let rules = &log["runs"][0]["tool"]["driver"]["rules"];
+        assert_eq!(rules[0]["id"], "E049");
+        assert_eq!(rules[1]["id"], "E022");
+        assert_eq!(rules[1]["name"], "reference__unresolved");
+    }
+
+    #[test]
+    fn every_code_has_a_unique_code_and_an_explanation() {
+        let mut codes = ErrNo::ALL.iter().map(ErrNo::get_code).collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), ErrNo::ALL.len());
+        for code in ErrNo::ALL {
+            assert!(!code.get_explanation().is_empty(), "{}", code.get_code());
+            assert_eq!(code.get_code().parse::<ErrNo>().as_ref(), Ok(code));
+        }
+        assert_eq!(
+            "e031".parse::<ErrNo>(),
+            Ok(ErrNo::type__incompatible_arrayaccess_range)
+        );
+        assert_eq!(
+            Diagnostic::unresolved_constant("x", None, SourceRange::undefined()).get_type(),
+            &ErrNo::var__unresolved_constant
+        );
+    }
 }
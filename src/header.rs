@@ -0,0 +1,424 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! C header files for the compiled POUs (`--emit-header`).
+//!
+//! The header is generated from the `Index` and mirrors the types created by the
+//! `data_type_generator`: the instance structs of programs and function blocks with their
+//! members in the generated order, enums, typedefs for arrays and strings, the prototypes of
+//! the POUs and extern declarations for the global variables and program instances.
+//! Only the POUs and globals of the compiled sources (not the included ones) are declared,
+//! together with every type they use.
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::{
+    ast::{LinkageType, SourceRange},
+    diagnostics::Diagnostic,
+    index::{self, Index, PouIndexEntry, VariableIndexEntry},
+    retain,
+    typesystem::{DataType, DataTypeInformation, StringEncoding, VarArgs, DINT_TYPE},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// generates the header for the given index, the name of the header is used for its
+/// include guard
+pub fn generate_header(index: &Index, header_name: &str) -> Result<String, Diagnostic> {
+    let mut generator = HeaderGenerator {
+        index,
+        declared: HashSet::new(),
+        defined: HashSet::new(),
+        types: vec![],
+    };
+
+    let pous = index
+        .get_pous()
+        .values()
+        .filter(|it| matches!(it.get_linkage(), LinkageType::Internal | LinkageType::Weak))
+        .filter(|it| !it.is_generic())
+        .collect::<Vec<_>>();
+
+    let mut variables = vec![];
+    for global in index.get_globals().values().filter(|it| !it.is_external()) {
+        variables.push(generator.generate_variable(global)?);
+    }
+    for pou in &pous {
+        match pou {
+            PouIndexEntry::Program {
+                instance_variable, ..
+            } => variables.push(generator.generate_variable(instance_variable)?),
+            PouIndexEntry::FunctionBlock { name, .. } | PouIndexEntry::Class { name, .. } => {
+                //the initial values of an instance
+                if let Some(initializer) =
+                    index.find_global_initializer(&index::get_initializer_name(name))
+                {
+                    variables.push(generator.generate_variable(initializer)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut prototypes = vec![];
+    for pou in &pous {
+        if let Some(prototype) = generator.generate_prototype(pou)? {
+            prototypes.push(prototype);
+        }
+    }
+    //the entry points to save & restore the retained variables (see `retain`)
+    if !retain::collect_retained_variables(index).is_empty() {
+        prototypes.push(format!("uint64_t {}(void);", retain::SIZE_FUNCTION));
+        prototypes.push(format!("void {}(uint8_t* image);", retain::SAVE_FUNCTION));
+        prototypes.push(format!(
+            "void {}(const uint8_t* image);",
+            retain::RESTORE_FUNCTION
+        ));
+    }
+
+    let guard = format!("{}_", to_identifier(header_name).to_uppercase());
+    let mut header = vec![
+        "/* generated by rustyc, do not edit */".to_string(),
+        format!("#ifndef {}", guard),
+        format!("#define {}", guard),
+        String::new(),
+        "#include <stdbool.h>".to_string(),
+        "#include <stdint.h>".to_string(),
+        String::new(),
+        "#ifdef __cplusplus".to_string(),
+        "extern \"C\" {".to_string(),
+        "#endif".to_string(),
+    ];
+    for section in [generator.types, variables, prototypes] {
+        if !section.is_empty() {
+            header.push(String::new());
+            header.extend(section);
+        }
+    }
+    header.extend(
+        [
+            "",
+            "#ifdef __cplusplus",
+            "}",
+            "#endif",
+            "",
+            &format!("#endif /* {} */", guard),
+            "",
+        ]
+        .iter()
+        .map(|it| it.to_string()),
+    );
+    Ok(header.join("\n"))
+}
+
+/// generates the header for the given index and writes it to the given location
+pub fn write_header(index: &Index, location: &Path) -> Result<(), Diagnostic> {
+    let header_name = location
+        .file_name()
+        .map(|it| it.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let header = generate_header(index, &header_name)?;
+    fs::write(location, header)
+        .map_err(|err| Diagnostic::io_write_error(&location.to_string_lossy(), &err.to_string()))
+}
+
+struct HeaderGenerator<'i> {
+    index: &'i Index,
+    /// the (lower case) names of the structs that were declared
+    declared: HashSet<String>,
+    /// the (lower case) names of the types that were defined
+    defined: HashSet<String>,
+    /// the declarations and definitions of the types, every type comes after the types it uses
+    types: Vec<String>,
+}
+
+impl<'i> HeaderGenerator<'i> {
+    /// returns the extern declaration of the given global variable
+    fn generate_variable(&mut self, variable: &VariableIndexEntry) -> Result<String, Diagnostic> {
+        let data_type = self.reference(variable.get_type_name(), true)?;
+        Ok(format!(
+            "extern {}{} {};",
+            if variable.is_constant() { "const " } else { "" },
+            data_type,
+            to_identifier(&index::get_symbol_name(variable.get_name()))
+        ))
+    }
+
+    /// returns the prototype of the given POU. POUs whose signature cannot be declared in C
+    /// are listed in a comment, actions and methods are not declared because their names are
+    /// no C identifiers
+    fn generate_prototype(&mut self, pou: &PouIndexEntry) -> Result<Option<String>, Diagnostic> {
+        let name = pou.get_name();
+        let (return_type, parameters) = match pou {
+            PouIndexEntry::Program {
+                instance_struct_name,
+                ..
+            }
+            | PouIndexEntry::FunctionBlock {
+                instance_struct_name,
+                ..
+            } => {
+                //the instance struct is defined, so instances can be created in C
+                let instance = self.reference(instance_struct_name, true)?;
+                ("void".to_string(), vec![format!("{}* self", instance)])
+            }
+            PouIndexEntry::Function { .. } => {
+                //arrays, strings and structs are passed by value as LLVM aggregates, which does
+                //not match the calling convention of C for them
+                let mut by_value_aggregates = vec![];
+                let return_type = match self.index.find_return_type(name) {
+                    Some(return_type) => {
+                        if self.is_aggregate(return_type.get_name()) {
+                            by_value_aggregates.push("its result".to_string());
+                        }
+                        self.reference(return_type.get_name(), true)?
+                    }
+                    None => "void".to_string(),
+                };
+                let mut parameters = vec![];
+                for parameter in self.index.get_declared_parameters(name) {
+                    if self.is_aggregate(parameter.get_type_name()) {
+                        by_value_aggregates.push(parameter.get_name().to_string());
+                    }
+                    parameters.push(format!(
+                        "{} {}",
+                        self.reference(parameter.get_type_name(), true)?,
+                        to_identifier(parameter.get_name())
+                    ));
+                }
+                let variadic = self.index.get_variadic_member(name);
+                match variadic.and_then(VariableIndexEntry::get_varargs) {
+                    Some(VarArgs::Unsized(_)) => parameters.push("...".to_string()),
+                    Some(VarArgs::Sized(Some(type_name))) => {
+                        //sized variadic arguments are passed as their number and a pointer,
+                        //`import-c` recognizes them by the `_count` suffix
+                        let variadic = variadic.map(|it| it.get_name()).unwrap_or("args");
+                        parameters.push(format!(
+                            "{} {}_count",
+                            self.reference(DINT_TYPE, true)?,
+                            to_identifier(variadic)
+                        ));
+                        parameters.push(format!(
+                            "{}* {}",
+                            self.reference(type_name, false)?,
+                            to_identifier(variadic)
+                        ));
+                    }
+                    Some(VarArgs::Sized(None)) | None => {}
+                }
+                if !by_value_aggregates.is_empty() {
+                    return Ok(Some(format!(
+                        "/* {} cannot be declared, it passes {} by value */",
+                        name,
+                        by_value_aggregates.join(", ")
+                    )));
+                }
+                (return_type, parameters)
+            }
+            _ => return Ok(None),
+        };
+
+        let parameters = if parameters.is_empty() {
+            "void".to_string()
+        } else {
+            parameters.join(", ")
+        };
+        Ok(Some(format!(
+            "{} {}({});",
+            return_type,
+            to_identifier(&index::get_symbol_name(name)),
+            parameters
+        )))
+    }
+
+    /// returns the C type of the given type. Types used by value are defined before,
+    /// structs used through a pointer only need to be declared
+    fn reference(&mut self, type_name: &str, by_value: bool) -> Result<String, Diagnostic> {
+        let data_type = self.index.get_type(type_name)?;
+        match data_type.get_type_information() {
+            DataTypeInformation::Integer {
+                signed,
+                size,
+                semantic_size,
+                ..
+            } => Ok(if *semantic_size == Some(1) {
+                "bool".to_string()
+            } else {
+                format!("{}int{}_t", if *signed { "" } else { "u" }, size)
+            }),
+            DataTypeInformation::Float { size, .. } => Ok(if *size == 32 {
+                "float".to_string()
+            } else {
+                "double".to_string()
+            }),
+            DataTypeInformation::Void => Ok("void".to_string()),
+            DataTypeInformation::Pointer {
+                inner_type_name, ..
+            } => Ok(format!("{}*", self.reference(inner_type_name, false)?)),
+            DataTypeInformation::Generic { .. } => Err(Diagnostic::codegen_error(
+                &format!("Cannot declare the generic type {} in a header", type_name),
+                SourceRange::undefined(),
+            )),
+            DataTypeInformation::Struct { name, .. } if !by_value => {
+                self.declare_struct(name);
+                Ok(to_identifier(name))
+            }
+            DataTypeInformation::Struct { name, .. } => {
+                self.define(data_type)?;
+                Ok(to_identifier(name))
+            }
+            _ => {
+                self.define(data_type)?;
+                Ok(to_identifier(data_type.get_name()))
+            }
+        }
+    }
+
+    /// returns true if values of the given type are arrays or structs in C
+    fn is_aggregate(&self, type_name: &str) -> bool {
+        matches!(
+            self.index
+                .get_effective_type_by_name(type_name)
+                .get_type_information(),
+            DataTypeInformation::Array { .. }
+                | DataTypeInformation::String { .. }
+                | DataTypeInformation::Struct { .. }
+        )
+    }
+
+    fn declare_struct(&mut self, name: &str) {
+        if self.declared.insert(name.to_lowercase()) {
+            let name = to_identifier(name);
+            self.types
+                .push(format!("typedef struct {} {};", name, name));
+        }
+    }
+
+    /// defines the given type after all the types it uses
+    fn define(&mut self, data_type: &DataType) -> Result<(), Diagnostic> {
+        let information = data_type.get_type_information();
+        //structs are named after their generated struct type
+        let key = match information {
+            DataTypeInformation::Struct { name, .. } => name.to_lowercase(),
+            _ => data_type.get_name().to_lowercase(),
+        };
+        if self.defined.contains(&key) {
+            return Ok(());
+        }
+        let name = to_identifier(data_type.get_name());
+        let to_diagnostic = |err: String| {
+            Diagnostic::codegen_error(
+                &format!(
+                    "Cannot declare {} in a header: {}",
+                    data_type.get_name(),
+                    err
+                ),
+                SourceRange::undefined(),
+            )
+        };
+        let definition = match information {
+            DataTypeInformation::Struct {
+                name: struct_name, ..
+            } => {
+                //the members of the generated struct, see `data_type_generator`
+                let mut members = vec![];
+                for member in self
+                    .index
+                    .get_container_members(data_type.get_name())
+                    .into_iter()
+                    .filter(|it| !it.is_temp() && !it.is_return())
+                {
+                    members.push(format!(
+                        "    {} {};",
+                        self.reference(member.get_type_name(), true)?,
+                        to_identifier(member.get_name())
+                    ));
+                }
+                self.declare_struct(struct_name);
+                let mut definition = vec![format!("struct {} {{", to_identifier(struct_name))];
+                definition.extend(members);
+                definition.push("};".to_string());
+                definition.join("\n")
+            }
+            DataTypeInformation::Array {
+                inner_type_name,
+                dimensions,
+                ..
+            } => {
+                let inner_type = self.reference(inner_type_name, true)?;
+                let mut lengths = String::new();
+                for dimension in dimensions {
+                    let length = dimension.get_length(self.index).map_err(to_diagnostic)?;
+                    lengths.push_str(&format!("[{}]", length));
+                }
+                format!("typedef {} {}{};", inner_type, name, lengths)
+            }
+            DataTypeInformation::String { size, encoding } => {
+                let length = size.as_int_value(self.index).map_err(to_diagnostic)?;
+                let character = match encoding {
+                    StringEncoding::Utf8 => "char",
+                    StringEncoding::Utf16 => "uint16_t",
+                };
+                format!("typedef {} {}[{}];", character, name, length)
+            }
+            DataTypeInformation::Alias {
+                referenced_type, ..
+            }
+            | DataTypeInformation::SubRange {
+                referenced_type, ..
+            } => format!(
+                "typedef {} {};",
+                self.reference(referenced_type, true)?,
+                name
+            ),
+            DataTypeInformation::Enum {
+                referenced_type,
+                elements,
+                ..
+            } => {
+                let mut definition = vec![
+                    format!(
+                        "typedef {} {};",
+                        self.reference(referenced_type, true)?,
+                        name
+                    ),
+                    "enum {".to_string(),
+                ];
+                //elements without a value follow their predecessor
+                let mut next_value = 0;
+                for element in elements {
+                    let value = match self
+                        .index
+                        .find_enum_element(data_type.get_name(), element)
+                        .and_then(|it| it.initial_value)
+                    {
+                        Some(initial_value) => self
+                            .index
+                            .get_const_expressions()
+                            .get_constant_int_statement_value(&initial_value)
+                            .map_err(to_diagnostic)?,
+                        None => next_value,
+                    };
+                    next_value = value + 1;
+                    definition.push(format!(
+                        "    {}_{} = {},",
+                        name,
+                        to_identifier(element),
+                        value
+                    ));
+                }
+                definition.push("};".to_string());
+                definition.join("\n")
+            }
+            _ => return Ok(()),
+        };
+        self.defined.insert(key);
+        self.types.push(definition);
+        Ok(())
+    }
+}
+
+/// replaces all characters that are not allowed in a C identifier
+fn to_identifier(name: &str) -> String {
+    name.chars()
+        .map(|it| if it.is_ascii_alphanumeric() { it } else { '_' })
+        .collect()
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use inkwell::context::Context;
+
+use crate::{diagnostics::Diagnostician, header, SourceCode};
+
+/// compiles the given source and returns its header
+fn generate_header(source: &str) -> String {
+    let context = Context::create();
+    let (index, _) = crate::compile_module(
+        &context,
+        vec![SourceCode {
+            path: "lib.st".into(),
+            source: source.into(),
+        }],
+        vec![],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap();
+    header::generate_header(&index, "lib.h").unwrap()
+}
+
+#[test]
+fn programs_and_function_blocks_are_declared_with_their_instance_structs() {
+    let header = generate_header(
+        "
+        PROGRAM mainProg
+        VAR_INPUT a : INT; END_VAR
+        VAR_OUTPUT b : BOOL; END_VAR
+        VAR c : LREAL; counter : Counter; END_VAR
+        VAR_TEMP t : DINT; END_VAR
+        END_PROGRAM
+
+        FUNCTION_BLOCK Counter
+        VAR_INPUT step : DINT; END_VAR
+        VAR count : ULINT; END_VAR
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    assert!(header.starts_with("/* generated by rustyc, do not edit */\n#ifndef LIB_H_\n"));
+    assert!(header.contains("#include <stdint.h>"));
+    //the members are in the order of the generated struct, without temporary variables
+    let counter = "struct Counter_interface {\n    int32_t step;\n    uint64_t count;\n};";
+    let main = "struct mainProg_interface {\n    int16_t a;\n    bool* b;\n    double c;\n    Counter_interface counter;\n};";
+    assert!(header.contains(counter), "{}", header);
+    assert!(header.contains(main), "{}", header);
+    assert!(header.find(counter) < header.find(main));
+    assert!(header.contains("typedef struct mainProg_interface mainProg_interface;"));
+
+    assert!(header.contains("extern mainProg_interface mainProg_instance;"));
+    assert!(header.contains("extern const Counter_interface Counter__init;"));
+    assert!(header.contains("void mainProg(mainProg_interface* self);"));
+    assert!(header.contains("void Counter(Counter_interface* self);"));
+}
+
+#[test]
+fn functions_are_declared_with_their_parameter_conventions() {
+    let header = generate_header(
+        "
+        FUNCTION scale : REAL
+        VAR_INPUT value : REAL; factor : DINT; END_VAR
+        VAR_IN_OUT total : LINT; END_VAR
+        VAR tmp : INT; END_VAR
+        END_FUNCTION
+
+        FUNCTION reset
+        END_FUNCTION
+
+        FUNCTION greet : STRING
+        VAR_INPUT name : STRING; END_VAR
+        END_FUNCTION
+        ",
+    );
+
+    assert!(
+        header.contains("float scale(float value, int32_t factor, int64_t* total);"),
+        "{}",
+        header
+    );
+    assert!(header.contains("void reset(void);"), "{}", header);
+    //arrays cannot be passed by value in C
+    assert!(
+        header.contains("/* greet cannot be declared, it passes its result, name by value */"),
+        "{}",
+        header
+    );
+}
+
+#[test]
+fn functions_passing_structs_by_value_are_not_declared() {
+    let header = generate_header(
+        "
+        TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+
+        FUNCTION mirror : Point
+        VAR_INPUT p : Point; END_VAR
+        END_FUNCTION
+
+        FUNCTION length : INT
+        VAR_IN_OUT p : Point; END_VAR
+        END_FUNCTION
+        ",
+    );
+
+    //the generated code passes the struct as an LLVM aggregate, not as C would
+    assert!(
+        header.contains("/* mirror cannot be declared, it passes its result, p by value */"),
+        "{}",
+        header
+    );
+    assert!(!header.contains("Point mirror("), "{}", header);
+    assert!(header.contains("int16_t length(Point* p);"), "{}", header);
+}
+
+#[test]
+fn types_are_declared_before_they_are_used() {
+    let header = generate_header(
+        "
+        TYPE Color : (red, green := 5, blue); END_TYPE
+        TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+        TYPE Points : ARRAY[1..3] OF Point; END_TYPE
+        TYPE Name : STRING[20]; END_TYPE
+
+        VAR_GLOBAL
+            color : Color;
+            points : Points;
+            name : Name;
+        END_VAR
+
+        VAR_GLOBAL CONSTANT
+            limit : INT := 10;
+        END_VAR
+        ",
+    );
+
+    assert!(
+        header.contains(
+            "typedef int32_t Color;\nenum {\n    Color_red = 0,\n    Color_green = 5,\n    Color_blue = 6,\n};"
+        ),
+        "{}",
+        header
+    );
+    let point = "struct Point {\n    int16_t x;\n    int16_t y;\n};";
+    let points = "typedef Point Points[3];";
+    assert!(header.contains(point), "{}", header);
+    assert!(header.contains(points), "{}", header);
+    assert!(header.find(point) < header.find(points));
+    assert!(header.contains("typedef char Name[21];"), "{}", header);
+
+    assert!(header.contains("extern Color color;"));
+    assert!(header.contains("extern Points points;"));
+    assert!(header.contains("extern Name name;"));
+    assert!(header.contains("extern const int16_t limit;"));
+}
+
+#[test]
+fn included_declarations_are_not_declared() {
+    let context = Context::create();
+    let (index, _) = crate::compile_module(
+        &context,
+        vec![SourceCode {
+            path: "lib.st".into(),
+            source: "FUNCTION twice : INT VAR_INPUT x : INT; END_VAR twice := x * 2; END_FUNCTION"
+                .into(),
+        }],
+        vec![SourceCode {
+            path: "other.st".into(),
+            source: "FUNCTION other : INT END_FUNCTION VAR_GLOBAL shared : INT; END_VAR".into(),
+        }],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap();
+    let header = header::generate_header(&index, "lib.h").unwrap();
+
+    assert!(header.contains("int16_t twice(int16_t x);"), "{}", header);
+    assert!(!header.contains("other"), "{}", header);
+    assert!(!header.contains("shared"), "{}", header);
+}
+
+#[test]
+fn retained_variables_declare_the_retain_entry_points() {
+    let header = generate_header("VAR_GLOBAL RETAIN counter : DINT; END_VAR");
+
+    assert!(
+        header.contains("uint64_t __retain_size(void);"),
+        "{}",
+        header
+    );
+    assert!(
+        header.contains("void __retain_save(uint8_t* image);"),
+        "{}",
+        header
+    );
+    assert!(
+        header.contains("void __retain_restore(const uint8_t* image);"),
+        "{}",
+        header
+    );
+    assert!(
+        !generate_header("VAR_GLOBAL counter : DINT; END_VAR").contains("__retain"),
+        "{}",
+        header
+    );
+}
+
+#[test]
+fn namespaced_declarations_use_their_symbol_names() {
+    let header = generate_header(
+        "
+        NAMESPACE Vendor.Motion
+            VAR_GLOBAL gSpeed : INT; END_VAR
+
+            FUNCTION scale : INT
+            VAR_INPUT x : INT; END_VAR
+            END_FUNCTION
+        END_NAMESPACE
+        ",
+    );
+
+    assert!(
+        header.contains("int16_t Vendor__Motion__scale(int16_t x);"),
+        "{}",
+        header
+    );
+    assert!(
+        header.contains("extern int16_t Vendor__Motion__gSpeed;"),
+        "{}",
+        header
+    );
+}
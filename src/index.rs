@@ -1,10 +1,11 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     ast::{
         AstStatement, DirectAccessType, GenericBinding, HardwareAccessType, Implementation,
-        LinkageType, PouType, SourceRange, TypeNature,
+        LinkageType, PouType, Pragma, SourceRange, TypeNature,
     },
     builtins::{self, BuiltIn},
     diagnostics::Diagnostic,
@@ -22,7 +23,7 @@ mod instance_iterator;
 mod tests;
 pub mod visitor;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct VariableIndexEntry {
     /// the name of this variable (e.g. 'x' for 'PLC_PRG.x')
     name: String,
@@ -46,9 +47,44 @@ pub struct VariableIndexEntry {
     pub source_location: SourceRange,
     /// Variadic information placeholder for the variable, if any
     varargs: Option<VarArgs>,
+    /// the pragmas attached to the variable's declaration
+    pragmas: Vec<Pragma>,
+    /// whether the variable is declared in a `RETAIN` or `PERSISTENT` block
+    retain: Option<RetainKind>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// the way a retained variable outlives a restart of the runtime
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetainKind {
+    /// the variable keeps its value across a warm restart (`RETAIN`)
+    Retain,
+    /// the variable keeps its value across a cold restart (`PERSISTENT`)
+    Persistent,
+}
+
+impl RetainKind {
+    /// returns the retain kind of the given variable block's flags, `PERSISTENT` includes `RETAIN`
+    pub fn from_flags(retain: bool, persistent: bool) -> Option<Self> {
+        if persistent {
+            Some(RetainKind::Persistent)
+        } else if retain {
+            Some(RetainKind::Retain)
+        } else {
+            None
+        }
+    }
+
+    /// the linker section retained variables of this kind are placed in
+    pub fn get_section(&self) -> &'static str {
+        match self {
+            RetainKind::Retain => ".retain",
+            RetainKind::Persistent => ".persistent",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct HardwareBinding {
     /// Specifies if the binding is an In/Out or Memory binding
     pub direction: HardwareAccessType,
@@ -100,6 +136,8 @@ pub struct MemberInfo<'b> {
     binding: Option<HardwareBinding>,
     is_constant: bool,
     varargs: Option<VarArgs>,
+    pragmas: &'b [Pragma],
+    retain: Option<RetainKind>,
 }
 
 impl VariableIndexEntry {
@@ -123,6 +161,8 @@ impl VariableIndexEntry {
             binding: None,
             source_location,
             varargs: None,
+            pragmas: vec![],
+            retain: None,
         }
     }
 
@@ -144,6 +184,8 @@ impl VariableIndexEntry {
             binding: None,
             source_location,
             varargs: None,
+            pragmas: vec![],
+            retain: None,
         }
     }
 
@@ -172,6 +214,16 @@ impl VariableIndexEntry {
         self
     }
 
+    pub fn set_pragmas(mut self, pragmas: Vec<Pragma>) -> Self {
+        self.pragmas = pragmas;
+        self
+    }
+
+    pub fn set_retain(mut self, retain: Option<RetainKind>) -> Self {
+        self.retain = retain;
+        self
+    }
+
     /// Creates a new VariableIndexEntry from the current entry with a new container and type
     /// This is used to create new entries from previously generic entries
     pub fn into_typed(&self, container: &str, new_type: &str) -> Self {
@@ -202,10 +254,28 @@ impl VariableIndexEntry {
         self.qualified_name.as_str()
     }
 
+    pub fn get_pragmas(&self) -> &[Pragma] {
+        self.pragmas.as_slice()
+    }
+
+    /// returns true if the variable is declared with the given `{attribute '<name>'}`
+    pub fn has_attribute(&self, attribute: &str) -> bool {
+        self.pragmas.iter().any(|it| it.is_attribute(attribute))
+    }
+
     pub fn get_type_name(&self) -> &str {
         self.data_type_name.as_str()
     }
 
+    /// returns the name of the container this variable is declared in
+    /// (e.g. 'PLC_PRG' for 'PLC_PRG.x')
+    pub fn get_container_name(&self) -> &str {
+        self.qualified_name
+            .rsplit_once('.')
+            .map(|(container, _)| container)
+            .unwrap_or_default()
+    }
+
     pub fn get_location_in_parent(&self) -> u32 {
         self.location_in_parent
     }
@@ -225,10 +295,19 @@ impl VariableIndexEntry {
         self.is_constant
     }
 
+    pub fn get_retain(&self) -> Option<RetainKind> {
+        self.retain
+    }
+
     pub fn is_external(&self) -> bool {
         self.linkage == LinkageType::External
     }
 
+    /// returns true if the variable is defined in every output using it (see `LinkageType::Weak`)
+    pub fn is_weak(&self) -> bool {
+        self.linkage == LinkageType::Weak
+    }
+
     pub fn get_variable_type(&self) -> VariableType {
         self.variable_type.get_variable_type()
     }
@@ -261,7 +340,7 @@ impl VariableIndexEntry {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ArgumentType {
     ByVal(VariableType),
     ByRef(VariableType),
@@ -280,7 +359,7 @@ impl ArgumentType {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum VariableType {
     Local, // functions have no locals; others: VAR-block
     Temp,  // for functions: VAR & VAR_TEMP; others: VAR_TEMP
@@ -314,7 +393,7 @@ pub enum DataTypeType {
     AliasType,     // a Custom-Alias-dataType
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ImplementationType {
     Program,
     Function,
@@ -322,9 +401,10 @@ pub enum ImplementationType {
     Action,
     Class,
     Method,
+    Interface,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ImplementationIndexEntry {
     pub(crate) call_name: String,
     pub(crate) type_name: String,
@@ -350,6 +430,20 @@ impl ImplementationIndexEntry {
     pub fn is_generic(&self) -> bool {
         self.generic
     }
+
+    /// returns the name of the function generated for this implementation (see `get_symbol_name`),
+    /// methods and actions keep the `.` separating them from their pou (e.g. `Vendor__FB_Axis.move`)
+    pub fn get_symbol_name(&self) -> String {
+        match (&self.implementation_type, self.call_name.rsplit_once('.')) {
+            (
+                ImplementationType::Method | ImplementationType::Action,
+                Some((container, member)),
+            ) => {
+                format!("{}.{}", get_symbol_name(container), member)
+            }
+            _ => get_symbol_name(&self.call_name),
+        }
+    }
 }
 
 impl From<&Implementation> for ImplementationIndexEntry {
@@ -374,22 +468,27 @@ impl From<&PouType> for ImplementationType {
             PouType::Action => ImplementationType::Action,
             PouType::Class => ImplementationType::Class,
             PouType::Method { .. } => ImplementationType::Method,
+            PouType::Interface => ImplementationType::Interface,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum PouIndexEntry {
     Program {
         name: String,
         instance_struct_name: String,
         instance_variable: VariableIndexEntry,
         linkage: LinkageType,
+        location: SourceRange,
     },
     FunctionBlock {
         name: String,
         instance_struct_name: String,
         linkage: LinkageType,
+        super_class: Option<String>,
+        interfaces: Vec<String>,
+        location: SourceRange,
     },
     Function {
         name: String,
@@ -398,11 +497,21 @@ pub enum PouIndexEntry {
         generics: Vec<GenericBinding>,
         linkage: LinkageType,
         is_variadic: bool,
+        location: SourceRange,
     },
     Class {
         name: String,
         instance_struct_name: String,
         linkage: LinkageType,
+        super_class: Option<String>,
+        interfaces: Vec<String>,
+        location: SourceRange,
+    },
+    Interface {
+        name: String,
+        instance_struct_name: String,
+        linkage: LinkageType,
+        location: SourceRange,
     },
     Method {
         name: String,
@@ -410,12 +519,14 @@ pub enum PouIndexEntry {
         return_type: String,
         instance_struct_name: String,
         linkage: LinkageType,
+        location: SourceRange,
     },
     Action {
         name: String,
         parent_pou_name: String,
         instance_struct_name: String,
         linkage: LinkageType,
+        location: SourceRange,
     },
 }
 
@@ -424,16 +535,19 @@ impl PouIndexEntry {
     /// # Arguments
     /// - `name` the name of the function
     /// - `instance_variable` the global instance-variable of the program
+    /// - `location` the location of the program's name
     pub fn create_program_entry(
         pou_name: &str,
         instance_variable: VariableIndexEntry,
         linkage: LinkageType,
+        location: SourceRange,
     ) -> PouIndexEntry {
         PouIndexEntry::Program {
             name: pou_name.into(),
             instance_struct_name: pou_name.into(),
             instance_variable,
             linkage,
+            location,
         }
     }
 
@@ -441,11 +555,23 @@ impl PouIndexEntry {
     /// # Arguments
     /// - `name` the name of the FunctionBlock
     /// - `linkage` the linkage type of the pou
-    pub fn create_function_block_entry(pou_name: &str, linkage: LinkageType) -> PouIndexEntry {
+    /// - `super_class` the name of the FunctionBlock this FunctionBlock extends
+    /// - `interfaces` the names of the interfaces this FunctionBlock implements
+    /// - `location` the location of the FunctionBlock's name
+    pub fn create_function_block_entry(
+        pou_name: &str,
+        linkage: LinkageType,
+        super_class: Option<&str>,
+        interfaces: &[String],
+        location: SourceRange,
+    ) -> PouIndexEntry {
         PouIndexEntry::FunctionBlock {
             name: pou_name.into(),
             instance_struct_name: pou_name.into(),
             linkage,
+            super_class: super_class.map(str::to_string),
+            interfaces: interfaces.to_vec(),
+            location,
         }
     }
 
@@ -453,12 +579,14 @@ impl PouIndexEntry {
     /// # Arguments
     /// - `name` the name of the function
     /// - `return_type` the function's return type
+    /// - `location` the location of the function's name
     pub fn create_function_entry(
         name: &str,
         return_type: &str,
         generic_names: &[GenericBinding],
         linkage: LinkageType,
         is_variadic: bool,
+        location: SourceRange,
     ) -> PouIndexEntry {
         PouIndexEntry::Function {
             name: name.into(),
@@ -467,6 +595,7 @@ impl PouIndexEntry {
             instance_struct_name: name.into(),
             linkage,
             is_variadic,
+            location,
         }
     }
 
@@ -474,27 +603,59 @@ impl PouIndexEntry {
     /// # Arguments
     /// - `name` the name of the action (without the pou-qualifier)
     /// - `pou_name` the name of the parent pou
+    /// - `location` the location of the action's implementation
     pub fn create_action_entry(
         qualified_name: &str,
         pou_name: &str,
         linkage: LinkageType,
+        location: SourceRange,
     ) -> PouIndexEntry {
         PouIndexEntry::Action {
             name: qualified_name.into(),
             parent_pou_name: pou_name.into(),
             instance_struct_name: pou_name.into(),
             linkage,
+            location,
         }
     }
 
     /// creates a new Class-PouIndexEntry
     /// # Arguments
     /// - `name` the name of the Class
-    pub fn create_class_entry(pou_name: &str, linkage: LinkageType) -> PouIndexEntry {
+    /// - `super_class` the name of the Class this Class extends
+    /// - `interfaces` the names of the interfaces this Class implements
+    /// - `location` the location of the Class' name
+    pub fn create_class_entry(
+        pou_name: &str,
+        linkage: LinkageType,
+        super_class: Option<&str>,
+        interfaces: &[String],
+        location: SourceRange,
+    ) -> PouIndexEntry {
         PouIndexEntry::Class {
             name: pou_name.into(),
             instance_struct_name: pou_name.into(),
             linkage,
+            super_class: super_class.map(str::to_string),
+            interfaces: interfaces.to_vec(),
+            location,
+        }
+    }
+
+    /// creates a new Interface-PouIndexEntry
+    /// # Arguments
+    /// - `name` the name of the Interface
+    /// - `location` the location of the Interface's name
+    pub fn create_interface_entry(
+        pou_name: &str,
+        linkage: LinkageType,
+        location: SourceRange,
+    ) -> PouIndexEntry {
+        PouIndexEntry::Interface {
+            name: pou_name.into(),
+            instance_struct_name: pou_name.into(),
+            linkage,
+            location,
         }
     }
 
@@ -503,11 +664,13 @@ impl PouIndexEntry {
     /// - `name` the name of the method (without the pou-qualifier)
     /// - `return_type` the name of the method's return type
     /// - `owner_class` the name of the parent pou
+    /// - `location` the location of the method's name
     pub fn create_method_entry(
         name: &str,
         return_type: &str,
         owner_class: &str,
         linkage: LinkageType,
+        location: SourceRange,
     ) -> PouIndexEntry {
         PouIndexEntry::Method {
             name: name.into(),
@@ -515,6 +678,7 @@ impl PouIndexEntry {
             instance_struct_name: name.into(),
             return_type: return_type.into(),
             linkage,
+            location,
         }
     }
 
@@ -526,7 +690,8 @@ impl PouIndexEntry {
             | PouIndexEntry::Function { name, .. }
             | PouIndexEntry::Method { name, .. }
             | PouIndexEntry::Action { name, .. }
-            | PouIndexEntry::Class { name, .. } => name,
+            | PouIndexEntry::Class { name, .. }
+            | PouIndexEntry::Interface { name, .. } => name,
         }
     }
 
@@ -553,6 +718,10 @@ impl PouIndexEntry {
             | PouIndexEntry::Class {
                 instance_struct_name,
                 ..
+            }
+            | PouIndexEntry::Interface {
+                instance_struct_name,
+                ..
             } => Some(instance_struct_name.as_str()),
             _ => None, //functions have no struct type
         }
@@ -583,6 +752,7 @@ impl PouIndexEntry {
             PouIndexEntry::Program { .. }
             | PouIndexEntry::FunctionBlock { .. }
             | PouIndexEntry::Class { .. }
+            | PouIndexEntry::Interface { .. }
             | PouIndexEntry::Function { .. } => self.get_name(),
             PouIndexEntry::Action {
                 parent_pou_name, ..
@@ -593,6 +763,40 @@ impl PouIndexEntry {
         }
     }
 
+    /// returns the name of this pou without its container's qualifier
+    /// (e.g. `m` for the method `MyClass.m`)
+    pub fn get_unqualified_name(&self) -> &str {
+        self.get_name()
+            .strip_prefix(self.get_container())
+            .and_then(|it| it.strip_prefix('.'))
+            .unwrap_or_else(|| self.get_name())
+    }
+
+    /// returns the name of the pou this pou EXTENDS or `None` if it
+    /// does not extend another pou
+    pub fn get_super_class(&self) -> Option<&str> {
+        match self {
+            PouIndexEntry::FunctionBlock { super_class, .. }
+            | PouIndexEntry::Class { super_class, .. } => super_class.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// returns the names of the interfaces this pou IMPLEMENTS directly
+    /// (interfaces implemented by a super class are not included)
+    pub fn get_interfaces(&self) -> &[String] {
+        match self {
+            PouIndexEntry::FunctionBlock { interfaces, .. }
+            | PouIndexEntry::Class { interfaces, .. } => interfaces.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// returns true if this pou is an interface
+    pub fn is_interface(&self) -> bool {
+        matches!(self, PouIndexEntry::Interface { .. })
+    }
+
     /// returns the ImplementationIndexEntry associated with this POU
     pub fn find_implementation<'idx>(
         &self,
@@ -609,7 +813,21 @@ impl PouIndexEntry {
             | PouIndexEntry::Function { linkage, .. }
             | PouIndexEntry::Method { linkage, .. }
             | PouIndexEntry::Action { linkage, .. }
-            | PouIndexEntry::Class { linkage, .. } => linkage,
+            | PouIndexEntry::Class { linkage, .. }
+            | PouIndexEntry::Interface { linkage, .. } => linkage,
+        }
+    }
+
+    /// returns the location of this pou's definition
+    pub fn get_location(&self) -> &SourceRange {
+        match self {
+            PouIndexEntry::Program { location, .. }
+            | PouIndexEntry::FunctionBlock { location, .. }
+            | PouIndexEntry::Function { location, .. }
+            | PouIndexEntry::Method { location, .. }
+            | PouIndexEntry::Action { location, .. }
+            | PouIndexEntry::Class { location, .. }
+            | PouIndexEntry::Interface { location, .. } => location,
         }
     }
 
@@ -640,7 +858,7 @@ impl PouIndexEntry {
 /// the TypeIndex carries all types.
 /// it is extracted into its seaprate struct so it can be
 /// internally borrowed individually from the other maps
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct TypeIndex {
     /// all types (structs, enums, type, POUs, etc.)
     types: IndexMap<String, DataType>,
@@ -711,7 +929,7 @@ impl TypeIndex {
 /// The global index of the rusty-compiler
 ///
 /// The index contains information about all referencable elements.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Index {
     /// all global variables
     global_variables: IndexMap<String, VariableIndexEntry>,
@@ -738,6 +956,10 @@ pub struct Index {
     type_index: TypeIndex,
 
     constant_expressions: ConstExpressions,
+
+    /// the namespaces searched for the names used by a POU, type or global variable,
+    /// grouped by the lowercase name of the declaring element
+    scopes: IndexMap<String, Vec<String>>,
 }
 
 impl Index {
@@ -815,6 +1037,9 @@ impl Index {
         //pous
         self.pous.extend(other.pous);
 
+        //scopes
+        self.scopes.extend(other.scopes);
+
         //Constant expressions are intentionally not imported
         // self.constant_expressions.import(other.constant_expressions)
     }
@@ -930,6 +1155,16 @@ impl Index {
         self.member_variables
             .get(&container_name.to_lowercase())
             .and_then(|map| map.get(&variable_name.to_lowercase()))
+            .or_else(|| {
+                //check inherited members
+                self.get_super_classes(container_name)
+                    .into_iter()
+                    .find_map(|super_class| {
+                        self.member_variables
+                            .get(&super_class.to_lowercase())
+                            .and_then(|map| map.get(&variable_name.to_lowercase()))
+                    })
+            })
             .or_else(|| {
                 //check qualifier
                 container_name
@@ -945,6 +1180,15 @@ impl Index {
         &self,
         fully_qualified_name: &str,
     ) -> Option<&VariableIndexEntry> {
+        //containers and global variables declared in a NAMESPACE contain dots themselves
+        let member = fully_qualified_name
+            .rsplit_once('.')
+            .and_then(|(container, name)| self.find_member(container, name))
+            .or_else(|| self.find_global_variable(fully_qualified_name));
+        if member.is_some() {
+            return member;
+        }
+
         let segments: Vec<&str> = fully_qualified_name.split('.').collect();
         let (q, segments) = if segments.len() > 1 {
             (
@@ -969,6 +1213,12 @@ impl Index {
         let mut result = match context {
             Some(context) => self
                 .find_member(context, first_var)
+                .or_else(|| {
+                    //global variables of the context's namespaces hide the ones without a namespace
+                    self.get_scopes(context).iter().find_map(|scope| {
+                        self.find_global_variable(&format!("{}.{}", scope, first_var))
+                    })
+                })
                 .or_else(|| self.find_global_variable(first_var)),
             None => self.find_global_variable(first_var),
         };
@@ -1192,7 +1442,344 @@ impl Index {
         self.pous.get(&pou_name.to_lowercase())
     }
 
-    pub fn register_program(&mut self, name: &str, location: &SourceRange, linkage: LinkageType) {
+    /// registers the namespaces searched for the names used by the given POU, type or global
+    /// variable, in the order they are searched
+    pub fn register_scopes(&mut self, owner: &str, scopes: Vec<String>) {
+        self.scopes.insert(owner.to_lowercase(), scopes);
+    }
+
+    /// returns the namespaces searched for the names used by the given owner. Elements without
+    /// scopes of their own (e.g. actions) use the scopes of their container.
+    pub fn get_scopes(&self, owner: &str) -> &[String] {
+        let mut owner = owner;
+        loop {
+            if let Some(scopes) = self.scopes.get(&owner.to_lowercase()) {
+                return scopes;
+            }
+            match owner.rsplit_once('.') {
+                Some((container, _)) => owner = container,
+                None => return &[],
+            }
+        }
+    }
+
+    /// returns the declared name of the POU, global variable or type with the given
+    /// fully qualified name
+    pub fn find_declared_name(&self, qualified_name: &str) -> Option<&str> {
+        self.find_pou(qualified_name)
+            .map(PouIndexEntry::get_name)
+            .or_else(|| {
+                self.global_variables
+                    .get(&qualified_name.to_lowercase())
+                    .map(VariableIndexEntry::get_name)
+            })
+            .or_else(|| {
+                self.type_index
+                    .find_type(qualified_name)
+                    .map(DataType::get_name)
+            })
+    }
+
+    /// returns the declared name of the POU, global variable or type the given name refers to
+    /// when it is used by `owner`. The owner's namespaces are searched in order, names that
+    /// are not found in one of them do not refer to an element of a namespace.
+    pub fn find_qualified_name(&self, owner: Option<&str>, name: &str) -> Option<&str> {
+        owner
+            .map(|it| self.get_scopes(it))
+            .unwrap_or_default()
+            .iter()
+            .find_map(|scope| self.find_declared_name(&format!("{}.{}", scope, name)))
+    }
+
+    /// returns the fully qualified name of the POU, global variable or type the given name
+    /// refers to when it is used by `owner` (see `find_qualified_name`), or the given name
+    /// if it does not refer to an element of a namespace
+    pub fn resolve_name(&self, owner: Option<&str>, name: &str) -> String {
+        self.find_qualified_name(owner, name)
+            .unwrap_or(name)
+            .to_string()
+    }
+
+    /// qualifies the type names of all members, global variables, POUs and types with the
+    /// namespace they refer to (see `find_qualified_name`). This needs the declarations of
+    /// all units, so it must run after the last unit was imported.
+    pub fn resolve_namespaces(&mut self) {
+        //without namespaces there is nothing to resolve
+        if self.scopes.is_empty() {
+            return;
+        }
+
+        //members, by-ref members point to the qualified type
+        let mut members = vec![];
+        for (container, variables) in &self.member_variables {
+            for (key, variable) in variables {
+                let data_type = self
+                    .find_byref_pointee(&variable.data_type_name)
+                    .and_then(|it| self.find_qualified_name(Some(container.as_str()), it))
+                    .map(|it| (format!("auto_pointer_to_{}", it), Some(it.to_string())))
+                    .or_else(|| {
+                        self.find_qualified_name(Some(container.as_str()), &variable.data_type_name)
+                            .map(|it| (it.to_string(), None))
+                    });
+                let varargs = match &variable.varargs {
+                    Some(VarArgs::Sized(Some(name))) => self
+                        .find_qualified_name(Some(container.as_str()), name)
+                        .map(|it| VarArgs::Sized(Some(it.to_string()))),
+                    Some(VarArgs::Unsized(Some(name))) => self
+                        .find_qualified_name(Some(container.as_str()), name)
+                        .map(|it| VarArgs::Unsized(Some(it.to_string()))),
+                    _ => None,
+                };
+                if data_type.is_some() || varargs.is_some() {
+                    members.push((container.clone(), key.clone(), data_type, varargs));
+                }
+            }
+        }
+        for (container, key, data_type, varargs) in members {
+            if let Some((_, Some(pointee))) = &data_type {
+                visitor::register_byref_pointer_type_for(self, pointee);
+            }
+            if let Some(variable) = self
+                .member_variables
+                .get_mut(&container)
+                .and_then(|it| it.get_mut(&key))
+            {
+                if let Some((data_type_name, _)) = data_type {
+                    variable.data_type_name = data_type_name;
+                }
+                if varargs.is_some() {
+                    variable.varargs = varargs;
+                }
+            }
+        }
+
+        //global variables
+        let globals = self
+            .global_variables
+            .iter()
+            .filter_map(|(key, variable)| {
+                self.find_qualified_name(Some(variable.get_name()), &variable.data_type_name)
+                    .map(|it| (key.clone(), it.to_string()))
+            })
+            .collect::<Vec<_>>();
+        for (key, data_type_name) in globals {
+            if let Some(variable) = self.global_variables.get_mut(&key) {
+                variable.data_type_name = data_type_name;
+            }
+        }
+
+        //return types, super classes and interfaces of POUs
+        let mut pous = vec![];
+        for (key, pou) in &self.pous {
+            let owner = Some(pou.get_name());
+            let return_type = match pou {
+                PouIndexEntry::Function { return_type, .. }
+                | PouIndexEntry::Method { return_type, .. } => {
+                    self.find_qualified_name(owner, return_type)
+                }
+                _ => None,
+            };
+            let super_class = pou
+                .get_super_class()
+                .and_then(|it| self.find_qualified_name(owner, it));
+            let interfaces = pou
+                .get_interfaces()
+                .iter()
+                .map(|it| self.resolve_name(owner, it))
+                .collect::<Vec<_>>();
+            pous.push((
+                key.clone(),
+                return_type.map(str::to_string),
+                super_class.map(str::to_string),
+                interfaces,
+            ));
+        }
+        for (key, resolved_return_type, resolved_super_class, resolved_interfaces) in pous {
+            match self.pous.get_mut(&key) {
+                Some(
+                    PouIndexEntry::Function { return_type, .. }
+                    | PouIndexEntry::Method { return_type, .. },
+                ) => {
+                    if let Some(resolved) = resolved_return_type {
+                        *return_type = resolved;
+                    }
+                }
+                Some(
+                    PouIndexEntry::FunctionBlock {
+                        super_class,
+                        interfaces,
+                        ..
+                    }
+                    | PouIndexEntry::Class {
+                        super_class,
+                        interfaces,
+                        ..
+                    },
+                ) => {
+                    if resolved_super_class.is_some() {
+                        *super_class = resolved_super_class;
+                    }
+                    *interfaces = resolved_interfaces;
+                }
+                _ => {}
+            }
+        }
+
+        //the types referenced by other types
+        let mut types = vec![];
+        for (key, data_type) in &self.type_index.types {
+            let referenced_type = match &data_type.information {
+                DataTypeInformation::Array {
+                    inner_type_name, ..
+                }
+                | DataTypeInformation::Pointer {
+                    inner_type_name, ..
+                } => inner_type_name,
+                DataTypeInformation::Alias {
+                    referenced_type, ..
+                }
+                | DataTypeInformation::SubRange {
+                    referenced_type, ..
+                }
+                | DataTypeInformation::Enum {
+                    referenced_type, ..
+                } => referenced_type,
+                _ => continue,
+            };
+            if let Some(qualified) =
+                self.find_qualified_name(Some(data_type.get_name()), referenced_type)
+            {
+                types.push((key.clone(), qualified.to_string()));
+            }
+        }
+        for (key, qualified) in types {
+            if let Some(data_type) = self.type_index.types.get_mut(&key) {
+                match &mut data_type.information {
+                    DataTypeInformation::Array {
+                        inner_type_name, ..
+                    }
+                    | DataTypeInformation::Pointer {
+                        inner_type_name, ..
+                    } => *inner_type_name = qualified,
+                    DataTypeInformation::Alias {
+                        referenced_type, ..
+                    }
+                    | DataTypeInformation::SubRange {
+                        referenced_type, ..
+                    }
+                    | DataTypeInformation::Enum {
+                        referenced_type, ..
+                    } => *referenced_type = qualified,
+                    _ => {}
+                }
+            }
+        }
+
+        //the types initial values are assigned to
+        let expressions = self
+            .constant_expressions
+            .iter_target_types()
+            .filter_map(|(id, target_type, scope)| {
+                self.find_qualified_name(scope, target_type)
+                    .map(|it| (id, it.to_string()))
+            })
+            .collect::<Vec<_>>();
+        for (id, target_type) in expressions {
+            self.constant_expressions.set_target_type(&id, target_type);
+        }
+    }
+
+    /// returns the type a pointer created for a by-ref member points to, or None if the
+    /// given type is no such pointer
+    fn find_byref_pointee(&self, type_name: &str) -> Option<&str> {
+        type_name.strip_prefix("auto_pointer_to_")?;
+        match self.type_index.find_type(type_name)?.get_type_information() {
+            DataTypeInformation::Pointer {
+                inner_type_name,
+                auto_deref: true,
+                ..
+            } => Some(inner_type_name),
+            _ => None,
+        }
+    }
+
+    /// returns the names of all pous the given pou inherits from, starting with
+    /// its direct super class. Cyclic inheritance is cut off at the first pou that
+    /// was already visited.
+    pub fn get_super_classes(&self, pou_name: &str) -> Vec<&str> {
+        let mut super_classes: Vec<&str> = vec![];
+        let mut current = self
+            .find_pou(pou_name)
+            .and_then(PouIndexEntry::get_super_class);
+        while let Some(super_class) = current {
+            if super_class.eq_ignore_ascii_case(pou_name)
+                || super_classes
+                    .iter()
+                    .any(|it| it.eq_ignore_ascii_case(super_class))
+            {
+                break;
+            }
+            super_classes.push(super_class);
+            current = self
+                .find_pou(super_class)
+                .and_then(PouIndexEntry::get_super_class);
+        }
+        super_classes
+    }
+
+    /// returns the method (or action) with the given name declared in the given pou.
+    /// If the pou does not declare such a method, its super classes are searched.
+    pub fn find_method(&self, pou_name: &str, method_name: &str) -> Option<&PouIndexEntry> {
+        std::iter::once(pou_name)
+            .chain(self.get_super_classes(pou_name))
+            .find_map(|it| self.find_pou(format!("{}.{}", it, method_name).as_str()))
+    }
+
+    /// returns all methods declared by the given interface in the order of their declaration
+    pub fn get_interface_methods(&self, interface_name: &str) -> Vec<&PouIndexEntry> {
+        self.pous
+            .values()
+            .filter(|it| {
+                matches!(it, PouIndexEntry::Method { parent_pou_name, .. }
+                    if parent_pou_name.eq_ignore_ascii_case(interface_name))
+            })
+            .collect()
+    }
+
+    /// returns the names of all interfaces the given pou or one of its super classes IMPLEMENTS
+    pub fn get_implemented_interfaces(&self, pou_name: &str) -> Vec<&str> {
+        let mut interfaces: Vec<&str> = vec![];
+        for interface in std::iter::once(pou_name)
+            .chain(self.get_super_classes(pou_name))
+            .filter_map(|it| self.find_pou(it))
+            .flat_map(PouIndexEntry::get_interfaces)
+        {
+            if !interfaces
+                .iter()
+                .any(|it| it.eq_ignore_ascii_case(interface))
+            {
+                interfaces.push(interface);
+            }
+        }
+        interfaces
+    }
+
+    /// returns true if the given name refers to an interface
+    pub fn is_interface(&self, pou_name: &str) -> bool {
+        self.find_pou(pou_name)
+            .map(PouIndexEntry::is_interface)
+            .unwrap_or_default()
+    }
+
+    /// registers the program with the given name, the instance variable is located at
+    /// `location`, the program itself at `name_location`
+    pub fn register_program(
+        &mut self,
+        name: &str,
+        location: &SourceRange,
+        name_location: &SourceRange,
+        linkage: LinkageType,
+    ) {
         let instance_variable = VariableIndexEntry::create_global(
             &format!("{}_instance", &name),
             name,
@@ -1201,7 +1788,12 @@ impl Index {
         )
         .set_linkage(linkage);
         // self.register_global_variable(name, instance_variable.clone());
-        let entry = PouIndexEntry::create_program_entry(name, instance_variable, linkage);
+        let entry = PouIndexEntry::create_program_entry(
+            name,
+            instance_variable,
+            linkage,
+            name_location.clone(),
+        );
         self.pous.insert(entry.get_name().to_lowercase(), entry);
     }
 
@@ -1256,7 +1848,9 @@ impl Index {
         .set_constant(member_info.is_constant)
         .set_initial_value(initial_value)
         .set_hardware_binding(member_info.binding)
-        .set_varargs(member_info.varargs);
+        .set_varargs(member_info.varargs)
+        .set_pragmas(member_info.pragmas.to_vec())
+        .set_retain(member_info.retain);
 
         self.register_member_entry(container_name, entry);
     }
@@ -1398,6 +1992,19 @@ impl Index {
 pub fn get_initializer_name(name: &str) -> String {
     format!("{}__init", name)
 }
+
+/// Returns the name of the symbol generated for the given qualified name. Names declared
+/// in a NAMESPACE contain dots (e.g. `Vendor.Motion.FB_Axis`), they are replaced so the
+/// symbol is a valid C identifier (e.g. `Vendor__Motion__FB_Axis`).
+pub fn get_symbol_name(qualified_name: &str) -> String {
+    qualified_name.replace('.', "__")
+}
+
+/// Returns the name of the method table that dispatches the given interface's methods
+/// to the given pou
+pub fn get_vtable_name(pou_name: &str, interface_name: &str) -> String {
+    format!("__vtable_{}_{}", pou_name, interface_name)
+}
 impl VariableType {
     pub(crate) fn is_private(&self) -> bool {
         return matches!(self, VariableType::Temp | VariableType::Local);
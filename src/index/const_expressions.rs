@@ -2,13 +2,14 @@
 
 use crate::ast::AstStatement;
 use generational_arena::{Arena, Iter};
+use serde::{Deserialize, Serialize};
 
 pub type ConstId = generational_arena::Index;
 
 /// wrapper around ConstExpression stored in the arena
 /// changing expr allows to change the referenced const-expression
 /// without aquiring a new ID in the arena
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ConstWrapper {
     /// the constant expression
     expr: ConstExpression,
@@ -29,7 +30,7 @@ impl ConstWrapper {
 /// constant expressions registered here are wrapped behind this enum to indicate
 /// whether this expression was already (potentially) resolved or not, or if a
 /// resolving failed.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum ConstExpression {
     Unresolved {
         statement: AstStatement,
@@ -69,7 +70,7 @@ impl ConstExpression {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ConstExpressions {
     expressions: Arena<ConstWrapper>,
 }
@@ -112,6 +113,20 @@ impl ConstExpressions {
             .map(|it| it.target_type_name.as_str())
     }
 
+    /// returns the ids of all expressions together with their target type and scope
+    pub fn iter_target_types(&self) -> impl Iterator<Item = (ConstId, &str, Option<&str>)> {
+        self.expressions
+            .iter()
+            .map(|(id, it)| (id, it.target_type_name.as_str(), it.expr.get_qualifier()))
+    }
+
+    /// changes the datatype the expression with the given `id` resolves to
+    pub fn set_target_type(&mut self, id: &ConstId, target_type_name: String) {
+        if let Some(wrapper) = self.expressions.get_mut(*id) {
+            wrapper.target_type_name = target_type_name;
+        }
+    }
+
     /// similar to `find_expression` but it does not return the `AstStatement` directly.
     /// it returns a ConstExpression wrapper that indicates whether this expression
     /// was successfully resolved yet or not
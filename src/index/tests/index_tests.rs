@@ -1,7 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use pretty_assertions::assert_eq;
 
-use crate::index::{ArgumentType, PouIndexEntry, VariableIndexEntry};
+use crate::index::{ArgumentType, PouIndexEntry, RetainKind, VariableIndexEntry};
 use crate::lexer::IdProvider;
 use crate::parser::tests::literal_int;
 use crate::test_utils::tests::{annotate, index, parse_and_preprocess};
@@ -669,6 +669,7 @@ fn pre_processing_generates_inline_structs_global() {
                 location: (54..55).into(),
                 initializer: None,
                 address: None,
+                pragmas: vec![],
             },],
         },
         new_struct_type
@@ -748,6 +749,7 @@ fn pre_processing_generates_inline_structs() {
                 location: (67..68).into(),
                 initializer: None,
                 address: None,
+                pragmas: vec![],
             }],
         },
         new_struct_type
@@ -1129,6 +1131,7 @@ fn pre_processing_nested_array_in_struct() {
                 location: SourceRange::undefined(),
                 initializer: None,
                 address: None,
+                pragmas: vec![],
             }],
         },
         initializer: None,
@@ -1863,7 +1866,7 @@ fn a_program_pou_is_indexed() {
     "#;
 
     // WHEN the code is indexed
-    let (_, index) = index(src);
+    let (unit, index) = index(src);
 
     // THEN I expect an entry for the program
     assert_eq!(
@@ -1883,7 +1886,10 @@ fn a_program_pou_is_indexed() {
                 binding: None,
                 source_location: SourceRange::new(9..46),
                 varargs: None,
-            }
+                pragmas: vec![],
+                retain: None,
+            },
+            location: SourceRange::new(17..26),
         }),
         index.find_pou("myProgram"),
     );
@@ -1899,7 +1905,8 @@ fn a_program_pou_is_indexed() {
             }]
             .to_vec(),
             return_type: "INT".into(),
-            is_variadic: false
+            is_variadic: false,
+            location: SourceRange::new(65..75),
         }),
         index.find_pou("myFunction"),
     );
@@ -1908,7 +1915,10 @@ fn a_program_pou_is_indexed() {
         Some(&PouIndexEntry::FunctionBlock {
             name: "myFunctionBlock".into(),
             linkage: LinkageType::Internal,
-            instance_struct_name: "myFunctionBlock".into()
+            instance_struct_name: "myFunctionBlock".into(),
+            super_class: None,
+            interfaces: vec![],
+            location: SourceRange::new(139..154),
         }),
         index.find_pou("myFunctionBlock"),
     );
@@ -1917,18 +1927,269 @@ fn a_program_pou_is_indexed() {
         Some(&PouIndexEntry::Class {
             name: "myClass".into(),
             linkage: LinkageType::Internal,
-            instance_struct_name: "myClass".into()
+            instance_struct_name: "myClass".into(),
+            super_class: None,
+            interfaces: vec![],
+            location: SourceRange::new(197..204),
         }),
         index.find_pou("myClass"),
     );
 
+    // AND actions to be located at their implementation
+    let action_location = unit
+        .implementations
+        .iter()
+        .find(|it| it.name == "myProgram.act")
+        .map(|it| it.location.clone())
+        .unwrap();
     assert_eq!(
         Some(&PouIndexEntry::Action {
             name: "myProgram.act".into(),
             parent_pou_name: "myProgram".into(),
             linkage: LinkageType::Internal,
-            instance_struct_name: "myProgram".into()
+            instance_struct_name: "myProgram".into(),
+            location: action_location,
         }),
         index.find_pou("myProgram.act"),
     );
 }
+
+#[test]
+fn derived_pous_embed_their_super_class_as_first_member() {
+    let (_, index) = index(
+        r#"
+        FUNCTION_BLOCK BaseDrive
+            VAR_INPUT speed : INT; END_VAR
+            METHOD start END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Motor EXTENDS BaseDrive
+            VAR_INPUT torque : DINT; END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK ServoMotor EXTENDS Motor
+        END_FUNCTION_BLOCK
+    "#,
+    );
+
+    assert_eq!(
+        Some("BaseDrive"),
+        index
+            .find_pou("Motor")
+            .and_then(PouIndexEntry::get_super_class)
+    );
+    assert_eq!(
+        vec!["Motor", "BaseDrive"],
+        index.get_super_classes("ServoMotor")
+    );
+
+    let members = index.get_container_members("Motor");
+    assert_eq!(SUPER_MEMBER_NAME, members[0].get_name());
+    assert_eq!("BaseDrive", members[0].get_type_name());
+    assert_eq!(0, members[0].get_location_in_parent());
+    assert_eq!("torque", members[1].get_name());
+    assert_eq!(1, members[1].get_location_in_parent());
+
+    //inherited members and methods are found through the super classes
+    let speed = index.find_member("ServoMotor", "speed").unwrap();
+    assert_eq!("BaseDrive.speed", speed.get_qualified_name());
+    assert_eq!("BaseDrive", speed.get_container_name());
+    assert_eq!(
+        Some("BaseDrive.start"),
+        index
+            .find_method("ServoMotor", "start")
+            .map(PouIndexEntry::get_name)
+    );
+}
+
+#[test]
+fn cyclic_inheritance_does_not_loop_forever() {
+    let (_, index) = index(
+        r#"
+        CLASS A EXTENDS B END_CLASS
+        CLASS B EXTENDS A END_CLASS
+    "#,
+    );
+
+    assert_eq!(vec!["B"], index.get_super_classes("A"));
+    assert_eq!(None, index.find_member("A", "x"));
+}
+
+#[test]
+fn interfaces_are_indexed_with_their_methods() {
+    let (_, index) = index(
+        r#"
+        INTERFACE IShape
+            METHOD area : REAL END_METHOD
+            METHOD scale VAR_INPUT factor : REAL; END_VAR END_METHOD
+        END_INTERFACE
+
+        CLASS Shape IMPLEMENTS IShape
+            METHOD area : REAL END_METHOD
+            METHOD scale VAR_INPUT factor : REAL; END_VAR END_METHOD
+        END_CLASS
+
+        CLASS Circle EXTENDS Shape
+        END_CLASS
+    "#,
+    );
+
+    assert_eq!(
+        Some(&PouIndexEntry::Interface {
+            name: "IShape".into(),
+            linkage: LinkageType::Internal,
+            instance_struct_name: "IShape".into(),
+            location: SourceRange::new(19..25),
+        }),
+        index.find_pou("IShape"),
+    );
+    assert_eq!(
+        vec!["IShape.area", "IShape.scale"],
+        index
+            .get_interface_methods("IShape")
+            .into_iter()
+            .map(PouIndexEntry::get_name)
+            .collect::<Vec<_>>()
+    );
+
+    //an interface is stored as a pointer to a method table and a pointer to the instance
+    let members = index.get_container_members("IShape");
+    assert_eq!(VTABLE_MEMBER_NAME, members[0].get_name());
+    assert_eq!(INSTANCE_MEMBER_NAME, members[1].get_name());
+
+    //implemented interfaces are inherited
+    assert_eq!(vec!["IShape"], index.get_implemented_interfaces("Circle"));
+    assert!(index.is_interface("ishape"));
+    assert!(!index.is_interface("Circle"));
+}
+
+#[test]
+fn pous_with_the_same_name_in_different_namespaces_are_indexed() {
+    // GIVEN two namespaces declaring an FB_Axis
+    let (_, index) = index(
+        r#"
+        NAMESPACE Vendor.A
+            FUNCTION_BLOCK FB_Axis VAR x : INT; END_VAR END_FUNCTION_BLOCK
+        END_NAMESPACE
+
+        NAMESPACE Vendor.B
+            TYPE Position : DINT; END_TYPE
+            FUNCTION_BLOCK FB_Axis VAR y : Position; END_VAR END_FUNCTION_BLOCK
+            VAR_GLOBAL gAxis : FB_Axis; END_VAR
+        END_NAMESPACE
+
+        PROGRAM main
+        USING Vendor.A;
+        VAR
+            a : FB_Axis;
+            b : Vendor.B.FB_Axis;
+        END_VAR
+        END_PROGRAM
+    "#,
+    );
+
+    // THEN both are indexed with their fully qualified names
+    assert!(index.find_pou("Vendor.A.FB_Axis").is_some());
+    assert!(index.find_pou("Vendor.B.FB_Axis").is_some());
+    assert!(index.find_pou("FB_Axis").is_none());
+    assert_eq!(
+        "Vendor.B.Position",
+        index
+            .find_member("Vendor.B.FB_Axis", "y")
+            .unwrap()
+            .get_type_name()
+    );
+    assert_eq!(
+        "Vendor.B.FB_Axis",
+        index
+            .find_global_variable("Vendor.B.gAxis")
+            .unwrap()
+            .get_type_name()
+    );
+
+    // AND unqualified names are resolved using the USING directives
+    assert_eq!(
+        "Vendor.A.FB_Axis",
+        index.find_member("main", "a").unwrap().get_type_name()
+    );
+    assert_eq!(
+        "Vendor.B.FB_Axis",
+        index.find_member("main", "b").unwrap().get_type_name()
+    );
+    assert_eq!(
+        "Vendor.B.FB_Axis.y",
+        index
+            .find_fully_qualified_variable("Vendor.B.FB_Axis.y")
+            .unwrap()
+            .get_qualified_name()
+    );
+}
+
+#[test]
+fn pragmas_are_stored_in_the_variable_entries() {
+    let (_, index) = index(
+        r#"
+        VAR_GLOBAL
+            {attribute 'persistent'}
+            g : INT;
+        END_VAR
+
+        TYPE MyStruct : STRUCT
+            {attribute 'hide'}
+            x : INT;
+        END_STRUCT END_TYPE
+
+        PROGRAM main
+        VAR
+            {attribute 'hide'}
+            a : INT;
+            b : INT;
+        END_VAR
+        END_PROGRAM
+    "#,
+    );
+
+    assert!(index
+        .find_global_variable("g")
+        .unwrap()
+        .has_attribute("persistent"));
+    assert!(index
+        .find_member("MyStruct", "x")
+        .unwrap()
+        .has_attribute("hide"));
+    assert!(index
+        .find_member("main", "a")
+        .unwrap()
+        .has_attribute("hide"));
+    assert!(index
+        .find_member("main", "b")
+        .unwrap()
+        .get_pragmas()
+        .is_empty());
+}
+
+#[test]
+fn retain_kinds_are_stored_in_the_variable_entries() {
+    let (_, index) = index(
+        r#"
+        VAR_GLOBAL RETAIN a : INT; END_VAR
+        VAR_GLOBAL PERSISTENT RETAIN b : INT; END_VAR
+        VAR_GLOBAL c : INT; END_VAR
+
+        PROGRAM main
+        VAR RETAIN x : INT; END_VAR
+        VAR_OUTPUT PERSISTENT y : INT; END_VAR
+        VAR z : INT; END_VAR
+        END_PROGRAM
+    "#,
+    );
+
+    let global = |name| index.find_global_variable(name).unwrap().get_retain();
+    assert_eq!(global("a"), Some(RetainKind::Retain));
+    assert_eq!(global("b"), Some(RetainKind::Persistent));
+    assert_eq!(global("c"), None);
+    let member = |name| index.find_member("main", name).unwrap().get_retain();
+    assert_eq!(member("x"), Some(RetainKind::Retain));
+    assert_eq!(member("y"), Some(RetainKind::Persistent));
+    assert_eq!(member("z"), None);
+}
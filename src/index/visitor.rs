@@ -1,9 +1,9 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
-use super::{HardwareBinding, PouIndexEntry, VariableIndexEntry, VariableType};
+use super::{HardwareBinding, PouIndexEntry, RetainKind, VariableIndexEntry, VariableType};
 use crate::ast::{
     self, ArgumentProperty, AstStatement, CompilationUnit, DataType, DataTypeDeclaration,
     Implementation, Pou, PouType, SourceRange, TypeNature, UserTypeDeclaration, VariableBlock,
-    VariableBlockType,
+    VariableBlockType, INSTANCE_MEMBER_NAME, SUPER_MEMBER_NAME, VTABLE_MEMBER_NAME,
 };
 use crate::diagnostics::Diagnostic;
 use crate::index::{ArgumentType, Index, MemberInfo};
@@ -18,6 +18,13 @@ pub fn visit(unit: &CompilationUnit, mut id_provider: IdProvider) -> Index {
         index.register_type(data_type);
     }
 
+    //Register the namespaces searched by the declared elements, sorted to keep the index stable
+    let mut usings = unit.usings.iter().collect::<Vec<_>>();
+    usings.sort();
+    for (owner, scopes) in usings {
+        index.register_scopes(owner, scopes.clone());
+    }
+
     //Create user defined datatypes
     for user_type in &unit.types {
         visit_data_type(&mut index, &mut id_provider, user_type);
@@ -47,6 +54,55 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
     //register the pou's member variables
     let mut member_varargs = None;
     let mut count = 0;
+
+    //a derived pou embeds the instance of its super class as its first member
+    if let Some(super_class) = &pou.super_class {
+        member_names.push(SUPER_MEMBER_NAME.to_string());
+        index.register_member_variable(
+            MemberInfo {
+                container_name: &pou.name,
+                variable_name: SUPER_MEMBER_NAME,
+                variable_linkage: ArgumentType::ByVal(VariableType::Local),
+                variable_type_name: super_class,
+                is_constant: false,
+                binding: None,
+                varargs: None,
+                pragmas: &[],
+                retain: None,
+            },
+            None,
+            pou.name_location.clone(),
+            count,
+        );
+        count += 1;
+    }
+
+    //an interface is stored as a pair of pointers to the implementing pou's
+    //method table and to the implementing pou's instance
+    if pou.pou_type == PouType::Interface {
+        let pointer_type = register_untyped_pointer_type(index);
+        for member in [VTABLE_MEMBER_NAME, INSTANCE_MEMBER_NAME] {
+            member_names.push(member.to_string());
+            index.register_member_variable(
+                MemberInfo {
+                    container_name: &pou.name,
+                    variable_name: member,
+                    variable_linkage: ArgumentType::ByVal(VariableType::Local),
+                    variable_type_name: &pointer_type,
+                    is_constant: false,
+                    binding: None,
+                    varargs: None,
+                    pragmas: &[],
+                    retain: None,
+                },
+                None,
+                pou.name_location.clone(),
+                count,
+            );
+            count += 1;
+        }
+    }
+
     for block in &pou.variable_blocks {
         let block_type = get_declaration_type_for(block);
         for var in &block.variables {
@@ -107,6 +163,8 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                         is_constant: block.constant,
                         binding,
                         varargs,
+                        pragmas: &var.pragmas,
+                        retain: RetainKind::from_flags(block.retain, block.persistent),
                     },
                     initial_value,
                     var.location.clone(),
@@ -125,7 +183,9 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
         .unwrap_or(VOID_TYPE);
     if pou.return_type.is_some() {
         member_names.push(pou.get_return_name().into());
-        let source_location = SourceRange::new(pou.location.get_end()..pou.location.get_end());
+        let source_location = pou
+            .location
+            .with_range(pou.location.get_end()..pou.location.get_end());
         index.register_member_variable(
             MemberInfo {
                 container_name: &pou.name,
@@ -135,6 +195,8 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 is_constant: false, //return variables are not constants
                 binding: None,
                 varargs: None,
+                pragmas: &[],
+                retain: None,
             },
             None,
             source_location,
@@ -156,7 +218,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
 
     match &pou.pou_type {
         PouType::Program => {
-            index.register_program(&pou.name, &pou.location, pou.linkage);
+            index.register_program(&pou.name, &pou.location, &pou.name_location, pou.linkage);
             index.register_pou_type(datatype);
         }
         PouType::FunctionBlock => {
@@ -167,11 +229,15 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 &pou.name,
                 pou.location.clone(),
             )
-            .set_constant(true);
+            .set_constant(true)
+            .set_linkage(get_initializer_linkage(pou));
             index.register_global_initializer(&global_struct_name, variable);
             index.register_pou(PouIndexEntry::create_function_block_entry(
                 &pou.name,
                 pou.linkage,
+                pou.super_class.as_deref(),
+                &pou.interfaces,
+                pou.name_location.clone(),
             ));
             index.register_pou_type(datatype);
         }
@@ -183,9 +249,24 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 &pou.name,
                 pou.location.clone(),
             )
-            .set_constant(true);
+            .set_constant(true)
+            .set_linkage(get_initializer_linkage(pou));
             index.register_global_initializer(&global_struct_name, variable);
-            index.register_pou(PouIndexEntry::create_class_entry(&pou.name, pou.linkage));
+            index.register_pou(PouIndexEntry::create_class_entry(
+                &pou.name,
+                pou.linkage,
+                pou.super_class.as_deref(),
+                &pou.interfaces,
+                pou.name_location.clone(),
+            ));
+            index.register_pou_type(datatype);
+        }
+        PouType::Interface => {
+            index.register_pou(PouIndexEntry::create_interface_entry(
+                &pou.name,
+                pou.linkage,
+                pou.name_location.clone(),
+            ));
             index.register_pou_type(datatype);
         }
         PouType::Function => {
@@ -195,6 +276,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 &pou.generics,
                 pou.linkage,
                 has_varargs,
+                pou.name_location.clone(),
             ));
             index.register_pou_type(datatype);
         }
@@ -204,6 +286,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 return_type_name,
                 owner_class,
                 pou.linkage,
+                pou.name_location.clone(),
             ));
             index.register_pou_type(datatype);
         }
@@ -211,6 +294,16 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
     };
 }
 
+/// returns the linkage of the initializer-global of the given POU, the initializers of weak POUs
+/// are weak as well
+fn get_initializer_linkage(pou: &Pou) -> ast::LinkageType {
+    if pou.linkage == ast::LinkageType::Weak {
+        ast::LinkageType::Weak
+    } else {
+        ast::LinkageType::Internal
+    }
+}
+
 /// returns the declaration type (ByRef or ByVal) for the given VariableBlock (VAR_INPUT, VAR_OUTPUT, VAR_INOUT, etc.)
 fn get_declaration_type_for(block: &VariableBlock) -> ArgumentType {
     if matches!(
@@ -250,12 +343,13 @@ fn visit_implementation(index: &mut Index, implementation: &Implementation) {
             implementation.name.as_str(),
             implementation.type_name.as_str(),
             ast::LinkageType::Internal, //TODO: where do I get correct linkage from?
+            implementation.location.clone(),
         ));
         index.register_pou_type(datatype);
     }
 }
 
-fn register_byref_pointer_type_for(index: &mut Index, inner_type_name: &str) -> String {
+pub(crate) fn register_byref_pointer_type_for(index: &mut Index, inner_type_name: &str) -> String {
     //get unique name
     let type_name = format!("auto_pointer_to_{}", inner_type_name);
 
@@ -274,13 +368,31 @@ fn register_byref_pointer_type_for(index: &mut Index, inner_type_name: &str) ->
     type_name
 }
 
+/// registers the untyped pointer used for an interface's members and returns its name
+fn register_untyped_pointer_type(index: &mut Index) -> String {
+    let type_name = format!("__POINTER_TO_{}", BYTE_TYPE);
+    index.register_type(typesystem::DataType {
+        name: type_name.clone(),
+        initial_value: None,
+        information: DataTypeInformation::Pointer {
+            name: type_name.clone(),
+            inner_type_name: BYTE_TYPE.to_string(),
+            auto_deref: false,
+        },
+        nature: TypeNature::Any,
+    });
+    type_name
+}
+
 fn visit_global_var_block(index: &mut Index, block: &VariableBlock) {
     let linkage = block.linkage;
     for var in &block.variables {
         let target_type = var.data_type.get_name().unwrap_or_default();
+        //a variable declared in a NAMESPACE resolves its initializer in the namespace
+        let scope = Some(var.name.clone()).filter(|it| !index.get_scopes(it).is_empty());
         let initializer = index
             .get_mut_const_expressions()
-            .maybe_add_constant_expression(var.initializer.clone(), target_type, None);
+            .maybe_add_constant_expression(var.initializer.clone(), target_type, scope);
         let variable = VariableIndexEntry::create_global(
             &var.name,
             &var.name,
@@ -294,7 +406,9 @@ fn visit_global_var_block(index: &mut Index, block: &VariableBlock) {
             var.address
                 .as_ref()
                 .and_then(|it| HardwareBinding::from_statement(index, it, None)),
-        );
+        )
+        .set_pragmas(var.pragmas.clone())
+        .set_retain(RetainKind::from_flags(block.retain, block.persistent));
         index.register_global_variable(&var.name, variable);
     }
 }
@@ -316,7 +430,13 @@ fn visit_data_type(
     type_declaration: &UserTypeDeclaration,
 ) {
     let data_type = &type_declaration.data_type;
-    let scope = &type_declaration.scope;
+    //a type declared in a NAMESPACE resolves its constants in the namespace
+    let scope = &type_declaration.scope.clone().or_else(|| {
+        data_type
+            .get_name()
+            .filter(|it| !index.get_scopes(it).is_empty())
+            .map(str::to_string)
+    });
     //names should not be empty
     match data_type {
         DataType::StructType {
@@ -400,6 +520,8 @@ fn visit_data_type(
                         is_constant: false, //struct members are not constants //TODO thats probably not true (you can define a struct in an CONST-block?!)
                         binding,
                         varargs: None,
+                        pragmas: &var.pragmas,
+                        retain: None,
                     },
                     init,
                     var.location.clone(),
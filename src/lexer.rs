@@ -11,9 +11,16 @@ pub use tokens::Token;
 use crate::ast::AstId;
 use crate::ast::DirectAccessType;
 use crate::ast::HardwareAccessType;
+use crate::ast::Pragma;
 use crate::ast::SourceRange;
+use crate::ast::Suppression;
+use crate::ast::KNOWN_PRAGMAS;
 use crate::Diagnostic;
+use conditionals::ConditionalRegion;
+use std::collections::HashMap;
+use std::collections::HashSet;
 
+mod conditionals;
 #[cfg(test)]
 mod tests;
 mod tokens;
@@ -30,6 +37,23 @@ pub struct ParseSession<'a> {
     pub parse_progress: usize,
     id_provider: IdProvider,
     pub scope: Option<String>,
+    /// the fully qualified name of the NAMESPACE currently parsed
+    pub namespace: Option<String>,
+    /// the namespaces searched for unqualified names at the current position
+    /// (enclosing namespaces and USING directives)
+    pub usings: Vec<String>,
+    /// the pragmas lexed since the parser last attached pragmas to an element
+    pub pragmas: Vec<Pragma>,
+    /// the pragmas attached to statements, keyed by the statement's id
+    pub statement_pragmas: HashMap<AstId, Vec<Pragma>>,
+    /// the diagnostics disabled by pragmas on the parsed POUs and statements
+    pub suppressions: Vec<Suppression>,
+    /// the (lowercase) names defined for the conditional compilation
+    defines: HashSet<String>,
+    /// the currently open `{IF ...}` regions, the innermost region last
+    conditionals: Vec<ConditionalRegion>,
+    /// the id of the lexed file, attached to all created source ranges
+    file_id: Option<usize>,
 }
 
 #[macro_export]
@@ -47,7 +71,21 @@ macro_rules! expect_token {
 }
 
 impl<'a> ParseSession<'a> {
-    pub fn new(l: Lexer<'a, Token>, id_provider: IdProvider) -> ParseSession<'a> {
+    pub fn new(
+        l: Lexer<'a, Token>,
+        id_provider: IdProvider,
+        defines: &[String],
+    ) -> ParseSession<'a> {
+        ParseSession::new_in_file(l, id_provider, defines, None)
+    }
+
+    /// creates a session whose source ranges point into the file with the given id
+    pub fn new_in_file(
+        l: Lexer<'a, Token>,
+        id_provider: IdProvider,
+        defines: &[String],
+        file_id: Option<usize>,
+    ) -> ParseSession<'a> {
         let mut lexer = ParseSession {
             lexer: l,
             token: Token::KeywordBy,
@@ -58,6 +96,14 @@ impl<'a> ParseSession<'a> {
             parse_progress: 0,
             id_provider,
             scope: None,
+            namespace: None,
+            usings: vec![],
+            pragmas: vec![],
+            statement_pragmas: HashMap::new(),
+            suppressions: vec![],
+            defines: defines.iter().map(|it| it.to_lowercase()).collect(),
+            conditionals: vec![],
+            file_id,
         };
         lexer.advance();
         lexer
@@ -67,6 +113,11 @@ impl<'a> ParseSession<'a> {
         self.id_provider.next_id()
     }
 
+    /// returns the pragmas lexed since the last call, they belong to the element parsed next
+    pub fn take_pragmas(&mut self) -> Vec<Pragma> {
+        std::mem::take(&mut self.pragmas)
+    }
+
     /// this function will be removed soon:
     pub fn expect(&self, token: Token) -> Result<(), Diagnostic> {
         if self.token != token {
@@ -113,10 +164,33 @@ impl<'a> ParseSession<'a> {
         &self.lexer.source()[range]
     }
 
+    /// reports the pragmas lexed since the parser last attached pragmas to an element, they
+    /// preceded a token that does not start an element accepting pragmas
+    pub fn report_unattached_pragmas(&mut self) {
+        for pragma in std::mem::take(&mut self.pragmas) {
+            self.accept_diagnostic(Diagnostic::unattached_pragma(&pragma.name, pragma.location));
+        }
+    }
+
     pub fn advance(&mut self) {
+        //pragmas in front of the current token must be taken before it is consumed
+        self.report_unattached_pragmas();
         self.last_range = self.range();
-        self.last_token =
-            std::mem::replace(&mut self.token, self.lexer.next().unwrap_or(Token::End));
+        let mut token = self.lexer.next().unwrap_or(Token::End);
+        //pragmas and tokens in disabled regions of the conditional compilation never reach the parser
+        while token == Token::Pragma || (token != Token::End && !self.is_compiled()) {
+            if token == Token::Pragma {
+                self.handle_pragma();
+            }
+            token = self.lexer.next().unwrap_or(Token::End);
+        }
+        if token == Token::End {
+            //regions still open at the end of the file are reported once
+            for region in std::mem::take(&mut self.conditionals) {
+                self.accept_diagnostic(Diagnostic::missing_token("{END_IF}", region.location));
+            }
+        }
+        self.last_token = std::mem::replace(&mut self.token, token);
         self.parse_progress += 1;
 
         match self.token {
@@ -140,31 +214,119 @@ impl<'a> ParseSession<'a> {
             | Token::KeywordEndFor
             | Token::KeywordEndRepeat
             | Token::KeywordEndMethod
-            | Token::KeywordEndClass => {
+            | Token::KeywordEndClass
+            | Token::KeywordEndInterface
+            | Token::KeywordEndNamespace => {
                 if !self.slice().to_string().contains('_') {
-                    self.accept_diagnostic(Diagnostic::ImprovementSuggestion {
-                        message: format!(
-                            "the words in {} should be separated by a '_'",
-                            self.slice()
-                        ),
-                        range: self.location(),
-                    });
+                    self.accept_diagnostic(Diagnostic::missing_word_separator(
+                        self.slice(),
+                        self.location(),
+                    ));
                 }
             }
             _ => {}
         }
     }
 
+    /// returns true if the current position is not inside a disabled region of
+    /// the conditional compilation
+    fn is_compiled(&self) -> bool {
+        self.conditionals.last().map_or(true, |it| it.active)
+    }
+
+    /// evaluates the conditional compilation pragmas, all other pragmas are collected
+    /// until the parser attaches them to the next element
+    fn handle_pragma(&mut self) {
+        let location = self.location();
+        let (name, arguments) = split_pragma(self.slice());
+        let (name, arguments) = (name.to_lowercase(), arguments.to_string());
+        match name.as_str() {
+            "if" => {
+                let enclosing_active = self.is_compiled();
+                let active = enclosing_active && self.evaluate_condition(&arguments, &location);
+                self.conditionals.push(ConditionalRegion {
+                    active,
+                    taken: active,
+                    enclosing_active,
+                    location,
+                });
+            }
+            "elsif" | "else" => {
+                let condition = if name == "elsif" {
+                    Some(arguments)
+                } else {
+                    None
+                };
+                let region = self.conditionals.pop();
+                if let Some(mut region) = region {
+                    region.active = region.enclosing_active
+                        && !region.taken
+                        && condition.map_or(true, |it| self.evaluate_condition(&it, &location));
+                    region.taken |= region.active;
+                    self.conditionals.push(region);
+                } else {
+                    self.accept_diagnostic(Diagnostic::invalid_conditional_pragma(
+                        &format!("{{{}}} without {{IF}}", name.to_uppercase()),
+                        location,
+                    ));
+                }
+            }
+            "end_if" => {
+                if self.conditionals.pop().is_none() {
+                    self.accept_diagnostic(Diagnostic::invalid_conditional_pragma(
+                        "{END_IF} without {IF}",
+                        location,
+                    ));
+                }
+            }
+            //all other pragmas in disabled regions are ignored
+            _ if !self.is_compiled() => {}
+            "define" => {
+                self.defines.insert(arguments.trim().to_lowercase());
+            }
+            "undefine" => {
+                self.defines.remove(&arguments.trim().to_lowercase());
+            }
+            _ => {
+                let pragma = create_pragma(self.slice(), location);
+                if !KNOWN_PRAGMAS.contains(&name.as_str()) {
+                    self.accept_diagnostic(Diagnostic::unknown_pragma(
+                        &pragma.name,
+                        pragma.location.clone(),
+                    ));
+                }
+                self.pragmas.push(pragma);
+            }
+        }
+    }
+
+    /// evaluates the condition of an `{IF ...}` or `{ELSIF ...}`, malformed conditions are
+    /// reported and treated as false
+    fn evaluate_condition(&mut self, condition: &str, location: &SourceRange) -> bool {
+        conditionals::evaluate_condition(condition, &self.defines).unwrap_or_else(|| {
+            self.accept_diagnostic(Diagnostic::invalid_conditional_pragma(
+                &format!("cannot evaluate condition '{}'", condition.trim()),
+                location.clone(),
+            ));
+            false
+        })
+    }
+
     pub fn slice(&self) -> &str {
         self.lexer.slice()
     }
 
     pub fn location(&self) -> SourceRange {
-        SourceRange::new(self.range())
+        self.source_range(self.range())
     }
 
     pub fn last_location(&self) -> SourceRange {
-        SourceRange::new(self.last_range.clone())
+        self.source_range(self.last_range.clone())
+    }
+
+    /// creates a source range with the given offsets in the lexed file
+    pub fn source_range(&self, range: Range<usize>) -> SourceRange {
+        SourceRange::in_file(range, self.file_id)
     }
 
     pub fn range(&self) -> Range<usize> {
@@ -232,7 +394,7 @@ impl<'a> ParseSession<'a> {
                 )
                 .as_str(),
                 format!("'{}'", self.slice_region(range.clone())).as_str(),
-                SourceRange::new(range),
+                self.source_range(range),
             ));
         }
 
@@ -252,7 +414,7 @@ impl<'a> ParseSession<'a> {
     }
 }
 
-fn parse_pragma(lexer: &mut Lexer<Token>) -> Filter<()> {
+fn parse_pragma(lexer: &mut Lexer<Token>) -> bool {
     let remainder = lexer.remainder();
     let chars = remainder.chars();
     let mut traversed = 0;
@@ -260,10 +422,48 @@ fn parse_pragma(lexer: &mut Lexer<Token>) -> Filter<()> {
         traversed += c.len_utf8();
         if c == '}' {
             lexer.bump(traversed);
-            return Filter::Skip;
+            return true;
         }
     }
-    Filter::Emit(())
+    false
+}
+
+/// splits the source of a pragma into its name and the remaining text
+/// (e.g. `{IF defined(X)}` into `IF` and `defined(X)`)
+fn split_pragma(source: &str) -> (&str, &str) {
+    let content = source.trim_start_matches('{').trim_end_matches('}').trim();
+    content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""))
+}
+
+/// creates a `Pragma` from its source (e.g. `{attribute 'hide'}`)
+fn create_pragma(source: &str, location: SourceRange) -> Pragma {
+    let (name, mut remainder) = split_pragma(source);
+
+    //the arguments are quoted strings or plain words, optionally separated by ':='
+    let mut arguments = vec![];
+    loop {
+        remainder = remainder.trim_start().trim_start_matches(":=").trim_start();
+        if remainder.is_empty() {
+            break;
+        }
+        let (argument, rest) = if let Some(quoted) = remainder.strip_prefix('\'') {
+            quoted.split_once('\'').unwrap_or((quoted, ""))
+        } else {
+            remainder
+                .split_once(char::is_whitespace)
+                .unwrap_or((remainder, ""))
+        };
+        arguments.push(argument.to_string());
+        remainder = rest;
+    }
+
+    Pragma {
+        name: name.to_string(),
+        arguments,
+        location,
+    }
 }
 
 fn parse_comments(lexer: &mut Lexer<Token>) -> Filter<()> {
@@ -375,11 +575,31 @@ impl Default for IdProvider {
 
 #[cfg(test)]
 pub fn lex(source: &str) -> ParseSession {
-    ParseSession::new(Token::lexer(source), IdProvider::default())
+    ParseSession::new(Token::lexer(source), IdProvider::default(), &[])
 }
 
 pub fn lex_with_ids(source: &str, id_provider: IdProvider) -> ParseSession {
-    ParseSession::new(Token::lexer(source), id_provider)
+    ParseSession::new(Token::lexer(source), id_provider, &[])
+}
+
+/// lexes the given source, the given names are defined for the conditional compilation
+pub fn lex_with_defines<'a>(
+    source: &'a str,
+    id_provider: IdProvider,
+    defines: &[String],
+) -> ParseSession<'a> {
+    ParseSession::new(Token::lexer(source), id_provider, defines)
+}
+
+/// lexes the given source of the file with the given id, the created source ranges
+/// point into this file
+pub fn lex_file<'a>(
+    source: &'a str,
+    file_id: usize,
+    id_provider: IdProvider,
+    defines: &[String],
+) -> ParseSession<'a> {
+    ParseSession::new_in_file(Token::lexer(source), id_provider, defines, Some(file_id))
 }
 
 #[cfg(test)]
@@ -0,0 +1,115 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::collections::HashSet;
+
+use crate::ast::SourceRange;
+
+/// an open `{IF ...}` region of the conditional compilation
+pub struct ConditionalRegion {
+    /// true if the tokens of the current branch are compiled
+    pub active: bool,
+    /// true if one of the region's branches was already compiled
+    pub taken: bool,
+    /// true if the code surrounding the region is compiled
+    pub enclosing_active: bool,
+    /// the location of the opening `{IF ...}`
+    pub location: SourceRange,
+}
+
+/// evaluates the condition of an `{IF ...}` or `{ELSIF ...}` pragma against the given defines.
+/// A condition consists of `defined(NAME)` checks combined with `NOT`, `AND`, `OR` and parentheses.
+/// Returns `None` if the condition is malformed.
+pub fn evaluate_condition(condition: &str, defines: &HashSet<String>) -> Option<bool> {
+    let tokens = split_condition(condition);
+    let mut position = 0;
+    let result = evaluate_or(&tokens, &mut position, defines)?;
+    if position == tokens.len() {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// splits a condition into words and parentheses
+fn split_condition(condition: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    for c in condition.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word).to_lowercase());
+        }
+        if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word.to_lowercase());
+    }
+    tokens
+}
+
+fn evaluate_or(tokens: &[String], position: &mut usize, defines: &HashSet<String>) -> Option<bool> {
+    let mut result = evaluate_and(tokens, position, defines)?;
+    while next_is(tokens, position, "or") {
+        let right = evaluate_and(tokens, position, defines)?;
+        result = result || right;
+    }
+    Some(result)
+}
+
+fn evaluate_and(
+    tokens: &[String],
+    position: &mut usize,
+    defines: &HashSet<String>,
+) -> Option<bool> {
+    let mut result = evaluate_unary(tokens, position, defines)?;
+    while next_is(tokens, position, "and") {
+        let right = evaluate_unary(tokens, position, defines)?;
+        result = result && right;
+    }
+    Some(result)
+}
+
+fn evaluate_unary(
+    tokens: &[String],
+    position: &mut usize,
+    defines: &HashSet<String>,
+) -> Option<bool> {
+    if next_is(tokens, position, "not") {
+        evaluate_unary(tokens, position, defines).map(|it| !it)
+    } else if next_is(tokens, position, "(") {
+        let result = evaluate_or(tokens, position, defines)?;
+        closing_parenthesis(tokens, position, result)
+    } else if next_is(tokens, position, "defined") && next_is(tokens, position, "(") {
+        let name = tokens
+            .get(*position)
+            .filter(|it| it.starts_with(|c: char| c.is_alphanumeric() || c == '_'))?;
+        *position += 1;
+        let result = defines.contains(name);
+        closing_parenthesis(tokens, position, result)
+    } else {
+        None
+    }
+}
+
+/// consumes the closing parenthesis of a group and returns its result
+fn closing_parenthesis(tokens: &[String], position: &mut usize, result: bool) -> Option<bool> {
+    if next_is(tokens, position, ")") {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// consumes the next token if it equals the expected one
+fn next_is(tokens: &[String], position: &mut usize, expected: &str) -> bool {
+    if tokens.get(*position).map(String::as_str) == Some(expected) {
+        *position += 1;
+        true
+    } else {
+        false
+    }
+}
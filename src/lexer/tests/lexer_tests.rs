@@ -2,8 +2,10 @@
 use pretty_assertions::{assert_eq, assert_ne};
 
 use crate::{
-    ast::{DirectAccessType, HardwareAccessType, SourceRange},
-    lexer::{lex, Token::*},
+    ast::{DirectAccessType, HardwareAccessType, Pragma, SourceRange},
+    diagnostics::ErrNo,
+    lexer::{lex, lex_file, lex_with_defines, IdProvider, Token::*},
+    Diagnostic,
 };
 
 #[test]
@@ -80,6 +82,136 @@ fn registered_pragmas_parsed() {
     assert_eq!(lexer.token, End);
 }
 
+#[test]
+fn pragmas_are_collected_by_the_lexer() {
+    let mut lexer = lex(r"
+        {attribute 'hide'} {attribute 'monitoring' := 'call'} PROGRAM
+        {warning 'check this'}{unknown}
+        END_PROGRAM
+        ");
+    assert_eq!(lexer.token, KeywordProgram, "Token : {}", lexer.slice());
+    assert_eq!(
+        lexer.take_pragmas(),
+        vec![
+            Pragma {
+                name: "attribute".into(),
+                arguments: vec!["hide".into()],
+                location: SourceRange::new(9..27),
+            },
+            Pragma {
+                name: "attribute".into(),
+                arguments: vec!["monitoring".into(), "call".into()],
+                location: SourceRange::new(28..62),
+            },
+        ]
+    );
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndProgram, "Token : {}", lexer.slice());
+    let pragmas = lexer.take_pragmas();
+    assert_eq!(pragmas[0].name, "warning");
+    assert_eq!(pragmas[0].arguments, vec!["check this"]);
+    assert_eq!(pragmas[1].name, "unknown");
+    assert!(pragmas[1].arguments.is_empty());
+
+    //unknown pragmas are reported
+    assert_eq!(
+        lexer.diagnostics,
+        vec![Diagnostic::unknown_pragma(
+            "unknown",
+            SourceRange::new(101..110)
+        )]
+    );
+}
+
+#[test]
+fn locations_of_a_lexed_file_point_into_the_file() {
+    let mut lexer = lex_file("PROGRAM prg END_PROGRAM", 3, IdProvider::default(), &[]);
+    let start = lexer.location();
+    assert_eq!(start, SourceRange::in_file(0..7, Some(3)));
+    lexer.advance();
+    lexer.advance();
+
+    let end = lexer.location();
+    assert_eq!(lexer.last_location(), SourceRange::in_file(8..11, Some(3)));
+    assert_eq!(start.span(&end), SourceRange::in_file(0..23, Some(3)));
+    assert_eq!(
+        format!("{:?}", start),
+        "SourceRange { range: 0..7, file_id: 3 }"
+    );
+    assert_ne!(start, SourceRange::new(0..7));
+}
+
+#[test]
+fn disabled_conditional_regions_are_skipped_by_the_lexer() {
+    let mut lexer = lex_with_defines(
+        r"
+        {IF defined(PLC_A)} PROGRAM {ELSE} FUNCTION {END_IF}
+        {IF defined(PLC_B)} FUNCTION_BLOCK ) this is no valid code ( {END_IF}
+        END_PROGRAM
+        ",
+        IdProvider::default(),
+        &["plc_a".to_string()],
+    );
+    assert_eq!(lexer.token, KeywordProgram, "Token : {}", lexer.slice());
+    assert_eq!(lexer.location(), SourceRange::new(29..36));
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndProgram, "Token : {}", lexer.slice());
+    assert_eq!(lexer.location(), SourceRange::new(148..159));
+    lexer.advance();
+    assert_eq!(lexer.token, End);
+    assert!(lexer.diagnostics.is_empty());
+}
+
+#[test]
+fn conditional_branches_are_evaluated_in_order() {
+    let mut lexer = lex(r"
+        {define plc_b}
+        {IF defined(PLC_A)} PROGRAM
+        {ELSIF defined(PLC_B) AND NOT (defined(PLC_C) OR defined(PLC_D))} FUNCTION
+            {IF defined(PLC_B)} {attribute 'nested'} TYPE {ELSE} STRUCT {END_IF}
+        {ELSIF defined(PLC_B)} FUNCTION_BLOCK
+        {ELSE} CLASS
+        {END_IF}
+        {undefine PLC_B}
+        {IF defined(PLC_B)} {unknown} METHOD {END_IF}
+        ");
+    assert_eq!(lexer.token, KeywordFunction, "Token : {}", lexer.slice());
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordType, "Token : {}", lexer.slice());
+    assert_eq!(lexer.take_pragmas().len(), 1);
+    lexer.advance();
+    assert_eq!(lexer.token, End);
+    //pragmas in disabled regions are neither collected nor reported
+    assert!(lexer.take_pragmas().is_empty());
+    assert!(lexer.diagnostics.is_empty());
+}
+
+#[test]
+fn invalid_conditional_pragmas_are_reported() {
+    let mut lexer = lex(r"
+        {END_IF}
+        {IF defined(A) AND} PROGRAM {END_IF}
+        {IF defined(A)} FUNCTION
+        ");
+    assert_eq!(lexer.token, End);
+    assert_eq!(
+        lexer.diagnostics,
+        vec![
+            Diagnostic::invalid_conditional_pragma(
+                "{END_IF} without {IF}",
+                SourceRange::new(9..17)
+            ),
+            Diagnostic::invalid_conditional_pragma(
+                "cannot evaluate condition 'defined(A) AND'",
+                SourceRange::new(26..45)
+            ),
+            Diagnostic::missing_token("{END_IF}", SourceRange::new(71..86)),
+        ]
+    );
+    lexer.advance();
+    assert_eq!(lexer.diagnostics.len(), 3);
+}
+
 #[test]
 fn comments_are_not_ignored_in_strings() {
     let mut lexer = lex(r#"
@@ -820,6 +952,7 @@ fn multi_named_keywords_without_underscore_test() {
         "the words in VARINPUT should be separated by a '_'"
     );
     assert_eq!(d1.get_location(), SourceRange::new(0..8));
+    assert_eq!(d1.get_type(), &ErrNo::syntax__missing_word_separator);
 
     assert_eq!(
         d2.get_message(),
@@ -833,8 +966,10 @@ fn lowercase_keywords_accepted() {
     let mut result = lex(r###"
         program class end_class endclass var_input varinput var_output
         varoutput var abstract final method constant retain non_retain 
-        nonretain var_temp vartemp end_method endmethod
-        public private internal protected override
+        nonretain persistent var_temp vartemp end_method endmethod
+        public private internal protected override extends super
+        implements interface end_interface endinterface
+        namespace end_namespace endnamespace using
         var_global varglobal var_in_out varinout end_var endvar
         end_program endprogram end_function endfunction end_function_block endfunctionblock
         type struct end_type endtype end_struct endstruct 
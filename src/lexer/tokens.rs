@@ -7,11 +7,13 @@ pub enum Token {
     #[error]
     #[regex(r"\(\*", |lex| super::parse_comments(lex))]
     #[regex(r"/\*", |lex| super::parse_comments(lex))]
-    #[regex(r"\{", |lex| super::parse_pragma(lex))]
     #[regex(r"//.*", logos::skip)]
     #[regex(r"(?m)\r", logos::skip)]
     Error,
 
+    #[regex(r"\{", |lex| super::parse_pragma(lex))]
+    Pragma,
+
     #[token("@EXTERNAL")]
     #[token("{external}")]
     PropertyExternal,
@@ -52,12 +54,41 @@ pub enum Token {
     #[token("METHOD", ignore(case))]
     KeywordMethod,
 
+    #[token("EXTENDS", ignore(case))]
+    KeywordExtends,
+
+    #[token("IMPLEMENTS", ignore(case))]
+    KeywordImplements,
+
+    #[token("INTERFACE", ignore(case))]
+    KeywordInterface,
+
+    #[token("END_INTERFACE", ignore(case))]
+    #[token("ENDINTERFACE", ignore(case))]
+    KeywordEndInterface,
+
+    #[token("SUPER", ignore(case))]
+    KeywordSuper,
+
+    #[token("NAMESPACE", ignore(case))]
+    KeywordNamespace,
+
+    #[token("END_NAMESPACE", ignore(case))]
+    #[token("ENDNAMESPACE", ignore(case))]
+    KeywordEndNamespace,
+
+    #[token("USING", ignore(case))]
+    KeywordUsing,
+
     #[token("CONSTANT", ignore(case))]
     KeywordConstant,
 
     #[token("RETAIN", ignore(case))]
     KeywordRetain,
 
+    #[token("PERSISTENT", ignore(case))]
+    KeywordPersistent,
+
     #[token("NON_RETAIN", ignore(case))]
     #[token("NONRETAIN", ignore(case))]
     KeywordNonRetain,
@@ -17,16 +17,18 @@
 //! [`ST`]: https://en.wikipedia.org/wiki/Structured_text
 //! [`IEC61131-3`]: https://en.wikipedia.org/wiki/IEC_61131-3
 //! [`IR`]: https://llvm.org/docs/LangRef.html
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io::{self, Write};
+use std::ops::Range;
 use std::process::Command;
 use std::str::FromStr;
 
 use build::{get_project_from_file, Libraries, PackageFormat};
 use clap::ArgEnum;
-use codegen::CodeGen;
+use codegen::{CodeGen, CodeGenOptions};
 use glob::glob;
 use inkwell::passes::PassBuilderOptions;
 use regex::{Captures, Regex};
@@ -40,6 +42,8 @@ use encoding_rs::Encoding;
 use encoding_rs_io::DecodeReaderBytesBuilder;
 use index::Index;
 use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::module::Module;
 use inkwell::targets::{
     self, CodeModel, FileType, InitializationConfig, RelocMode, TargetMachine, TargetTriple,
 };
@@ -49,21 +53,29 @@ use std::{fs::File, io::Read};
 use validation::Validator;
 
 use crate::ast::CompilationUnit;
-use crate::diagnostics::Diagnostician;
+use crate::diagnostics::{remove_suppressed, DiagnosticPolicy, Diagnostician, SarifLog};
 use crate::resolver::{AnnotationMapImpl, TypeAnnotator};
 mod ast;
 pub mod build;
 mod builtins;
+mod c_import;
+mod cache;
 pub mod cli;
 mod codegen;
 pub mod diagnostics;
 pub mod expression_path;
 mod hardware_binding;
+mod header;
 pub mod index;
 mod lexer;
+mod library;
 mod linker;
+pub mod lsp;
+mod parallel;
 mod parser;
 mod resolver;
+mod retain;
+mod stdlib;
 mod test_utils;
 
 pub mod runner;
@@ -82,12 +94,18 @@ pub enum Target {
     Param {
         triple: String,
         sysroot: Option<String>,
+        /// the linker used for this target instead of the one in the link options
+        linker: Option<String>,
     },
 }
 
 impl Target {
     pub fn new(triple: String, sysroot: Option<String>) -> Target {
-        Target::Param { triple, sysroot }
+        Target::Param {
+            triple,
+            sysroot,
+            linker: None,
+        }
     }
 
     pub fn get_target_triple(&self) -> TargetTriple {
@@ -111,6 +129,86 @@ impl Target {
             _ => None,
         }
     }
+
+    pub fn get_linker(&self) -> Option<&str> {
+        match self {
+            Target::Param { linker, .. } => linker.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// returns the sizes of the C integer types on this target
+    pub fn get_data_model(&self) -> DataModel {
+        let triple = self
+            .get_target_triple()
+            .as_str()
+            .to_string_lossy()
+            .to_lowercase();
+        let architecture = triple.split('-').next().unwrap_or_default();
+        let is_64bit = !triple.ends_with("x32")
+            && ["64", "s390x", "sparcv9"]
+                .iter()
+                .any(|it| architecture.contains(it));
+        if !is_64bit {
+            DataModel::Ilp32
+        } else if triple.contains("windows") {
+            DataModel::Llp64
+        } else {
+            DataModel::Lp64
+        }
+    }
+
+    /// returns the names defined for the conditional compilation on this target, they name the
+    /// target's operating system and data model (e.g. `__TARGET_LINUX` and `__TARGET_LP64`)
+    pub fn get_defines(&self) -> Vec<String> {
+        let triple = self
+            .get_target_triple()
+            .as_str()
+            .to_string_lossy()
+            .to_lowercase();
+        let operating_system = [
+            ("linux", "__TARGET_LINUX"),
+            ("darwin", "__TARGET_DARWIN"),
+            ("macos", "__TARGET_DARWIN"),
+            ("windows", "__TARGET_WINDOWS"),
+            ("freebsd", "__TARGET_FREEBSD"),
+            ("netbsd", "__TARGET_NETBSD"),
+            ("openbsd", "__TARGET_OPENBSD"),
+        ]
+        .iter()
+        .find(|(name, _)| triple.contains(name))
+        .map(|(_, define)| define.to_string());
+        let data_model = match self.get_data_model() {
+            DataModel::Lp64 => "__TARGET_LP64",
+            DataModel::Llp64 => "__TARGET_LLP64",
+            DataModel::Ilp32 => "__TARGET_ILP32",
+        };
+        operating_system
+            .into_iter()
+            .chain(std::iter::once(data_model.to_string()))
+            .collect()
+    }
+}
+
+/// the sizes of the C types `int`, `long` and pointers on a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataModel {
+    /// 64 bit `long` and pointers (e.g. 64 bit linux and macOS)
+    Lp64,
+    /// 32 bit `long` and 64 bit pointers (64 bit windows)
+    Llp64,
+    /// 32 bit `int`, `long` and pointers
+    Ilp32,
+}
+
+/// returns the names defined for the conditional compilation on all of the given targets
+fn get_target_defines(targets: &[Target]) -> Vec<String> {
+    let mut defines = targets.first().map(Target::get_defines).unwrap_or_default();
+    for target in targets.iter().skip(1) {
+        let target_defines = target.get_defines();
+        defines.retain(|it| target_defines.contains(it));
+    }
+    defines
 }
 
 impl<T> From<T> for Target
@@ -178,13 +276,76 @@ impl FromStr for ConfigFormat {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CompileOptions {
     pub format: FormatOption,
     pub build_location: Option<PathBuf>,
     pub output: String,
     pub optimization: OptimizationLevel,
     pub error_format: ErrorFormat,
+    /// the names defined for the conditional compilation
+    pub defines: Vec<String>,
+    pub debug_level: DebugLevel,
+    /// the directory of the incremental build cache, `None` compiles everything from scratch
+    pub cache_dir: Option<PathBuf>,
+    /// the number of threads used for the compilation, `0` uses all available cores. Defaults to
+    /// `1`, which generates all units into a single module
+    pub jobs: usize,
+    /// generate a separate module & output file for every source file
+    pub separate_objects: bool,
+    /// write the library interface of the sources to the given file (see `library`)
+    pub emit_interface: Option<PathBuf>,
+    /// write a C header for the compiled POUs to the given file (see `header`)
+    pub emit_header: Option<PathBuf>,
+    /// write the map of the retained variables to the given file (see `retain`)
+    pub retain_map: Option<PathBuf>,
+    /// compile the sources as a dependency of a workspace project: the standard library is only
+    /// declared and the retain functions are left to the project linking the dependency
+    pub as_dependency: bool,
+    /// the severities of the reported diagnostics
+    pub diagnostic_policy: DiagnosticPolicy,
+    /// check the indices of array accesses against the array's bounds at runtime
+    pub bounds_check: bool,
+    /// the log the diagnostics are added to with `ErrorFormat::Sarif`, it is written by the
+    /// caller once the build is finished
+    #[serde(skip)]
+    pub sarif_log: SarifLog,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        CompileOptions {
+            format: FormatOption::default(),
+            build_location: None,
+            output: String::new(),
+            optimization: OptimizationLevel::default(),
+            error_format: ErrorFormat::default(),
+            defines: Vec::new(),
+            debug_level: DebugLevel::default(),
+            cache_dir: None,
+            jobs: 1,
+            separate_objects: false,
+            emit_interface: None,
+            emit_header: None,
+            retain_map: None,
+            as_dependency: false,
+            diagnostic_policy: DiagnosticPolicy::default(),
+            bounds_check: false,
+            sarif_log: SarifLog::default(),
+        }
+    }
+}
+
+impl CompileOptions {
+    /// returns the options to generate the module of all sources with
+    fn get_codegen_options(&self) -> CodeGenOptions {
+        CodeGenOptions {
+            debug_level: self.debug_level,
+            bounds_check: self.bounds_check,
+            retain_functions: !self.as_dependency,
+            ..CodeGenOptions::default()
+        }
+    }
 }
 
 #[derive(Clone, Default, Debug)]
@@ -205,6 +366,10 @@ pub struct ConfigurationOptions {
 pub enum ErrorFormat {
     Rich,
     Clang,
+    /// one json object per diagnostic
+    Json,
+    /// a SARIF log of all diagnostics
+    Sarif,
 }
 
 impl Default for ErrorFormat {
@@ -221,6 +386,21 @@ pub enum OptimizationLevel {
     Aggressive,
 }
 
+/// the amount of debug information generated into the compiled output
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DebugLevel {
+    /// no debug information
+    None,
+    /// compile units, subprograms, line locations and variables (`-g`)
+    Full,
+}
+
+impl Default for DebugLevel {
+    fn default() -> Self {
+        DebugLevel::None
+    }
+}
+
 impl From<OptimizationLevel> for inkwell::OptimizationLevel {
     fn from(val: OptimizationLevel) -> Self {
         match val {
@@ -515,64 +695,131 @@ pub fn persist_to_ir(codegen: &CodeGen, output: &Path) -> Result<(), Diagnostic>
 struct IndexComponents {
     id_provider: IdProvider,
     all_annotations: AnnotationMapImpl,
-    all_literals: StringLiterals,
-    annotated_units: Vec<CompilationUnit>,
+    /// the annotated units with the id of their file and their string literals
+    annotated_units: Vec<(usize, CompilationUnit, StringLiterals)>,
+    /// the positions of the units that only declare symbols in `annotated_units`, the included
+    /// files and the standard library of a dependency
+    included_units: Range<usize>,
 }
 
+/// a unit annotated & validated by `annotate_units`
+struct AnnotatedUnit {
+    file_id: usize,
+    syntax_errors: Vec<Diagnostic>,
+    validation_errors: Vec<Diagnostic>,
+    unit: CompilationUnit,
+    annotations: AnnotationMapImpl,
+    literals: StringLiterals,
+}
+
+/// parses, indexes and annotates the given sources and includes. The unchanged files of an
+/// `incremental` build are loaded from its cache instead of being parsed and indexed, the files
+/// whose object files are up-to-date are not annotated
 fn index_module<T: SourceContainer>(
     sources: Vec<T>,
     includes: Vec<T>,
     encoding: Option<&'static Encoding>,
+    compile_options: &CompileOptions,
+    mut incremental: Option<&mut IncrementalBuild>,
     mut diagnostician: Diagnostician,
 ) -> Result<(Index, IndexComponents), Diagnostic> {
-    let mut full_index = Index::default();
+    let defines = &compile_options.defines;
+    let jobs = compile_options.jobs;
     let id_provider = IdProvider::default();
-
-    let mut all_units = Vec::new();
+    let (includes, interfaces) = read_interfaces(includes, &id_provider)?;
 
     // ### PHASE 1 ###
     // parse & index everything
-    let (index, mut units) = parse_and_index(
+    let mut all_units = parse_sources(
         sources,
         encoding,
+        compile_options,
         &id_provider,
         &mut diagnostician,
         LinkageType::Internal,
+        incremental.as_deref_mut(),
     )?;
-    full_index.import(index);
-    all_units.append(&mut units);
-
-    let (includes_index, mut includes_units) = parse_and_index(
+    let included_units = all_units.len()..all_units.len() + includes.len();
+    all_units.append(&mut parse_sources(
         includes,
         encoding,
+        compile_options,
         &id_provider,
         &mut diagnostician,
         LinkageType::External,
+        None,
+    )?);
+    //the standard library is only added if the sources use it
+    let mut standard_library = stdlib::parse_standard_library(
+        &all_units,
+        &interfaces,
+        defines,
+        get_standard_library_linkage(compile_options),
+        &id_provider,
+        &mut diagnostician,
+    );
+    all_units.append(&mut standard_library);
+    let included_units = if compile_options.as_dependency {
+        included_units.start..all_units.len()
+    } else {
+        included_units
+    };
+
+    let mut full_index = index_units(
+        &mut all_units,
+        &id_provider,
+        jobs,
+        incremental.as_deref_mut(),
     )?;
-    full_index.import(includes_index);
-    all_units.append(&mut includes_units);
+    for interface in interfaces {
+        full_index.import(interface);
+    }
+    //namespaces may span multiple files, so names are resolved once everything is indexed
+    full_index.resolve_namespaces();
+    if let Some(incremental) = incremental.as_deref_mut() {
+        incremental.find_dirty_files()?;
+    }
 
     // ### PHASE 1.1 resolve constant literal values
     let (mut full_index, _unresolvables) =
         resolver::const_evaluator::evaluate_constants(full_index);
 
     // ### PHASE 2 ###
-    // annotation & validation everything
-    let mut annotated_units: Vec<CompilationUnit> = Vec::new();
+    // annotation & validation everything that needs to be generated
+    let (all_units, included_units) = match incremental.as_deref_mut() {
+        Some(incremental) => {
+            let count = all_units.len();
+            let units = incremental.skip_up_to_date(all_units);
+            //only sources are skipped, they come before the included units
+            let skipped = count - units.len();
+            (
+                units,
+                included_units.start - skipped..included_units.end - skipped,
+            )
+        }
+        None => (all_units, included_units),
+    };
+    let mut annotated_units = Vec::new();
     let mut all_annotations = AnnotationMapImpl::default();
-    let mut all_literals = StringLiterals::default();
-    for (file_id, syntax_errors, unit) in all_units.into_iter() {
-        let (annotations, string_literals) = TypeAnnotator::visit_unit(&full_index, &unit);
-
-        let mut validator = Validator::new();
-        validator.visit_unit(&annotations, &full_index, &unit);
-        //log errors
-        diagnostician.handle(syntax_errors, file_id);
-        diagnostician.handle(validator.diagnostics(), file_id);
+    for annotated in annotate_units(all_units, &full_index, compile_options) {
+        //log errors in the order of the units, without the ones disabled by pragmas
+        let suppressions = &annotated.unit.suppressions;
+        let has_syntax_errors = diagnostician.handle(
+            remove_suppressed(annotated.syntax_errors, suppressions),
+            annotated.file_id,
+        );
+        let has_errors = diagnostician.handle(
+            remove_suppressed(annotated.validation_errors, suppressions),
+            annotated.file_id,
+        );
+        if has_syntax_errors || has_errors {
+            if let Some(incremental) = incremental.as_deref_mut() {
+                incremental.failed.insert(annotated.file_id);
+            }
+        }
 
-        annotated_units.push(unit);
-        all_annotations.import(annotations);
-        all_literals.import(string_literals);
+        all_annotations.import(annotated.annotations);
+        annotated_units.push((annotated.file_id, annotated.unit, annotated.literals));
     }
 
     //Merge the new indices with the full index
@@ -583,8 +830,8 @@ fn index_module<T: SourceContainer>(
         IndexComponents {
             id_provider,
             all_annotations,
-            all_literals,
             annotated_units,
+            included_units,
         },
     ))
 }
@@ -604,38 +851,509 @@ pub fn compile_module<'c, T: SourceContainer>(
     encoding: Option<&'static Encoding>,
     diagnostician: Diagnostician,
 ) -> Result<(Index, CodeGen<'c>), Diagnostic> {
-    let (full_index, mut index) = index_module(sources, includes, encoding, diagnostician)?;
+    compile_module_in_parallel(
+        context,
+        sources,
+        includes,
+        encoding,
+        &CompileOptions::default(),
+        diagnostician,
+    )
+}
+
+///
+/// Compiles the given source like `compile_module` with the given options (e.g. the names
+/// defined for the conditional compilation), it parses, annotates and generates the units on
+/// up to `compile_options.jobs` threads (`0` uses all available cores). The results
+/// are merged in the order of the units, so diagnostics and the generated module do not depend
+/// on the scheduling of the threads
+pub fn compile_module_in_parallel<'c, T: SourceContainer>(
+    context: &'c Context,
+    sources: Vec<T>,
+    includes: Vec<T>,
+    encoding: Option<&'static Encoding>,
+    compile_options: &CompileOptions,
+    diagnostician: Diagnostician,
+) -> Result<(Index, CodeGen<'c>), Diagnostic> {
+    let jobs = compile_options.jobs;
+    let (full_index, mut index) = index_module(
+        sources,
+        includes,
+        encoding,
+        compile_options,
+        None,
+        diagnostician,
+    )?;
 
     // ### PHASE 3 ###
     // - codegen
-    let code_generator = codegen::CodeGen::new(context, "main");
+    let annotations = AstAnnotations::new(index.all_annotations, &mut index.id_provider);
+    if jobs != 1 {
+        let code_generator = generate_in_parallel(
+            context,
+            index.annotated_units,
+            &annotations,
+            &full_index,
+            compile_options,
+        )?;
+        return Ok((full_index, code_generator));
+    }
 
-    let annotations = AstAnnotations::new(index.all_annotations, index.id_provider.next_id());
+    let code_generator =
+        codegen::CodeGen::new(context, "main", compile_options.get_codegen_options());
+    let mut all_literals = StringLiterals::default();
+    for (_, _, literals) in index.annotated_units.iter_mut() {
+        all_literals.import(std::mem::take(literals));
+    }
     //Associate the index type with LLVM types
-    let llvm_index =
-        code_generator.generate_llvm_index(&annotations, index.all_literals, &full_index)?;
-    for unit in index.annotated_units {
+    let llvm_index = code_generator.generate_llvm_index(&annotations, all_literals, &full_index)?;
+    for (_, unit, _) in index.annotated_units {
         code_generator.generate(&unit, &annotations, &full_index, &llvm_index)?;
     }
 
     Ok((full_index, code_generator))
 }
 
+/// generates every unit into its own module on up to `compile_options.jobs` threads and links
+/// the modules into a single one in the order of the units
+fn generate_in_parallel<'c>(
+    context: &'c Context,
+    units: Vec<(usize, CompilationUnit, StringLiterals)>,
+    annotations: &AstAnnotations,
+    index: &Index,
+    compile_options: &CompileOptions,
+) -> Result<CodeGen<'c>, Diagnostic> {
+    let options = compile_options.get_codegen_options();
+    let bitcodes = parallel::map(
+        units,
+        compile_options.jobs,
+        |(file_id, unit, literals)| -> Result<Vec<u8>, Diagnostic> {
+            //a context cannot be shared between threads, every unit gets its own
+            let context = Context::create();
+            let code_generator =
+                codegen::CodeGen::new(&context, &unit.file_name, options.for_file(file_id));
+            let llvm_index = code_generator.generate_llvm_index(annotations, literals, index)?;
+            code_generator.generate(&unit, annotations, index, &llvm_index)?;
+            Ok(code_generator
+                .module
+                .write_bitcode_to_memory()
+                .as_slice()
+                .to_vec())
+        },
+    );
+
+    let code_generator = codegen::CodeGen::new(context, "main", options);
+    for bitcode in bitcodes {
+        let buffer = MemoryBuffer::create_from_memory_range_copy(&bitcode?, "unit");
+        Module::parse_bitcode_from_buffer(&buffer, context)
+            .and_then(|module| code_generator.module.link_in_module(module))
+            .map_err(|err| Diagnostic::codegen_error(&err.to_string(), SourceRange::undefined()))?;
+    }
+    if !compile_options.as_dependency {
+        code_generator.generate_retain_functions(index)?;
+    }
+    Ok(code_generator)
+}
+
+///
+/// Compiles the given sources like `compile_module`, but every file is generated into its own
+/// module, named after the file. The symbols of the other files are only declared, so the
+/// modules can be persisted and linked separately. Included files only declare symbols, they
+/// do not get a module. The entry points to save & restore the retained variables (see
+/// `retain`) are generated into the first module
+pub fn compile_module_per_file<'c, T: SourceContainer>(
+    context: &'c Context,
+    sources: Vec<T>,
+    includes: Vec<T>,
+    encoding: Option<&'static Encoding>,
+    compile_options: &CompileOptions,
+    diagnostician: Diagnostician,
+) -> Result<(Index, Vec<(String, CodeGen<'c>)>), Diagnostic> {
+    let (full_index, mut index) = index_module(
+        sources,
+        includes,
+        encoding,
+        compile_options,
+        None,
+        diagnostician,
+    )?;
+
+    // ### PHASE 3 ###
+    // - codegen
+    let annotations = AstAnnotations::new(index.all_annotations, &mut index.id_provider);
+    let mut modules = Vec::new();
+    for (idx, (file_id, unit, literals)) in index.annotated_units.into_iter().enumerate() {
+        if index.included_units.contains(&idx) {
+            continue;
+        }
+        let code_generator = codegen::CodeGen::new(
+            context,
+            &unit.file_name,
+            compile_options.get_codegen_options().for_file(file_id),
+        );
+        let llvm_index = code_generator.generate_llvm_index(&annotations, literals, &full_index)?;
+        code_generator.generate(&unit, &annotations, &full_index, &llvm_index)?;
+        if modules.is_empty() && !compile_options.as_dependency {
+            code_generator.generate_retain_functions(&full_index)?;
+        }
+        modules.push((unit.file_name, code_generator));
+    }
+    Ok((full_index, modules))
+}
+
+/// a module generated by `build_and_link`
+struct GeneratedModule<'c> {
+    /// the file the module was generated for, `None` if it contains all files
+    source: Option<String>,
+    /// whether the module's object file is kept in the build cache
+    cached: bool,
+    codegen: CodeGen<'c>,
+}
+
+/// the result of an incremental compilation (see `compile_module_incremental`)
+struct IncrementalModules<'c> {
+    index: Index,
+    modules: Vec<GeneratedModule<'c>>,
+    /// the source files whose cached object files are up-to-date
+    reused: Vec<String>,
+    cache: cache::BuildCache,
+}
+
+/// the build cache of an incremental build (see `compile_module_incremental`) while
+/// `index_module` parses, indexes and annotates its files
+struct IncrementalBuild<'a> {
+    cache: cache::BuildCache,
+    /// the configurations every source file gets an object file for, `None` if all units are
+    /// generated into a single module
+    object_keys: Option<&'a [String]>,
+    /// the states of the files in the last build
+    previous: BTreeMap<String, cache::FileState>,
+    /// the states of the files in this build
+    current: BTreeMap<String, cache::FileState>,
+    /// the files whose content changed since the last build (including removed files)
+    changed: HashSet<String>,
+    /// the files that need to be annotated and generated again
+    dirty: HashSet<String>,
+    /// the hashes of the parsed sources, by their file id
+    hashes: HashMap<usize, u64>,
+    /// the index contributions of the sources loaded from the cache, by their file id
+    contributions: HashMap<usize, Index>,
+    /// the source files whose cached object files are up-to-date, they are not annotated
+    reused: Vec<String>,
+    /// the files with errors, their objects are not cached so their errors are reported again
+    failed: HashSet<usize>,
+}
+
+impl<'a> IncrementalBuild<'a> {
+    fn new(cache: cache::BuildCache, object_keys: Option<&'a [String]>) -> Self {
+        IncrementalBuild {
+            previous: cache.get_states().clone(),
+            cache,
+            object_keys,
+            current: BTreeMap::new(),
+            changed: HashSet::new(),
+            dirty: HashSet::new(),
+            hashes: HashMap::new(),
+            contributions: HashMap::new(),
+            reused: Vec::new(),
+            failed: HashSet::new(),
+        }
+    }
+
+    /// loads the unit of the given source from the cache if the source did not change since
+    /// the last build, returns `None` if it needs to be parsed
+    fn load(
+        &mut self,
+        source: &SourceCode,
+        file_id: usize,
+        id_provider: &IdProvider,
+    ) -> Option<CompilationUnit> {
+        let hash = cache::hash(&[&source.source]);
+        let loaded = self
+            .cache
+            .get_state(&source.path)
+            .filter(|it| it.hash == hash)
+            .cloned()
+            .and_then(|state| {
+                let (unit, index) = self.cache.load(&source.path, file_id, id_provider)?;
+                Some((state, unit, index))
+            });
+        match loaded {
+            Some((state, unit, index)) => {
+                self.current.insert(source.path.clone(), state);
+                self.contributions.insert(file_id, index);
+                Some(unit)
+            }
+            None => {
+                self.hashes.insert(file_id, hash);
+                None
+            }
+        }
+    }
+
+    /// returns the index contribution of the unit loaded from the cache for the given file
+    fn take_contribution(&mut self, file_id: usize) -> Option<Index> {
+        self.contributions.remove(&file_id)
+    }
+
+    /// stores the given pre-processed unit and its index contribution if the unit was parsed from
+    /// a source, a file with syntax errors is parsed again, so its errors are reported again
+    fn store(
+        &mut self,
+        file_id: usize,
+        unit: &CompilationUnit,
+        index: &Index,
+        has_syntax_errors: bool,
+    ) -> Result<(), Diagnostic> {
+        let hash = match self.hashes.get(&file_id) {
+            Some(hash) => *hash,
+            None => return Ok(()),
+        };
+        let path = unit.file_name.clone();
+        let serialized = serde_json::to_value(unit).and_then(|unit| {
+            serde_json::to_value(index).map(|index_contribution| (unit, index_contribution))
+        });
+        if let Ok((unit, index_contribution)) = serialized {
+            let state = cache::FileState {
+                hash,
+                interface: cache::interface_hash(&index_contribution),
+                declared: cache::declared_names(index),
+                referenced: cache::referenced_names(&unit),
+                objects: vec![],
+            };
+            if has_syntax_errors {
+                self.cache.remove(&path);
+            } else {
+                self.cache
+                    .store(&path, state.clone(), unit, index_contribution)?;
+            }
+            self.current.insert(path.clone(), state);
+        } else {
+            self.cache.remove(&path);
+        }
+        self.changed.insert(path);
+        Ok(())
+    }
+
+    /// determines the files that need to be annotated and generated again (see
+    /// `cache::find_dirty_files`) and saves the cache
+    fn find_dirty_files(&mut self) -> Result<(), Diagnostic> {
+        //the symbols of removed files are gone, their users need to be checked again
+        let removed = self
+            .previous
+            .keys()
+            .filter(|it| !self.current.contains_key(*it))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in removed {
+            self.cache.remove(&path);
+            self.changed.insert(path);
+        }
+        self.dirty = cache::find_dirty_files(&self.previous, &self.current, &self.changed);
+        for path in &self.dirty {
+            self.cache.invalidate_objects(path);
+        }
+        self.cache.save()
+    }
+
+    /// returns the units that need to be annotated and generated again, the units of sources
+    /// with an up-to-date object file for every configuration are reused
+    fn skip_up_to_date(&mut self, units: Units) -> Units {
+        let mut pending = Vec::new();
+        for unit in units {
+            let path = &unit.2.file_name;
+            let is_up_to_date = self.object_keys.map_or(false, |keys| {
+                !self.dirty.contains(path)
+                    && keys.iter().all(|key| self.cache.has_object(path, key))
+            });
+            if is_up_to_date {
+                self.reused.push(path.clone());
+            } else {
+                pending.push(unit);
+            }
+        }
+        pending
+    }
+
+    /// returns true if the object file generated for the given file can be cached, the objects
+    /// of files with errors and of the standard library are not cached
+    fn is_cacheable(&self, file_id: usize, path: &str) -> bool {
+        !self.failed.contains(&file_id) && self.current.contains_key(path)
+    }
+}
+
+/// Compiles the given sources like `compile_module`, but files that did not change since the
+/// last build are loaded from the cache in `compile_options.cache_dir` instead of being parsed
+/// and indexed.
+///
+/// With `object_keys` every source file is generated into its own module. Files that are not
+/// affected by a change and have an up-to-date object file for every key are neither annotated
+/// nor generated again. Without `object_keys` all units are generated into a single module.
+/// The files are parsed and annotated on `compile_options.jobs` threads.
+fn compile_module_incremental<'c, T: SourceContainer>(
+    context: &'c Context,
+    sources: Vec<T>,
+    includes: Vec<T>,
+    encoding: Option<&'static Encoding>,
+    compile_options: &CompileOptions,
+    object_keys: Option<&[String]>,
+    diagnostician: Diagnostician,
+) -> Result<IncrementalModules<'c>, Diagnostic> {
+    let cache_dir = compile_options
+        .cache_dir
+        .as_deref()
+        .ok_or_else(|| Diagnostic::param_error("An incremental build needs a cache directory"))?;
+    let load = |container: T| {
+        let location = container.get_location().to_string();
+        container
+            .load_source(encoding)
+            .map_err(|err| Diagnostic::io_read_error(location.as_str(), err.as_str()))
+    };
+    let sources = sources
+        .into_iter()
+        .map(load)
+        .collect::<Result<Vec<_>, _>>()?;
+    let includes = includes
+        .into_iter()
+        .map(load)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    //everything besides the sources that influences the cached results
+    let mut configuration = vec![env!("CARGO_PKG_VERSION").to_string()];
+    configuration.extend(compile_options.defines.iter().map(|it| format!("-D{}", it)));
+    for include in &includes {
+        configuration.push(format!("-i{}", include.path));
+        configuration.push(include.source.clone());
+    }
+    let cache = cache::BuildCache::open(
+        cache_dir,
+        cache::hash(&configuration.iter().map(String::as_str).collect::<Vec<_>>()),
+    )?;
+
+    // ### PHASE 1 & 2 ###
+    // load the unchanged sources from the cache, parse & index everything else and annotate
+    // everything that needs to be generated
+    let mut incremental = IncrementalBuild::new(cache, object_keys);
+    let (full_index, mut index) = index_module(
+        sources,
+        includes,
+        encoding,
+        compile_options,
+        Some(&mut incremental),
+        diagnostician,
+    )?;
+
+    // ### PHASE 3 ###
+    // - codegen
+    let options = compile_options.get_codegen_options();
+    let annotations = AstAnnotations::new(index.all_annotations, &mut index.id_provider);
+    let mut modules = Vec::new();
+    if object_keys.is_some() {
+        for (idx, (file_id, unit, literals)) in index.annotated_units.into_iter().enumerate() {
+            //included files only declare external symbols, there is nothing to generate
+            if index.included_units.contains(&idx) {
+                continue;
+            }
+            let code_generator =
+                codegen::CodeGen::new(context, &unit.file_name, options.for_file(file_id));
+            let llvm_index =
+                code_generator.generate_llvm_index(&annotations, literals, &full_index)?;
+            code_generator.generate(&unit, &annotations, &full_index, &llvm_index)?;
+            modules.push(GeneratedModule {
+                source: Some(unit.file_name.clone()),
+                cached: incremental.is_cacheable(file_id, &unit.file_name),
+                codegen: code_generator,
+            });
+        }
+        //the cached objects may be reused with other retained variables, so the entry points
+        //to save & restore them get a module of their own that is generated every time
+        if !compile_options.as_dependency
+            && !retain::collect_retained_variables(&full_index).is_empty()
+        {
+            let code_generator =
+                codegen::CodeGen::new(context, "retain", CodeGenOptions::for_declarations());
+            code_generator.generate_llvm_index(
+                &annotations,
+                StringLiterals::default(),
+                &full_index,
+            )?;
+            code_generator.generate_retain_functions(&full_index)?;
+            modules.push(GeneratedModule {
+                source: None,
+                cached: false,
+                codegen: code_generator,
+            });
+        }
+    } else {
+        let code_generator = codegen::CodeGen::new(context, "main", options);
+        let mut all_literals = StringLiterals::default();
+        for (.., literals) in index.annotated_units.iter_mut() {
+            all_literals.import(std::mem::take(literals));
+        }
+        let llvm_index =
+            code_generator.generate_llvm_index(&annotations, all_literals, &full_index)?;
+        for (_, unit, _) in index.annotated_units {
+            code_generator.generate(&unit, &annotations, &full_index, &llvm_index)?;
+        }
+        modules.push(GeneratedModule {
+            source: None,
+            cached: false,
+            codegen: code_generator,
+        });
+    }
+
+    Ok(IncrementalModules {
+        index: full_index,
+        modules,
+        reused: incremental.reused,
+        cache: incremental.cache,
+    })
+}
+
+/// the standard library is defined by the built project and only declared by its dependencies
+fn get_standard_library_linkage(compile_options: &CompileOptions) -> LinkageType {
+    if compile_options.as_dependency {
+        LinkageType::External
+    } else {
+        LinkageType::Weak
+    }
+}
+
+/// splits the library interface files (see `library`) from the given includes and reads them
+fn read_interfaces<T: SourceContainer>(
+    includes: Vec<T>,
+    id_provider: &IdProvider,
+) -> Result<(Vec<T>, Vec<Index>), Diagnostic> {
+    let mut sources = Vec::new();
+    let mut interfaces = Vec::new();
+    for container in includes {
+        if library::is_interface(container.get_location()) {
+            let location = container.get_location().to_string();
+            //interface files are always UTF-8
+            let source = container
+                .load_source(None)
+                .map_err(|err| Diagnostic::io_read_error(&location, &err))?;
+            interfaces.push(library::read_interface(&source, id_provider)?);
+        } else {
+            sources.push(container);
+        }
+    }
+    Ok((sources, interfaces))
+}
+
 type Units = Vec<(usize, Vec<Diagnostic>, CompilationUnit)>;
-fn parse_and_index<T: SourceContainer>(
+/// parses the given sources on up to `compile_options.jobs` threads, the unchanged sources of
+/// an `incremental` build are loaded from its cache instead
+fn parse_sources<T: SourceContainer>(
     source: Vec<T>,
     encoding: Option<&'static Encoding>,
+    compile_options: &CompileOptions,
     id_provider: &IdProvider,
     diagnostician: &mut Diagnostician,
     linkage: LinkageType,
-) -> Result<(Index, Units), Diagnostic> {
-    let mut index = Index::default();
-
-    let mut units = Vec::new();
-
-    //parse the builtins into the index
-    let builtins = builtins::parse_built_ins(id_provider.clone());
-    index.import(index::visitor::visit(&builtins, id_provider.clone()));
+    mut incremental: Option<&mut IncrementalBuild>,
+) -> Result<Units, Diagnostic> {
+    let mut sources = Vec::new();
 
     for container in source {
         let location: String = container.get_location().into();
@@ -643,21 +1361,118 @@ fn parse_and_index<T: SourceContainer>(
             .load_source(encoding)
             .map_err(|err| Diagnostic::io_read_error(location.as_str(), err.as_str()))?;
 
-        let (mut parse_result, diagnostics) = parser::parse(
-            lexer::lex_with_ids(e.source.as_str(), id_provider.clone()),
-            linkage,
-        );
+        //register the file with the diagnstician, so diagnostics are later able to show snippets from the code
+        //the parsed source ranges carry the file's id
+        let file_id = diagnostician.register_file(location.clone(), e.source.clone());
+        let cached = incremental
+            .as_deref_mut()
+            .and_then(|it| it.load(&e, file_id, id_provider));
+        sources.push((file_id, e, cached));
+    }
+
+    //the files are independent of each other until their names are resolved
+    let defines = &compile_options.defines;
+    Ok(parallel::map(
+        sources,
+        compile_options.jobs,
+        |(file_id, source, cached)| match cached {
+            Some(unit) => (file_id, vec![], unit),
+            None => {
+                let (parse_result, diagnostics) =
+                    parse_file(&source, file_id, defines, id_provider, linkage);
+                (file_id, diagnostics, parse_result)
+            }
+        },
+    ))
+}
 
-        //pre-process the ast (create inlined types)
-        ast::pre_process(&mut parse_result, id_provider.clone());
-        //index the pou
-        index.import(index::visitor::visit(&parse_result, id_provider.clone()));
+/// parses the given source code of the file registered with the given id
+fn parse_file(
+    source: &SourceCode,
+    file_id: usize,
+    defines: &[String],
+    id_provider: &IdProvider,
+    linkage: LinkageType,
+) -> (CompilationUnit, Vec<Diagnostic>) {
+    let (mut parse_result, diagnostics) = parser::parse(
+        lexer::lex_file(
+            source.source.as_str(),
+            file_id,
+            id_provider.clone(),
+            defines,
+        ),
+        linkage,
+    );
+    parse_result.file_name = source.path.clone();
+    parse_result.new_lines = ast::NewLines::build(&source.source);
+    (parse_result, diagnostics)
+}
 
-        //register the file with the diagnstician, so diagnostics are later able to show snippets from the code
-        let file_id = diagnostician.register_file(location.clone(), e.source);
-        units.push((file_id, diagnostics, parse_result));
+/// pre-processes & indexes the given units on up to `jobs` threads, the contributions of the
+/// units an `incremental` build loaded from its cache are reused and the new ones are stored
+fn index_units(
+    units: &mut Units,
+    id_provider: &IdProvider,
+    jobs: usize,
+    mut incremental: Option<&mut IncrementalBuild>,
+) -> Result<Index, Diagnostic> {
+    let mut index = Index::default();
+
+    //parse the builtins into the index
+    let builtins = builtins::parse_built_ins(id_provider.clone());
+    index.import(index::visitor::visit(&builtins, id_provider.clone()));
+
+    let units = units
+        .iter_mut()
+        .map(|unit| {
+            let cached = incremental
+                .as_deref_mut()
+                .and_then(|it| it.take_contribution(unit.0));
+            (unit, cached)
+        })
+        .collect::<Vec<_>>();
+    let contributions = parallel::map(units, jobs, |((file_id, syntax_errors, unit), cached)| {
+        let contribution = cached.unwrap_or_else(|| {
+            //pre-process the ast (create inlined types)
+            ast::pre_process(unit, id_provider.clone());
+            //index the pou
+            index::visitor::visit(unit, id_provider.clone())
+        });
+        (*file_id, !syntax_errors.is_empty(), unit, contribution)
+    });
+    //the contributions are imported in the order of the units
+    for (file_id, has_syntax_errors, unit, contribution) in contributions {
+        if let Some(incremental) = incremental.as_deref_mut() {
+            incremental.store(file_id, unit, &contribution, has_syntax_errors)?;
+        }
+        index.import(contribution);
     }
-    Ok((index, units))
+    Ok(index)
+}
+
+/// annotates & validates the given units on up to `compile_options.jobs` threads, the units only
+/// depend on the (complete) index
+fn annotate_units(
+    units: Units,
+    index: &Index,
+    compile_options: &CompileOptions,
+) -> Vec<AnnotatedUnit> {
+    let jobs = compile_options.jobs;
+    let bounds_check = compile_options.bounds_check;
+    parallel::map(units, jobs, |(file_id, syntax_errors, unit)| {
+        let (annotations, literals) = TypeAnnotator::visit_unit(index, &unit);
+
+        let mut validator = Validator::with_bounds_check(bounds_check);
+        validator.visit_unit(&annotations, index, &unit);
+        AnnotatedUnit {
+            file_id,
+            syntax_errors,
+            validation_errors: validator.diagnostics(),
+            unit,
+            annotations,
+            literals,
+        }
+    })
 }
 
 fn create_file_paths<T: Display + std::ops::Deref<Target = str>>(
@@ -690,6 +1505,42 @@ fn create_file_paths<T: Display + std::ops::Deref<Target = str>>(
     Ok(sources)
 }
 pub fn build_with_subcommand(parameters: CompileParameters) -> Result<(), Diagnostic> {
+    let sarif_log = SarifLog::default();
+    //until the build description is read, errors are reported in the format of the parameters
+    let mut error_format = parameters.error_format.unwrap_or_default();
+    let result = run_subcommand(&parameters, &mut error_format, &sarif_log);
+    report_build_result(result, error_format, &sarif_log)
+}
+
+/// runs the subcommand of the given parameters, the error format of a build is set once it is
+/// known from the build description
+fn run_subcommand(
+    parameters: &CompileParameters,
+    error_format: &mut ErrorFormat,
+    sarif_log: &SarifLog,
+) -> Result<(), Diagnostic> {
+    if let Some(SubCommands::ImportC { header, output }) = &parameters.commands {
+        let header = Path::new(header);
+        //without an output, the declarations are written to <header>.st in the current directory
+        let output = output.as_ref().map(PathBuf::from).unwrap_or_else(|| {
+            PathBuf::from(header.file_stem().unwrap_or(header.as_os_str())).with_extension("st")
+        });
+        //the sizes of the C types are taken from the targets, they have to agree on them
+        let mut data_models = parameters
+            .target
+            .iter()
+            .map(|it| Target::new(it.clone(), None).get_data_model());
+        let data_model = data_models
+            .next()
+            .unwrap_or_else(|| Target::System.get_data_model());
+        if data_models.any(|it| it != data_model) {
+            return Err(Diagnostic::param_error(
+                "Cannot import a C header for targets with different data models",
+            ));
+        }
+        return c_import::import_header_file(header, &output, data_model);
+    }
+
     let config_options = parameters
         .hardware_config
         .as_ref()
@@ -704,20 +1555,21 @@ pub fn build_with_subcommand(parameters: CompileParameters) -> Result<(), Diagno
         build_config,
         build_location,
         lib_location,
-        ..
+        profile,
     }) = &parameters.commands
     {
+        //without a build description, plc.json is used unless there is only a plc.toml
+        let default_config = if !Path::new("plc.json").exists() && Path::new("plc.toml").exists() {
+            "plc.toml"
+        } else {
+            "plc.json"
+        };
         let build_config = build_config
             .as_deref()
-            .or(Some("plc.json"))
+            .or(Some(default_config))
             .map(PathBuf::from)
             .ok_or_else(|| unreachable!("The or plc.json means this exists"))
             .and_then(|it| env::current_dir().map(|cd| cd.join(it)))?;
-        let root = build_config
-            .parent()
-            .map(|it| Ok(it.to_path_buf()))
-            .unwrap_or_else(env::current_dir)?;
-        env::set_var("PROJECT_ROOT", &root);
         let build_location = Path::new(build_location.as_deref().unwrap_or("build"));
         if !build_location.is_dir() {
             std::fs::create_dir_all(build_location)?;
@@ -730,95 +1582,312 @@ pub fn build_with_subcommand(parameters: CompileParameters) -> Result<(), Diagno
             .unwrap_or(build_location);
         // let lib_location = make_absolute(lib_location, &root);
         env::set_var("LIB_LOCATION", &lib_location);
-        let project = get_project_from_file(&build_config).map(|it| it.to_resolved(&root))?;
 
-        let input = project
-            .files
-            .first()
-            .and_then(|it| it.file_stem())
-            .and_then(OsStr::to_str)
-            .unwrap_or("out");
-
-        let includes = if !project.libraries.is_empty() {
-            create_file_paths(
-                &project
-                    .libraries
-                    .iter()
-                    .flat_map(|it| &it.include_path)
-                    .flat_map(|it| it.as_os_str().to_str())
-                    .collect::<Vec<_>>(),
-            )?
-        } else {
-            vec![]
+        //the dependencies are built first, the project itself is the last one
+        let workspace = build::collect_workspace(&build_config)?;
+        let project_index = workspace.len() - 1;
+        //the diagnostics of all projects are reported in the format of the built project
+        *error_format = parameters
+            .error_format
+            .or(workspace[project_index].project.error_format)
+            .unwrap_or_default();
+        let settings = WorkspaceSettings {
+            parameters,
+            profile: profile.as_deref(),
+            build_location,
+            lib_location,
+            error_format: *error_format,
+            sarif_log,
         };
+        let mut config_options = config_options;
+        let mut built: HashMap<PathBuf, Vec<DependencyOutput>> = HashMap::new();
+        for (index, member) in workspace.into_iter().enumerate() {
+            //a project uses its dependencies' dependencies as well
+            let mut dependencies: Vec<DependencyOutput> = vec![];
+            for output in member.dependencies.iter().flat_map(|it| &built[it]) {
+                if !dependencies.contains(output) {
+                    dependencies.push(output.clone());
+                }
+            }
+            let is_dependency = index < project_index;
+            let output = build_project(
+                &settings,
+                &member,
+                if is_dependency {
+                    None
+                } else {
+                    config_options.take()
+                },
+                &dependencies,
+                is_dependency,
+            )?;
+            dependencies.push(output);
+            built.insert(member.location, dependencies);
+        }
+    }
+    Ok(())
+}
 
-        let compile_options = CompileOptions {
-            build_location: Some(build_location.to_owned()),
-            output: get_output_name(project.output.as_deref(), project.compile_type, input),
-            format: if parameters.check_only {
-                FormatOption::None
-            } else {
-                project.compile_type
-            },
-            optimization: parameters.optimization,
-            error_format: parameters.error_format,
-        };
+/// the results of building a dependency, used by the projects depending on it
+#[derive(Clone, PartialEq)]
+struct DependencyOutput {
+    /// the interface of the dependency, included by the dependent projects
+    interface: PathBuf,
+    /// the dependency's output relative to the build directory of a target,
+    /// if it can be linked into the dependent projects
+    output: Option<PathBuf>,
+}
+
+/// the settings shared by all projects of a workspace build
+struct WorkspaceSettings<'a> {
+    parameters: &'a CompileParameters,
+    /// the selected profile, dependencies that do not declare it are built without a profile
+    profile: Option<&'a str>,
+    build_location: &'a Path,
+    lib_location: &'a Path,
+    /// the error format of the built project, used for all projects of the workspace
+    error_format: ErrorFormat,
+    /// the log the diagnostics of all projects are added to with `ErrorFormat::Sarif`
+    sarif_log: &'a SarifLog,
+}
+
+/// builds a project of the workspace against its already built dependencies
+fn build_project(
+    settings: &WorkspaceSettings,
+    member: &build::WorkspaceProject,
+    config_options: Option<ConfigurationOptions>,
+    dependencies: &[DependencyOutput],
+    is_dependency: bool,
+) -> Result<DependencyOutput, Diagnostic> {
+    let WorkspaceSettings {
+        parameters,
+        profile,
+        build_location,
+        lib_location,
+        error_format,
+        sarif_log,
+    } = *settings;
+    let project = &member.project;
+    env::set_var("PROJECT_ROOT", &member.root);
+    //dependencies are built without the profiles they do not declare
+    let profile = match profile {
+        Some(profile) if is_dependency => project.profiles.get(profile),
+        Some(profile) => Some(project.get_profile(profile)?),
+        None => None,
+    };
+
+    //the hardware configuration of the parameters replaces the one of the project
+    let config_options = match (config_options, project.hardware_config.as_ref()) {
+        (None, Some(config)) => {
+            let output = config.to_string_lossy().to_string();
+            Some(ConfigurationOptions {
+                format: cli::get_config_format(&output).ok_or_else(|| {
+                    Diagnostic::param_error(&format!(
+                        r#"Cannot identify format type for {}, valid extensions : "json", "toml""#,
+                        output
+                    ))
+                })?,
+                output,
+            })
+        }
+        (config_options, _) => config_options,
+    };
+
+    let input = project
+        .files
+        .first()
+        .and_then(|it| it.file_stem())
+        .and_then(OsStr::to_str)
+        .unwrap_or("out");
+
+    let includes = create_file_paths(
+        &project
+            .libraries
+            .iter()
+            .flat_map(|it| it.include_path.iter().chain(it.interface.iter()))
+            .chain(project.includes.iter())
+            .chain(dependencies.iter().map(|it| &it.interface))
+            .flat_map(|it| it.as_os_str().to_str())
+            .collect::<Vec<_>>(),
+    )?;
+
+    //options given as parameters replace the ones of the profile and the project
+    let optimization = parameters
+        .optimization
+        .or_else(|| profile.and_then(|it| it.optimization))
+        .or(project.optimization)
+        .unwrap_or_default();
+    let diagnostic_policy = parameters.apply_warning_options(project.get_diagnostic_policy()?)?;
+    let debug_level = if profile.map(|it| it.debug).unwrap_or_default() {
+        DebugLevel::Full
+    } else {
+        parameters.debug_level()
+    };
+
+    let output = get_output_name(project.output.as_deref(), project.compile_type, input);
+    //a dependency's interface is written next to its output, the interface and the cache
+    //of the parameters belong to the built project
+    let output_stem = Path::new(&output)
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or(input);
+    let interface =
+        build_location.join(format!("{}.{}", output_stem, library::INTERFACE_EXTENSION));
+    let (emit_interface, cache_dir) = if is_dependency {
+        (
+            Some(interface.clone()),
+            parameters
+                .cache_dir
+                .as_ref()
+                .map(|it| Path::new(it).join(output_stem)),
+        )
+    } else {
+        (
+            parameters.emit_interface.as_ref().map(PathBuf::from),
+            parameters.cache_dir.as_ref().map(PathBuf::from),
+        )
+    };
+
+    let compile_options = CompileOptions {
+        build_location: Some(build_location.to_owned()),
+        output: output.clone(),
+        format: if parameters.check_only {
+            FormatOption::None
+        } else {
+            project.compile_type
+        },
+        optimization,
+        error_format,
+        defines: parameters
+            .defines
+            .iter()
+            .chain(project.defines.iter())
+            .chain(profile.iter().flat_map(|it| it.defines.iter()))
+            .cloned()
+            .collect(),
+        debug_level,
+        cache_dir,
+        jobs: parameters.jobs,
+        separate_objects: parameters.separate_objects,
+        emit_interface,
+        emit_header: if is_dependency {
+            None
+        } else {
+            parameters.emit_header.as_ref().map(PathBuf::from)
+        },
+        retain_map: if is_dependency {
+            None
+        } else {
+            parameters.retain_map.as_ref().map(PathBuf::from)
+        },
+        as_dependency: is_dependency,
+        diagnostic_policy,
+        bounds_check: parameters.bounds_check,
+        sarif_log: sarif_log.clone(),
+    };
 
-        let targets = parameters
+    //targets declared in the project are selected by their name, others are used as triples
+    let targets = if parameters.target.is_empty() {
+        project
+            .targets
+            .iter()
+            .map(|it| it.to_target(None, parameters.linker.clone()))
+            .collect::<Vec<_>>()
+    } else {
+        parameters
             .target
-            .into_iter()
+            .iter()
             .enumerate()
             .map(|(index, target)| {
-                let sysroot = parameters.sysroot.get(index);
-                Target::new(target, sysroot.cloned())
+                let sysroot = parameters.sysroot.get(index).cloned();
+                match project.get_target(target) {
+                    Some(description) => description.to_target(sysroot, parameters.linker.clone()),
+                    None => Target::new(target.clone(), sysroot),
+                }
             })
-            .collect::<Vec<_>>();
+            .collect::<Vec<_>>()
+    };
 
-        let files = create_file_paths(
-            &project
-                .files
-                .iter()
-                .map(|it| it.to_string_lossy())
-                .map(|it| it.to_string())
-                .collect::<Vec<_>>(),
-        )?;
-        let link_options = if parameters.compile_only {
-            None
+    let files = create_file_paths(
+        &project
+            .files
+            .iter()
+            .map(|it| it.to_string_lossy())
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>(),
+    )?;
+    let link_options = if parameters.compile_only {
+        None
+    } else {
+        //the dependencies are linked by their file name from the build directory of every target
+        let target_locations = if targets.is_empty() {
+            vec![build_location.to_path_buf()]
         } else {
-            Some(LinkOptions {
-                libraries: project
-                    .libraries
-                    .iter()
-                    .map(|it| it.name.clone())
-                    .collect::<Vec<_>>(),
-                library_pathes: project
-                    .libraries
-                    .iter()
-                    .map(|it| it.path.to_string_lossy())
-                    .map(|it| it.to_string())
-                    .collect(),
-                format: project.compile_type,
-                linker: parameters.linker,
-            })
+            targets
+                .iter()
+                .map(|it| match it.try_get_name() {
+                    Some(name) => build_location.join(name),
+                    None => build_location.to_path_buf(),
+                })
+                .collect()
         };
+        let linked_dependencies = dependencies
+            .iter()
+            .flat_map(|it| it.output.as_ref())
+            .collect::<Vec<_>>();
+        Some(LinkOptions {
+            libraries: project
+                .libraries
+                .iter()
+                .map(|it| it.name.clone())
+                .chain(
+                    linked_dependencies
+                        .iter()
+                        .flat_map(|it| it.file_name())
+                        .map(|it| format!(":{}", it.to_string_lossy())),
+                )
+                .collect::<Vec<_>>(),
+            library_pathes: project
+                .libraries
+                .iter()
+                .map(|it| it.path.to_string_lossy())
+                .map(|it| it.to_string())
+                .chain(target_locations.iter().flat_map(|location| {
+                    linked_dependencies
+                        .iter()
+                        .map(move |it| location.join(it))
+                        .flat_map(|it| it.parent().map(|it| it.to_string_lossy().to_string()))
+                }))
+                .collect(),
+            format: project.compile_type,
+            linker: parameters.linker.clone(),
+        })
+    };
 
-        copy_libs_to_build(&project.libraries, lib_location)?;
+    copy_libs_to_build(&project.libraries, lib_location)?;
 
-        build_and_link(
-            files,
-            includes,
-            parameters.encoding,
-            &compile_options,
-            targets,
-            config_options,
-            link_options,
-        )?;
+    build_and_link(
+        files,
+        includes,
+        parameters.encoding,
+        &compile_options,
+        targets,
+        config_options,
+        link_options,
+    )?;
 
-        if !project.package_commands.is_empty() {
-            execute_commands(project.package_commands)?;
-        }
+    if !project.package_commands.is_empty() {
+        execute_commands(project.package_commands.clone())?;
     }
-    Ok(())
+
+    let linkable = matches!(
+        project.compile_type,
+        FormatOption::Shared | FormatOption::PIC | FormatOption::Relocatable | FormatOption::Object
+    );
+    Ok(DependencyOutput {
+        interface,
+        output: linkable.then(|| PathBuf::from(output)),
+    })
 }
 
 // fn make_absolute(location: &Path, root: &Path) -> PathBuf {
@@ -878,6 +1947,35 @@ fn resolve_environment_variables(to_replace: &str) -> Result<String, Diagnostic>
     Ok(result.replace('\\', r"\\"))
 }
 
+/// returns the offset within `to_replace` of the given offset within its resolved content (see
+/// `resolve_environment_variables`), an offset within a resolved variable maps to the variable
+fn get_unresolved_offset(to_replace: &str, resolved_offset: usize) -> Result<usize, Diagnostic> {
+    let pattern = Regex::new(r"\$(\w+)|\\")?;
+    let (mut offset, mut resolved) = (0, 0);
+    for it in pattern.captures_iter(to_replace) {
+        let replaced = it.get(0).unwrap();
+        let unchanged = replaced.start() - offset;
+        if resolved_offset < resolved + unchanged {
+            break;
+        }
+        resolved += unchanged;
+        offset = replaced.start();
+
+        //variables are resolved before the backslashes are escaped
+        let value = it
+            .get(1)
+            .and_then(|var| env::var(var.as_str()).ok())
+            .unwrap_or_else(|| replaced.as_str().to_string());
+        let replacement = value.len() + value.matches('\\').count();
+        if resolved_offset < resolved + replacement {
+            return Ok(offset);
+        }
+        resolved += replacement;
+        offset = replaced.end();
+    }
+    Ok(offset + resolved_offset.saturating_sub(resolved))
+}
+
 fn copy_libs_to_build(libraries: &[Libraries], lib_location: &Path) -> Result<(), Diagnostic> {
     for library in libraries {
         if library.package == PackageFormat::Copy {
@@ -891,6 +1989,46 @@ fn copy_libs_to_build(libraries: &[Libraries], lib_location: &Path) -> Result<()
     Ok(())
 }
 
+/// returns the explanation of the given diagnostic code (e.g. `E031` or its name)
+pub fn explain(code: &str) -> Result<String, Diagnostic> {
+    let code = code
+        .parse::<diagnostics::ErrNo>()
+        .map_err(|err| Diagnostic::param_error(&err))?;
+    Ok(format!(
+        "{} ({})\n\n{}",
+        code.get_code(),
+        code.get_name(),
+        code.get_explanation()
+    ))
+}
+
+/// creates the diagnostician reporting in the given format, SARIF results are added to the
+/// given log
+fn create_diagnostician(error_format: ErrorFormat, sarif_log: &SarifLog) -> Diagnostician {
+    match error_format {
+        ErrorFormat::Rich => Diagnostician::default(),
+        ErrorFormat::Clang => Diagnostician::clang_format_diagnostician(),
+        ErrorFormat::Json => Diagnostician::json_diagnostician(),
+        ErrorFormat::Sarif => Diagnostician::sarif_diagnostician(sarif_log.clone()),
+    }
+}
+
+/// reports the error that stopped a build in the given format and writes the SARIF log of the
+/// build, the result is returned unchanged
+fn report_build_result(
+    result: Result<(), Diagnostic>,
+    error_format: ErrorFormat,
+    sarif_log: &SarifLog,
+) -> Result<(), Diagnostic> {
+    if let Err(error) = &result {
+        create_diagnostician(error_format, sarif_log).report_fatal(error.clone());
+    }
+    if error_format == ErrorFormat::Sarif {
+        sarif_log.write();
+    }
+    result
+}
+
 /// The driver function for the compilation
 /// Sorts files that need compilation
 /// Parses, validates and generates code for the given source files
@@ -898,6 +2036,18 @@ fn copy_libs_to_build(libraries: &[Libraries], lib_location: &Path) -> Result<()
 /// Links any provided libraries
 /// Returns the location of the output file
 pub fn build_with_params(parameters: CompileParameters) -> Result<(), Diagnostic> {
+    let error_format = parameters.error_format.unwrap_or_default();
+    let sarif_log = SarifLog::default();
+    let result = if let Some(code) = &parameters.explain {
+        explain(code).map(|explanation| println!("{}", explanation))
+    } else {
+        build_parameters(parameters, &sarif_log)
+    };
+    report_build_result(result, error_format, &sarif_log)
+}
+
+/// builds the files given as parameters, the diagnostics are added to the given SARIF log
+fn build_parameters(parameters: CompileParameters, sarif_log: &SarifLog) -> Result<(), Diagnostic> {
     let format = parameters.output_format_or_default();
     let output = parameters.output_name();
 
@@ -911,6 +2061,7 @@ pub fn build_with_params(parameters: CompileParameters) -> Result<(), Diagnostic
             output: config.to_owned(),
         });
 
+    let diagnostic_policy = parameters.apply_warning_options(DiagnosticPolicy::default())?;
     let compile_options = CompileOptions {
         build_location: None,
         output,
@@ -919,8 +2070,20 @@ pub fn build_with_params(parameters: CompileParameters) -> Result<(), Diagnostic
         } else {
             format
         },
-        optimization: parameters.optimization,
-        error_format: parameters.error_format,
+        optimization: parameters.optimization.unwrap_or_default(),
+        error_format: parameters.error_format.unwrap_or_default(),
+        debug_level: parameters.debug_level(),
+        defines: parameters.defines,
+        cache_dir: parameters.cache_dir.map(PathBuf::from),
+        jobs: parameters.jobs,
+        separate_objects: parameters.separate_objects,
+        emit_interface: parameters.emit_interface.map(PathBuf::from),
+        emit_header: parameters.emit_header.map(PathBuf::from),
+        retain_map: parameters.retain_map.map(PathBuf::from),
+        as_dependency: false,
+        diagnostic_policy,
+        bounds_check: parameters.bounds_check,
+        sarif_log: sarif_log.clone(),
     };
 
     let files = create_file_paths(
@@ -995,20 +2158,123 @@ pub fn build_and_link(
         }
     });
 
+    let targets = if targets.is_empty() {
+        vec![Target::System]
+    } else {
+        targets
+    };
+    //the sources are compiled once for all targets, so only what the targets share is defined
+    let compile_options = &CompileOptions {
+        defines: [
+            compile_options.defines.clone(),
+            get_target_defines(&targets),
+        ]
+        .concat(),
+        ..compile_options.clone()
+    };
+    let should_link = link_options
+        .as_ref()
+        .map(|it| it.format.should_link())
+        .unwrap_or_default();
+    //the object files are cached per target and for the options they were generated with
+    let object_keys = targets
+        .iter()
+        .map(|target| {
+            cache::object_key(&[
+                &target.get_target_triple().as_str().to_string_lossy(),
+                &format!("{:?}", compile_options.format),
+                &format!("{:?}", compile_options.optimization),
+                &format!("{:?}", compile_options.debug_level),
+                &format!("bounds-check={}", compile_options.bounds_check),
+            ])
+        })
+        .collect::<Vec<_>>();
+
     let context = Context::create();
-    let diagnostician = match compile_options.error_format {
-        ErrorFormat::Rich => Diagnostician::default(),
-        ErrorFormat::Clang => Diagnostician::clang_format_diagnostician(),
+    let mut diagnostician =
+        create_diagnostician(compile_options.error_format, &compile_options.sarif_log)
+            .with_policy(compile_options.diagnostic_policy.clone());
+    //the interface is created from the sources, as if they were included
+    let interface = if compile_options.emit_interface.is_some() {
+        Some(library::create_interface(
+            sources.clone(),
+            encoding,
+            &compile_options.defines,
+            &mut diagnostician,
+        )?)
+    } else {
+        None
+    };
+    //separate outputs are written next to the output instead of being linked into it
+    let separate_outputs = compile_options.separate_objects && !should_link;
+    let (index, modules, reused, mut cache) = if compile_options.cache_dir.is_some() {
+        //linked outputs are always split into one object per file
+        let compiled = compile_module_incremental(
+            &context,
+            sources,
+            includes,
+            encoding,
+            compile_options,
+            (should_link || compile_options.separate_objects).then(|| object_keys.as_slice()),
+            diagnostician,
+        )?;
+        (
+            compiled.index,
+            compiled.modules,
+            compiled.reused,
+            Some(compiled.cache),
+        )
+    } else if compile_options.separate_objects {
+        let (index, modules) = compile_module_per_file(
+            &context,
+            sources,
+            includes,
+            encoding,
+            compile_options,
+            diagnostician,
+        )?;
+        let modules = modules
+            .into_iter()
+            .map(|(source, codegen)| GeneratedModule {
+                source: Some(source),
+                cached: false,
+                codegen,
+            })
+            .collect();
+        (index, modules, vec![], None)
+    } else {
+        let (index, codegen) = compile_module_in_parallel(
+            &context,
+            sources,
+            includes,
+            encoding,
+            compile_options,
+            diagnostician,
+        )?;
+        let module = GeneratedModule {
+            source: None,
+            cached: false,
+            codegen,
+        };
+        (index, vec![module], vec![], None)
     };
-    let (index, codegen) = compile_module(&context, sources, includes, encoding, diagnostician)?;
+
+    if let (Some(location), Some(interface)) = (&compile_options.emit_interface, interface) {
+        library::write_interface(&interface, location)?;
+    }
+    if let Some(location) = &compile_options.emit_header {
+        header::write_header(&index, location)?;
+    }
+    if let Some(location) = &compile_options.retain_map {
+        let module = modules
+            .iter()
+            .map(|it| &it.codegen.module)
+            .find(|it| it.get_function(retain::SAVE_FUNCTION).is_some());
+        retain::write_retain_map(&index, module, location)?;
+    }
 
     if compile_options.format != FormatOption::None {
-        let targets = if targets.is_empty() {
-            vec![Target::System]
-        } else {
-            targets
-        };
-        for target in targets {
+        for (target, object_key) in targets.iter().zip(&object_keys) {
             let triple = target.get_target_triple();
             let output = if let Some(target_name) = target.try_get_name() {
                 let target_path = PathBuf::from(&target_name);
@@ -1028,24 +2294,76 @@ pub fn build_and_link(
                 .file_name()
                 .and_then(|it| it.to_str())
                 .unwrap_or("tmp.o");
-            let compile_location = if link_options
-                .as_ref()
-                .map(|it| it.format.should_link())
-                .unwrap_or_default()
-            {
+            let compile_location = if should_link {
                 let compile_dir = tempfile::tempdir()?;
                 compile_dir.path().join(compile_name)
             } else {
                 PathBuf::from(output_name)
             };
 
-            objects.push(persist(
-                &codegen,
-                &compile_location,
-                compile_options.format,
-                &triple,
-                compile_options.optimization,
-            )?);
+            //every file needs its own separate output
+            let mut separate_locations = HashSet::new();
+            let mut get_separate_location = |source: &str| {
+                let location = get_separate_output(&output, source, compile_options.format);
+                if separate_locations.insert(location.clone()) {
+                    Ok(location)
+                } else {
+                    Err(Diagnostic::param_error(&format!(
+                        "Cannot write the output of {} to {}, another file is written there",
+                        source,
+                        location.to_string_lossy()
+                    )))
+                }
+            };
+            //the objects kept in the cache with the file they were generated for
+            let mut cached_objects = Vec::new();
+            for (module_index, module) in modules.iter().enumerate() {
+                let source = module.source.as_deref();
+                let location = match (source, cache.as_ref()) {
+                    (Some(source), Some(cache)) if module.cached => {
+                        cache.get_object_path(source, object_key)
+                    }
+                    (Some(source), _) if separate_outputs => get_separate_location(source)?,
+                    _ if modules.len() > 1 => {
+                        compile_location.with_file_name(format!("{module_index}_{compile_name}"))
+                    }
+                    _ => compile_location.clone(),
+                };
+                objects.push(persist(
+                    &module.codegen,
+                    &location,
+                    compile_options.format,
+                    &triple,
+                    compile_options.optimization,
+                )?);
+                if let (Some(source), Some(cache)) = (source, cache.as_mut()) {
+                    if module.cached {
+                        cache.add_object(source, object_key);
+                        cached_objects.push((source.to_string(), location));
+                    }
+                }
+            }
+            if let Some(cache) = cache.as_ref() {
+                for source in &reused {
+                    let location = cache.get_object_path(source, object_key);
+                    objects.push(location.as_path().into());
+                    cached_objects.push((source.clone(), location));
+                }
+            }
+            if separate_outputs {
+                for (source, location) in cached_objects {
+                    let separate_output = get_separate_location(&source)?;
+                    if let Some(parent) = separate_output.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::copy(&location, &separate_output).map_err(|err| {
+                        Diagnostic::io_write_error(
+                            &separate_output.to_string_lossy(),
+                            &err.to_string(),
+                        )
+                    })?;
+                }
+            }
 
             if let Some(link_options) = link_options.as_ref() {
                 if link_options.format.should_link() {
@@ -1055,8 +2373,8 @@ pub fn build_and_link(
                         &objects,
                         &link_options.library_pathes,
                         &link_options.libraries,
-                        &target,
-                        link_options.linker.as_deref(),
+                        target,
+                        target.get_linker().or(link_options.linker.as_deref()),
                     )?;
                 }
             }
@@ -1074,11 +2392,31 @@ pub fn build_and_link(
                     })?;
             }
         }
+        if let Some(cache) = cache.as_ref() {
+            cache.save()?;
+        }
     }
 
     Ok(())
 }
 
+/// returns the location of the separately generated output of the given source file, it is
+/// written into the directory of the given output and named after the source file
+fn get_separate_output(output: &Path, source: &str, format: FormatOption) -> PathBuf {
+    let path = Path::new(source);
+    let name = path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or(DEFAULT_OUTPUT_NAME);
+    //the standard library's files get a reserved name so they don't clash with the user's files
+    let name = if path.starts_with(stdlib::LOCATION) {
+        format!("__stdlib_{name}")
+    } else {
+        name.to_string()
+    };
+    output.with_file_name(get_output_name(None, format, &name))
+}
+
 pub fn persist(
     input: &codegen::CodeGen,
     output: &Path,
@@ -1221,4 +2559,51 @@ END_PROGRAM
         let source = create_source_code(&mut source, None).unwrap();
         assert_eq!(expected, &source);
     }
+
+    #[test]
+    fn target_defines_name_the_operating_system_and_data_model() {
+        use crate::{get_target_defines, DataModel, Target};
+
+        let windows = Target::from("x86_64-pc-windows-msvc");
+        assert_eq!(windows.get_data_model(), DataModel::Llp64);
+        assert_eq!(
+            windows.get_defines(),
+            vec!["__TARGET_WINDOWS", "__TARGET_LLP64"]
+        );
+        let darwin = Target::from("aarch64-apple-darwin");
+        assert_eq!(
+            darwin.get_defines(),
+            vec!["__TARGET_DARWIN", "__TARGET_LP64"]
+        );
+        let arm = Target::from("armv7-unknown-linux-gnueabihf");
+        assert_eq!(arm.get_defines(), vec!["__TARGET_LINUX", "__TARGET_ILP32"]);
+
+        //only the names shared by all targets are defined
+        let linux = Target::from("x86_64-unknown-linux-gnu");
+        assert_eq!(
+            get_target_defines(&[linux, Target::from("aarch64-unknown-linux-gnu")]),
+            vec!["__TARGET_LINUX", "__TARGET_LP64"]
+        );
+        assert_eq!(get_target_defines(&[darwin, arm]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn diagnostic_codes_are_explained() {
+        let explanation = crate::explain("E031").unwrap();
+        assert!(
+            explanation.starts_with("E031 (type__incompatible_arrayaccess_range)\n\n"),
+            "{}",
+            explanation
+        );
+        assert!(explanation.contains("values[11] := 0;"), "{}", explanation);
+        //codes can also be given by their name
+        assert_eq!(
+            crate::explain("type__incompatible_arrayaccess_range").unwrap(),
+            explanation
+        );
+        assert_eq!(
+            crate::explain("E999").unwrap_err().get_message(),
+            "Unknown diagnostic code 'E999'"
+        );
+    }
 }
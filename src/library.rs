@@ -0,0 +1,131 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! Library interface files (`*.stif`).
+//!
+//! An interface file holds the declarations of a compiled library: the signatures of its POUs
+//! (including generic ones), its types and its global variables. They are stored as the `Index`
+//! the library's sources would contribute if they were included (see `-i`), so a consumer can
+//! include the interface file instead of the library's sources or hand-written headers.
+use std::{fs, path::Path};
+
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ast::{self, LinkageType},
+    cache,
+    diagnostics::{Diagnostic, DiagnosticAssessor, DiagnosticReporter, Diagnostician, Severity},
+    index::{self, Index},
+    lexer::{self, IdProvider},
+    parser, SourceCode, SourceContainer,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// the extension of library interface files
+pub const INTERFACE_EXTENSION: &str = "stif";
+
+#[derive(Serialize, Deserialize)]
+struct LibraryInterface {
+    /// the version of the compiler that created the interface, the format of the index may
+    /// change with every version
+    version: String,
+    index: Value,
+}
+
+/// returns true if the given location is a library interface file
+pub fn is_interface(location: &str) -> bool {
+    Path::new(location)
+        .extension()
+        .map_or(false, |it| it == INTERFACE_EXTENSION)
+}
+
+/// creates the interface of a library compiled from the given sources. All its declarations are
+/// external, the library's implementation is expected at link time.
+/// The errors found while parsing the sources are reported to the given `diagnostician`, no
+/// interface is created for erroneous sources. Their warnings are left to the compilation.
+pub fn create_interface<T: SourceContainer>(
+    sources: Vec<T>,
+    encoding: Option<&'static Encoding>,
+    defines: &[String],
+    diagnostician: &mut Diagnostician,
+) -> Result<Index, Diagnostic> {
+    let id_provider = IdProvider::default();
+    let mut units = Vec::new();
+    let mut has_errors = false;
+    for container in sources {
+        let location = container.get_location().to_string();
+        let source = container
+            .load_source(encoding)
+            .map_err(|err| Diagnostic::io_read_error(&location, &err))?;
+        let file_id = diagnostician.register_file(location, source.source.clone());
+        //the locations point into the library's sources, they are not available to a consumer
+        let (unit, diagnostics) = parser::parse(
+            lexer::lex_with_defines(&source.source, id_provider.clone(), defines),
+            LinkageType::External,
+        );
+        let errors = diagnostician
+            .assess_all(diagnostics)
+            .into_iter()
+            .filter(|it| matches!(it.severity, Severity::Error))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            diagnostician.report(&errors, file_id);
+            has_errors = true;
+        }
+        units.push(unit);
+    }
+    if has_errors {
+        return Err(Diagnostic::param_error(
+            "Cannot create the library interface, its sources contain errors",
+        ));
+    }
+
+    let mut index = Index::default();
+    for unit in units.iter_mut() {
+        ast::pre_process(unit, id_provider.clone());
+        index.import(index::visitor::visit(unit, id_provider.clone()));
+    }
+    index.resolve_namespaces();
+    Ok(index)
+}
+
+/// writes the given interface to the given file
+pub fn write_interface(interface: &Index, path: &Path) -> Result<(), Diagnostic> {
+    let content = serde_json::to_string(&LibraryInterface {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        index: serde_json::to_value(interface)?,
+    })?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+        .map_err(|err| Diagnostic::io_write_error(&path.to_string_lossy(), &err.to_string()))
+}
+
+/// reads the interface from the given interface file, its ids are replaced by new ones of the
+/// given `id_provider`
+pub fn read_interface(source: &SourceCode, id_provider: &IdProvider) -> Result<Index, Diagnostic> {
+    let invalid_interface = |err: serde_json::Error| {
+        Diagnostic::param_error(&format!(
+            "{} is not a valid library interface: {}",
+            source.path, err
+        ))
+    };
+    let interface: LibraryInterface =
+        serde_json::from_str(&source.source).map_err(invalid_interface)?;
+    if interface.version != env!("CARGO_PKG_VERSION") {
+        return Err(Diagnostic::param_error(&format!(
+            "{} was created by version {} of the compiler, it needs to be created again with version {}",
+            source.path,
+            interface.version,
+            env!("CARGO_PKG_VERSION")
+        )));
+    }
+
+    cache::with_renewed_ids(None, id_provider, || {
+        serde_json::from_value(interface.index)
+    })
+    .map_err(invalid_interface)
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use crate::{
+    ast::LinkageType,
+    diagnostics::Diagnostician,
+    lexer::IdProvider,
+    library::{self, INTERFACE_EXTENSION},
+    test_utils::tests::compile_to_string,
+    SourceCode,
+};
+
+fn vendor_library() -> SourceCode {
+    SourceCode {
+        path: "vendor.st".into(),
+        source: "
+        TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+
+        VAR_GLOBAL
+            origin : Point;
+            count : INT := 3;
+        END_VAR
+
+        FUNCTION distance : INT
+        VAR_INPUT p : Point; END_VAR
+            distance := p.x + p.y;
+        END_FUNCTION
+
+        FUNCTION times_two<T: ANY_NUM> : T
+        VAR_INPUT val : T; END_VAR
+        END_FUNCTION
+
+        FUNCTION times_two__INT : INT
+        VAR_INPUT val : INT; END_VAR
+            times_two__INT := val * 2;
+        END_FUNCTION
+        "
+        .into(),
+    }
+}
+
+/// creates the interface of the vendor library and returns the content of its interface file
+fn vendor_interface() -> SourceCode {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(format!("vendor.{}", INTERFACE_EXTENSION));
+    let interface = library::create_interface(
+        vec![vendor_library()],
+        None,
+        &[],
+        &mut Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+    library::write_interface(&interface, &path).unwrap();
+    SourceCode {
+        path: path.to_string_lossy().to_string(),
+        source: std::fs::read_to_string(&path).unwrap(),
+    }
+}
+
+#[test]
+fn interfaces_contain_the_external_declarations_of_the_library() {
+    let interface = library::read_interface(&vendor_interface(), &IdProvider::default()).unwrap();
+
+    let distance = interface.find_pou("distance").unwrap();
+    assert_eq!(distance.get_linkage(), &LinkageType::External);
+    assert!(interface.find_pou("times_two").unwrap().is_generic());
+    assert!(interface.find_pou("times_two__INT").is_some());
+    assert!(interface.find_global_variable("count").is_some());
+    assert!(interface.find_effective_type_by_name("Point").is_some());
+    assert!(distance.find_implementation(&interface).is_some());
+}
+
+#[test]
+fn including_the_interface_is_like_including_the_sources() {
+    let program = || -> SourceCode {
+        "
+        FUNCTION main : INT
+            main := distance(origin) + count + times_two(INT#2);
+        END_FUNCTION
+        "
+        .into()
+    };
+    let interface = vendor_interface();
+    assert!(library::is_interface(&interface.path));
+
+    let with_sources = compile_to_string(
+        vec![program()],
+        vec![vendor_library()],
+        None,
+        Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+    let with_interface = compile_to_string(
+        vec![program()],
+        vec![interface],
+        None,
+        Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+    assert_eq!(with_interface, with_sources);
+}
+
+#[test]
+fn interfaces_of_other_versions_are_rejected() {
+    let interface = SourceCode {
+        path: "old.stif".into(),
+        source: r#"{ "version": "0.0.1", "index": {} }"#.into(),
+    };
+    let error = library::read_interface(&interface, &IdProvider::default()).unwrap_err();
+    assert!(error.get_message().contains("version 0.0.1"));
+}
+
+#[test]
+fn interfaces_are_not_created_for_sources_with_errors() {
+    let library = SourceCode {
+        path: "broken.st".into(),
+        source: "FUNCTION broken : INT VAR_INPUT x : INT END_VAR END_FUNCTION".into(),
+    };
+    let error = library::create_interface(
+        vec![library],
+        None,
+        &[],
+        &mut Diagnostician::null_diagnostician(),
+    )
+    .unwrap_err();
+    assert!(error.get_message().contains("contain errors"));
+}
@@ -0,0 +1,20 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! A language server for Structured Text.
+//!
+//! The server speaks the Language Server Protocol over stdio and offers live diagnostics,
+//! go-to-definition, hover, member completion and find-references for the open documents.
+//! It uses the compiler's parser, index, resolver and validation to analyze them.
+use std::io;
+
+mod analysis;
+mod protocol;
+mod server;
+#[cfg(test)]
+mod tests;
+
+/// runs the language server on stdin and stdout until the client exits
+pub fn run() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    server::Server::new(stdout.lock()).run(&mut stdin.lock())
+}
@@ -0,0 +1,798 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! The analysis behind the language server.
+//!
+//! Whenever a document changes, all open documents are parsed, indexed, annotated and
+//! validated like a regular compilation (see `index_module`). The queries of the language
+//! server are then answered from the resulting AST, `Index` and `AnnotationMap`.
+use std::{
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use indexmap::IndexMap;
+
+use crate::{
+    ast::{AstStatement, CompilationUnit, DataType, DataTypeDeclaration, PouType, SourceRange},
+    diagnostics::{
+        AssessedDiagnostic, DefaultDiagnosticAssessor, Diagnostic, DiagnosticReporter,
+        Diagnostician, Severity,
+    },
+    index::{Index, PouIndexEntry},
+    resolver::{AnnotationMap, AnnotationMapImpl, StatementAnnotation},
+    typesystem::DataTypeInformation,
+    SourceCode,
+};
+
+use super::protocol::{
+    self, CompletionItem, DiagnosticRelatedInformation, Hover, Location, MarkupContent, Position,
+    Range, COMPLETION_KIND_FIELD, SEVERITY_ERROR, SEVERITY_INFORMATION, SEVERITY_WARNING,
+};
+
+/// the diagnostics reported during the analysis with the uri of their document and their severity
+type ReportedDiagnostics = Rc<RefCell<Vec<(String, Diagnostic, u32)>>>;
+
+/// a DiagnosticReporter that collects the reported diagnostics
+struct CollectingDiagnosticReporter {
+    /// the uris of the registered files, indexed by their file id
+    files: Rc<RefCell<Vec<String>>>,
+    diagnostics: ReportedDiagnostics,
+}
+
+impl DiagnosticReporter for CollectingDiagnosticReporter {
+    fn report(&self, diagnostics: &[AssessedDiagnostic], file_id: usize) {
+        let files = self.files.borrow();
+        self.diagnostics
+            .borrow_mut()
+            .extend(diagnostics.iter().map(|it| {
+                let severity = match it.severity {
+                    Severity::Error => SEVERITY_ERROR,
+                    Severity::Warning => SEVERITY_WARNING,
+                    Severity::Info => SEVERITY_INFORMATION,
+                };
+                //ranges that know their file point into it, others into the reported file
+                let file_id = it
+                    .diagnostic
+                    .get_location()
+                    .get_file_id()
+                    .unwrap_or(file_id);
+                let uri = files.get(file_id).cloned().unwrap_or_default();
+                (uri, it.diagnostic.clone(), severity)
+            }));
+    }
+
+    fn register(&mut self, path: String, _src: String) -> usize {
+        let mut files = self.files.borrow_mut();
+        files.push(path);
+        files.len() - 1
+    }
+}
+
+/// an open document
+struct Document {
+    text: String,
+    /// the offsets of the first character of every line
+    line_starts: Vec<usize>,
+}
+
+impl Document {
+    fn new(text: String) -> Document {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Document { text, line_starts }
+    }
+
+    /// returns the offset of the given position, positions beyond the end of
+    /// a line are moved to the end of the line
+    fn get_offset(&self, position: Position) -> usize {
+        let line_start = match self.line_starts.get(position.line as usize) {
+            Some(line_start) => *line_start,
+            None => return self.text.len(),
+        };
+        let mut character = 0;
+        for (offset, c) in self.text[line_start..].char_indices() {
+            if character >= position.character || c == '\n' {
+                return line_start + offset;
+            }
+            character += c.len_utf16() as u32;
+        }
+        self.text.len()
+    }
+
+    fn get_position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|it| *it <= offset) - 1;
+        let character = self
+            .text
+            .get(self.line_starts[line]..offset)
+            .map_or(0, |it| it.encode_utf16().count());
+        Position {
+            line: line as u32,
+            character: character as u32,
+        }
+    }
+
+    fn get_range(&self, location: &SourceRange) -> Range {
+        Range {
+            start: self.get_position(location.get_start()),
+            end: self.get_position(location.get_end()),
+        }
+    }
+}
+
+/// the result of the last successful analysis of the open documents
+struct Analysis {
+    index: Index,
+    annotations: AnnotationMapImpl,
+    units: Vec<CompilationUnit>,
+    diagnostics: Vec<(String, Diagnostic, u32)>,
+    /// the uris of the analyzed files, indexed by their file id
+    files: Vec<String>,
+}
+
+/// a declared POU, action, type, variable or enum element
+struct Declaration<'a> {
+    /// the qualified name used by the index and the annotations (e.g. `prg.x`)
+    name: String,
+    location: SourceRange,
+    unit: &'a CompilationUnit,
+}
+
+/// the open documents and their analysis
+#[derive(Default)]
+pub struct Workspace {
+    documents: IndexMap<String, Document>,
+    analysis: Option<Analysis>,
+}
+
+impl Workspace {
+    /// opens or updates the document with the given uri and re-analyzes the workspace
+    pub fn update(&mut self, uri: &str, text: String) {
+        self.documents.insert(uri.to_string(), Document::new(text));
+        self.analyze();
+    }
+
+    /// closes the document with the given uri and re-analyzes the workspace
+    pub fn close(&mut self, uri: &str) {
+        self.documents.shift_remove(uri);
+        self.analyze();
+    }
+
+    /// returns the uris of all open documents
+    pub fn get_uris(&self) -> Vec<String> {
+        self.documents.keys().cloned().collect()
+    }
+
+    fn analyze(&mut self) {
+        let sources = self
+            .documents
+            .iter()
+            .map(|(uri, document)| SourceCode {
+                source: document.text.clone(),
+                path: uri.clone(),
+            })
+            .collect::<Vec<_>>();
+        let files = Rc::new(RefCell::new(vec![]));
+        let diagnostics = ReportedDiagnostics::default();
+        let diagnostician = Diagnostician {
+            reporter: Box::new(CollectingDiagnosticReporter {
+                files: files.clone(),
+                diagnostics: diagnostics.clone(),
+            }),
+            assessor: Box::new(DefaultDiagnosticAssessor::default()),
+        };
+
+        //a half-written document must not bring down the server, the last analysis is kept instead
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            crate::index_module(sources, vec![], None, &[], 1, diagnostician)
+        }));
+        if let Ok(Ok((index, components))) = result {
+            self.analysis = Some(Analysis {
+                index,
+                annotations: components.all_annotations,
+                units: components
+                    .annotated_units
+                    .into_iter()
+                    .map(|(_, unit, _)| unit)
+                    .collect(),
+                diagnostics: diagnostics.take(),
+                files: files.take(),
+            });
+        }
+    }
+
+    /// returns the diagnostics of the document with the given uri
+    pub fn get_diagnostics(&self, uri: &str) -> Vec<protocol::Diagnostic> {
+        let (document, analysis) = match (self.documents.get(uri), &self.analysis) {
+            (Some(document), Some(analysis)) => (document, analysis),
+            _ => return vec![],
+        };
+        analysis
+            .diagnostics
+            .iter()
+            .filter(|(file, ..)| file == uri)
+            .map(|(_, diagnostic, severity)| protocol::Diagnostic {
+                range: document.get_range(&diagnostic.get_location()),
+                severity: *severity,
+                code: diagnostic.get_type().get_code().into(),
+                source: "rusty".into(),
+                message: diagnostic.get_message().into(),
+                related_information: diagnostic
+                    .get_related()
+                    .iter()
+                    .filter_map(|related| {
+                        let uri = analysis.files.get(related.range.get_file_id()?)?;
+                        Some(DiagnosticRelatedInformation {
+                            location: Location {
+                                uri: uri.clone(),
+                                range: self.documents.get(uri)?.get_range(&related.range),
+                            },
+                            message: related.message.clone(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// returns the location of the declaration of the element at the given position
+    pub fn get_definition(&self, uri: &str, position: Position) -> Option<Location> {
+        let analysis = self.analysis.as_ref()?;
+        let name = self.find_symbol(uri, position)?;
+        get_declarations(analysis)
+            .into_iter()
+            .find(|it| it.name.eq_ignore_ascii_case(&name))
+            .and_then(|it| self.get_location(it.unit, &it.location))
+    }
+
+    /// describes the resolved type of the expression at the given position
+    pub fn get_hover(&self, uri: &str, position: Position) -> Option<Hover> {
+        let analysis = self.analysis.as_ref()?;
+        let offset = self.documents.get(uri)?.get_offset(position);
+        let unit = analysis.units.iter().find(|it| it.file_name == uri)?;
+        let annotation = find_annotated_statement(analysis, unit, offset)
+            .and_then(|it| analysis.annotations.get(it))?;
+        let description = match annotation {
+            StatementAnnotation::Value { resulting_type } => resulting_type.to_string(),
+            StatementAnnotation::Variable {
+                resulting_type,
+                qualified_name,
+                ..
+            } => format!("{} : {}", qualified_name, resulting_type),
+            StatementAnnotation::Function {
+                return_type,
+                qualified_name,
+                ..
+            } => format!("FUNCTION {} : {}", qualified_name, return_type),
+            StatementAnnotation::Type { type_name } => format!("TYPE {}", type_name),
+            StatementAnnotation::Program { qualified_name } => {
+                format!("PROGRAM {}", qualified_name)
+            }
+            StatementAnnotation::Namespace { name } => format!("NAMESPACE {}", name),
+        };
+        Some(Hover {
+            contents: MarkupContent {
+                kind: "markdown".into(),
+                value: format!("```st\n{}\n```", description),
+            },
+        })
+    }
+
+    /// returns the members of the variable accessed in front of the `.` before the given position
+    pub fn get_completions(&self, uri: &str, position: Position) -> Vec<CompletionItem> {
+        self.find_completions(uri, position).unwrap_or_default()
+    }
+
+    fn find_completions(&self, uri: &str, position: Position) -> Option<Vec<CompletionItem>> {
+        let analysis = self.analysis.as_ref()?;
+        let document = self.documents.get(uri)?;
+        let offset = document.get_offset(position);
+        let segments = get_member_access_segments(&document.text[..offset])?;
+        let scope = analysis
+            .units
+            .iter()
+            .filter(|it| it.file_name == uri)
+            .flat_map(|it| it.implementations.iter())
+            .find(|it| contains(&it.location, offset))
+            .map(|it| it.type_name.as_str());
+
+        let index = &analysis.index;
+        let type_name = resolve_member_access(index, scope, &segments)?;
+        let container = match index.find_effective_type_info(&type_name)? {
+            DataTypeInformation::Struct { name, .. } => name.as_str(),
+            _ => return None,
+        };
+        let members = std::iter::once(container)
+            .chain(index.get_super_classes(container))
+            .flat_map(|it| index.get_container_members(it))
+            //internal members like __SUPER are not offered
+            .filter(|it| !it.is_temp() && !it.is_return() && !it.get_name().starts_with("__"))
+            .map(|it| CompletionItem {
+                label: it.get_name().to_string(),
+                kind: COMPLETION_KIND_FIELD,
+                detail: it.get_type_name().to_string(),
+            })
+            .collect();
+        Some(members)
+    }
+
+    /// returns the locations of all references to the element at the given position
+    pub fn get_references(
+        &self,
+        uri: &str,
+        position: Position,
+        include_declaration: bool,
+    ) -> Vec<Location> {
+        let (analysis, name) = match (&self.analysis, self.find_symbol(uri, position)) {
+            (Some(analysis), Some(name)) => (analysis, name),
+            _ => return vec![],
+        };
+
+        let mut references = vec![];
+        if include_declaration {
+            references.extend(
+                get_declarations(analysis)
+                    .into_iter()
+                    .filter(|it| it.name.eq_ignore_ascii_case(&name))
+                    .filter_map(|it| self.get_location(it.unit, &it.location)),
+            );
+        }
+        for unit in &analysis.units {
+            visit_statements(unit, &mut |statement| {
+                let is_reference = matches!(statement, AstStatement::Reference { .. })
+                    && analysis
+                        .annotations
+                        .get(statement)
+                        .and_then(get_declaration_name)
+                        .map_or(false, |it| it.eq_ignore_ascii_case(&name));
+                if is_reference {
+                    references.extend(self.get_location(unit, &statement.get_location()));
+                }
+            });
+            references.extend(
+                get_type_references(&analysis.index, unit)
+                    .into_iter()
+                    .filter(|(type_name, _)| type_name.eq_ignore_ascii_case(&name))
+                    .filter_map(|(_, location)| self.get_location(unit, &location)),
+            );
+        }
+        references
+    }
+
+    /// returns the qualified name of the declaration of the element at the given position,
+    /// the element may be a resolved reference, a declaration or a type used in a declaration
+    fn find_symbol(&self, uri: &str, position: Position) -> Option<String> {
+        let analysis = self.analysis.as_ref()?;
+        let offset = self.documents.get(uri)?.get_offset(position);
+        let unit = analysis.units.iter().find(|it| it.file_name == uri)?;
+
+        find_annotated_statement(analysis, unit, offset)
+            .and_then(|it| analysis.annotations.get(it))
+            .and_then(get_declaration_name)
+            .map(str::to_string)
+            .or_else(|| {
+                get_unit_declarations(unit)
+                    .into_iter()
+                    .find(|it| contains(&it.location, offset))
+                    .map(|it| it.name)
+            })
+            .or_else(|| {
+                //inline types (e.g. `ARRAY[1..2] OF Motor`) contain the types they reference
+                get_type_references(&analysis.index, unit)
+                    .into_iter()
+                    .filter(|(_, location)| contains(location, offset))
+                    .min_by_key(|(_, location)| location.get_end() - location.get_start())
+                    .map(|(type_name, _)| type_name)
+            })
+    }
+
+    /// returns the location of the given range in the given unit, units that are not open
+    /// in the workspace (e.g. the standard library) have no location
+    fn get_location(&self, unit: &CompilationUnit, location: &SourceRange) -> Option<Location> {
+        self.documents
+            .get(&unit.file_name)
+            .map(|document| Location {
+                uri: unit.file_name.clone(),
+                range: document.get_range(location),
+            })
+    }
+}
+
+fn contains(location: &SourceRange, offset: usize) -> bool {
+    !location.is_undefined() && location.get_start() <= offset && offset <= location.get_end()
+}
+
+/// returns the name of the declaration the given annotation resolves to
+fn get_declaration_name(annotation: &StatementAnnotation) -> Option<&str> {
+    match annotation {
+        StatementAnnotation::Variable { qualified_name, .. }
+        | StatementAnnotation::Function { qualified_name, .. }
+        | StatementAnnotation::Program { qualified_name } => Some(qualified_name.as_str()),
+        StatementAnnotation::Type { type_name } => Some(type_name.as_str()),
+        StatementAnnotation::Value { .. } | StatementAnnotation::Namespace { .. } => None,
+    }
+}
+
+/// returns the innermost annotated statement at the given offset
+fn find_annotated_statement<'a>(
+    analysis: &Analysis,
+    unit: &'a CompilationUnit,
+    offset: usize,
+) -> Option<&'a AstStatement> {
+    let mut result = None;
+    visit_statements(unit, &mut |statement| {
+        //children are visited after their parents, so the last match is the innermost one
+        if contains(&statement.get_location(), offset)
+            && analysis.annotations.get(statement).is_some()
+        {
+            result = Some(statement);
+        }
+    });
+    result
+}
+
+fn get_declarations(analysis: &Analysis) -> Vec<Declaration> {
+    analysis
+        .units
+        .iter()
+        .flat_map(get_unit_declarations)
+        .collect()
+}
+
+/// returns all POUs, actions, types, variables and enum elements declared in the given unit
+fn get_unit_declarations(unit: &CompilationUnit) -> Vec<Declaration> {
+    let mut declarations = vec![];
+    let mut declare = |name: String, location: SourceRange| {
+        declarations.push(Declaration {
+            name,
+            location,
+            unit,
+        })
+    };
+
+    for pou in &unit.units {
+        declare(pou.name.clone(), pou.name_location.clone());
+        for variable in pou
+            .variable_blocks
+            .iter()
+            .flat_map(|it| it.variables.iter())
+        {
+            declare(
+                format!("{}.{}", pou.name, variable.name),
+                variable.location.clone(),
+            );
+        }
+    }
+    for implementation in unit
+        .implementations
+        .iter()
+        .filter(|it| it.pou_type == PouType::Action)
+    {
+        //actions have no name location, they are declared at the start of their body
+        let start = implementation.location.get_start();
+        declare(implementation.name.clone(), SourceRange::new(start..start));
+    }
+    for variable in unit.global_vars.iter().flat_map(|it| it.variables.iter()) {
+        declare(variable.name.clone(), variable.location.clone());
+    }
+    for user_type in &unit.types {
+        //inline types get generated names (e.g. `__main_motors`), they are not declared by the user
+        let name = match user_type.data_type.get_name() {
+            Some(name) if !name.starts_with("__") => name,
+            _ => continue,
+        };
+        //the declaration starts with the (not namespace-qualified) name of the type
+        let start = user_type.location.get_start();
+        let length = name.rsplit('.').next().unwrap_or(name).len();
+        declare(name.to_string(), SourceRange::new(start..start + length));
+
+        match &user_type.data_type {
+            DataType::StructType { variables, .. } => {
+                for variable in variables {
+                    declare(
+                        format!("{}.{}", name, variable.name),
+                        variable.location.clone(),
+                    );
+                }
+            }
+            DataType::EnumType { elements, .. } => {
+                for element in crate::ast::flatten_expression_list(elements) {
+                    let location = match element {
+                        AstStatement::Assignment { left, .. } => left.get_location(),
+                        AstStatement::Reference { location, .. } => location.clone(),
+                        _ => continue,
+                    };
+                    declare(
+                        format!("{}.{}", name, crate::ast::get_enum_element_name(element)),
+                        location,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    declarations
+}
+
+/// returns the types referenced by name in the declarations of the given unit, qualified
+/// with the namespace they refer to
+fn get_type_references(index: &Index, unit: &CompilationUnit) -> Vec<(String, SourceRange)> {
+    //every type is referenced by the POU, variable or type it is used in
+    let pou_variables = unit.units.iter().flat_map(|pou| {
+        pou.variable_blocks
+            .iter()
+            .flat_map(|it| it.variables.iter())
+            .map(move |it| (pou.name.as_str(), &it.data_type))
+    });
+    let global_variables = unit
+        .global_vars
+        .iter()
+        .flat_map(|it| it.variables.iter())
+        .map(|it| (it.name.as_str(), &it.data_type));
+    let struct_variables = unit
+        .types
+        .iter()
+        .filter_map(|it| match &it.data_type {
+            DataType::StructType {
+                name: Some(name),
+                variables,
+            } => Some(
+                variables
+                    .iter()
+                    .map(move |it| (name.as_str(), &it.data_type)),
+            ),
+            _ => None,
+        })
+        .flatten();
+    let return_types = unit
+        .units
+        .iter()
+        .filter_map(|pou| pou.return_type.as_ref().map(|it| (pou.name.as_str(), it)));
+    let element_types = unit.types.iter().filter_map(|it| match &it.data_type {
+        DataType::ArrayType {
+            name,
+            referenced_type,
+            ..
+        }
+        | DataType::PointerType {
+            name,
+            referenced_type,
+            ..
+        } => Some((
+            name.as_deref().unwrap_or_default(),
+            referenced_type.as_ref(),
+        )),
+        _ => None,
+    });
+
+    pou_variables
+        .chain(global_variables)
+        .chain(struct_variables)
+        .chain(return_types)
+        .chain(element_types)
+        .filter_map(|(owner, it)| match it {
+            DataTypeDeclaration::DataTypeReference {
+                referenced_type,
+                location,
+            } => Some((
+                index.resolve_name(Some(owner), referenced_type),
+                location.clone(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// calls the given visitor for every statement of the given unit, parents are visited before
+/// their children
+fn visit_statements<'a>(unit: &'a CompilationUnit, visitor: &mut impl FnMut(&'a AstStatement)) {
+    let bodies = unit
+        .implementations
+        .iter()
+        .flat_map(|it| it.statements.iter());
+    let pou_initializers = unit
+        .units
+        .iter()
+        .flat_map(|it| it.variable_blocks.iter())
+        .chain(unit.global_vars.iter())
+        .flat_map(|it| it.variables.iter())
+        .filter_map(|it| it.initializer.as_ref());
+    let type_initializers = unit.types.iter().filter_map(|it| it.initializer.as_ref());
+
+    for statement in bodies.chain(pou_initializers).chain(type_initializers) {
+        visit_statement(statement, visitor);
+    }
+}
+
+fn visit_statement<'a>(statement: &'a AstStatement, visitor: &mut impl FnMut(&'a AstStatement)) {
+    visitor(statement);
+    for child in get_children(statement) {
+        visit_statement(child, visitor);
+    }
+}
+
+/// returns the direct sub-statements of the given statement
+fn get_children(statement: &AstStatement) -> Vec<&AstStatement> {
+    match statement {
+        AstStatement::LiteralArray {
+            elements: Some(elements),
+            ..
+        } => vec![elements],
+        AstStatement::CastStatement { target, .. } => vec![target],
+        AstStatement::MultipliedStatement { element, .. } => vec![element],
+        AstStatement::QualifiedReference { elements, .. } => elements.iter().collect(),
+        AstStatement::ArrayAccess {
+            reference, access, ..
+        } => vec![reference, access],
+        AstStatement::PointerAccess { reference, .. } => vec![reference],
+        AstStatement::DirectAccess { index, .. } => vec![index],
+        AstStatement::HardwareAccess { address, .. } => address.iter().collect(),
+        AstStatement::BinaryExpression { left, right, .. }
+        | AstStatement::Assignment { left, right, .. }
+        | AstStatement::OutputAssignment { left, right, .. } => vec![left, right],
+        AstStatement::UnaryExpression { value, .. } => vec![value],
+        AstStatement::ExpressionList { expressions, .. } => expressions.iter().collect(),
+        AstStatement::RangeStatement { start, end, .. } => vec![start, end],
+        AstStatement::CallStatement {
+            operator,
+            parameters,
+            ..
+        } => std::iter::once(operator.as_ref())
+            .chain(parameters.as_ref().as_ref())
+            .collect(),
+        AstStatement::IfStatement {
+            blocks, else_block, ..
+        } => blocks
+            .iter()
+            .flat_map(|it| std::iter::once(it.condition.as_ref()).chain(it.body.iter()))
+            .chain(else_block.iter())
+            .collect(),
+        AstStatement::ForLoopStatement {
+            counter,
+            start,
+            end,
+            by_step,
+            body,
+            ..
+        } => [counter, start, end]
+            .into_iter()
+            .map(|it| it.as_ref())
+            .chain(by_step.as_deref())
+            .chain(body.iter())
+            .collect(),
+        AstStatement::WhileLoopStatement {
+            condition, body, ..
+        }
+        | AstStatement::RepeatLoopStatement {
+            condition, body, ..
+        } => std::iter::once(condition.as_ref())
+            .chain(body.iter())
+            .collect(),
+        AstStatement::CaseStatement {
+            selector,
+            case_blocks,
+            else_block,
+            ..
+        } => std::iter::once(selector.as_ref())
+            .chain(
+                case_blocks
+                    .iter()
+                    .flat_map(|it| std::iter::once(it.condition.as_ref()).chain(it.body.iter())),
+            )
+            .chain(else_block.iter())
+            .collect(),
+        AstStatement::CaseCondition { condition, .. } => vec![condition],
+        _ => vec![],
+    }
+}
+
+/// a segment of a member access, the name and the number of array- or pointer-accesses
+/// applied to it (e.g. `a[1]^` for `a` with 2 accesses)
+type Segment<'a> = (&'a str, usize);
+
+/// returns the segments of the member access at the end of the given text, the name of the
+/// member after the last `.` is optional (e.g. `a.b[1].` or `a.b[1].c` for `a` and `b[1]`)
+fn get_member_access_segments(text: &str) -> Option<Vec<Segment>> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let mut text = text.trim_end_matches(is_identifier).strip_suffix('.')?;
+    let mut segments = vec![];
+    loop {
+        let mut accesses = 0;
+        loop {
+            if let Some(rest) = text.strip_suffix('^') {
+                text = rest;
+            } else if text.ends_with(']') {
+                text = strip_array_access(text)?;
+            } else {
+                break;
+            }
+            accesses += 1;
+        }
+        let name_start = text.trim_end_matches(is_identifier).len();
+        let name = &text[name_start..];
+        if name.is_empty() {
+            return None;
+        }
+        segments.push((name, accesses));
+        text = match text[..name_start].strip_suffix('.') {
+            Some(rest) => rest,
+            None => break,
+        };
+    }
+    segments.reverse();
+    Some(segments)
+}
+
+/// removes the (possibly nested) array access at the end of the given text
+fn strip_array_access(text: &str) -> Option<&str> {
+    let mut depth = 0;
+    for (offset, c) in text.char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' if depth == 1 => return Some(&text[..offset]),
+            '[' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// returns the name of the type the given member access resolves to
+fn resolve_member_access(
+    index: &Index,
+    scope: Option<&str>,
+    segments: &[Segment],
+) -> Option<String> {
+    let ((name, accesses), members) = segments.split_first()?;
+    let type_name = scope
+        .and_then(|scope| index.find_member(scope, name))
+        .or_else(|| index.find_global_variable(name))
+        .map(|it| it.get_type_name().to_string())
+        .or_else(|| match index.find_pou(name) {
+            Some(PouIndexEntry::Program {
+                instance_struct_name,
+                ..
+            }) => Some(instance_struct_name.clone()),
+            _ => None,
+        })?;
+    let mut type_name = dereference(index, type_name, *accesses)?;
+
+    for (name, accesses) in members {
+        let container = match index.find_effective_type_info(&type_name)? {
+            DataTypeInformation::Struct { name, .. } => name,
+            _ => return None,
+        };
+        let member = index
+            .find_member(container, name)?
+            .get_type_name()
+            .to_string();
+        type_name = dereference(index, member, *accesses)?;
+    }
+    Some(type_name)
+}
+
+/// returns the type reached by applying the given number of array- or pointer-accesses
+/// on the given type, auto-dereferenced pointers (e.g. VAR_IN_OUT) are dereferenced implicitly
+fn dereference(index: &Index, mut type_name: String, mut accesses: usize) -> Option<String> {
+    loop {
+        match index.find_effective_type_info(&type_name)? {
+            DataTypeInformation::Pointer {
+                inner_type_name,
+                auto_deref: true,
+                ..
+            } => type_name = inner_type_name.clone(),
+            DataTypeInformation::Array {
+                inner_type_name, ..
+            }
+            | DataTypeInformation::Pointer {
+                inner_type_name, ..
+            } if accesses > 0 => {
+                type_name = inner_type_name.clone();
+                accesses -= 1;
+            }
+            _ if accesses == 0 => return Some(type_name),
+            _ => return None,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::io::{self, BufRead, Write};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::{
+    analysis::Workspace,
+    protocol::{
+        self, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+        ReferenceParams, TextDocumentPositionParams, TEXT_DOCUMENT_SYNC_FULL,
+    },
+};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// an error answering a request, sent back as a JSON-RPC error
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl ResponseError {
+    fn method_not_found(method: &str) -> ResponseError {
+        ResponseError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unsupported method: {}", method),
+        }
+    }
+
+    fn invalid_params(reason: &str) -> ResponseError {
+        ResponseError {
+            code: INVALID_PARAMS,
+            message: format!("Invalid parameters: {}", reason),
+        }
+    }
+}
+
+/// the language server, it answers the client's requests using the analysis of the workspace
+pub struct Server<W: Write> {
+    writer: W,
+    workspace: Workspace,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(writer: W) -> Server<W> {
+        Server {
+            writer,
+            workspace: Workspace::default(),
+        }
+    }
+
+    /// handles the messages of the given reader until the client sends `exit`
+    /// or the reader reaches its end
+    pub fn run(&mut self, reader: &mut impl BufRead) -> io::Result<()> {
+        while let Some(message) = protocol::read_message(reader)? {
+            //responses from the client are not expected, they carry no method
+            let method = match message.get("method").and_then(Value::as_str) {
+                Some(method) => method.to_string(),
+                None => continue,
+            };
+            if method == "exit" {
+                break;
+            }
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            match message.get("id") {
+                Some(id) => {
+                    let response = match self.handle_request(&method, params) {
+                        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                        Err(error) => json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": error.code, "message": error.message },
+                        }),
+                    };
+                    protocol::write_message(&mut self.writer, &response)?;
+                }
+                None => self.handle_notification(&method, params)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, method: &str, params: Value) -> Result<Value, ResponseError> {
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "completionProvider": { "triggerCharacters": ["."] },
+                },
+                "serverInfo": { "name": "rustyc-lsp", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/definition" => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+                json!(self
+                    .workspace
+                    .get_definition(&params.text_document.uri, params.position))
+            }
+            "textDocument/hover" => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+                json!(self
+                    .workspace
+                    .get_hover(&params.text_document.uri, params.position))
+            }
+            "textDocument/completion" => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+                json!(self
+                    .workspace
+                    .get_completions(&params.text_document.uri, params.position))
+            }
+            "textDocument/references" => {
+                let params: ReferenceParams = parse_params(params)?;
+                json!(self.workspace.get_references(
+                    &params.text_document.uri,
+                    params.position,
+                    params.context.include_declaration
+                ))
+            }
+            _ => return Err(ResponseError::method_not_found(method)),
+        };
+        Ok(result)
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        //notifications cannot be answered, so invalid ones are ignored
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) {
+                    let document = params.text_document;
+                    self.workspace.update(&document.uri, document.text);
+                    self.publish_diagnostics()?;
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) {
+                    //the whole text is synchronized, the last change is the current text
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        self.workspace
+                            .update(&params.text_document.uri, change.text);
+                        self.publish_diagnostics()?;
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+                    self.workspace.close(&uri);
+                    self.send_diagnostics(&uri, vec![])?;
+                    self.publish_diagnostics()?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// publishes the diagnostics of all open documents, a change in one document
+    /// may affect the diagnostics of the others
+    fn publish_diagnostics(&mut self) -> io::Result<()> {
+        for uri in self.workspace.get_uris() {
+            let diagnostics = self.workspace.get_diagnostics(&uri);
+            self.send_diagnostics(&uri, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    fn send_diagnostics(
+        &mut self,
+        uri: &str,
+        diagnostics: Vec<protocol::Diagnostic>,
+    ) -> io::Result<()> {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        });
+        protocol::write_message(&mut self.writer, &notification)
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, ResponseError> {
+    serde_json::from_value(params).map_err(|err| ResponseError::invalid_params(&err.to_string()))
+}
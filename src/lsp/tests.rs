@@ -0,0 +1,3 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+mod analysis_tests;
+mod server_tests;
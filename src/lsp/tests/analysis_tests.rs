@@ -0,0 +1,219 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use crate::lsp::{
+    analysis::Workspace,
+    protocol::{DiagnosticRelatedInformation, Location, Position, Range, SEVERITY_ERROR},
+};
+
+const GLOBALS_URI: &str = "file:///project/globals.st";
+const MAIN_URI: &str = "file:///project/main.st";
+
+const GLOBALS: &str = "VAR_GLOBAL
+    counter : INT;
+END_VAR
+";
+
+const MAIN: &str = "FUNCTION_BLOCK Motor
+VAR_INPUT
+    speed : INT;
+END_VAR
+VAR_OUTPUT
+    running : BOOL;
+END_VAR
+VAR_TEMP
+    scratch : INT;
+END_VAR
+END_FUNCTION_BLOCK
+
+PROGRAM main
+VAR
+    motor : Motor;
+    motors : ARRAY[1..2] OF Motor;
+END_VAR
+    counter := counter + 1;
+    motor.speed := counter;
+    motors[1].running := motor.running;
+END_PROGRAM
+";
+
+fn workspace() -> Workspace {
+    let mut workspace = Workspace::default();
+    workspace.update(GLOBALS_URI, GLOBALS.into());
+    workspace.update(MAIN_URI, MAIN.into());
+    workspace
+}
+
+fn position(line: u32, character: u32) -> Position {
+    Position { line, character }
+}
+
+fn location(uri: &str, line: u32, start: u32, end: u32) -> Location {
+    Location {
+        uri: uri.into(),
+        range: Range {
+            start: position(line, start),
+            end: position(line, end),
+        },
+    }
+}
+
+#[test]
+fn diagnostics_are_reported_for_their_document() {
+    let mut workspace = workspace();
+    assert_eq!(workspace.get_diagnostics(GLOBALS_URI), vec![]);
+    assert_eq!(workspace.get_diagnostics(MAIN_URI), vec![]);
+
+    workspace.update(
+        MAIN_URI,
+        "PROGRAM main
+    counter := unknown;
+END_PROGRAM
+"
+        .into(),
+    );
+
+    let diagnostics = workspace.get_diagnostics(MAIN_URI);
+    let unresolved = diagnostics
+        .iter()
+        .find(|it| it.message == "Could not resolve reference to unknown")
+        .unwrap();
+    assert_eq!(unresolved.severity, SEVERITY_ERROR);
+    assert_eq!(unresolved.code, "E022");
+    assert_eq!(unresolved.range, location(MAIN_URI, 1, 15, 22).range);
+    assert_eq!(workspace.get_diagnostics(GLOBALS_URI), vec![]);
+}
+
+#[test]
+fn diagnostics_point_to_declarations_in_other_documents() {
+    let mut workspace = Workspace::default();
+    workspace.update(
+        GLOBALS_URI,
+        "VAR_GLOBAL CONSTANT\n    limit : INT := 10;\nEND_VAR\n".into(),
+    );
+    workspace.update(
+        MAIN_URI,
+        "PROGRAM main\n    limit := 5;\nEND_PROGRAM\n".into(),
+    );
+
+    let diagnostics = workspace.get_diagnostics(MAIN_URI);
+    let constant = diagnostics
+        .iter()
+        .find(|it| it.message == "Cannot assign to CONSTANT 'limit'")
+        .unwrap();
+    assert_eq!(constant.range, location(MAIN_URI, 1, 4, 9).range);
+    assert_eq!(
+        constant.related_information,
+        vec![DiagnosticRelatedInformation {
+            location: location(GLOBALS_URI, 1, 4, 9),
+            message: "'limit' is declared here".into(),
+        }]
+    );
+    assert_eq!(workspace.get_diagnostics(GLOBALS_URI), vec![]);
+}
+
+#[test]
+fn closing_a_document_removes_its_declarations() {
+    let mut workspace = workspace();
+    workspace.close(GLOBALS_URI);
+
+    let diagnostics = workspace.get_diagnostics(MAIN_URI);
+    assert!(diagnostics
+        .iter()
+        .any(|it| it.message == "Could not resolve reference to counter"));
+    assert_eq!(workspace.get_uris(), vec![MAIN_URI.to_string()]);
+}
+
+#[test]
+fn definition_of_a_global_variable_in_another_document() {
+    let workspace = workspace();
+
+    assert_eq!(
+        workspace.get_definition(MAIN_URI, position(17, 6)),
+        Some(location(GLOBALS_URI, 1, 4, 11))
+    );
+}
+
+#[test]
+fn definition_of_members_and_types() {
+    let workspace = workspace();
+
+    // motor.speed
+    assert_eq!(
+        workspace.get_definition(MAIN_URI, position(18, 12)),
+        Some(location(MAIN_URI, 2, 4, 9))
+    );
+    // motor : Motor
+    assert_eq!(
+        workspace.get_definition(MAIN_URI, position(14, 14)),
+        Some(location(MAIN_URI, 0, 15, 20))
+    );
+    // no definition for literals
+    assert_eq!(workspace.get_definition(MAIN_URI, position(17, 25)), None);
+}
+
+#[test]
+fn hover_shows_the_resolved_type() {
+    let workspace = workspace();
+
+    let hover = workspace.get_hover(MAIN_URI, position(19, 32)).unwrap();
+    assert_eq!(hover.contents.kind, "markdown");
+    assert_eq!(hover.contents.value, "```st\nMotor.running : BOOL\n```");
+
+    let hover = workspace.get_hover(MAIN_URI, position(17, 20)).unwrap();
+    assert_eq!(hover.contents.value, "```st\ncounter : INT\n```");
+}
+
+#[test]
+fn completion_offers_the_members_of_a_variable() {
+    let workspace = workspace();
+
+    // motor.|speed
+    let mut labels = workspace
+        .get_completions(MAIN_URI, position(18, 10))
+        .into_iter()
+        .map(|it| (it.label, it.detail))
+        .collect::<Vec<_>>();
+    labels.sort();
+    assert_eq!(
+        labels,
+        vec![
+            ("running".to_string(), "BOOL".to_string()),
+            ("speed".to_string(), "INT".to_string())
+        ]
+    );
+
+    // motors[1].run|ning
+    let labels = workspace
+        .get_completions(MAIN_URI, position(19, 17))
+        .into_iter()
+        .map(|it| it.label)
+        .collect::<Vec<_>>();
+    assert_eq!(labels.len(), 2);
+    assert!(labels.contains(&"running".to_string()));
+
+    // no member access
+    assert_eq!(workspace.get_completions(MAIN_URI, position(17, 6)), vec![]);
+}
+
+#[test]
+fn references_are_found_in_all_documents() {
+    let workspace = workspace();
+
+    assert_eq!(
+        workspace.get_references(MAIN_URI, position(17, 6), true),
+        vec![
+            location(GLOBALS_URI, 1, 4, 11),
+            location(MAIN_URI, 17, 4, 11),
+            location(MAIN_URI, 17, 15, 22),
+            location(MAIN_URI, 18, 19, 26),
+        ]
+    );
+
+    // from the declaration, without the declaration itself
+    assert_eq!(
+        workspace.get_references(MAIN_URI, position(0, 16), false),
+        vec![
+            location(MAIN_URI, 14, 12, 17),
+            location(MAIN_URI, 15, 28, 33)
+        ]
+    );
+}
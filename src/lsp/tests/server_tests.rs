@@ -0,0 +1,101 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::io::Cursor;
+
+use serde_json::{json, Value};
+
+use crate::lsp::{protocol, server::Server};
+
+/// runs a server on the given messages and returns the messages it sent
+fn run_server(messages: &[Value]) -> Vec<Value> {
+    let mut input = vec![];
+    for message in messages {
+        protocol::write_message(&mut input, message).unwrap();
+    }
+    let mut output = vec![];
+    Server::new(&mut output)
+        .run(&mut Cursor::new(input))
+        .unwrap();
+
+    let mut output = Cursor::new(output);
+    let mut sent = vec![];
+    while let Some(message) = protocol::read_message(&mut output).unwrap() {
+        sent.push(message);
+    }
+    sent
+}
+
+#[test]
+fn messages_are_read_with_their_content_length() {
+    let mut input =
+        Cursor::new("Content-Length: 17\r\nContent-Type: utf-8\r\n\r\n{\"method\":\"exit\"}");
+
+    assert_eq!(
+        protocol::read_message(&mut input).unwrap(),
+        Some(json!({ "method": "exit" }))
+    );
+    assert_eq!(protocol::read_message(&mut input).unwrap(), None);
+}
+
+#[test]
+fn server_answers_requests_and_publishes_diagnostics() {
+    let uri = "file:///project/main.st";
+    let sent = run_server(&[
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": {
+                "uri": uri,
+                "languageId": "st",
+                "version": 1,
+                "text": "PROGRAM main\nVAR\n    x : INT;\nEND_VAR\n    x := y;\nEND_PROGRAM\n",
+            }},
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/hover",
+            "params": { "textDocument": { "uri": uri }, "position": { "line": 4, "character": 4 } },
+        }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/formatting", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ]);
+
+    assert_eq!(sent.len(), 5);
+    assert_eq!(sent[0]["id"], 1);
+    assert_eq!(sent[0]["result"]["capabilities"]["hoverProvider"], true);
+    assert_eq!(
+        sent[0]["result"]["capabilities"]["completionProvider"]["triggerCharacters"],
+        json!(["."])
+    );
+
+    assert_eq!(sent[1]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(sent[1]["params"]["uri"], uri);
+    let diagnostics = sent[1]["params"]["diagnostics"].as_array().unwrap();
+    assert!(diagnostics.contains(&json!({
+        "range": {
+            "start": { "line": 4, "character": 9 },
+            "end": { "line": 4, "character": 10 },
+        },
+        "severity": 1,
+        "code": "E022",
+        "source": "rusty",
+        "message": "Could not resolve reference to y",
+    })));
+
+    assert_eq!(sent[2]["id"], 2);
+    assert_eq!(
+        sent[2]["result"]["contents"]["value"],
+        "```st\nmain.x : INT\n```"
+    );
+
+    assert_eq!(sent[3]["id"], 3);
+    assert_eq!(sent[3]["error"]["code"], -32601);
+
+    assert_eq!(
+        sent[4],
+        json!({ "jsonrpc": "2.0", "id": 4, "result": null })
+    );
+}
@@ -28,13 +28,13 @@ fn main() {
         CompileParameters::parse(&args);
     match compile_parameters {
         Ok(cp) => {
-            if cp.commands.is_some() {
-                if let Err(msg) = build_with_subcommand(cp) {
-                    eprintln!("Error: {:?}", msg);
-                    std::process::exit(1);
-                }
-            } else if let Err(msg) = build_with_params(cp) {
-                eprintln!("Error: {:?}", msg);
+            //errors are reported by the build in the configured error format
+            let result = if cp.commands.is_some() {
+                build_with_subcommand(cp)
+            } else {
+                build_with_params(cp)
+            };
+            if result.is_err() {
                 std::process::exit(1);
             }
         }
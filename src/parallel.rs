@@ -0,0 +1,48 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! Runs the independent work of a compilation phase on multiple threads (`-j`).
+use std::{sync::Mutex, thread};
+
+#[cfg(test)]
+mod tests;
+
+/// applies `f` to every item on up to `jobs` threads (`0` uses all available cores).
+/// The results keep the order of the items, so they do not depend on the scheduling
+pub fn map<T, R, F>(items: Vec<T>, jobs: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let jobs = if jobs == 0 {
+        thread::available_parallelism().map_or(1, |it| it.get())
+    } else {
+        jobs
+    };
+    let threads = jobs.min(items.len());
+    if threads <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(count));
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                //the lock is released before the item is processed
+                let next = queue.lock().unwrap().next();
+                match next {
+                    Some((position, item)) => {
+                        let result = f(item);
+                        results.lock().unwrap().push((position, result));
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(position, _)| *position);
+    results.into_iter().map(|(_, it)| it).collect()
+}
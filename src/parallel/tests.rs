@@ -0,0 +1,17 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use crate::parallel;
+
+#[test]
+fn results_keep_the_order_of_the_items() {
+    let items = (0..100).collect::<Vec<u64>>();
+    let expected = items.iter().map(|it| it * it).collect::<Vec<_>>();
+
+    for jobs in [0, 1, 4, 200] {
+        assert_eq!(parallel::map(items.clone(), jobs, |it| it * it), expected);
+    }
+}
+
+#[test]
+fn no_items_give_no_results() {
+    assert!(parallel::map(Vec::<u64>::new(), 4, |it| it).is_empty());
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::{
     ast::*,
+    diagnostics::ErrNo,
     expect_token, lexer,
     lexer::{ParseSession, Token, Token::*},
     typesystem::DINT_TYPE,
@@ -21,23 +22,35 @@ pub type ParsedAst = (CompilationUnit, Vec<Diagnostic>);
 
 pub fn parse(mut lexer: ParseSession, lnk: LinkageType) -> ParsedAst {
     let mut unit = CompilationUnit::default();
+    //the namespace and usings to restore at the next END_NAMESPACE
+    let mut enclosing_namespaces = vec![];
 
     let mut linkage = lnk;
     loop {
         match lexer.token {
             PropertyExternal => {
                 linkage = LinkageType::External;
+                //pragmas in front of {external} belong to the following POU
+                let pragmas = lexer.take_pragmas();
                 lexer.advance();
+                lexer.pragmas.splice(0..0, pragmas);
                 //Don't reset linkage
                 continue;
             }
-            KeywordVarGlobal => unit
-                .global_vars
-                .push(parse_variable_block(&mut lexer, linkage)),
-            KeywordProgram | KeywordClass | KeywordFunction | KeywordFunctionBlock => {
+            KeywordVarGlobal => {
+                let mut block = parse_variable_block(&mut lexer, linkage);
+                for variable in block.variables.iter_mut() {
+                    variable.name = qualify_name(&lexer, std::mem::take(&mut variable.name));
+                    register_scopes(&lexer, &mut unit, &variable.name, &[]);
+                }
+                unit.global_vars.push(block);
+            }
+            KeywordProgram | KeywordClass | KeywordFunction | KeywordFunctionBlock
+            | KeywordInterface => {
                 let params = match lexer.token {
                     KeywordProgram => (PouType::Program, KeywordEndProgram),
                     KeywordClass => (PouType::Class, KeywordEndClass),
+                    KeywordInterface => (PouType::Interface, KeywordEndInterface),
                     KeywordFunction => (PouType::Function, KeywordEndFunction),
                     _ => (PouType::FunctionBlock, KeywordEndFunctionBlock),
                 };
@@ -45,6 +58,12 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType) -> ParsedAst {
                 let (mut pou, mut implementation) =
                     parse_pou(&mut lexer, params.0, linkage, params.1);
 
+                //methods are searched with the usings of their class
+                let usings = pou.first().map(|it| it.usings.clone()).unwrap_or_default();
+                for it in pou.iter() {
+                    register_scopes(&lexer, &mut unit, &it.name, &usings);
+                    register_suppressions(&mut lexer, &it.pragmas, &it.location);
+                }
                 unit.units.append(&mut pou);
                 unit.implementations.append(&mut implementation);
             }
@@ -65,10 +84,53 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType) -> ParsedAst {
             KeywordType => {
                 let unit_type = parse_type(&mut lexer);
                 for utype in unit_type {
+                    if let Some(name) = utype.data_type.get_name() {
+                        register_scopes(&lexer, &mut unit, name, &[]);
+                    }
                     unit.types.push(utype);
                 }
             }
-            KeywordEndActions | End => return (unit, lexer.diagnostics),
+            KeywordNamespace => {
+                lexer.advance();
+                let name = parse_qualified_name(&mut lexer);
+                let namespace = qualify_name(&lexer, name);
+                enclosing_namespaces.push((
+                    lexer.namespace.replace(namespace.clone()),
+                    lexer.usings.clone(),
+                ));
+                lexer.usings.insert(0, namespace);
+            }
+            KeywordUsing => {
+                let usings = parse_using_directives(&mut lexer);
+                //the namespace itself is always searched first
+                let position = if lexer.namespace.is_some() { 1 } else { 0 };
+                lexer.usings.splice(position..position, usings);
+            }
+            KeywordEndNamespace => {
+                if let Some((namespace, usings)) = enclosing_namespaces.pop() {
+                    lexer.namespace = namespace;
+                    lexer.usings = usings;
+                } else {
+                    lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                        "StartKeyword",
+                        lexer.slice(),
+                        lexer.location(),
+                    ));
+                }
+                lexer.advance();
+            }
+            KeywordEndActions | End => {
+                if !enclosing_namespaces.is_empty() {
+                    lexer.accept_diagnostic(Diagnostic::missing_token(
+                        "END_NAMESPACE",
+                        lexer.location(),
+                    ));
+                }
+                lexer.report_unattached_pragmas();
+                unit.pragmas = lexer.statement_pragmas;
+                unit.suppressions = lexer.suppressions;
+                return (unit, lexer.diagnostics);
+            }
             _ => {
                 lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
                     "StartKeyword",
@@ -91,7 +153,8 @@ fn parse_actions(
     parse_any_in_region(lexer, vec![KeywordEndActions], |lexer| {
         lexer.advance();
         let container = if lexer.token == Identifier {
-            lexer.slice_and_advance()
+            let container = lexer.slice_and_advance();
+            qualify_name(lexer, container)
         } else {
             lexer.accept_diagnostic(Diagnostic::missing_action_container(lexer.location()));
             default_container.into()
@@ -136,6 +199,7 @@ fn parse_pou(
     expected_end_token: lexer::Token,
 ) -> (Vec<Pou>, Vec<Implementation>) {
     let start = lexer.range().start;
+    let pragmas = lexer.take_pragmas();
     lexer.advance(); //Consume ProgramKeyword
     let closing_tokens = vec![
         expected_end_token.clone(),
@@ -155,27 +219,30 @@ fn parse_pou(
 
         let (name, name_location) =
             parse_identifier(lexer).unwrap_or_else(|| ("".to_string(), SourceRange::undefined())); // parse POU name
+        let name = qualify_name(lexer, name);
 
         let generics = parse_generics(lexer);
 
         with_scope(lexer, name.clone(), |lexer| {
-            // TODO: Parse USING directives
-            // TODO: Parse EXTENDS specifier
-            // TODO: Parse IMPLEMENTS specifier
+            let super_class = parse_super_class(lexer, &pou_type);
+            let interfaces = parse_interface_list(lexer, &pou_type);
 
-            let return_type = if pou_type != PouType::Class {
+            let return_type = if !matches!(pou_type, PouType::Class | PouType::Interface) {
                 // parse an optional return type
                 parse_return_type(lexer, &pou_type)
             } else {
-                // classes do not have a return type
+                // classes and interfaces do not have a return type
                 None
             };
+            let usings = parse_using_directives(lexer);
 
             // parse variable declarations. note that var in/out/inout
-            // blocks are not allowed inside of class declarations.
+            // blocks are not allowed inside of class declarations and
+            // interfaces do not declare any variables.
             let mut variable_blocks = vec![];
             let allowed_var_types = match pou_type {
                 PouType::Class => vec![KeywordVar],
+                PouType::Interface => vec![],
                 _ => vec![
                     KeywordVar,
                     KeywordVarInput,
@@ -190,21 +257,33 @@ fn parse_pou(
 
             let mut impl_pous = vec![];
             let mut implementations = vec![];
-            if pou_type == PouType::Class || pou_type == PouType::FunctionBlock {
+            if matches!(
+                pou_type,
+                PouType::Class | PouType::FunctionBlock | PouType::Interface
+            ) {
                 // classes and function blocks can have methods. methods consist of a Pou part
                 // and an implementation part. That's why we get another (Pou, Implementation)
                 // tuple out of parse_method() that has to be added to the list of Pous and
                 // implementations. Note that function blocks have to start with the method
-                // declarations before their implementation.
+                // declarations before their implementation. The methods of an interface only
+                // declare a signature, so they may not contain any statements.
                 while lexer.token == KeywordMethod {
                     if let Some((pou, implementation)) = parse_method(lexer, &name, linkage) {
+                        if pou_type != PouType::Interface {
+                            implementations.push(implementation);
+                        } else if let Some(first) = implementation.statements.first() {
+                            lexer.accept_diagnostic(Diagnostic::invalid_interface(
+                                &name,
+                                &format!("Method '{}' cannot have an implementation", pou.name),
+                                first.get_location(),
+                            ));
+                        }
                         impl_pous.push(pou);
-                        implementations.push(implementation);
                     }
                 }
             }
-            if pou_type != PouType::Class {
-                // a class may not contain an implementation
+            if !matches!(pou_type, PouType::Class | PouType::Interface) {
+                // a class or an interface may not contain an implementation
                 implementations.push(parse_implementation(
                     lexer,
                     linkage,
@@ -220,11 +299,15 @@ fn parse_pou(
                 pou_type,
                 variable_blocks,
                 return_type,
-                location: SourceRange::new(start..lexer.range().end),
+                location: lexer.source_range(start..lexer.range().end),
                 name_location,
                 poly_mode,
                 generics,
                 linkage,
+                super_class,
+                interfaces,
+                usings,
+                pragmas,
             }];
             pous.append(&mut impl_pous);
 
@@ -237,7 +320,7 @@ fn parse_pou(
         lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
             format!("{:?}", expected_end_token).as_str(),
             lexer.slice_region(lexer.last_range.clone()),
-            SourceRange::new(lexer.last_range.clone()),
+            lexer.last_location(),
         ));
     }
     pou
@@ -317,6 +400,86 @@ fn parse_polymorphism_mode(
     }
 }
 
+/// parses an optional `EXTENDS <name>` specifier
+/// only classes and function blocks can extend another pou
+fn parse_super_class(lexer: &mut ParseSession, pou_type: &PouType) -> Option<String> {
+    match pou_type {
+        PouType::Class | PouType::FunctionBlock if lexer.allow(&KeywordExtends) => {
+            Some(parse_qualified_name(lexer))
+        }
+        _ => None,
+    }
+}
+
+/// parses the `USING <namespace>[, <namespace>]*;` directives at the current position
+fn parse_using_directives(lexer: &mut ParseSession) -> Vec<String> {
+    let mut usings = vec![];
+    while lexer.allow(&KeywordUsing) {
+        loop {
+            usings.push(parse_qualified_name(lexer));
+            if !lexer.allow(&KeywordComma) {
+                break;
+            }
+        }
+        lexer.consume_or_report(KeywordSemicolon);
+    }
+    usings
+}
+
+/// parses a name that may be qualified with namespaces (e.g. `Vendor.Motion.FB_Axis`)
+fn parse_qualified_name(lexer: &mut ParseSession) -> String {
+    let mut name = parse_identifier(lexer)
+        .map(|(name, _)| name)
+        .unwrap_or_default();
+    while lexer.allow(&KeywordDot) {
+        if let Some((segment, _)) = parse_identifier(lexer) {
+            name = format!("{}.{}", name, segment);
+        }
+    }
+    name
+}
+
+/// registers the namespaces searched for unqualified names used by the declaration with the
+/// given name. The declaration's own `usings` are searched right after its namespace.
+fn register_scopes(
+    lexer: &ParseSession,
+    unit: &mut CompilationUnit,
+    name: &str,
+    usings: &[String],
+) {
+    let mut scopes = lexer.usings.clone();
+    let position = if lexer.namespace.is_some() { 1 } else { 0 };
+    scopes.splice(position..position, usings.iter().cloned());
+    if !scopes.is_empty() {
+        unit.usings.insert(name.to_lowercase(), scopes);
+    }
+}
+
+/// qualifies the given name with the NAMESPACE that is currently parsed
+fn qualify_name(lexer: &ParseSession, name: String) -> String {
+    match &lexer.namespace {
+        Some(namespace) => format!("{}.{}", namespace, name),
+        None => name,
+    }
+}
+
+/// parses an optional `IMPLEMENTS <name>[, <name>]*` specifier
+/// only classes and function blocks can implement interfaces
+fn parse_interface_list(lexer: &mut ParseSession, pou_type: &PouType) -> Vec<String> {
+    let mut interfaces = vec![];
+    if matches!(pou_type, PouType::Class | PouType::FunctionBlock)
+        && lexer.allow(&KeywordImplements)
+    {
+        loop {
+            interfaces.push(parse_qualified_name(lexer));
+            if !lexer.allow(&KeywordComma) {
+                break;
+            }
+        }
+    }
+    interfaces
+}
+
 fn parse_return_type(lexer: &mut ParseSession, pou_type: &PouType) -> Option<DataTypeDeclaration> {
     let start_return_type = lexer.range().start;
     if lexer.allow(&KeywordColon) {
@@ -330,7 +493,7 @@ fn parse_return_type(lexer: &mut ParseSession, pou_type: &PouType) -> Option<Dat
             if !matches!(pou_type, PouType::Function | PouType::Method { .. }) {
                 lexer.accept_diagnostic(Diagnostic::return_type_not_supported(
                     pou_type,
-                    SourceRange::new(start_return_type..lexer.last_range.end),
+                    lexer.source_range(start_return_type..lexer.last_range.end),
                 ));
             }
 
@@ -350,7 +513,7 @@ fn parse_return_type(lexer: &mut ParseSession, pou_type: &PouType) -> Option<Dat
             lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
                 "Datatype",
                 lexer.slice(),
-                SourceRange::new(lexer.range()),
+                lexer.location(),
             ));
             None
         }
@@ -371,6 +534,7 @@ fn parse_method(
         // END_METHOD
 
         let method_start = lexer.location().get_start();
+        let pragmas = lexer.take_pragmas();
         lexer.advance(); // eat METHOD keyword
 
         let access = Some(parse_access_modifier(lexer));
@@ -420,11 +584,15 @@ fn parse_method(
                 pou_type,
                 variable_blocks,
                 return_type,
-                location: SourceRange::new(method_start..method_end),
+                location: lexer.source_range(method_start..method_end),
                 name_location,
                 poly_mode,
                 generics,
                 linkage,
+                super_class: None,
+                interfaces: vec![],
+                usings: vec![],
+                pragmas,
             },
             implementation,
         ))
@@ -478,7 +646,7 @@ fn parse_implementation(
         linkage,
         pou_type,
         statements,
-        location: SourceRange::new(start..lexer.range().end),
+        location: lexer.source_range(start..lexer.range().end),
         overriding: false,
         generic,
         access: None,
@@ -511,7 +679,7 @@ fn parse_action(
             expect_token!(lexer, Identifier, None);
 
             let name = lexer.slice_and_advance();
-            (name_or_container, name)
+            (qualify_name(lexer, name_or_container), name)
         };
         let call_name = format!("{}.{}", &container, &name);
 
@@ -544,6 +712,7 @@ fn parse_type(lexer: &mut ParseSession) -> Vec<UserTypeDeclaration> {
         while !lexer.closes_open_region(&lexer.token) {
             let start = lexer.location().get_start();
             let name = lexer.slice_and_advance();
+            let name = qualify_name(lexer, name);
             lexer.consume_or_report(KeywordColon);
 
             let result = parse_full_data_type_definition(lexer, Some(name));
@@ -555,7 +724,7 @@ fn parse_type(lexer: &mut ParseSession) -> Vec<UserTypeDeclaration> {
                 declarations.push(UserTypeDeclaration {
                     data_type,
                     initializer,
-                    location: (start..end).into(),
+                    location: lexer.source_range(start..end),
                     scope: lexer.scope.clone(),
                 });
             }
@@ -584,7 +753,7 @@ fn parse_full_data_type_definition(
                         referenced_type: None,
                         sized,
                     },
-                    location: lexer.last_range.clone().into(),
+                    location: lexer.last_location(),
                     scope: lexer.scope.clone(),
                 },
                 None,
@@ -598,7 +767,7 @@ fn parse_full_data_type_definition(
                                 referenced_type: Some(Box::new(type_def)),
                                 sized,
                             },
-                            location: lexer.last_range.clone().into(),
+                            location: lexer.last_location(),
                             scope: lexer.scope.clone(),
                         },
                         None,
@@ -623,7 +792,7 @@ fn parse_data_type_definition(
         Some((
             DataTypeDeclaration::DataTypeDefinition {
                 data_type: DataType::StructType { name, variables },
-                location: (start..lexer.range().end).into(),
+                location: lexer.source_range(start..lexer.range().end),
                 scope: lexer.scope.clone(),
             },
             None,
@@ -633,10 +802,10 @@ fn parse_data_type_definition(
     } else if lexer.allow(&KeywordPointer) {
         let start_pos = lexer.last_range.start;
         //Report wrong keyword
-        lexer.accept_diagnostic(Diagnostic::ImprovementSuggestion {
-            message: "'POINTER TO' is not a standard keyword, use REF_TO instead".to_string(),
-            range: lexer.last_location(),
-        });
+        lexer.accept_diagnostic(Diagnostic::non_standard_keyword(
+            "'POINTER TO' is not a standard keyword, use REF_TO instead",
+            lexer.last_location(),
+        ));
         if let Err(diag) = lexer.expect(KeywordTo) {
             lexer.accept_diagnostic(diag);
         } else {
@@ -675,7 +844,7 @@ fn parse_pointer_definition(
                     name,
                     referenced_type: Box::new(decl),
                 },
-                location: (start_pos..lexer.last_range.end).into(),
+                location: lexer.source_range(start_pos..lexer.last_range.end),
                 scope: lexer.scope.clone(),
             },
             initializer,
@@ -689,7 +858,11 @@ fn parse_type_reference_type_definition(
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     let start = lexer.location().get_start();
     //Subrange
-    let referenced_type = lexer.slice_and_advance();
+    let mut referenced_type = lexer.slice_and_advance();
+    //the type may be qualified with its namespace
+    while lexer.allow(&KeywordDot) {
+        referenced_type = format!("{}.{}", referenced_type, lexer.slice_and_advance());
+    }
 
     let bounds = if lexer.allow(&KeywordParensOpen) {
         // INT (..) :=
@@ -718,7 +891,7 @@ fn parse_type_reference_type_definition(
                         numeric_type: referenced_type,
                         elements: AstStatement::ExpressionList { expressions, id },
                     },
-                    location: (start..end).into(),
+                    location: lexer.source_range(start..end),
                     scope: lexer.scope.clone(),
                 }
             }
@@ -730,7 +903,7 @@ fn parse_type_reference_type_definition(
                         numeric_type: referenced_type,
                         elements: bounds.unwrap(),
                     },
-                    location: (start..end).into(),
+                    location: lexer.source_range(start..end),
                     scope: lexer.scope.clone(),
                 }
             }
@@ -741,7 +914,7 @@ fn parse_type_reference_type_definition(
                     referenced_type,
                     bounds,
                 },
-                location: (start..end).into(),
+                location: lexer.source_range(start..end),
                 scope: lexer.scope.clone(),
             },
         };
@@ -750,7 +923,7 @@ fn parse_type_reference_type_definition(
         Some((
             DataTypeDeclaration::DataTypeReference {
                 referenced_type,
-                location: (start..end).into(),
+                location: lexer.source_range(start..end),
             },
             initial_value,
         ))
@@ -764,21 +937,20 @@ fn parse_string_size_expression(lexer: &mut ParseSession) -> Option<AstStatement
         let closing_tokens = vec![KeywordSquareParensClose, KeywordParensClose];
         parse_any_in_region(lexer, closing_tokens, |lexer| {
             let size_expr = parse_expression(lexer);
-            let error_range = SourceRange::new(opening_location..lexer.location().get_end());
+            let error_range = lexer.source_range(opening_location..lexer.location().get_end());
 
             if (opening_token == KeywordParensOpen && lexer.token == KeywordSquareParensClose)
                 || (opening_token == KeywordSquareParensOpen && lexer.token == KeywordParensClose)
             {
-                lexer.accept_diagnostic(Diagnostic::ImprovementSuggestion {
-                    message: "Mismatched types of parentheses around string size expression".into(),
-                    range: error_range,
-                });
+                lexer.accept_diagnostic(Diagnostic::mismatched_parentheses(
+                    "Mismatched types of parentheses around string size expression",
+                    error_range,
+                ));
             } else if opening_token == KeywordParensOpen || lexer.token == KeywordParensClose {
-                lexer.accept_diagnostic(Diagnostic::ImprovementSuggestion {
-                    message: "Unusual type of parentheses around string size expression, consider using square parentheses '[]'"
-                        .into(),
-                    range: error_range,
-                });
+                lexer.accept_diagnostic(Diagnostic::unusual_parentheses(
+                    "Unusual type of parentheses around string size expression, consider using square parentheses '[]'",
+                    error_range,
+                ));
             }
 
             Some(size_expr)
@@ -806,7 +978,7 @@ fn parse_string_type_definition(
                 is_wide,
                 size,
             },
-            location: (start..end).into(),
+            location: lexer.source_range(start..end),
             scope: lexer.scope.clone(),
         },
         lexer
@@ -833,7 +1005,7 @@ fn parse_enum_type_definition(
                 elements,
                 numeric_type: DINT_TYPE.to_string(),
             },
-            location: (start..lexer.last_range.end).into(),
+            location: lexer.source_range(start..lexer.last_range.end),
             scope: lexer.scope.clone(),
         },
         None,
@@ -861,7 +1033,7 @@ fn parse_array_type_definition(
 
     let inner_type_defintion = parse_data_type_definition(lexer, None);
     inner_type_defintion.map(|(reference, initializer)| {
-        let location = SourceRange::new(start..reference.get_location().get_end());
+        let location = lexer.source_range(start..reference.get_location().get_end());
         (
             DataTypeDeclaration::DataTypeDefinition {
                 data_type: DataType::ArrayType {
@@ -885,11 +1057,44 @@ fn parse_body_in_region(lexer: &mut ParseSession, end_keywords: Vec<Token>) -> V
 fn parse_body_standalone(lexer: &mut ParseSession) -> Vec<AstStatement> {
     let mut statements = Vec::new();
     while !lexer.closes_open_region(&lexer.token) {
-        statements.push(parse_control(lexer));
+        let pragmas = lexer.take_pragmas();
+        let statement = parse_control(lexer);
+        if !pragmas.is_empty() {
+            register_suppressions(lexer, &pragmas, &statement.get_location());
+            lexer.statement_pragmas.insert(statement.get_id(), pragmas);
+        }
+        statements.push(statement);
     }
     statements
 }
 
+/// records the codes disabled by `{warning disable <code>...}` pragmas attached to the POU or
+/// statement at the given location, unknown codes and codes that are not warnings are reported
+fn register_suppressions(lexer: &mut ParseSession, pragmas: &[Pragma], location: &SourceRange) {
+    for pragma in pragmas {
+        if let Some(codes) = pragma.get_disabled_codes() {
+            let mut disabled = vec![];
+            for code in codes {
+                match code.parse::<ErrNo>() {
+                    Ok(code) if code.is_warning_by_default() => disabled.push(code),
+                    Ok(code) => lexer.accept_diagnostic(Diagnostic::undisableable_diagnostic_code(
+                        &code,
+                        pragma.location.clone(),
+                    )),
+                    Err(_) => lexer.accept_diagnostic(Diagnostic::unknown_diagnostic_code(
+                        code,
+                        pragma.location.clone(),
+                    )),
+                }
+            }
+            lexer.suppressions.push(Suppression {
+                codes: disabled,
+                location: location.clone(),
+            });
+        }
+    }
+}
+
 /// parses a statement ending with a ';'
 fn parse_statement(lexer: &mut ParseSession) -> AstStatement {
     let result = parse_any_in_region(lexer, vec![KeywordSemicolon, KeywordColon], |lexer| {
@@ -983,8 +1188,18 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
 
     let constant = lexer.allow(&KeywordConstant);
 
-    let retain = lexer.allow(&KeywordRetain);
-    lexer.allow(&KeywordNonRetain);
+    //RETAIN and PERSISTENT may be combined in any order
+    let mut retain = false;
+    let mut persistent = false;
+    loop {
+        if lexer.allow(&KeywordRetain) {
+            retain = true;
+        } else if lexer.allow(&KeywordPersistent) {
+            persistent = true;
+        } else if !lexer.allow(&KeywordNonRetain) {
+            break;
+        }
+    }
 
     let access = parse_access_modifier(lexer);
 
@@ -995,6 +1210,7 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
         access,
         constant,
         retain,
+        persistent,
         variables,
         variable_block_type,
         linkage,
@@ -1012,6 +1228,7 @@ fn parse_variable_list(lexer: &mut ParseSession) -> Vec<Variable> {
 }
 
 fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
+    let pragmas = lexer.take_pragmas();
     // read in a comma separated list of variable names
     let mut var_names: Vec<(String, SourceRange)> = vec![];
     while lexer.token == Identifier {
@@ -1027,7 +1244,7 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
             let next_token_start = lexer.location().get_start();
             lexer.accept_diagnostic(Diagnostic::missing_token(
                 format!("{:?} or {:?}", KeywordColon, KeywordComma).as_str(),
-                SourceRange::new(identifier_end..next_token_start),
+                lexer.source_range(identifier_end..next_token_start),
             ));
         }
     }
@@ -1060,7 +1277,7 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
                     access: access_type,
                     direction,
                     address,
-                    location: (start_location..lexer.last_range.end).into(),
+                    location: lexer.source_range(start_location..lexer.last_range.end),
                     id: lexer.next_id(),
                 })
             } else {
@@ -1099,6 +1316,7 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
                 location,
                 initializer: initializer.clone(),
                 address: address.clone(),
+                pragmas: pragmas.clone(),
             });
         }
     }
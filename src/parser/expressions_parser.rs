@@ -154,7 +154,7 @@ fn parse_exponent_expression(lexer: &mut ParseSession) -> AstStatement {
                 expressions: vec![left, right],
                 id: lexer.next_id(),
             })),
-            location: (start_location.get_start()..lexer.last_location().get_end()).into(),
+            location: start_location.span(&lexer.last_location()),
             id: lexer.next_id(),
         }
     }
@@ -175,7 +175,7 @@ fn parse_unary_expression(lexer: &mut ParseSession) -> AstStatement {
         lexer.advance();
         let expression = parse_parenthesized_expression(lexer);
         let expression_location = expression.get_location();
-        let location = SourceRange::new(start..expression_location.get_end());
+        let location = lexer.source_range(start..expression_location.get_end());
 
         if let (AstStatement::LiteralInteger { value, .. }, Operator::Minus) =
             (&expression, &operator)
@@ -263,7 +263,7 @@ fn parse_leaf_expression(lexer: &mut ParseSession) -> AstStatement {
     } else {
         // no minus ... so this may be anything
         match lexer.token {
-            Identifier => parse_qualified_reference(lexer),
+            Identifier | KeywordSuper => parse_qualified_reference(lexer),
             LiteralInteger => parse_literal_number(lexer, false),
             LiteralIntegerBin => parse_literal_number_with_modifier(lexer, 2, false),
             LiteralIntegerOct => parse_literal_number_with_modifier(lexer, 8, false),
@@ -294,7 +294,7 @@ fn parse_leaf_expression(lexer: &mut ParseSession) -> AstStatement {
 
             Ok(AstStatement::CastStatement {
                 id: lexer.next_id(),
-                location: (location.get_start()..statement.get_location().get_end()).into(),
+                location: location.span(&statement.get_location()),
                 target: Box::new(statement),
                 type_name: cast,
             })
@@ -344,7 +344,7 @@ fn parse_array_literal(lexer: &mut ParseSession) -> Result<AstStatement, Diagnos
     lexer.advance();
     Ok(AstStatement::LiteralArray {
         elements,
-        location: SourceRange::new(start..end),
+        location: lexer.source_range(start..end),
         id: lexer.next_id(),
     })
 }
@@ -412,7 +412,7 @@ pub fn parse_qualified_reference(lexer: &mut ParseSession) -> Result<AstStatemen
             AstStatement::CallStatement {
                 operator: Box::new(reference),
                 parameters: Box::new(None),
-                location: SourceRange::new(start..lexer.range().end),
+                location: lexer.source_range(start..lexer.range().end),
                 id: lexer.next_id(),
             }
         } else {
@@ -420,7 +420,7 @@ pub fn parse_qualified_reference(lexer: &mut ParseSession) -> Result<AstStatemen
                 AstStatement::CallStatement {
                     operator: Box::new(reference),
                     parameters: Box::new(Some(parse_expression_list(lexer))),
-                    location: SourceRange::new(start..lexer.range().end),
+                    location: lexer.source_range(start..lexer.range().end),
                     id: lexer.next_id(),
                 }
             })
@@ -449,7 +449,7 @@ fn parse_direct_access(
         )),
     }?;
 
-    let location = (location.get_start()..lexer.last_location().get_end()).into();
+    let location = location.span(&lexer.last_location());
     Ok(AstStatement::DirectAccess {
         access,
         index: Box::new(index),
@@ -459,6 +459,9 @@ fn parse_direct_access(
 }
 
 pub fn parse_reference_access(lexer: &mut ParseSession) -> Result<AstStatement, Diagnostic> {
+    if lexer.token == KeywordSuper {
+        return parse_super_reference(lexer);
+    }
     let location = lexer.location();
     let reference = AstStatement::Reference {
         name: lexer.slice_and_advance(),
@@ -468,6 +471,20 @@ pub fn parse_reference_access(lexer: &mut ParseSession) -> Result<AstStatement,
     parse_access_modifiers(lexer, reference)
 }
 
+/// parses `SUPER^` which references the embedded instance of the current pou's super class
+fn parse_super_reference(lexer: &mut ParseSession) -> Result<AstStatement, Diagnostic> {
+    let start = lexer.range().start;
+    lexer.advance(); //SUPER
+    lexer.expect(OperatorDeref)?;
+    lexer.advance();
+    let reference = AstStatement::Reference {
+        name: SUPER_MEMBER_NAME.into(),
+        location: lexer.source_range(start..lexer.last_location().get_end()),
+        id: lexer.next_id(),
+    };
+    parse_access_modifiers(lexer, reference)
+}
+
 fn parse_access_modifiers(
     lexer: &mut ParseSession,
     original_reference: AstStatement,
@@ -522,7 +539,7 @@ fn parse_literal_number(
 ) -> Result<AstStatement, Diagnostic> {
     //correct the location if we just parsed a minus before
     let location = if is_negative {
-        (lexer.last_range.start..lexer.location().get_end()).into()
+        lexer.last_location().span(&lexer.location())
     } else {
         lexer.location()
     };
@@ -548,7 +565,7 @@ fn parse_literal_number(
         return Ok(AstStatement::MultipliedStatement {
             multiplier,
             element: Box::new(element),
-            location: SourceRange::new(location.get_start()..end),
+            location: lexer.source_range(location.get_start()..end),
             id: lexer.next_id(),
         });
     }
@@ -908,7 +925,7 @@ fn parse_literal_real(
             integer,
             fractional
         );
-        let new_location = SourceRange::new(start..end);
+        let new_location = lexer.source_range(start..end);
         Ok(AstStatement::LiteralReal {
             value: result,
             location: new_location,
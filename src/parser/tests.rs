@@ -8,8 +8,10 @@ mod expressions_parser_tests;
 mod function_parser_tests;
 mod initializer_parser_tests;
 mod misc_parser_tests;
+mod namespace_parser_tests;
 mod parse_errors;
 mod parse_generics;
+mod pragma_parser_tests;
 mod program_parser_tests;
 mod statement_parser_tests;
 mod type_parser_tests;
@@ -1,4 +1,4 @@
-use crate::{ast::*, test_utils::tests::parse};
+use crate::{ast::*, diagnostics::Diagnostic, test_utils::tests::parse};
 
 #[test]
 fn simple_class_with_defaults_can_be_parsed() {
@@ -323,3 +323,124 @@ fn fb_method_with_return_type_can_be_parsed() {
     assert_ne!(method_pou.return_type, None);
     assert_eq!(method.overriding, true);
 }
+
+#[test]
+fn class_with_super_class_can_be_parsed() {
+    let src = "CLASS MyClass EXTENDS MyBase END_CLASS";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let class = &unit.units[0];
+    assert_eq!(class.name, "MyClass");
+    assert_eq!(class.super_class, Some("MyBase".into()));
+}
+
+#[test]
+fn fb_with_super_class_can_be_parsed() {
+    let src = r#"
+        FUNCTION_BLOCK FINAL Motor EXTENDS BaseDrive
+            VAR_INPUT speed : INT; END_VAR
+            METHOD start END_METHOD
+        END_FUNCTION_BLOCK
+    "#;
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let fb = &unit.units[0];
+    assert_eq!(fb.pou_type, PouType::FunctionBlock);
+    assert_eq!(fb.name, "Motor");
+    assert_eq!(fb.poly_mode, Some(PolymorphismMode::Final));
+    assert_eq!(fb.super_class, Some("BaseDrive".into()));
+    assert_eq!(fb.variable_blocks.len(), 1);
+    assert_eq!(unit.units[1].name, "Motor.start");
+}
+
+#[test]
+fn super_access_is_parsed_as_reference_to_the_super_instance() {
+    let src = r#"
+        FUNCTION_BLOCK Motor EXTENDS BaseDrive
+            SUPER^.start();
+        END_FUNCTION_BLOCK
+    "#;
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let statement = &unit.implementations[0].statements[0];
+    if let AstStatement::CallStatement { operator, .. } = statement {
+        if let AstStatement::QualifiedReference { elements, .. } = operator.as_ref() {
+            assert_eq!(
+                format!("{:?}", elements),
+                r#"[Reference { name: "__SUPER" }, Reference { name: "start" }]"#
+            );
+            return;
+        }
+    }
+    panic!("unexpected statement {:?}", statement);
+}
+
+#[test]
+fn interface_with_methods_can_be_parsed() {
+    let src = r#"
+        INTERFACE IShape
+            METHOD area : REAL END_METHOD
+            METHOD scale
+                VAR_INPUT factor : REAL; END_VAR
+            END_METHOD
+        END_INTERFACE
+    "#;
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let interface = &unit.units[0];
+    assert_eq!(interface.pou_type, PouType::Interface);
+    assert_eq!(interface.name, "IShape");
+    assert_eq!(interface.return_type, None);
+    assert_eq!(unit.units[1].name, "IShape.area");
+    assert_eq!(unit.units[2].name, "IShape.scale");
+    assert_eq!(unit.units[2].variable_blocks.len(), 1);
+    //interfaces only declare signatures
+    assert_eq!(unit.implementations.len(), 0);
+}
+
+#[test]
+fn interface_methods_with_an_implementation_are_reported() {
+    let src = r#"
+        INTERFACE IShape
+            METHOD area : REAL
+                area := 1.0;
+            END_METHOD
+        END_INTERFACE
+    "#;
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_interface(
+            "IShape",
+            "Method 'IShape.area' cannot have an implementation",
+            (73..84).into()
+        )]
+    );
+    assert_eq!(unit.implementations.len(), 0);
+}
+
+#[test]
+fn class_implementing_interfaces_can_be_parsed() {
+    let src = r#"
+        CLASS Circle EXTENDS Shape IMPLEMENTS IShape, IPrintable
+        END_CLASS
+
+        FUNCTION_BLOCK Timer IMPLEMENTS ITimer
+        END_FUNCTION_BLOCK
+    "#;
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let class = &unit.units[0];
+    assert_eq!(class.super_class, Some("Shape".into()));
+    assert_eq!(
+        class.interfaces,
+        vec!["IShape".to_string(), "IPrintable".to_string()]
+    );
+    assert_eq!(unit.units[1].interfaces, vec!["ITimer".to_string()]);
+}
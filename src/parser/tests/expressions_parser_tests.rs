@@ -2791,6 +2791,10 @@ fn sized_string_as_function_return() {
         name_location: SourceRange::undefined(),
         generics: vec![],
         linkage: crate::ast::LinkageType::Internal,
+        super_class: None,
+        interfaces: vec![],
+        usings: vec![],
+        pragmas: vec![],
     };
 
     assert_eq!(format!("{:?}", ast.units[0]), format!("{:?}", expected));
@@ -2839,6 +2843,10 @@ fn array_type_as_function_return() {
         name_location: SourceRange::undefined(),
         generics: vec![],
         linkage: crate::ast::LinkageType::Internal,
+        super_class: None,
+        interfaces: vec![],
+        usings: vec![],
+        pragmas: vec![],
     };
 
     assert_eq!(format!("{:?}", ast.units[0]), format!("{:?}", expected));
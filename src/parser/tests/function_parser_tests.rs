@@ -204,6 +204,7 @@ fn varargs_parameters_can_be_parsed() {
             constant: false,
             access: AccessModifier::Protected,
             retain: false,
+            persistent: false,
             variable_block_type: VariableBlockType::Input(ArgumentProperty::ByVal),
             location: SourceRange::undefined(),
             linkage: LinkageType::Internal,
@@ -220,6 +221,7 @@ fn varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 },
                 Variable {
@@ -239,6 +241,7 @@ fn varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 },
             ],
@@ -248,6 +251,10 @@ fn varargs_parameters_can_be_parsed() {
         poly_mode: None,
         generics: vec![],
         linkage: crate::ast::LinkageType::Internal,
+        super_class: None,
+        interfaces: vec![],
+        usings: vec![],
+        pragmas: vec![],
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", x).as_str());
 }
@@ -281,6 +288,7 @@ fn sized_varargs_parameters_can_be_parsed() {
             constant: false,
             access: AccessModifier::Protected,
             retain: false,
+            persistent: false,
             variable_block_type: VariableBlockType::Input(ArgumentProperty::ByVal),
             location: SourceRange::undefined(),
             linkage: LinkageType::Internal,
@@ -297,6 +305,7 @@ fn sized_varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 },
                 Variable {
@@ -316,6 +325,7 @@ fn sized_varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 },
             ],
@@ -325,6 +335,10 @@ fn sized_varargs_parameters_can_be_parsed() {
         poly_mode: None,
         generics: vec![],
         linkage: crate::ast::LinkageType::Internal,
+        super_class: None,
+        interfaces: vec![],
+        usings: vec![],
+        pragmas: vec![],
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", x).as_str());
 }
@@ -495,6 +509,7 @@ fn function_inline_struct_return_unsupported() {
                             },
                             initializer: None,
                             address: None,
+                            pragmas: vec![],
                         },
                         Variable {
                             name: "y".into(),
@@ -505,6 +520,7 @@ fn function_inline_struct_return_unsupported() {
                             },
                             initializer: None,
                             address: None,
+                            pragmas: vec![],
                         }
                     ],
                 },
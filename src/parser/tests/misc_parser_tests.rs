@@ -47,6 +47,7 @@ fn exponent_literals_parsed_as_variables() {
             access: AccessModifier::Internal,
             constant: false,
             retain: false,
+            persistent: false,
             location: SourceRange::undefined(),
             linkage: LinkageType::Internal,
             variables: vec![Variable {
@@ -57,6 +58,7 @@ fn exponent_literals_parsed_as_variables() {
                 },
                 initializer: None,
                 address: None,
+                pragmas: vec![],
                 location: SourceRange::undefined(),
             }],
         }],
@@ -64,6 +66,10 @@ fn exponent_literals_parsed_as_variables() {
         name_location: SourceRange::undefined(),
         generics: vec![],
         linkage: crate::ast::LinkageType::Internal,
+        super_class: None,
+        interfaces: vec![],
+        usings: vec![],
+        pragmas: vec![],
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", pou).as_str());
     let implementation = &parse_result.implementations[0];
@@ -0,0 +1,87 @@
+use crate::{ast::*, test_utils::tests::parse, Diagnostic};
+
+#[test]
+fn declarations_in_a_namespace_are_qualified() {
+    let src = "
+        NAMESPACE Vendor.Motion
+            TYPE State : (Idle, Running); END_TYPE
+            VAR_GLOBAL gAxis : FB_Axis; END_VAR
+            FUNCTION_BLOCK FB_Axis
+                METHOD MoveTo END_METHOD
+            END_FUNCTION_BLOCK
+            NAMESPACE Internal
+                FUNCTION Clamp : INT END_FUNCTION
+            END_NAMESPACE
+        END_NAMESPACE
+        PROGRAM main END_PROGRAM
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let pous = unit
+        .units
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pous,
+        vec![
+            "Vendor.Motion.FB_Axis",
+            "Vendor.Motion.FB_Axis.MoveTo",
+            "Vendor.Motion.Internal.Clamp",
+            "main"
+        ]
+    );
+    assert_eq!(
+        unit.types[0].data_type.get_name(),
+        Some("Vendor.Motion.State")
+    );
+    assert_eq!(unit.global_vars[0].variables[0].name, "Vendor.Motion.gAxis");
+    assert_eq!(unit.implementations[0].name, "Vendor.Motion.FB_Axis.MoveTo");
+}
+
+#[test]
+fn usings_are_searched_after_the_enclosing_namespaces() {
+    let src = "
+        NAMESPACE Vendor
+            USING Standard.Timers;
+            NAMESPACE Motion
+                FUNCTION_BLOCK FB_Axis
+                USING Standard.Math, Util;
+                END_FUNCTION_BLOCK
+            END_NAMESPACE
+        END_NAMESPACE
+        USING Vendor;
+        PROGRAM main END_PROGRAM
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.units[0].usings, vec!["Standard.Math", "Util"]);
+    assert_eq!(
+        unit.usings["vendor.motion.fb_axis"],
+        vec![
+            "Vendor.Motion",
+            "Standard.Math",
+            "Util",
+            "Vendor",
+            "Standard.Timers"
+        ]
+    );
+    assert_eq!(unit.usings["main"], vec!["Vendor"]);
+}
+
+#[test]
+fn missing_end_namespace_is_reported() {
+    let src = "NAMESPACE Vendor PROGRAM main END_PROGRAM";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(unit.units[0].name, "Vendor.main");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::missing_token(
+            "END_NAMESPACE",
+            SourceRange::new(41..41)
+        )]
+    );
+}
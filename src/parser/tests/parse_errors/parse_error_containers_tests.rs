@@ -255,6 +255,7 @@ fn unclosed_var_container() {
                 constant: false,
                 access: AccessModifier::Protected,
                 retain: false,
+                persistent: false,
                 variable_block_type: VariableBlockType::Local,
                 location: SourceRange::undefined(),
                 variables: vec![Variable {
@@ -265,6 +266,7 @@ fn unclosed_var_container() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 }],
                 linkage: LinkageType::Internal,
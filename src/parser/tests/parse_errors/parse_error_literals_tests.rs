@@ -1,4 +1,7 @@
-use crate::{ast::AstStatement::LiteralInteger, ast::*, test_utils::tests::parse, Diagnostic};
+use crate::{
+    ast::AstStatement::LiteralInteger, ast::*, diagnostics::ErrNo, test_utils::tests::parse,
+    Diagnostic,
+};
 
 #[test]
 fn illegal_literal_time_missing_segments_test() {
@@ -135,14 +138,17 @@ fn string_with_round_parens_can_be_parsed() {
                 message: "Unusual type of parentheses around string size expression, consider using square parentheses '[]'"
                     .into(),
                 range: SourceRange::new(37..41),
+                err_no: ErrNo::syntax__unusual_parentheses,
             },
             Diagnostic::ImprovementSuggestion {
                 message: "Mismatched types of parentheses around string size expression".into(),
                 range: SourceRange::new(88..92),
+                err_no: ErrNo::syntax__mismatched_parentheses,
             },
             Diagnostic::ImprovementSuggestion {
                 message: "Mismatched types of parentheses around string size expression".into(),
                 range: SourceRange::new(148..152),
+                err_no: ErrNo::syntax__mismatched_parentheses,
             }
         ]
     );
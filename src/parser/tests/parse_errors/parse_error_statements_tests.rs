@@ -1,6 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::{
     ast::*,
+    diagnostics::ErrNo,
     lexer::Token,
     parser::tests::{empty_stmt, ref_to},
     test_utils::tests::parse,
@@ -272,6 +273,7 @@ fn invalid_variable_name_error_recovery() {
                 constant: false,
                 access: AccessModifier::Protected,
                 retain: false,
+                persistent: false,
                 location: SourceRange::undefined(),
                 variables: vec![Variable {
                     name: "c".into(),
@@ -281,6 +283,7 @@ fn invalid_variable_name_error_recovery() {
                     },
                     initializer: None,
                     address: None,
+                    pragmas: vec![],
                     location: SourceRange::undefined(),
                 },],
                 variable_block_type: VariableBlockType::Local,
@@ -1046,7 +1049,8 @@ fn pointer_type_without_to_test() {
         vec![
             Diagnostic::ImprovementSuggestion {
                 message: "'POINTER TO' is not a standard keyword, use REF_TO instead".to_string(),
-                range: SourceRange::new(42..49)
+                range: SourceRange::new(42..49),
+                err_no: ErrNo::syntax__non_standard_keyword,
             },
             Diagnostic::unexpected_token_found("KeywordTo", "INT", (50..53).into())
         ],
@@ -1083,7 +1087,8 @@ fn pointer_type_with_wrong_keyword_to_test() {
         vec![
             Diagnostic::ImprovementSuggestion {
                 message: "'POINTER TO' is not a standard keyword, use REF_TO instead".to_string(),
-                range: SourceRange::new(42..49)
+                range: SourceRange::new(42..49),
+                err_no: ErrNo::syntax__non_standard_keyword,
             },
             Diagnostic::unexpected_token_found("KeywordTo", "tu", (50..52).into()),
             Diagnostic::unexpected_token_found("KeywordSemicolon", "'INT'", (53..56).into())
@@ -189,6 +189,7 @@ fn generic_parameters_are_datatypes() {
                 },
                 initializer: None,
                 address: None,
+                pragmas: vec![],
                 location: SourceRange::new(49..50),
             },
             Variable {
@@ -199,6 +200,7 @@ fn generic_parameters_are_datatypes() {
                 },
                 initializer: None,
                 address: None,
+                pragmas: vec![],
                 location: SourceRange::new(56..57),
             },
         ],
@@ -222,6 +224,7 @@ fn generic_method_parameters_are_datatypes() {
                 },
                 initializer: None,
                 address: None,
+                pragmas: vec![],
                 location: SourceRange::new(57..58),
             },
             Variable {
@@ -232,6 +235,7 @@ fn generic_method_parameters_are_datatypes() {
                 },
                 initializer: None,
                 address: None,
+                pragmas: vec![],
 
                 location: SourceRange::new(64..65),
             },
@@ -0,0 +1,145 @@
+use crate::{ast::*, diagnostics::ErrNo, test_utils::tests::parse};
+
+#[test]
+fn pragmas_are_attached_to_the_following_pou_variable_and_statement() {
+    let src = "
+        {attribute 'hide'}
+        FUNCTION_BLOCK fb
+        VAR
+            {attribute 'no_init'}
+            a, b : INT;
+            c : INT;
+        END_VAR
+            a := 1;
+            {warning 'do not use'}
+            b := 2;
+        END_FUNCTION_BLOCK
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let pou = &unit.units[0];
+    assert!(pou.pragmas[0].is_attribute("hide"));
+
+    let variables = &pou.variable_blocks[0].variables;
+    assert!(variables[0].pragmas[0].is_attribute("no_init"));
+    assert!(variables[1].pragmas[0].is_attribute("no_init"));
+    assert!(variables[2].pragmas.is_empty());
+
+    let statements = &unit.implementations[0].statements;
+    assert!(!unit.pragmas.contains_key(&statements[0].get_id()));
+    let pragmas = &unit.pragmas[&statements[1].get_id()];
+    assert_eq!(pragmas[0].name, "warning");
+    assert_eq!(pragmas[0].arguments, vec!["do not use"]);
+}
+
+#[test]
+fn warning_disable_pragmas_suppress_diagnostics_in_their_pou_or_statement() {
+    let src = "
+        {warning disable pragma__unattached}
+        PROGRAM prg
+            x := 1;
+            {warning disable syntax__unusual_parentheses E049}
+            y := 2;
+        END_PROGRAM
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let pou = &unit.units[0];
+    let statement = &unit.implementations[0].statements[1];
+    assert_eq!(
+        unit.suppressions,
+        vec![
+            Suppression {
+                codes: vec![ErrNo::syntax__unusual_parentheses, ErrNo::pragma__unknown],
+                location: statement.get_location(),
+            },
+            Suppression {
+                codes: vec![ErrNo::pragma__unattached],
+                location: pou.location.clone(),
+            },
+        ]
+    );
+    assert!(unit.suppressions[0].contains(&statement.get_location()));
+    assert!(!unit.suppressions[0].contains(&unit.implementations[0].statements[0].get_location()));
+}
+
+#[test]
+fn unknown_codes_of_warning_disable_pragmas_are_reported() {
+    let src = "
+        PROGRAM prg
+            {warning disable no_such_code}
+            x := 1;
+        END_PROGRAM
+        ";
+    let (_, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].get_message(),
+        "Unknown diagnostic code 'no_such_code' cannot be disabled"
+    );
+    assert_eq!(
+        diagnostics[0].get_type(),
+        &ErrNo::pragma__unknown_diagnostic_code
+    );
+}
+
+#[test]
+fn codes_that_are_not_warnings_cannot_be_disabled() {
+    let src = "
+        PROGRAM prg
+            {warning disable reference__unresolved pragma__unknown}
+            x := 1;
+        END_PROGRAM
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].get_message(),
+        "'reference__unresolved' is not a warning and cannot be disabled"
+    );
+    assert_eq!(unit.suppressions[0].codes, vec![ErrNo::pragma__unknown]);
+}
+
+#[test]
+fn pragmas_without_a_following_element_are_reported_as_unattached() {
+    let src = "
+        {attribute 'hide'}
+        TYPE MyInt : INT; END_TYPE
+        {attribute 'hide'} {external}
+        FUNCTION foo : INT
+        VAR_INPUT
+            a : INT;
+            {attribute 'no_init'}
+        END_VAR
+            a := {warning 'value'} 1;
+        END_FUNCTION
+        {attribute 'hide'}
+        ";
+    let (unit, diagnostics) = parse(src);
+
+    let messages = diagnostics
+        .iter()
+        .map(|it| it.get_message())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Pragma 'attribute' is not attached to any element and will be ignored",
+            "Pragma 'attribute' is not attached to any element and will be ignored",
+            "Pragma 'warning' is not attached to any element and will be ignored",
+            "Pragma 'attribute' is not attached to any element and will be ignored",
+        ]
+    );
+    assert!(diagnostics
+        .iter()
+        .all(|it| it.get_type() == &ErrNo::pragma__unattached));
+    assert!(unit.units[0].pragmas[0].is_attribute("hide"));
+    assert!(unit.units[0].variable_blocks[0].variables[0]
+        .pragmas
+        .is_empty());
+    assert!(unit.pragmas.is_empty());
+}
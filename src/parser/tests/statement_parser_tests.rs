@@ -166,6 +166,7 @@ fn inline_enum_declaration_can_be_parsed() {
         },
         initializer: None,
         address: None,
+        pragmas: vec![],
         location: SourceRange::undefined(),
     };
     let expected_ast = format!("{:#?}", &v);
@@ -1,4 +1,7 @@
-use crate::{ast::*, parser::AstStatement::LiteralInteger, test_utils::tests::parse, Diagnostic};
+use crate::{
+    ast::*, diagnostics::ErrNo, parser::AstStatement::LiteralInteger, test_utils::tests::parse,
+    Diagnostic,
+};
 use pretty_assertions::*;
 
 #[test]
@@ -48,6 +51,7 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         address: None,
+                        pragmas: vec![],
                         location: SourceRange::undefined(),
                     },
                     Variable {
@@ -58,6 +62,7 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         address: None,
+                        pragmas: vec![],
                         location: SourceRange::undefined(),
                     },
                     Variable {
@@ -68,6 +73,7 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         address: None,
+                        pragmas: vec![],
                         location: SourceRange::undefined(),
                     },
                 ),
@@ -327,6 +333,7 @@ fn subrangetype_can_be_parsed() {
         },
         initializer: None,
         address: None,
+        pragmas: vec![],
         location: (0..0).into(),
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", x).as_str());
@@ -409,6 +416,7 @@ fn pointer_type_test() {
     let diagnostic = Diagnostic::ImprovementSuggestion {
         message: "'POINTER TO' is not a standard keyword, use REF_TO instead".to_string(),
         range: SourceRange::new(42..49),
+        err_no: ErrNo::syntax__non_standard_keyword,
     };
     assert_eq!(diagnostics[0], diagnostic);
 }
@@ -468,6 +476,7 @@ fn global_pointer_declaration() {
         },
         initializer: None,
         address: None,
+        pragmas: vec![],
         location: (0..0).into(),
     };
     assert_eq!(
@@ -490,6 +499,7 @@ fn global_pointer_declaration() {
         },
         initializer: None,
         address: None,
+        pragmas: vec![],
         location: (0..0).into(),
     };
     assert_eq!(
@@ -500,6 +510,7 @@ fn global_pointer_declaration() {
     let diagnostic = Diagnostic::ImprovementSuggestion {
         message: "'POINTER TO' is not a standard keyword, use REF_TO instead".to_string(),
         range: SourceRange::new(91..98),
+        err_no: ErrNo::syntax__non_standard_keyword,
     };
     assert_eq!(diagnostics[0], diagnostic);
 }
@@ -223,3 +223,32 @@ fn struct_with_address() {
     assert_eq!(diag, vec![]);
     insta::assert_snapshot!(format!("{:?}", result));
 }
+
+#[test]
+fn retain_and_persistent_blocks_can_be_parsed() {
+    let src = "
+    VAR_GLOBAL RETAIN a : INT; END_VAR
+    VAR_GLOBAL PERSISTENT b : INT; END_VAR
+    VAR_GLOBAL PERSISTENT RETAIN c : INT; END_VAR
+    VAR_GLOBAL CONSTANT RETAIN PERSISTENT d : INT := 1; END_VAR
+    VAR_GLOBAL NON_RETAIN e : INT; END_VAR
+    ";
+    let (result, diag) = parse(src);
+
+    assert_eq!(diag, vec![]);
+    let flags = result
+        .global_vars
+        .iter()
+        .map(|it| (it.constant, it.retain, it.persistent))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        flags,
+        vec![
+            (false, true, false),
+            (false, false, true),
+            (false, true, true),
+            (true, true, true),
+            (false, false, false),
+        ]
+    );
+}
@@ -17,8 +17,9 @@ use crate::{
         flatten_expression_list, AstId, AstStatement, CompilationUnit, DataType,
         DataTypeDeclaration, Operator, Pou, TypeNature, UserTypeDeclaration, Variable,
     },
-    builtins::{self, BuiltIn},
+    builtins::BuiltIn,
     index::{Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    lexer::IdProvider,
     typesystem::{
         self, get_bigger_type, DataTypeInformation, StringEncoding, BOOL_TYPE, BYTE_TYPE,
         DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, DWORD_TYPE, LINT_TYPE, REAL_TYPE,
@@ -153,6 +154,8 @@ pub enum StatementAnnotation {
     Type { type_name: String },
     /// a reference to a program call or reference (e.g. `PLC_PRG`)
     Program { qualified_name: String },
+    /// a reference to a namespace qualifying a POU or global variable (e.g. `Vendor` in `Vendor.gAxis`)
+    Namespace { name: String },
 }
 
 impl StatementAnnotation {
@@ -176,9 +179,11 @@ impl From<&PouIndexEntry> for StatementAnnotation {
             PouIndexEntry::Program { name, .. } => StatementAnnotation::Program {
                 qualified_name: name.to_string(),
             },
-            PouIndexEntry::FunctionBlock { name, .. } => StatementAnnotation::Type {
-                type_name: name.to_string(),
-            },
+            PouIndexEntry::FunctionBlock { name, .. } | PouIndexEntry::Interface { name, .. } => {
+                StatementAnnotation::Type {
+                    type_name: name.to_string(),
+                }
+            }
             PouIndexEntry::Function {
                 name, return_type, ..
             } => StatementAnnotation::Function {
@@ -213,6 +218,7 @@ pub fn get_type_for_annotation<'a>(
         StatementAnnotation::Function { .. } => None,
         StatementAnnotation::Type { .. } => None,
         StatementAnnotation::Program { .. } => None,
+        StatementAnnotation::Namespace { .. } => None,
     }
     .and_then(|type_name| index.get_type(type_name).ok())
 }
@@ -290,43 +296,60 @@ pub trait AnnotationMap {
     }
 }
 
+/// the annotations of the ast, extended by the annotations of the statements synthesized by the
+/// code generator (e.g. the operands of a range check)
 pub struct AstAnnotations {
     annotation_map: AnnotationMapImpl,
     bool_id: AstId,
+    dint_id: AstId,
 
     bool_annotation: StatementAnnotation,
+    dint_annotation: StatementAnnotation,
 }
 
 impl AnnotationMap for AstAnnotations {
     fn get(&self, s: &AstStatement) -> Option<&StatementAnnotation> {
-        if s.get_id() == self.bool_id {
-            Some(&self.bool_annotation)
-        } else {
-            self.annotation_map.get(s)
-        }
+        self.get_synthetic_annotation(s)
+            .or_else(|| self.annotation_map.get(s))
     }
 
     fn get_hint(&self, s: &AstStatement) -> Option<&StatementAnnotation> {
-        if s.get_id() == self.bool_id {
-            Some(&self.bool_annotation)
-        } else {
-            self.annotation_map.get_hint(s)
-        }
+        self.get_synthetic_annotation(s)
+            .or_else(|| self.annotation_map.get_hint(s))
     }
 }
 
 impl AstAnnotations {
-    pub fn new(annotation_map: AnnotationMapImpl, bool_id: AstId) -> Self {
+    pub fn new(annotation_map: AnnotationMapImpl, id_provider: &mut IdProvider) -> Self {
         AstAnnotations {
             annotation_map,
-            bool_id,
+            bool_id: id_provider.next_id(),
+            dint_id: id_provider.next_id(),
             bool_annotation: StatementAnnotation::value(BOOL_TYPE),
+            dint_annotation: StatementAnnotation::value(DINT_TYPE),
         }
     }
 
+    /// returns the id of synthesized statements of type BOOL
     pub fn get_bool_id(&self) -> AstId {
         self.bool_id
     }
+
+    /// returns the id of synthesized statements of type DINT
+    pub fn get_dint_id(&self) -> AstId {
+        self.dint_id
+    }
+
+    fn get_synthetic_annotation(&self, s: &AstStatement) -> Option<&StatementAnnotation> {
+        let id = s.get_id();
+        if id == self.bool_id {
+            Some(&self.bool_annotation)
+        } else if id == self.dint_id {
+            Some(&self.dint_annotation)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Default, Debug)]
@@ -448,7 +471,12 @@ impl<'i> TypeAnnotator<'i> {
         };
 
         for global_variable in unit.global_vars.iter().flat_map(|it| it.variables.iter()) {
-            visitor.visit_variable(ctx, global_variable);
+            //a variable declared in a NAMESPACE resolves its initializer in the namespace
+            if index.get_scopes(&global_variable.name).is_empty() {
+                visitor.visit_variable(ctx, global_variable);
+            } else {
+                visitor.visit_variable(&ctx.with_pou(&global_variable.name), global_variable);
+            }
         }
 
         for pou in &unit.units {
@@ -733,7 +761,8 @@ impl<'i> TypeAnnotator<'i> {
                 bounds: Some(bounds),
                 ..
             } => {
-                if let Some(expected_type) = self.index.find_effective_type(referenced_type) {
+                let referenced_type = self.index.resolve_name(ctx.pou, referenced_type);
+                if let Some(expected_type) = self.index.find_effective_type(&referenced_type) {
                     self.visit_statement(ctx, bounds);
                     self.update_expected_types(expected_type, bounds);
                 }
@@ -1041,13 +1070,9 @@ impl<'i> TypeAnnotator<'i> {
                         .find_member(qualifier, name)
                         // 2nd try an enum-element qualifier#name
                         .or_else(|| self.index.find_enum_element(qualifier, name.as_str()))
-                        // 3rd try - look for a method qualifier.name
+                        // 3rd try - look for a (possibly inherited) method qualifier.name
                         .map_or_else(
-                            || {
-                                self.index
-                                    .find_pou(format!("{}.{}", qualifier, name).as_str())
-                                    .map(|it| it.into())
-                            },
+                            || self.index.find_method(qualifier, name).map(|it| it.into()),
                             |v| Some(to_variable_annotation(v, self.index, ctx.constant)),
                         )
                 } else {
@@ -1085,12 +1110,16 @@ impl<'i> TypeAnnotator<'i> {
                                 .and_then(|pou_name| self.index.find_pou(pou_name))
                                 .and_then(|it| {
                                     self.index
-                                        .find_pou(
-                                            format!("{}.{}", it.get_container(), name).as_str(),
-                                        )
+                                        .find_method(it.get_container(), name)
                                         .map(Into::into)
                                 })
                         })
+                        .or_else(|| {
+                            // ... then try the POUs and global variables of the current namespaces
+                            self.index
+                                .find_qualified_name(ctx.pou, name)
+                                .and_then(|it| self.find_global_symbol(ctx, it))
+                        })
                         .or_else(|| {
                             // ... then try if we find a global-pou with that name (maybe it's a call to a function or program?)
                             {
@@ -1110,9 +1139,13 @@ impl<'i> TypeAnnotator<'i> {
                 }
             }
             AstStatement::QualifiedReference { elements, .. } => {
+                //the leading elements may name a POU or global variable declared in a NAMESPACE
+                let resolved_elements = self.visit_namespace_prefix(ctx, elements);
                 let mut ctx = ctx.clone();
-                for s in elements.iter() {
-                    self.visit_statement(&ctx, s);
+                for (i, s) in elements.iter().enumerate() {
+                    if i >= resolved_elements {
+                        self.visit_statement(&ctx, s);
+                    }
 
                     let (qualifier, constant) = self
                         .annotation_map
@@ -1131,6 +1164,7 @@ impl<'i> TypeAnnotator<'i> {
                             StatementAnnotation::Program { qualified_name } => {
                                 (qualified_name.as_str(), false)
                             }
+                            StatementAnnotation::Namespace { .. } => (VOID_TYPE, false),
                         })
                         .unwrap_or_else(|| (VOID_TYPE, false));
                     let mut new_ctx = ctx.with_qualifier(qualifier.to_string());
@@ -1180,7 +1214,8 @@ impl<'i> TypeAnnotator<'i> {
                 target, type_name, ..
             } => {
                 //see if this type really exists
-                let data_type = self.index.find_effective_type_info(type_name);
+                let type_name = self.index.resolve_name(ctx.pou, type_name);
+                let data_type = self.index.find_effective_type_info(&type_name);
                 let statement_to_annotation =
                     if let Some(DataTypeInformation::Enum { name, .. }) = data_type {
                         //enum cast
@@ -1212,6 +1247,71 @@ impl<'i> TypeAnnotator<'i> {
         }
     }
 
+    /// returns the annotation of the POU or global variable with the given qualified name
+    fn find_global_symbol(
+        &self,
+        ctx: &VisitorContext,
+        qualified_name: &str,
+    ) -> Option<StatementAnnotation> {
+        self.index
+            .find_pou(qualified_name)
+            .map(Into::into)
+            .or_else(|| {
+                self.index
+                    .find_global_variable(qualified_name)
+                    .map(|v| to_variable_annotation(v, self.index, ctx.constant))
+            })
+    }
+
+    /// annotates the leading elements of a qualified reference that name a POU or global
+    /// variable declared in a NAMESPACE (e.g. `Vendor.Motion.gAxis`). The namespace's elements
+    /// are annotated as `Namespace`, the last one as the referenced element.
+    /// Returns the number of annotated elements, 0 if the reference does not start with a namespace.
+    fn visit_namespace_prefix(&mut self, ctx: &VisitorContext, elements: &[AstStatement]) -> usize {
+        if ctx.qualifier.is_some() {
+            return 0;
+        }
+        let names = elements
+            .iter()
+            .map_while(|it| match it {
+                AstStatement::Reference { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        //a local variable hides a namespace with the same name
+        if names.len() < 2
+            || ctx
+                .pou
+                .and_then(|pou| self.index.find_member(pou, names[0]))
+                .is_some()
+        {
+            return 0;
+        }
+
+        let index = self.index;
+        for len in (2..=names.len()).rev() {
+            let name = names[..len].join(".");
+            let qualified_name = index
+                .find_qualified_name(ctx.pou, &name)
+                .or_else(|| index.find_declared_name(&name));
+            if let Some((qualified_name, annotation)) =
+                qualified_name.and_then(|it| self.find_global_symbol(ctx, it).map(|a| (it, a)))
+            {
+                //the namespace may be given relative to one of the current namespaces
+                let segments = qualified_name.split('.').collect::<Vec<_>>();
+                let offset = segments.len() - len;
+                for (i, element) in elements[..len - 1].iter().enumerate() {
+                    let namespace = segments[..=offset + i].join(".");
+                    self.annotation_map
+                        .annotate(element, StatementAnnotation::Namespace { name: namespace });
+                }
+                self.annotation_map.annotate(&elements[len - 1], annotation);
+                return len;
+            }
+        }
+        0
+    }
+
     fn visit_call_statement(&mut self, statement: &AstStatement, ctx: &VisitorContext) {
         let (operator, parameters_stmt) = if let AstStatement::CallStatement {
             operator,
@@ -1232,8 +1332,10 @@ impl<'i> TypeAnnotator<'i> {
         } else {
             vec![]
         };
-        if let Some(anntation) =
-            builtins::get_builtin(&operator_qualifier).and_then(BuiltIn::get_annotation)
+        if let Some(anntation) = self
+            .index
+            .get_builtin_function(&operator_qualifier)
+            .and_then(BuiltIn::get_annotation)
         {
             anntation(self, operator, parameters_stmt, ctx).unwrap();
         } else {
@@ -1306,11 +1408,19 @@ impl<'i> TypeAnnotator<'i> {
         if let Some(StatementAnnotation::Function { return_type, .. }) =
             self.annotation_map.get(operator)
         {
-            if let Some(return_type) = self.index.find_effective_type(return_type) {
-                self.annotation_map.annotate(
-                    statement,
-                    StatementAnnotation::value(return_type.get_name()),
-                );
+            //builtins may return types that were registered during the annotation (e.g. sized strings)
+            let return_type = self
+                .index
+                .find_effective_type(return_type)
+                .or_else(|| {
+                    self.annotation_map
+                        .new_index
+                        .find_effective_type(return_type)
+                })
+                .map(|it| it.get_name().to_string());
+            if let Some(return_type) = return_type {
+                self.annotation_map
+                    .annotate(statement, StatementAnnotation::new_value(return_type));
             }
         }
     }
@@ -1429,7 +1539,7 @@ impl<'i> TypeAnnotator<'i> {
 }
 
 /// adds a string-type to the given index and returns it's name
-fn register_string_type(index: &mut Index, is_wide: bool, len: usize) -> String {
+pub(crate) fn register_string_type(index: &mut Index, is_wide: bool, len: usize) -> String {
     let new_type_name = if is_wide {
         format!("__WSTRING_{}", len)
     } else {
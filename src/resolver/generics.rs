@@ -2,7 +2,6 @@ use std::collections::HashMap;
 
 use crate::{
     ast::{self, AstStatement, GenericBinding, LinkageType, TypeNature},
-    builtins,
     index::{Index, PouIndexEntry, VariableIndexEntry},
     resolver::AnnotationMap,
     typesystem::{self, DataType, DataTypeInformation},
@@ -76,7 +75,9 @@ impl<'i> TypeAnnotator<'i> {
                     let cloned_return_type = return_type.clone(); //borrow checker will not allow to use return_type below :-(
 
                     //Find the generic resolver
-                    let generic_name_resolver = builtins::get_builtin(qualified_name)
+                    let generic_name_resolver = self
+                        .index
+                        .get_builtin_function(qualified_name)
                         .map(|it| it.get_generic_name_resolver())
                         .unwrap_or_else(|| generic_name_resolver);
                     //Figure out the new name for the call
@@ -144,6 +145,7 @@ impl<'i> TypeAnnotator<'i> {
                     &[],
                     LinkageType::External, //it has to be external, we should have already found this in the global index if it was internal
                     generic_function.is_variadic(),
+                    generic_function.get_location().clone(),
                 ));
 
             // register the member-variables (interface) of the new function
@@ -3131,3 +3131,188 @@ fn and_statement_of_dints_results_in_dint() {
         None
     );
 }
+
+#[test]
+fn inherited_members_and_methods_get_annotated() {
+    let (unit, mut index) = index(
+        "
+        FUNCTION_BLOCK BaseDrive
+            VAR speed : INT; END_VAR
+            METHOD start : BOOL END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Motor EXTENDS BaseDrive
+            VAR torque : DINT; END_VAR
+            speed;
+            torque;
+            start();
+            SUPER^.start();
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR m : Motor; END_VAR
+            m.speed;
+            m.start();
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &mut index);
+    let fb_body = &unit.implementations[2].statements;
+    assert_eq!(
+        Some(&StatementAnnotation::Variable {
+            qualified_name: "BaseDrive.speed".into(),
+            resulting_type: "INT".into(),
+            constant: false,
+            is_auto_deref: false,
+            variable_type: VariableType::Local,
+        }),
+        annotations.get(&fb_body[0])
+    );
+    assert_type_and_hint!(&annotations, &index, &fb_body[1], "DINT", None);
+    assert_type_and_hint!(&annotations, &index, &fb_body[2], "BOOL", None);
+    assert_type_and_hint!(&annotations, &index, &fb_body[3], "BOOL", None);
+
+    let prg_body = &unit.implementations[3].statements;
+    assert_type_and_hint!(&annotations, &index, &prg_body[0], "INT", None);
+    assert_type_and_hint!(&annotations, &index, &prg_body[1], "BOOL", None);
+    if let AstStatement::CallStatement { operator, .. } = &prg_body[1] {
+        assert_eq!(
+            Some("BaseDrive.start"),
+            annotations.get_qualified_name(operator)
+        );
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn interface_method_calls_get_annotated() {
+    let (unit, mut index) = index(
+        "
+        INTERFACE IShape
+            METHOD area : REAL
+                VAR_INPUT factor : INT; END_VAR
+            END_METHOD
+        END_INTERFACE
+
+        PROGRAM prg
+            VAR shape : IShape; END_VAR
+            shape;
+            shape.area(2);
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &mut index);
+    let prg_body = &unit.implementations[0].statements;
+    assert_type_and_hint!(&annotations, &index, &prg_body[0], "IShape", None);
+    assert_type_and_hint!(&annotations, &index, &prg_body[1], "REAL", None);
+    if let AstStatement::CallStatement {
+        operator,
+        parameters,
+        ..
+    } = &prg_body[1]
+    {
+        assert_eq!(
+            Some("IShape.area"),
+            annotations.get_qualified_name(operator)
+        );
+        assert_type_and_hint!(
+            &annotations,
+            &index,
+            parameters.as_ref().unwrap(),
+            "DINT",
+            Some("INT")
+        );
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn namespaced_references_get_annotated() {
+    let (unit, mut index) = index(
+        "
+        NAMESPACE Lib
+            VAR_GLOBAL gOffset : INT; END_VAR
+            FUNCTION Add : DINT
+                VAR_INPUT a : DINT; END_VAR
+                Add := a + gOffset;
+            END_FUNCTION
+        END_NAMESPACE
+
+        PROGRAM prg
+            VAR x : DINT; END_VAR
+            Lib.gOffset;
+            x := Lib.Add(2);
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &mut index);
+    let variable_name = |it: &AstStatement| match annotations.get(it) {
+        Some(StatementAnnotation::Variable { qualified_name, .. }) => Some(qualified_name.clone()),
+        _ => None,
+    };
+
+    //unqualified names inside the namespace refer to the namespace's symbols
+    if let AstStatement::Assignment { left, right, .. } = &unit.implementations[0].statements[0] {
+        assert_eq!(Some("Lib.Add.Add".into()), variable_name(left));
+        if let AstStatement::BinaryExpression { right, .. } = right.as_ref() {
+            assert_eq!(Some("Lib.gOffset".into()), variable_name(right));
+        } else {
+            unreachable!()
+        }
+    } else {
+        unreachable!()
+    }
+
+    //qualified names refer to the namespace's symbols
+    let prg_body = &unit.implementations[1].statements;
+    assert_eq!(Some("Lib.gOffset".into()), variable_name(&prg_body[0]));
+    assert_type_and_hint!(&annotations, &index, &prg_body[0], "INT", None);
+    if let AstStatement::Assignment { right, .. } = &prg_body[1] {
+        assert_type_and_hint!(&annotations, &index, right.as_ref(), "DINT", Some("DINT"));
+        if let AstStatement::CallStatement { operator, .. } = right.as_ref() {
+            assert_eq!(Some("Lib.Add"), annotations.get_qualified_name(operator));
+        } else {
+            unreachable!()
+        }
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn namespaces_of_qualified_references_get_annotated() {
+    let (unit, mut index) = index(
+        "
+        NAMESPACE Vendor
+            NAMESPACE Motion
+                VAR_GLOBAL gSpeed : INT; END_VAR
+            END_NAMESPACE
+
+            PROGRAM prg
+                Motion.gSpeed;
+            END_PROGRAM
+        END_NAMESPACE
+        ",
+    );
+
+    let annotations = annotate(&unit, &mut index);
+    //the namespace is given relative to the enclosing one
+    if let AstStatement::QualifiedReference { elements, .. } =
+        &unit.implementations[0].statements[0]
+    {
+        assert_eq!(
+            Some(&StatementAnnotation::Namespace {
+                name: "Vendor.Motion".into()
+            }),
+            annotations.get(&elements[0])
+        );
+        assert_type_and_hint!(&annotations, &index, &elements[1], "INT", None);
+    } else {
+        unreachable!()
+    }
+}
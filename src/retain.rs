@@ -0,0 +1,234 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! Retained variables (`VAR_GLOBAL RETAIN`, `VAR PERSISTENT`, ...).
+//!
+//! Retained global variables are placed in the linker section of their kind (`.retain` or
+//! `.persistent`). The members of a program are part of its instance, so a program with
+//! retained members has its whole instance placed in the section. All members of such a
+//! program have to be retained the same way (see `E043`).
+//!
+//! The runtime persists the retained variables through the generated entry points:
+//! `__retain_size` returns the size of the retain image, `__retain_save` copies every
+//! retained variable into an image of that size and `__retain_restore` copies them back.
+//! The image contains the variables in the order of the retain map (`--retain-map`), which
+//! lists the offset, size and type of every variable in the image. The entry points are
+//! generated by the project that is linked last, they include the retained variables of the
+//! included files (e.g. of the dependencies of a workspace project).
+use std::{fs, path::Path};
+
+use inkwell::{module::Module, targets::TargetData, types::AnyTypeEnum};
+use serde::Serialize;
+
+use crate::{
+    ast::SourceRange,
+    diagnostics::Diagnostic,
+    index::{Index, PouIndexEntry, RetainKind, VariableIndexEntry},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// returns the size of the retain image in bytes: `uint64_t __retain_size(void)`
+pub const SIZE_FUNCTION: &str = "__retain_size";
+/// copies the retained variables into the given image: `void __retain_save(uint8_t* image)`
+pub const SAVE_FUNCTION: &str = "__retain_save";
+/// copies the retained variables back from the given image:
+/// `void __retain_restore(const uint8_t* image)`
+pub const RESTORE_FUNCTION: &str = "__retain_restore";
+
+/// a retained variable, either a global variable or a member of a program instance
+pub struct RetainedVariable<'i> {
+    /// the global variable that is (or contains) the retained variable
+    pub global: &'i VariableIndexEntry,
+    /// the retained member of the program instance with its index in the instance struct
+    pub member: Option<(&'i VariableIndexEntry, u32)>,
+}
+
+impl RetainedVariable<'_> {
+    /// returns the name of the symbol containing the variable
+    pub fn get_symbol(&self) -> String {
+        crate::index::get_symbol_name(self.global.get_name())
+    }
+
+    /// returns the qualified name of the variable (e.g. `PLC_PRG.x`)
+    pub fn get_qualified_name(&self) -> &str {
+        self.member
+            .map_or(self.global, |(member, _)| member)
+            .get_qualified_name()
+    }
+
+    pub fn get_type_name(&self) -> &str {
+        self.member
+            .map_or(self.global, |(member, _)| member)
+            .get_type_name()
+    }
+
+    pub fn get_kind(&self) -> RetainKind {
+        self.member
+            .map_or(self.global, |(member, _)| member)
+            .get_retain()
+            .unwrap_or(RetainKind::Retain)
+    }
+}
+
+/// returns the retained variables of the compiled sources and of the included files in the
+/// order of the retain image, sorted by their qualified names
+pub fn collect_retained_variables(index: &Index) -> Vec<RetainedVariable> {
+    let mut variables = index
+        .get_globals()
+        .values()
+        .filter(|it| !it.is_constant() && it.get_retain().is_some())
+        .map(|global| RetainedVariable {
+            global,
+            member: None,
+        })
+        .collect::<Vec<_>>();
+    for pou in index.get_pous().values() {
+        if let PouIndexEntry::Program {
+            name,
+            instance_variable,
+            ..
+        } = pou
+        {
+            //temporary variables are not part of the instance struct
+            let members = index
+                .get_container_members(name)
+                .into_iter()
+                .filter(|it| !it.is_temp() && !it.is_return())
+                .zip(0..);
+            for (member, member_index) in members {
+                if member.get_retain().is_some() && !member.is_constant() {
+                    variables.push(RetainedVariable {
+                        global: instance_variable,
+                        member: Some((member, member_index)),
+                    });
+                }
+            }
+        }
+    }
+    variables.sort_by_cached_key(|it| it.get_qualified_name().to_lowercase());
+    variables
+}
+
+/// returns the linker section of the given global variable or program instance, `None` if
+/// it contains no retained variables
+pub fn get_section(index: &Index, variable: &VariableIndexEntry) -> Option<&'static str> {
+    let kind = match index.find_pou(variable.get_qualified_name()) {
+        Some(PouIndexEntry::Program {
+            instance_variable, ..
+        }) if instance_variable == variable => index
+            .get_container_members(variable.get_type_name())
+            .into_iter()
+            .filter(|it| !it.is_temp() && !it.is_constant())
+            .filter_map(VariableIndexEntry::get_retain)
+            .max_by_key(|it| *it == RetainKind::Persistent),
+        _ => variable.get_retain().filter(|_| !variable.is_constant()),
+    };
+    kind.as_ref().map(RetainKind::get_section)
+}
+
+/// an entry of the retain map
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RetainMapEntry {
+    /// the qualified name of the variable
+    pub name: String,
+    /// the symbol containing the variable
+    pub symbol: String,
+    /// the offset of the variable in its symbol
+    pub symbol_offset: u64,
+    /// the offset of the variable in the retain image
+    pub offset: u64,
+    pub size: u64,
+    /// the variable's datatype
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub kind: RetainKind,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RetainMap {
+    /// the size of the retain image, as returned by `__retain_size`
+    pub size: u64,
+    pub variables: Vec<RetainMapEntry>,
+}
+
+/// creates the retain map of the given index, the sizes and offsets are taken from the types
+/// of the retained variables' symbols in the given module
+pub fn create_retain_map(index: &Index, module: Option<&Module>) -> Result<RetainMap, Diagnostic> {
+    let mut map = RetainMap {
+        size: 0,
+        variables: vec![],
+    };
+    let variables = collect_retained_variables(index);
+    let module = match module {
+        Some(module) => module,
+        None if variables.is_empty() => return Ok(map),
+        None => {
+            return Err(Diagnostic::codegen_error(
+                "Cannot create the retain map without the generated retained variables",
+                SourceRange::undefined(),
+            ))
+        }
+    };
+    let target_data = TargetData::create(&module.get_data_layout().as_str().to_string_lossy());
+    for variable in variables {
+        let global = module.get_global(&variable.get_symbol()).ok_or_else(|| {
+            Diagnostic::codegen_error(
+                &format!(
+                    "Cannot find the symbol of {}",
+                    variable.get_qualified_name()
+                ),
+                variable.global.source_location.clone(),
+            )
+        })?;
+        let symbol_type = global.as_pointer_value().get_type().get_element_type();
+        let (symbol_offset, size) = match (variable.member, symbol_type) {
+            (None, _) => (0, target_data.get_abi_size(&symbol_type)),
+            (Some((member, member_index)), AnyTypeEnum::StructType(instance_type)) => {
+                let member_type = instance_type
+                    .get_field_type_at_index(member_index)
+                    .ok_or_else(|| {
+                        Diagnostic::codegen_error(
+                            &format!("Cannot find the member {}", member.get_qualified_name()),
+                            member.source_location.clone(),
+                        )
+                    })?;
+                (
+                    target_data
+                        .offset_of_element(&instance_type, member_index)
+                        .unwrap_or_default(),
+                    target_data.get_abi_size(&member_type),
+                )
+            }
+            (Some((member, _)), _) => {
+                return Err(Diagnostic::codegen_error(
+                    &format!("{} is not a struct", variable.get_symbol()),
+                    member.source_location.clone(),
+                ))
+            }
+        };
+        map.variables.push(RetainMapEntry {
+            name: variable.get_qualified_name().to_string(),
+            symbol: variable.get_symbol(),
+            symbol_offset,
+            offset: map.size,
+            size,
+            type_name: variable.get_type_name().to_string(),
+            kind: variable.get_kind(),
+        });
+        map.size += size;
+    }
+    Ok(map)
+}
+
+/// writes the retain map of the given index as json to the given location
+pub fn write_retain_map(
+    index: &Index,
+    module: Option<&Module>,
+    location: &Path,
+) -> Result<(), Diagnostic> {
+    let map = create_retain_map(index, module)?;
+    let json = serde_json::to_string_pretty(&map)
+        .map_err(|err| Diagnostic::codegen_error(&err.to_string(), SourceRange::undefined()))?;
+    fs::write(location, json)
+        .map_err(|err| Diagnostic::io_write_error(&location.to_string_lossy(), &err.to_string()))
+}
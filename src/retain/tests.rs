@@ -0,0 +1,223 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use inkwell::context::Context;
+
+use crate::{
+    diagnostics::Diagnostician,
+    index::RetainKind,
+    retain::{create_retain_map, RetainMapEntry},
+    SourceCode,
+};
+
+const SOURCE: &str = "
+    VAR_GLOBAL RETAIN
+        counter : DINT;
+    END_VAR
+    VAR_GLOBAL PERSISTENT
+        calibration : ARRAY[0..3] OF LREAL;
+    END_VAR
+    VAR_GLOBAL
+        volatile : INT;
+    END_VAR
+
+    PROGRAM mainProg
+    VAR_TEMP
+        t : DINT;
+    END_VAR
+    VAR RETAIN
+        flag : BOOL;
+        total : DINT;
+    END_VAR
+    END_PROGRAM
+
+    PROGRAM settings
+    VAR PERSISTENT
+        limit : LREAL;
+    END_VAR
+    END_PROGRAM
+
+    PROGRAM other
+    VAR
+        x : INT;
+    END_VAR
+    END_PROGRAM
+    ";
+
+#[test]
+fn retained_variables_are_placed_in_their_sections() {
+    let context = Context::create();
+    let (_, codegen) = compile(&context);
+    let ir = codegen.module.print_to_string().to_string();
+
+    assert!(
+        ir.contains("@counter = global i32 0, section \".retain\""),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains("@calibration = global [4 x double] zeroinitializer, section \".persistent\""),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("@volatile = global i16 0\n"), "{}", ir);
+    //the instance of a program with retained members is placed as a whole
+    assert!(
+        ir.contains(
+            "@mainProg_instance = global %mainProg_interface zeroinitializer, section \".retain\""
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains(
+            "@settings_instance = global %settings_interface zeroinitializer, section \".persistent\""
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains("@other_instance = global %other_interface zeroinitializer\n"),
+        "{}",
+        ir
+    );
+
+    assert!(ir.contains("define i64 @__retain_size()"), "{}", ir);
+    assert!(ir.contains("define void @__retain_save(i8* %0)"), "{}", ir);
+    assert!(
+        ir.contains("define void @__retain_restore(i8* %0)"),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn retain_map_lists_the_variables_in_the_retain_image() {
+    let context = Context::create();
+    let (index, codegen) = compile(&context);
+    let map = create_retain_map(&index, Some(&codegen.module)).unwrap();
+
+    let entry = |name: &str, symbol: &str, symbol_offset, offset, size, type_name: &str, kind| {
+        RetainMapEntry {
+            name: name.into(),
+            symbol: symbol.into(),
+            symbol_offset,
+            offset,
+            size,
+            type_name: type_name.into(),
+            kind,
+        }
+    };
+    assert_eq!(
+        map.variables,
+        vec![
+            entry(
+                "calibration",
+                "calibration",
+                0,
+                0,
+                32,
+                "__global_calibration",
+                RetainKind::Persistent
+            ),
+            entry("counter", "counter", 0, 32, 4, "DINT", RetainKind::Retain),
+            entry(
+                "mainProg.flag",
+                "mainProg_instance",
+                0,
+                36,
+                1,
+                "BOOL",
+                RetainKind::Retain
+            ),
+            entry(
+                "mainProg.total",
+                "mainProg_instance",
+                4,
+                37,
+                4,
+                "DINT",
+                RetainKind::Retain
+            ),
+            entry(
+                "settings.limit",
+                "settings_instance",
+                0,
+                41,
+                8,
+                "LREAL",
+                RetainKind::Persistent
+            ),
+        ]
+    );
+    assert_eq!(map.size, 49);
+}
+
+#[test]
+fn sources_without_retained_variables_have_an_empty_retain_map() {
+    let context = Context::create();
+    let (index, codegen) = crate::compile_module(
+        &context,
+        vec![SourceCode {
+            path: "main.st".into(),
+            source: "VAR_GLOBAL x : INT; END_VAR PROGRAM prg VAR y : INT; END_VAR END_PROGRAM"
+                .into(),
+        }],
+        vec![],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap();
+
+    assert!(codegen.module.get_function("__retain_save").is_none());
+    let map = create_retain_map(&index, None).unwrap();
+    assert_eq!(map.size, 0);
+    assert_eq!(map.variables, vec![]);
+}
+
+fn compile(context: &Context) -> (crate::index::Index, crate::codegen::CodeGen) {
+    crate::compile_module(
+        context,
+        vec![SourceCode {
+            path: "main.st".into(),
+            source: SOURCE.into(),
+        }],
+        vec![],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn the_retained_variables_of_included_files_are_part_of_the_retain_image() {
+    let context = Context::create();
+    let (index, codegen) = crate::compile_module(
+        &context,
+        vec![SourceCode {
+            path: "main.st".into(),
+            source: "VAR_GLOBAL RETAIN counter : DINT; END_VAR".into(),
+        }],
+        vec![SourceCode {
+            path: "library.st".into(),
+            source: "PROGRAM machine VAR RETAIN cycles : LINT; END_VAR END_PROGRAM".into(),
+        }],
+        None,
+        Diagnostician::default(),
+    )
+    .unwrap();
+
+    //the library is compiled without the entry points, they are generated by its user
+    let ir = codegen.module.print_to_string().to_string();
+    assert!(
+        ir.contains("@machine_instance = external global %machine_interface"),
+        "{}",
+        ir
+    );
+    let map = create_retain_map(&index, Some(&codegen.module)).unwrap();
+    let names = map
+        .variables
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["counter", "machine.cycles"]);
+    assert_eq!(map.size, 12);
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+//! The standard function block library bundled with the compiler.
+//!
+//! The library is written in ST and compiled together with the user's sources. It is only
+//! added if one of its POUs is referenced, POUs and types declared by the user take precedence
+//! over the library's. This way an integrator can replace the time source of the timers by
+//! declaring an own `FUNCTION __GET_TIME : TIME`.
+use std::collections::HashSet;
+
+use crate::{
+    ast::{CompilationUnit, DataType, DataTypeDeclaration, LinkageType, NewLines, VariableBlock},
+    diagnostics::Diagnostician,
+    index::Index,
+    lexer::{self, IdProvider},
+    parser,
+    typesystem::DataTypeInformation,
+    Units,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// the directory of the library's (virtual) file names
+pub const LOCATION: &str = "<stdlib>";
+
+/// the library's sources with the (virtual) file names used in diagnostics
+const SOURCES: &[(&str, &str)] = &[
+    (
+        "<stdlib>/timers.st",
+        include_str!("../libs/stdlib/timers.st"),
+    ),
+    (
+        "<stdlib>/counters.st",
+        include_str!("../libs/stdlib/counters.st"),
+    ),
+    ("<stdlib>/edges.st", include_str!("../libs/stdlib/edges.st")),
+    (
+        "<stdlib>/bistables.st",
+        include_str!("../libs/stdlib/bistables.st"),
+    ),
+];
+
+/// parses the standard library if any of its POUs is referenced by the given units or by the
+/// included library interfaces. POUs and types already declared by them are removed
+/// from the library.
+/// The `defines` select the definitions for the compiled targets (e.g. `__TARGET_DARWIN`).
+/// The dependencies of a workspace project only declare the library (`LinkageType::External`),
+/// it is defined once by the project linking them.
+pub fn parse_standard_library(
+    units: &Units,
+    interfaces: &[Index],
+    defines: &[String],
+    linkage: LinkageType,
+    id_provider: &IdProvider,
+    diagnostician: &mut Diagnostician,
+) -> Units {
+    let library = SOURCES
+        .iter()
+        .map(|(location, source)| {
+            let file_id = diagnostician.register_file(location.to_string(), source.to_string());
+            let (mut unit, diagnostics) = parser::parse(
+                lexer::lex_file(source, file_id, id_provider.clone(), defines),
+                linkage,
+            );
+            unit.file_name = location.to_string();
+            unit.new_lines = NewLines::build(source);
+            (file_id, diagnostics, unit)
+        })
+        .collect::<Vec<_>>();
+
+    let library_pous = library
+        .iter()
+        .flat_map(|(_, _, unit)| unit.units.iter())
+        .map(|pou| pou.name.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut referenced = HashSet::new();
+    let mut declared = HashSet::new();
+    for (_, _, unit) in units {
+        collect_referenced_types(unit, &mut referenced);
+        collect_declared_names(unit, &mut declared);
+    }
+    for interface in interfaces {
+        collect_interface_references(interface, &mut referenced);
+        let pous = interface.get_pous().values().map(|pou| pou.get_name());
+        let types = interface.get_types().values().map(|it| it.get_name());
+        declared.extend(pous.chain(types).map(str::to_lowercase));
+    }
+    if library_pous.is_disjoint(&referenced) {
+        return vec![];
+    }
+
+    library
+        .into_iter()
+        .map(|(file_id, diagnostics, mut unit)| {
+            let is_library_owned = |name: &str| !declared.contains(&name.to_lowercase());
+            unit.units.retain(|pou| is_library_owned(&pou.name));
+            unit.implementations
+                .retain(|implementation| is_library_owned(&implementation.type_name));
+            unit.types.retain(|user_type| {
+                user_type
+                    .data_type
+                    .get_name()
+                    .map_or(true, is_library_owned)
+            });
+            (file_id, diagnostics, unit)
+        })
+        .collect()
+}
+
+/// collects the lowercase names of all POUs and types declared in the given unit
+fn collect_declared_names(unit: &CompilationUnit, declared: &mut HashSet<String>) {
+    let pous = unit.units.iter().map(|pou| pou.name.as_str());
+    let types = unit
+        .types
+        .iter()
+        .filter_map(|user_type| user_type.data_type.get_name());
+    declared.extend(pous.chain(types).map(str::to_lowercase));
+}
+
+/// collects the lowercase names of all types referenced by declarations in the given unit
+fn collect_referenced_types(unit: &CompilationUnit, referenced: &mut HashSet<String>) {
+    let blocks = unit
+        .global_vars
+        .iter()
+        .chain(unit.units.iter().flat_map(|pou| pou.variable_blocks.iter()));
+    collect_from_blocks(blocks, referenced);
+
+    for pou in &unit.units {
+        if let Some(return_type) = &pou.return_type {
+            collect_from_declaration(return_type, referenced);
+        }
+        if let Some(super_class) = &pou.super_class {
+            referenced.insert(super_class.to_lowercase());
+        }
+    }
+    for user_type in &unit.types {
+        collect_from_data_type(&user_type.data_type, referenced);
+    }
+}
+
+/// collects the lowercase names of all types referenced by the declarations of the given
+/// library interface, e.g. the type of a function block's member
+fn collect_interface_references(interface: &Index, referenced: &mut HashSet<String>) {
+    let containers = interface
+        .get_pous()
+        .values()
+        .map(|pou| pou.get_name())
+        .chain(interface.get_types().values().map(|it| it.get_name()));
+    let members = containers
+        .filter_map(|container| interface.get_members(container))
+        .flat_map(|members| members.values());
+    for variable in interface.get_globals().values().chain(members) {
+        referenced.insert(variable.get_type_name().to_lowercase());
+    }
+
+    for data_type in interface.get_types().values() {
+        if let DataTypeInformation::Array {
+            inner_type_name, ..
+        }
+        | DataTypeInformation::Pointer {
+            inner_type_name, ..
+        } = data_type.get_type_information()
+        {
+            referenced.insert(inner_type_name.to_lowercase());
+        }
+    }
+    for pou in interface.get_pous().values() {
+        if let Some(super_class) = pou.get_super_class() {
+            referenced.insert(super_class.to_lowercase());
+        }
+    }
+}
+
+fn collect_from_blocks<'a>(
+    blocks: impl Iterator<Item = &'a VariableBlock>,
+    referenced: &mut HashSet<String>,
+) {
+    blocks
+        .flat_map(|block| block.variables.iter())
+        .for_each(|variable| collect_from_declaration(&variable.data_type, referenced));
+}
+
+fn collect_from_declaration(declaration: &DataTypeDeclaration, referenced: &mut HashSet<String>) {
+    match declaration {
+        DataTypeDeclaration::DataTypeReference {
+            referenced_type, ..
+        } => {
+            referenced.insert(referenced_type.to_lowercase());
+        }
+        DataTypeDeclaration::DataTypeDefinition { data_type, .. } => {
+            collect_from_data_type(data_type, referenced)
+        }
+    }
+}
+
+fn collect_from_data_type(data_type: &DataType, referenced: &mut HashSet<String>) {
+    match data_type {
+        DataType::StructType { variables, .. } => variables
+            .iter()
+            .for_each(|variable| collect_from_declaration(&variable.data_type, referenced)),
+        DataType::ArrayType {
+            referenced_type, ..
+        }
+        | DataType::PointerType {
+            referenced_type, ..
+        } => collect_from_declaration(referenced_type, referenced),
+        DataType::VarArgs {
+            referenced_type: Some(referenced_type),
+            ..
+        } => collect_from_declaration(referenced_type, referenced),
+        _ => {}
+    }
+}
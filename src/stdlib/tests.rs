@@ -0,0 +1,88 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use inkwell::context::Context;
+
+use crate::{
+    diagnostics::Diagnostician, test_utils::tests::compile_to_string, CompileOptions, SourceCode,
+    Target,
+};
+
+#[test]
+fn library_symbols_are_defined_weak() {
+    let program = SourceCode {
+        path: "main.st".into(),
+        source: "
+        PROGRAM main
+        VAR
+            delay : TON;
+        END_VAR
+            delay(IN := TRUE, PT := T#1s);
+        END_PROGRAM
+        "
+        .into(),
+    };
+    let result = compile_to_string(
+        vec![program],
+        vec![],
+        None,
+        Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+
+    //every output using the library defines it, the linker keeps one of the definitions
+    assert!(result.contains("define weak_odr void @TON("), "{}", result);
+    assert!(
+        result.contains("define weak_odr i64 @__GET_TIME("),
+        "{}",
+        result
+    );
+    assert!(result.contains("@__TON__init = weak_odr "), "{}", result);
+    assert!(result.contains("define void @main("), "{}", result);
+}
+
+#[test]
+fn windows_targets_read_the_performance_counter() {
+    let program = SourceCode {
+        path: "main.st".into(),
+        source: "
+        PROGRAM main
+        VAR
+            delay : TP;
+        END_VAR
+            delay(IN := TRUE, PT := T#1s);
+        END_PROGRAM
+        "
+        .into(),
+    };
+    let context = Context::create();
+    let (_, code_generator) = crate::compile_module_in_parallel(
+        &context,
+        vec![program],
+        vec![],
+        None,
+        &CompileOptions {
+            defines: Target::from("x86_64-pc-windows-msvc").get_defines(),
+            ..Default::default()
+        },
+        Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+    let result = code_generator.module.print_to_string().to_string();
+
+    //windows has no clock_gettime
+    assert!(
+        result.contains("declare i32 @QueryPerformanceCounter(i64*"),
+        "{}",
+        result
+    );
+    assert!(
+        result.contains("declare i32 @QueryPerformanceFrequency(i64*"),
+        "{}",
+        result
+    );
+    assert!(!result.contains("clock_gettime"), "{}", result);
+    assert!(
+        result.contains("define weak_odr i64 @__GET_TIME("),
+        "{}",
+        result
+    );
+}
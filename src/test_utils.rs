@@ -7,6 +7,7 @@ pub mod tests {
     use crate::{
         ast::{self, CompilationUnit},
         builtins,
+        codegen::CodeGenOptions,
         diagnostics::{Diagnostic, Diagnostician},
         index::{self, Index},
         lexer::{self, IdProvider},
@@ -14,7 +15,7 @@ pub mod tests {
         resolver::{
             const_evaluator::evaluate_constants, AnnotationMapImpl, AstAnnotations, TypeAnnotator,
         },
-        SourceContainer, Validator,
+        DebugLevel, SourceContainer, Validator,
     };
 
     pub fn parse(src: &str) -> (CompilationUnit, Vec<Diagnostic>) {
@@ -47,6 +48,7 @@ pub mod tests {
         );
         ast::pre_process(&mut unit, id_provider.clone());
         index.import(index::visitor::visit(&unit, id_provider));
+        index.resolve_namespaces();
         (unit, index)
     }
 
@@ -62,28 +64,65 @@ pub mod tests {
     }
 
     pub fn parse_and_validate(src: &str) -> Vec<Diagnostic> {
+        validate(src, Validator::new())
+    }
+
+    /// validates the given source like the code generated with `--bounds-check`
+    pub fn parse_and_validate_with_bounds_check(src: &str) -> Vec<Diagnostic> {
+        validate(src, Validator::with_bounds_check(true))
+    }
+
+    fn validate(src: &str, mut validator: Validator) -> Vec<Diagnostic> {
         let (unit, index) = index(src);
 
         let (mut index, ..) = evaluate_constants(index);
         let (mut annotations, _) = TypeAnnotator::visit_unit(&index, &unit);
         index.import(std::mem::take(&mut annotations.new_index));
 
-        let mut validator = Validator::new();
         validator.visit_unit(&annotations, &index, &unit);
         validator.diagnostics()
     }
 
     pub fn codegen_without_unwrap(src: &str) -> Result<String, Diagnostic> {
+        codegen_debug_without_unwrap(src, DebugLevel::None)
+    }
+
+    pub fn codegen_debug_without_unwrap(
+        src: &str,
+        debug_level: DebugLevel,
+    ) -> Result<String, Diagnostic> {
+        do_codegen(
+            src,
+            CodeGenOptions {
+                debug_level,
+                ..CodeGenOptions::default()
+            },
+        )
+    }
+
+    pub fn codegen_with_bounds_check(src: &str) -> Result<String, Diagnostic> {
+        do_codegen(
+            src,
+            CodeGenOptions {
+                bounds_check: true,
+                ..CodeGenOptions::default()
+            },
+        )
+    }
+
+    fn do_codegen(src: &str, options: CodeGenOptions) -> Result<String, Diagnostic> {
         let mut id_provider = IdProvider::default();
-        let (unit, index) = do_index(src, id_provider.clone());
+        let (mut unit, index) = do_index(src, id_provider.clone());
+        unit.file_name = "main.st".into();
+        unit.new_lines = ast::NewLines::build(src);
 
         let (mut index, ..) = evaluate_constants(index);
         let (mut annotations, literals) = TypeAnnotator::visit_unit(&index, &unit);
         index.import(std::mem::take(&mut annotations.new_index));
 
         let context = inkwell::context::Context::create();
-        let code_generator = crate::codegen::CodeGen::new(&context, "main");
-        let annotations = AstAnnotations::new(annotations, id_provider.next_id());
+        let code_generator = crate::codegen::CodeGen::new(&context, "main", options);
+        let annotations = AstAnnotations::new(annotations, &mut id_provider);
         let llvm_index = code_generator.generate_llvm_index(&annotations, literals, &index)?;
         code_generator
             .generate(&unit, &annotations, &index, &llvm_index)
@@ -94,6 +133,10 @@ pub mod tests {
         codegen_without_unwrap(src).unwrap()
     }
 
+    pub fn codegen_with_debug(src: &str) -> String {
+        codegen_debug_without_unwrap(src, DebugLevel::Full).unwrap()
+    }
+
     pub fn generate_with_empty_program(src: &str) -> String {
         let source = format!("{} {}", "PROGRAM main END_PROGRAM", src);
         codegen(source.as_str())
@@ -1,4 +1,8 @@
-use crate::{diagnostics::Diagnostician, test_utils::tests::compile_to_string, SourceCode};
+use inkwell::context::Context;
+
+use crate::{
+    diagnostics::Diagnostician, test_utils::tests::compile_to_string, CompileOptions, SourceCode,
+};
 
 #[test]
 fn multiple_source_files_generated() {
@@ -36,3 +40,88 @@ fn multiple_source_files_generated() {
     //The functions are defined correctly
     insta::assert_snapshot!(res);
 }
+
+#[test]
+fn parallel_compilation_does_not_depend_on_the_threads() {
+    let sources = || -> Vec<SourceCode> {
+        vec![
+            "FUNCTION main : INT main := helper(counter); END_FUNCTION".into(),
+            "FUNCTION helper : INT VAR_INPUT x : INT; END_VAR helper := x + 1; END_FUNCTION".into(),
+            "VAR_GLOBAL counter : INT := 7; END_VAR".into(),
+        ]
+    };
+    let compile = || {
+        let context = Context::create();
+        let (_, code_generator) = crate::compile_module_in_parallel(
+            &context,
+            sources(),
+            vec![],
+            None,
+            &CompileOptions {
+                jobs: 4,
+                ..Default::default()
+            },
+            Diagnostician::null_diagnostician(),
+        )
+        .unwrap();
+        code_generator.module.print_to_string().to_string()
+    };
+
+    let first = compile();
+    for _ in 0..5 {
+        assert_eq!(compile(), first);
+    }
+    //every file defines its own symbols exactly once
+    assert_eq!(first.matches("define i16 @main(").count(), 1);
+    assert_eq!(first.matches("define i16 @helper(").count(), 1);
+    assert_eq!(first.matches("@counter = global i16 7").count(), 1);
+}
+
+#[test]
+fn every_file_gets_its_own_module_declaring_the_other_files_symbols() {
+    let sources: Vec<SourceCode> = vec![
+        SourceCode {
+            path: "main.st".into(),
+            source: "FUNCTION main : INT main := helper(counter); END_FUNCTION".into(),
+        },
+        SourceCode {
+            path: "helper.st".into(),
+            source: "FUNCTION helper : INT VAR_INPUT x : INT; END_VAR helper := x; END_FUNCTION
+            VAR_GLOBAL counter : INT := 7; END_VAR"
+                .into(),
+        },
+    ];
+
+    let context = Context::create();
+    let (_, modules) = crate::compile_module_per_file(
+        &context,
+        sources,
+        vec![],
+        None,
+        &CompileOptions::default(),
+        Diagnostician::null_diagnostician(),
+    )
+    .unwrap();
+    let modules = modules
+        .iter()
+        .map(|(file, code_generator)| {
+            (
+                file.as_str(),
+                code_generator.module.print_to_string().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(modules.len(), 2);
+    let (file, main) = &modules[0];
+    assert_eq!(*file, "main.st");
+    assert!(main.contains("define i16 @main("));
+    assert!(main.contains("declare i16 @helper("));
+    assert!(main.contains("@counter = external global i16"));
+
+    let (file, helper) = &modules[1];
+    assert_eq!(*file, "helper.st");
+    assert!(helper.contains("define i16 @helper("));
+    assert!(helper.contains("@counter = global i16 7"));
+    assert!(!helper.contains("@main("));
+}
@@ -4,6 +4,8 @@ use std::{
     ops::{Range, RangeInclusive},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     ast::{AstStatement, Operator, PouType, TypeNature},
     index::{const_expressions::ConstId, Index},
@@ -16,6 +18,8 @@ pub const RANGE_CHECK_S_FN: &str = "CheckRangeSigned";
 pub const RANGE_CHECK_LS_FN: &str = "CheckLRangeSigned";
 pub const RANGE_CHECK_U_FN: &str = "CheckRangeUnsigned";
 pub const RANGE_CHECK_LU_FN: &str = "CheckLRangeUnsigned";
+// Array bounds check function name, called for every index with `--bounds-check`
+pub const BOUNDS_CHECK_FN: &str = "CheckBounds";
 
 pub type NativeSintType = i8;
 pub type NativeIntType = i16;
@@ -80,7 +84,7 @@ pub const VOID_TYPE: &str = "VOID";
 #[cfg(test)]
 mod tests;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DataType {
     pub name: String,
     /// the initial value defined on the TYPE-declration
@@ -123,7 +127,7 @@ impl DataType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VarArgs {
     Sized(Option<String>),
     Unsized(Option<String>),
@@ -143,7 +147,7 @@ impl VarArgs {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StringEncoding {
     Utf8,
     Utf16,
@@ -158,7 +162,7 @@ impl StringEncoding {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TypeSize {
     LiteralInteger(i64),
     ConstExpression(ConstId),
@@ -197,7 +201,7 @@ impl TypeSize {
 }
 
 /// indicates where this Struct origins from.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StructSource {
     OriginalDeclaration,
     Pou(PouType),
@@ -205,7 +209,7 @@ pub enum StructSource {
 
 type TypeId = String;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataTypeInformation {
     Struct {
         name: TypeId,
@@ -333,6 +337,16 @@ impl DataTypeInformation {
         matches!(self, DataTypeInformation::Struct { .. })
     }
 
+    pub fn is_interface(&self) -> bool {
+        matches!(
+            self,
+            DataTypeInformation::Struct {
+                source: StructSource::Pou(PouType::Interface),
+                ..
+            }
+        )
+    }
+
     pub fn is_array(&self) -> bool {
         matches!(self, DataTypeInformation::Array { .. })
     }
@@ -403,7 +417,7 @@ impl DataTypeInformation {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Dimension {
     pub start_offset: TypeSize,
     pub end_offset: TypeSize,
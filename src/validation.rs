@@ -53,6 +53,14 @@ impl Validator {
         }
     }
 
+    /// creates a validator for code generated with or without `--bounds-check`, the signature
+    /// of `CheckBounds` is only validated if the generated code calls it
+    pub fn with_bounds_check(bounds_check: bool) -> Validator {
+        let mut validator = Validator::new();
+        validator.pou_validator.bounds_check = bounds_check;
+        validator
+    }
+
     pub fn diagnostics(&mut self) -> Vec<Diagnostic> {
         let mut all_diagnostics = Vec::new();
         all_diagnostics.append(&mut self.pou_validator.diagnostics);
@@ -1,15 +1,23 @@
 use super::ValidationContext;
-use crate::{ast::Pou, Diagnostic, PouType};
+use crate::{
+    ast::Pou,
+    index::{PouIndexEntry, VariableType},
+    typesystem::{BOUNDS_CHECK_FN, DINT_TYPE},
+    Diagnostic, PouType,
+};
 
 /// validates POUs
 pub struct PouValidator {
     pub diagnostics: Vec<Diagnostic>,
+    /// whether the code is generated with `--bounds-check`, only then `CheckBounds` is called
+    pub bounds_check: bool,
 }
 
 impl PouValidator {
     pub fn new() -> PouValidator {
         PouValidator {
             diagnostics: Vec::new(),
+            bounds_check: false,
         }
     }
 
@@ -17,6 +25,20 @@ impl PouValidator {
         if pou.pou_type == PouType::Function {
             self.validate_function(pou, context);
         };
+        if pou.pou_type == PouType::Program {
+            self.validate_retained_members(pou, context);
+        }
+        if self.bounds_check && pou.name.eq_ignore_ascii_case(BOUNDS_CHECK_FN) {
+            self.validate_bounds_check_function(pou, context);
+        }
+        //the index knows the namespaces the names refer to
+        let entry = context.index.find_pou(&pou.name);
+        if let Some(super_class) = entry.and_then(PouIndexEntry::get_super_class) {
+            self.validate_super_class(pou, super_class, context);
+        }
+        for interface in entry.map(PouIndexEntry::get_interfaces).unwrap_or_default() {
+            self.validate_interface(pou, interface, context);
+        }
     }
 
     pub fn validate_function(&mut self, pou: &Pou, context: &ValidationContext) {
@@ -28,4 +50,168 @@ impl PouValidator {
             ));
         }
     }
+
+    /// the instance of a program is retained as a whole, so all of its members need to be
+    /// retained the same way
+    pub fn validate_retained_members(&mut self, pou: &Pou, context: &ValidationContext) {
+        let mut kinds = vec![];
+        for member in context.index.get_container_members(&pou.name) {
+            if member.is_temp() || member.is_return() || member.is_constant() {
+                continue;
+            }
+            if !kinds.contains(&member.get_retain()) {
+                kinds.push(member.get_retain());
+            }
+        }
+        if kinds.len() > 1 {
+            self.diagnostics.push(Diagnostic::mixed_retain(
+                &pou.name,
+                pou.name_location.to_owned(),
+            ));
+        }
+    }
+
+    /// the code generated with `--bounds-check` calls `CheckBounds(index, lower, upper)` and
+    /// accesses the returned index
+    pub fn validate_bounds_check_function(&mut self, pou: &Pou, context: &ValidationContext) {
+        let is_dint = |type_name: &str| {
+            context
+                .index
+                .get_effective_type_by_name(type_name)
+                .get_name()
+                .eq_ignore_ascii_case(DINT_TYPE)
+        };
+        let parameters = context.index.get_declared_parameters(&pou.name);
+        let is_valid = pou.pou_type == PouType::Function
+            && context
+                .index
+                .find_return_type(&pou.name)
+                .map_or(false, |it| is_dint(it.get_name()))
+            && parameters.len() == 3
+            && parameters.iter().all(|it| {
+                it.get_variable_type() == VariableType::Input && is_dint(it.get_type_name())
+            });
+        if !is_valid {
+            self.diagnostics.push(Diagnostic::invalid_check_function(
+                BOUNDS_CHECK_FN,
+                "FUNCTION CheckBounds : DINT VAR_INPUT index, lower, upper : DINT; END_VAR",
+                pou.name_location.to_owned(),
+            ));
+        }
+    }
+
+    pub fn validate_super_class(
+        &mut self,
+        pou: &Pou,
+        super_class: &str,
+        context: &ValidationContext,
+    ) {
+        let reason = match context.index.find_pou(super_class) {
+            None => Some("Unknown POU"),
+            Some(PouIndexEntry::FunctionBlock { .. }) if pou.pou_type == PouType::FunctionBlock => {
+                None
+            }
+            Some(PouIndexEntry::Class { .. }) if pou.pou_type == PouType::Class => None,
+            Some(_) => Some(
+                "Classes can only extend classes, function blocks can only extend function blocks",
+            ),
+        }
+        .or_else(|| {
+            // the chain of super classes must not lead back to this pou
+            let is_cyclic = super_class.eq_ignore_ascii_case(&pou.name)
+                || context
+                    .index
+                    .get_super_classes(super_class)
+                    .iter()
+                    .any(|it| it.eq_ignore_ascii_case(&pou.name));
+            if is_cyclic {
+                Some("Cyclic inheritance")
+            } else {
+                None
+            }
+        });
+
+        if let Some(reason) = reason {
+            self.diagnostics.push(Diagnostic::invalid_super_class(
+                super_class,
+                reason,
+                pou.name_location.to_owned(),
+            ));
+        }
+    }
+
+    pub fn validate_interface(&mut self, pou: &Pou, interface: &str, context: &ValidationContext) {
+        let index = context.index;
+        match index.find_pou(interface) {
+            None => self.diagnostics.push(Diagnostic::invalid_interface(
+                interface,
+                "Unknown interface",
+                pou.name_location.to_owned(),
+            )),
+            Some(it) if !it.is_interface() => self.diagnostics.push(Diagnostic::invalid_interface(
+                interface,
+                "Only interfaces can be implemented",
+                pou.name_location.to_owned(),
+            )),
+            Some(_) => {
+                // every method of the interface must be implemented with the same signature
+                for interface_method in index.get_interface_methods(interface) {
+                    match index.find_method(&pou.name, interface_method.get_unqualified_name()) {
+                        None => self.diagnostics.push(
+                            Diagnostic::missing_interface_method(
+                                &pou.name,
+                                interface_method.get_name(),
+                                pou.name_location.to_owned(),
+                            )
+                            .with_declaration(
+                                interface_method.get_name(),
+                                interface_method.get_location().to_owned(),
+                            ),
+                        ),
+                        Some(method)
+                            if !has_same_signature(
+                                context,
+                                method.get_name(),
+                                interface_method.get_name(),
+                            ) =>
+                        {
+                            self.diagnostics.push(
+                                Diagnostic::interface_method_mismatch(
+                                    method.get_name(),
+                                    interface_method.get_name(),
+                                    pou.name_location.to_owned(),
+                                )
+                                .with_declaration(
+                                    interface_method.get_name(),
+                                    interface_method.get_location().to_owned(),
+                                ),
+                            )
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// returns true if both methods declare the same return type and the same parameters
+/// (name, type and declaration type) in the same order
+fn has_same_signature(context: &ValidationContext, method: &str, other_method: &str) -> bool {
+    let index = context.index;
+    let return_type = |name: &str| {
+        index
+            .find_return_type(name)
+            .map(|it| it.get_name().to_lowercase())
+    };
+    let parameters = index.get_declared_parameters(method);
+    let other_parameters = index.get_declared_parameters(other_method);
+
+    return_type(method) == return_type(other_method)
+        && parameters.len() == other_parameters.len()
+        && parameters.iter().zip(other_parameters).all(|(a, b)| {
+            a.get_name().eq_ignore_ascii_case(b.get_name())
+                && a.get_type_name().eq_ignore_ascii_case(b.get_type_name())
+                && a.get_declaration_type() == b.get_declaration_type()
+        })
 }
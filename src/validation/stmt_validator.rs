@@ -123,10 +123,26 @@ impl StatementValidator {
                 {
                     // check if we assign to a constant variable
                     if *constant {
-                        self.diagnostics.push(Diagnostic::cannot_assign_to_constant(
+                        let mut diagnostic = Diagnostic::cannot_assign_to_constant(
                             l_qualified_name.as_str(),
                             left.get_location(),
-                        ));
+                        );
+                        //enum elements are implicitly constant, they have no declaration to point to
+                        let index = context.index;
+                        if index
+                            .find_qualified_enum_element(l_qualified_name)
+                            .is_none()
+                        {
+                            if let Some(variable) =
+                                index.find_fully_qualified_variable(l_qualified_name)
+                            {
+                                diagnostic = diagnostic.with_declaration(
+                                    variable.get_name(),
+                                    variable.source_location.clone(),
+                                );
+                            }
+                        }
+                        self.diagnostics.push(diagnostic);
                     }
 
                     let l_effective_type = context
@@ -406,9 +422,10 @@ impl StatementValidator {
         location: &SourceRange,
         context: &ValidationContext,
     ) {
+        let type_name = context.index.resolve_name(context.qualifier, type_name);
         let cast_type = context
             .index
-            .get_effective_type_by_name(type_name)
+            .get_effective_type_by_name(&type_name)
             .get_type_information();
 
         let literal_type = context
@@ -1,4 +1,7 @@
-use crate::{test_utils::tests::parse_and_validate, Diagnostic};
+use crate::{
+    test_utils::tests::{parse_and_validate, parse_and_validate_with_bounds_check},
+    Diagnostic,
+};
 
 #[test]
 fn function_no_return_unsupported() {
@@ -11,3 +14,142 @@ fn function_no_return_unsupported() {
         vec![Diagnostic::function_return_missing((9..12).into())]
     );
 }
+
+#[test]
+fn extending_an_unknown_pou_is_reported() {
+    let diagnostics = parse_and_validate("CLASS foo EXTENDS bar END_CLASS");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_super_class(
+            "bar",
+            "Unknown POU",
+            (6..9).into()
+        )]
+    );
+}
+
+#[test]
+fn function_blocks_cannot_extend_classes() {
+    let diagnostics =
+        parse_and_validate("CLASS bar END_CLASS FUNCTION_BLOCK foo EXTENDS bar END_FUNCTION_BLOCK");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_super_class(
+            "bar",
+            "Classes can only extend classes, function blocks can only extend function blocks",
+            (35..38).into()
+        )]
+    );
+}
+
+#[test]
+fn cyclic_inheritance_is_reported() {
+    let diagnostics =
+        parse_and_validate("CLASS foo EXTENDS bar END_CLASS CLASS bar EXTENDS foo END_CLASS");
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_super_class("bar", "Cyclic inheritance", (6..9).into()),
+            Diagnostic::invalid_super_class("foo", "Cyclic inheritance", (38..41).into()),
+        ]
+    );
+}
+
+#[test]
+fn implementing_an_unknown_interface_is_reported() {
+    let diagnostics = parse_and_validate("CLASS foo IMPLEMENTS bar END_CLASS");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_interface(
+            "bar",
+            "Unknown interface",
+            (6..9).into()
+        )]
+    );
+}
+
+#[test]
+fn only_interfaces_can_be_implemented() {
+    let diagnostics = parse_and_validate("CLASS bar END_CLASS CLASS foo IMPLEMENTS bar END_CLASS");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_interface(
+            "bar",
+            "Only interfaces can be implemented",
+            (26..29).into()
+        )]
+    );
+}
+
+#[test]
+fn missing_and_mismatching_interface_methods_are_reported() {
+    let diagnostics = parse_and_validate(
+        r#"
+        INTERFACE IShape
+            METHOD area : REAL END_METHOD
+            METHOD scale VAR_INPUT factor : REAL; END_VAR END_METHOD
+        END_INTERFACE
+
+        CLASS Square IMPLEMENTS IShape
+            METHOD scale VAR_INPUT factor : INT; END_VAR END_METHOD
+        END_CLASS
+
+        CLASS Circle IMPLEMENTS IShape
+            METHOD area : REAL END_METHOD
+            METHOD scale VAR_INPUT factor : REAL; END_VAR END_METHOD
+        END_CLASS
+    "#,
+    );
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::missing_interface_method("Square", "IShape.area", (174..180).into())
+                .with_declaration("IShape.area", (45..49).into()),
+            Diagnostic::interface_method_mismatch(
+                "Square.scale",
+                "IShape.scale",
+                (174..180).into()
+            )
+            .with_declaration("IShape.scale", (87..92).into()),
+        ]
+    );
+}
+
+#[test]
+fn programs_mixing_retained_and_other_members_are_reported() {
+    let diagnostics = parse_and_validate(
+        "PROGRAM foo VAR RETAIN a : INT; END_VAR VAR b : INT; END_VAR END_PROGRAM
+        PROGRAM bar VAR RETAIN a : INT; END_VAR VAR PERSISTENT b : INT; END_VAR END_PROGRAM
+        PROGRAM baz VAR_TEMP t : INT; END_VAR VAR RETAIN a : INT; END_VAR END_PROGRAM",
+    );
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::mixed_retain("foo", (8..11).into()),
+            Diagnostic::mixed_retain("bar", (89..92).into()),
+        ]
+    );
+}
+
+#[test]
+fn check_bounds_with_an_invalid_signature_is_reported() {
+    let diagnostics = parse_and_validate_with_bounds_check(
+        "FUNCTION CheckBounds : DINT VAR_INPUT i, l, u : DINT; END_VAR CheckBounds := i; END_FUNCTION",
+    );
+    assert_eq!(diagnostics, vec![]);
+
+    let invalid =
+        "FUNCTION CheckBounds : INT VAR_INPUT index : INT; END_VAR CheckBounds := index; END_FUNCTION";
+    //without bounds checks the function is not called
+    assert_eq!(parse_and_validate(invalid), vec![]);
+
+    let diagnostics = parse_and_validate_with_bounds_check(invalid);
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_check_function(
+            "CheckBounds",
+            "FUNCTION CheckBounds : DINT VAR_INPUT index, lower, upper : DINT; END_VAR",
+            (9..20).into()
+        )]
+    );
+}
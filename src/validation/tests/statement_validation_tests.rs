@@ -118,8 +118,10 @@ fn assignment_to_constants_result_in_an_error() {
     assert_eq!(
         diagnostics,
         vec![
-            Diagnostic::cannot_assign_to_constant("prg.cl", (327..329).into()),
-            Diagnostic::cannot_assign_to_constant("ci", (371..373).into()),
+            Diagnostic::cannot_assign_to_constant("prg.cl", (327..329).into())
+                .with_declaration("cl", (190..192).into()),
+            Diagnostic::cannot_assign_to_constant("ci", (371..373).into())
+                .with_declaration("ci", (41..43).into()),
         ]
     );
 }
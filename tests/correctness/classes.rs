@@ -54,3 +54,242 @@ fn class_reference_in_pou() {
     let _: i32 = compile_and_run(source, &mut m);
     assert_eq!(m.x, 10);
 }
+
+#[test]
+fn inherited_members_and_methods_can_be_used() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct BaseDrive {
+        speed: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Motor {
+        base: BaseDrive,
+        torque: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        m: Motor,
+        x: i16,
+        y: i16,
+    }
+
+    let source = "
+        CLASS BaseDrive
+            VAR
+                speed : INT;
+            END_VAR
+
+            METHOD accelerate : INT
+                VAR_INPUT delta : INT; END_VAR
+                speed := speed + delta;
+                accelerate := speed;
+            END_METHOD
+        END_CLASS
+
+        CLASS Motor EXTENDS BaseDrive
+            VAR
+                torque : INT;
+            END_VAR
+
+            METHOD boost : INT
+                torque := SUPER^.accelerate(10) * 2;
+                boost := torque + speed;
+            END_METHOD
+        END_CLASS
+
+        PROGRAM main
+        VAR
+          m : Motor;
+          x : INT;
+          y : INT;
+        END_VAR
+        m.speed := 1;
+        x := m.accelerate(4);
+        y := m.boost();
+        END_PROGRAM
+        ";
+
+    let mut m = MainType {
+        m: Motor {
+            base: BaseDrive { speed: 0 },
+            torque: 0,
+        },
+        x: 0,
+        y: 0,
+    };
+    let _: i32 = compile_and_run(source, &mut m);
+    assert_eq!(m.m.base.speed, 15);
+    assert_eq!(m.m.torque, 30);
+    assert_eq!(m.x, 5);
+    assert_eq!(m.y, 45);
+}
+
+#[test]
+fn interface_method_calls_are_dispatched_to_the_assigned_instance() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Square {
+        side: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Shape {
+        id: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Circle {
+        base: Shape,
+        r: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        s: Square,
+        c: Circle,
+        shape: [usize; 2],
+        a: i16,
+        b: i16,
+    }
+
+    let source = "
+        INTERFACE IShape
+            METHOD area : INT
+                VAR_INPUT factor : INT; END_VAR
+            END_METHOD
+        END_INTERFACE
+
+        CLASS Square IMPLEMENTS IShape
+            VAR
+                side : INT;
+            END_VAR
+
+            METHOD area : INT
+                VAR_INPUT factor : INT; END_VAR
+                area := side * side * factor;
+            END_METHOD
+        END_CLASS
+
+        CLASS Shape IMPLEMENTS IShape
+            VAR
+                id : INT;
+            END_VAR
+
+            METHOD area : INT
+                VAR_INPUT factor : INT; END_VAR
+                area := 0;
+            END_METHOD
+        END_CLASS
+
+        CLASS Circle EXTENDS Shape
+            VAR
+                r : INT;
+            END_VAR
+
+            METHOD area : INT
+                VAR_INPUT factor : INT; END_VAR
+                area := 3 * r * r * factor;
+            END_METHOD
+        END_CLASS
+
+        PROGRAM main
+        VAR
+          s : Square;
+          c : Circle;
+          shape : IShape;
+          a : INT;
+          b : INT;
+        END_VAR
+        s.side := 3;
+        c.r := 2;
+        shape := s;
+        a := shape.area(2);
+        shape := c;
+        b := shape.area(factor := 3);
+        END_PROGRAM
+        ";
+
+    let mut m = MainType {
+        s: Square { side: 0 },
+        c: Circle {
+            base: Shape { id: 0 },
+            r: 0,
+        },
+        shape: [0; 2],
+        a: 0,
+        b: 0,
+    };
+    let _: i32 = compile_and_run(source, &mut m);
+    assert_eq!(m.a, 18);
+    assert_eq!(m.b, 36);
+}
+
+#[test]
+fn outputs_of_methods_called_through_an_interface_are_returned() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Counter {
+        total: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        c: Counter,
+        counter: [usize; 2],
+        first: i16,
+        second: i16,
+    }
+
+    let source = "
+        INTERFACE ICounter
+            METHOD next
+                VAR_INPUT step : INT; END_VAR
+                VAR_OUTPUT value : INT; END_VAR
+            END_METHOD
+        END_INTERFACE
+
+        CLASS Counter IMPLEMENTS ICounter
+            VAR
+                total : INT;
+            END_VAR
+
+            METHOD next
+                VAR_INPUT step : INT; END_VAR
+                VAR_OUTPUT value : INT; END_VAR
+                total := total + step;
+                value := total;
+            END_METHOD
+        END_CLASS
+
+        PROGRAM main
+        VAR
+          c : Counter;
+          counter : ICounter;
+          first : INT;
+          second : INT;
+        END_VAR
+        counter := c;
+        counter.next(step := 2, value => first);
+        counter.next(step := 3, value => second);
+        END_PROGRAM
+        ";
+
+    let mut m = MainType {
+        c: Counter { total: 0 },
+        counter: [0; 2],
+        first: 0,
+        second: 0,
+    };
+    let _: i32 = compile_and_run(source, &mut m);
+    assert_eq!(m.first, 2);
+    assert_eq!(m.second, 5);
+}
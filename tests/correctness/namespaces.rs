@@ -0,0 +1,68 @@
+// Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+
+#[test]
+fn pous_with_the_same_name_in_different_namespaces_can_be_called() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct FbAxis {
+        x: i16,
+        pos: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        a: FbAxis,
+        b: FbAxis,
+        ra: i16,
+        rb: i16,
+    }
+
+    let source = "
+        NAMESPACE Vendor.A
+            FUNCTION_BLOCK FB_Axis
+                VAR_INPUT x : INT; END_VAR
+                VAR_OUTPUT pos : INT; END_VAR
+                pos := x + Offset();
+            END_FUNCTION_BLOCK
+
+            FUNCTION Offset : INT
+                Offset := 100;
+            END_FUNCTION
+        END_NAMESPACE
+
+        NAMESPACE Vendor.B
+            VAR_GLOBAL gFactor : INT := 3; END_VAR
+
+            FUNCTION_BLOCK FB_Axis
+                VAR_INPUT x : INT; END_VAR
+                VAR_OUTPUT pos : INT; END_VAR
+                pos := x * gFactor;
+            END_FUNCTION_BLOCK
+        END_NAMESPACE
+
+        PROGRAM main
+        USING Vendor.A;
+        VAR
+            a : FB_Axis;
+            b : Vendor.B.FB_Axis;
+            ra : INT;
+            rb : INT;
+        END_VAR
+            a(x := 1, pos => ra);
+            b(x := 2);
+            rb := b.pos + Vendor.B.gFactor;
+        END_PROGRAM
+        ";
+
+    let mut m = MainType {
+        a: FbAxis { x: 0, pos: 0 },
+        b: FbAxis { x: 0, pos: 0 },
+        ra: 0,
+        rb: 0,
+    };
+    let _: i32 = compile_and_run(source, &mut m);
+    assert_eq!(m.ra, 101);
+    assert_eq!(m.rb, 9);
+}
@@ -0,0 +1,297 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+
+const MILLISECOND: i64 = 1_000_000;
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct TimerType {
+    current_time: i64,
+    input: bool,
+    ton_q: bool,
+    tof_q: bool,
+    tp_q: bool,
+    ton_et: i64,
+    tof_et: i64,
+    tp_et: i64,
+}
+
+#[test]
+fn timers_use_the_provided_time_source() {
+    let prog = "
+    VAR_GLOBAL
+        simulated_time : TIME;
+        on_delay : TON;
+        off_delay : TOF;
+        pulse : TP;
+    END_VAR
+
+    FUNCTION __GET_TIME : TIME
+        __GET_TIME := simulated_time;
+    END_FUNCTION
+
+    PROGRAM main
+    VAR
+        current_time : TIME;
+        input : BOOL;
+        ton_q : BOOL;
+        tof_q : BOOL;
+        tp_q : BOOL;
+        ton_et : TIME;
+        tof_et : TIME;
+        tp_et : TIME;
+    END_VAR
+        simulated_time := current_time;
+        on_delay(IN := input, PT := T#10ms);
+        off_delay(IN := input, PT := T#10ms);
+        pulse(IN := input, PT := T#10ms);
+        ton_q := on_delay.Q;
+        ton_et := on_delay.ET;
+        tof_q := off_delay.Q;
+        tof_et := off_delay.ET;
+        tp_q := pulse.Q;
+        tp_et := pulse.ET;
+    END_PROGRAM
+    ";
+
+    let context = inkwell::context::Context::create();
+    let exec_engine = compile(&context, prog);
+    let mut main = TimerType::default();
+
+    // (time in ms, IN) -> (TON.Q, TON.ET, TOF.Q, TOF.ET, TP.Q, TP.ET) with ET in ms
+    let cycles = [
+        ((0, false), (false, 0, false, 0, false, 0)),
+        ((1, true), (false, 0, true, 0, true, 0)),
+        ((5, false), (false, 0, true, 0, true, 4)),
+        ((6, true), (false, 0, true, 0, true, 5)),
+        ((12, true), (false, 6, true, 0, false, 10)),
+        ((16, true), (true, 10, true, 0, false, 10)),
+        ((20, false), (false, 0, true, 0, false, 0)),
+        ((25, false), (false, 0, true, 5, false, 0)),
+        ((31, false), (false, 0, false, 10, false, 0)),
+        ((40, false), (false, 0, false, 10, false, 0)),
+    ];
+    for ((time, input), expected) in cycles {
+        main.current_time = time * MILLISECOND;
+        main.input = input;
+        run::<_, i32>(&exec_engine, "main", &mut main);
+
+        let actual = (
+            main.ton_q,
+            main.ton_et / MILLISECOND,
+            main.tof_q,
+            main.tof_et / MILLISECOND,
+            main.tp_q,
+            main.tp_et / MILLISECOND,
+        );
+        assert_eq!(actual, expected, "at {}ms", time);
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct ClockType {
+    first: i64,
+    second: i64,
+}
+
+#[test]
+fn timers_default_to_the_monotonic_clock() {
+    let prog = "
+    VAR_GLOBAL
+        on_delay : TON;
+    END_VAR
+
+    PROGRAM main
+    VAR
+        first : TIME;
+        second : TIME;
+    END_VAR
+        first := __GET_TIME();
+        second := __GET_TIME();
+    END_PROGRAM
+    ";
+
+    let mut main = ClockType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert!(main.first > 0);
+    assert!(main.second >= main.first);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct CounterType {
+    up: bool,
+    down: bool,
+    reset: bool,
+    load: bool,
+    ctu_q: bool,
+    ctd_q: bool,
+    ctud_qu: bool,
+    ctud_qd: bool,
+    ctu_cv: i16,
+    ctd_cv: i16,
+    ctud_cv: i16,
+}
+
+#[test]
+fn counters_count_rising_edges() {
+    let prog = "
+    VAR_GLOBAL
+        up_counter : CTU;
+        down_counter : CTD;
+        up_down_counter : CTUD;
+    END_VAR
+
+    PROGRAM main
+    VAR
+        up : BOOL;
+        down : BOOL;
+        reset : BOOL;
+        load : BOOL;
+        ctu_q : BOOL;
+        ctd_q : BOOL;
+        ctud_qu : BOOL;
+        ctud_qd : BOOL;
+        ctu_cv : INT;
+        ctd_cv : INT;
+        ctud_cv : INT;
+    END_VAR
+        up_counter(CU := up, R := reset, PV := 2);
+        down_counter(CD := down, LD := load, PV := 2);
+        up_down_counter(CU := up, CD := down, R := reset, LD := load, PV := 2);
+        ctu_q := up_counter.Q;
+        ctu_cv := up_counter.CV;
+        ctd_q := down_counter.Q;
+        ctd_cv := down_counter.CV;
+        ctud_qu := up_down_counter.QU;
+        ctud_qd := up_down_counter.QD;
+        ctud_cv := up_down_counter.CV;
+    END_PROGRAM
+    ";
+
+    let context = inkwell::context::Context::create();
+    let exec_engine = compile(&context, prog);
+    let mut main = CounterType::default();
+
+    // (CU/CD, R/LD inputs) -> (CTU.Q, CTU.CV, CTD.Q, CTD.CV, CTUD.QU, CTUD.QD, CTUD.CV)
+    let cycles = [
+        (
+            (false, false, false, true),
+            (false, 0, false, 2, true, false, 2),
+        ),
+        (
+            (true, false, false, false),
+            (false, 1, false, 2, true, false, 3),
+        ),
+        (
+            (false, false, false, false),
+            (false, 1, false, 2, true, false, 3),
+        ),
+        (
+            (true, true, false, false),
+            (true, 2, false, 1, true, false, 3),
+        ),
+        (
+            (false, false, false, false),
+            (true, 2, false, 1, true, false, 3),
+        ),
+        (
+            (false, true, false, false),
+            (true, 2, true, 0, true, false, 2),
+        ),
+        (
+            (false, false, true, true),
+            (false, 0, false, 2, false, true, 0),
+        ),
+    ];
+    for (cycle, ((up, down, reset, load), expected)) in cycles.into_iter().enumerate() {
+        main.up = up;
+        main.down = down;
+        main.reset = reset;
+        main.load = load;
+        run::<_, i32>(&exec_engine, "main", &mut main);
+
+        let actual = (
+            main.ctu_q,
+            main.ctu_cv,
+            main.ctd_q,
+            main.ctd_cv,
+            main.ctud_qu,
+            main.ctud_qd,
+            main.ctud_cv,
+        );
+        assert_eq!(actual, expected, "in cycle {}", cycle);
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct EdgeType {
+    clk: bool,
+    set: bool,
+    reset: bool,
+    rising: bool,
+    falling: bool,
+    sr_q: bool,
+    rs_q: bool,
+}
+
+#[test]
+fn edge_detection_and_bistables() {
+    let prog = "
+    VAR_GLOBAL
+        rising_edge : R_TRIG;
+        falling_edge : F_TRIG;
+        set_dominant : SR;
+        reset_dominant : RS;
+    END_VAR
+
+    PROGRAM main
+    VAR
+        clk : BOOL;
+        set : BOOL;
+        reset : BOOL;
+        rising : BOOL;
+        falling : BOOL;
+        sr_q : BOOL;
+        rs_q : BOOL;
+    END_VAR
+        rising_edge(CLK := clk);
+        falling_edge(CLK := clk);
+        set_dominant(S1 := set, R := reset);
+        reset_dominant(S := set, R1 := reset);
+        rising := rising_edge.Q;
+        falling := falling_edge.Q;
+        sr_q := set_dominant.Q1;
+        rs_q := reset_dominant.Q1;
+    END_PROGRAM
+    ";
+
+    let context = inkwell::context::Context::create();
+    let exec_engine = compile(&context, prog);
+    let mut main = EdgeType::default();
+
+    // (CLK, set, reset) -> (R_TRIG.Q, F_TRIG.Q, SR.Q1, RS.Q1)
+    let cycles = [
+        ((false, false, false), (false, false, false, false)),
+        ((true, true, true), (true, false, true, false)),
+        ((true, false, false), (false, false, true, false)),
+        ((false, true, false), (false, true, true, true)),
+        ((false, false, true), (false, false, false, false)),
+    ];
+    for (cycle, ((clk, set, reset), expected)) in cycles.into_iter().enumerate() {
+        main.clk = clk;
+        main.set = set;
+        main.reset = reset;
+        run::<_, i32>(&exec_engine, "main", &mut main);
+
+        let actual = (main.rising, main.falling, main.sr_q, main.rs_q);
+        assert_eq!(actual, expected, "in cycle {}", cycle);
+    }
+}
@@ -0,0 +1,308 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct ConversionType {
+    int_to_real: f32,
+    real_to_int: i16,
+    negative_real_to_int: i16,
+    trunc: i32,
+    real_trunc_int: i16,
+    dint_to_bool: u8,
+    to_lreal: f64,
+    string_to_int: i16,
+    wstring_to_dint: i32,
+    time_to_dint: i32,
+}
+
+#[test]
+fn conversion_functions() {
+    let prog = "
+    PROGRAM main
+    VAR
+        int_to_real : REAL;
+        real_to_int : INT;
+        negative_real_to_int : INT;
+        trunc_real : DINT;
+        real_trunc_int : INT;
+        dint_to_bool : BOOL;
+        to_lreal : LREAL;
+        string_to_int : INT;
+        wstring_to_dint : DINT;
+        time_to_dint : DINT;
+    END_VAR
+        int_to_real := INT_TO_REAL(INT#7);
+        real_to_int := REAL_TO_INT(2.5);
+        negative_real_to_int := REAL_TO_INT(-3.7);
+        trunc_real := TRUNC(-3.7);
+        real_trunc_int := REAL_TRUNC_INT(3.7);
+        dint_to_bool := DINT_TO_BOOL(256);
+        to_lreal := TO_LREAL(DINT#42);
+        string_to_int := STRING_TO_INT('-123abc');
+        wstring_to_dint := WSTRING_TO_DINT(\"+4567\");
+        time_to_dint := TIME_TO_DINT(T#1ms);
+    END_PROGRAM
+    ";
+
+    let mut main = ConversionType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.int_to_real, 7.0);
+    assert_eq!(main.real_to_int, 3);
+    assert_eq!(main.negative_real_to_int, -4);
+    assert_eq!(main.trunc, -3);
+    assert_eq!(main.real_trunc_int, 3);
+    assert_eq!(main.dint_to_bool, 1);
+    assert_eq!(main.to_lreal, 42.0);
+    assert_eq!(main.string_to_int, -123);
+    assert_eq!(main.wstring_to_dint, 4567);
+    assert_eq!(main.time_to_dint, 1_000_000);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct DateConversionType {
+    date: i64,
+    time_of_day: i64,
+}
+
+#[test]
+fn date_and_time_conversions_split_date_and_time_of_day() {
+    let prog = "
+    PROGRAM main
+    VAR
+        date_value : DATE;
+        time_of_day_value : TOD;
+    END_VAR
+        date_value := DT_TO_DATE(DT#2021-05-04-13:14:15);
+        time_of_day_value := DT_TO_TOD(DT#2021-05-04-13:14:15);
+    END_PROGRAM
+    ";
+
+    let mut main = DateConversionType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    let nanos_per_second = 1_000_000_000;
+    assert_eq!(main.date % (86_400 * nanos_per_second), 0);
+    assert_eq!(
+        main.time_of_day,
+        (13 * 3600 + 14 * 60 + 15) * nanos_per_second
+    );
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct BcdType {
+    bcd_to_int: i16,
+    word_bcd_to_uint: u16,
+    uint_to_bcd: u16,
+}
+
+#[test]
+fn bcd_conversions() {
+    let prog = "
+    PROGRAM main
+    VAR
+        bcd_to_int : INT;
+        word_bcd_to_uint : UINT;
+        uint_to_bcd : WORD;
+    END_VAR
+        bcd_to_int := BCD_TO_INT(WORD#16#1234);
+        word_bcd_to_uint := WORD_BCD_TO_UINT(WORD#16#0099);
+        uint_to_bcd := UINT_TO_BCD_WORD(UINT#4321);
+    END_PROGRAM
+    ";
+
+    let mut main = BcdType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.bcd_to_int, 1234);
+    assert_eq!(main.word_bcd_to_uint, 99);
+    assert_eq!(main.uint_to_bcd, 0x4321);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct NumericType {
+    abs_int: i16,
+    abs_real: f32,
+    sqrt: f64,
+    ln: f32,
+    log: f32,
+    exp: f32,
+    sin: f64,
+    cos: f64,
+    tan: f32,
+    atan: f64,
+}
+
+#[test]
+fn numeric_functions() {
+    let prog = "
+    PROGRAM main
+    VAR
+        abs_int : INT;
+        abs_real : REAL;
+        sqrt_lreal : LREAL;
+        ln_real : REAL;
+        log_real : REAL;
+        exp_real : REAL;
+        sin_lreal : LREAL;
+        cos_lreal : LREAL;
+        tan_real : REAL;
+        atan_lreal : LREAL;
+    END_VAR
+        abs_int := ABS(INT#-5);
+        abs_real := ABS(-2.5);
+        sqrt_lreal := SQRT(LREAL#16.0);
+        ln_real := LN(REAL#1.0);
+        log_real := LOG(REAL#1000.0);
+        exp_real := EXP(REAL#0.0);
+        sin_lreal := SIN(LREAL#0.0);
+        cos_lreal := COS(LREAL#0.0);
+        tan_real := TAN(REAL#0.0);
+        atan_lreal := ATAN(LREAL#1.0);
+    END_PROGRAM
+    ";
+
+    let mut main = NumericType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.abs_int, 5);
+    assert_eq!(main.abs_real, 2.5);
+    assert_eq!(main.sqrt, 4.0);
+    assert_eq!(main.ln, 0.0);
+    assert!((main.log - 3.0).abs() < f32::EPSILON * 4.0);
+    assert_eq!(main.exp, 1.0);
+    assert_eq!(main.sin, 0.0);
+    assert_eq!(main.cos, 1.0);
+    assert_eq!(main.tan, 0.0);
+    assert!((main.atan - std::f64::consts::FRAC_PI_4).abs() < f64::EPSILON);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct SelectionType {
+    min: i32,
+    max: f32,
+    limit_low: i16,
+    limit_high: i16,
+    limit_in: i16,
+}
+
+#[test]
+fn selection_functions() {
+    let prog = "
+    PROGRAM main
+    VAR
+        min_dint : DINT;
+        max_real : REAL;
+        limit_low : INT;
+        limit_high : INT;
+        limit_in : INT;
+    END_VAR
+        min_dint := MIN(5, -3, 7);
+        max_real := MAX(REAL#1.5, REAL#-2.0, REAL#4.25);
+        limit_low := LIMIT(INT#0, INT#-10, INT#100);
+        limit_high := LIMIT(INT#0, INT#1000, INT#100);
+        limit_in := LIMIT(INT#0, INT#42, INT#100);
+    END_PROGRAM
+    ";
+
+    let mut main = SelectionType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.min, -3);
+    assert_eq!(main.max, 4.25);
+    assert_eq!(main.limit_low, 0);
+    assert_eq!(main.limit_high, 100);
+    assert_eq!(main.limit_in, 42);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct MixedSelectionType {
+    min: i64,
+    max: i64,
+    limit: i64,
+    max_real: f64,
+    small: i8,
+    big: i64,
+}
+
+#[test]
+fn selection_functions_with_arguments_of_different_sizes() {
+    let prog = "
+    PROGRAM main
+    VAR
+        min_lint : LINT;
+        max_lint : LINT;
+        limit_lint : LINT;
+        max_lreal : LREAL;
+        small : SINT;
+        big : LINT;
+    END_VAR
+        small := -5;
+        big := 5000000000;
+        min_lint := MIN(big, small, INT#7);
+        max_lint := MAX(small, big, DINT#-1);
+        limit_lint := LIMIT(small, big, DINT#100000);
+        max_lreal := MAX(REAL#1.5, LREAL#2.25, REAL#-4.0);
+    END_PROGRAM
+    ";
+
+    let mut main = MixedSelectionType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.min, -5);
+    assert_eq!(main.max, 5_000_000_000);
+    assert_eq!(main.limit, 100_000);
+    assert_eq!(main.max_real, 2.25);
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct ShiftType {
+    shl: u8,
+    shr: u16,
+    shl_out_of_range: u8,
+    rol: u8,
+    ror: u32,
+}
+
+#[test]
+fn shift_and_rotate_functions() {
+    let prog = "
+    PROGRAM main
+    VAR
+        shl_byte : BYTE;
+        shr_word : WORD;
+        shl_out_of_range : BYTE;
+        rol_byte : BYTE;
+        ror_dword : DWORD;
+    END_VAR
+        shl_byte := SHL(BYTE#2#0000_0011, 2);
+        shr_word := SHR(WORD#16#FF00, 4);
+        shl_out_of_range := SHL(BYTE#16#FF, 9);
+        rol_byte := ROL(BYTE#2#1000_0001, 1);
+        ror_dword := ROR(DWORD#16#0000_00F1, 4);
+    END_PROGRAM
+    ";
+
+    let mut main = ShiftType::default();
+    let _: i32 = compile_and_run(prog, &mut main);
+
+    assert_eq!(main.shl, 0b0000_1100);
+    assert_eq!(main.shr, 0x0FF0);
+    assert_eq!(main.shl_out_of_range, 0);
+    assert_eq!(main.rol, 0b0000_0011);
+    assert_eq!(main.ror, 0x1000_000F);
+}
@@ -605,3 +605,108 @@ fn wstring_as_function_parameters_cast() {
     let res = String::from_utf16_lossy(&main_type.res[..5]);
     assert_eq!(res, "hello");
 }
+
+#[test]
+fn string_functions() {
+    let src = r#"
+        PROGRAM main
+            VAR
+                text : STRING[20];
+                len_result : DINT;
+                left_result, right_result, mid_result : STRING[10];
+                concat_result, insert_result, delete_result, replace_result : STRING[20];
+                find_result, not_found : DINT;
+            END_VAR
+            text := 'hello world';
+            len_result := LEN(text);
+            left_result := LEFT(text, 5);
+            right_result := RIGHT(text, 5);
+            mid_result := MID(text, 3, 2);
+            concat_result := CONCAT(LEFT(text, 5), ', ', 'again');
+            insert_result := INSERT(text, 'big ', 6);
+            delete_result := DELETE(text, 6, 6);
+            replace_result := REPLACE(text, 'there', 5, 7);
+            find_result := FIND(text, 'wor');
+            not_found := FIND(text, 'xyz');
+        END_PROGRAM
+    "#;
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        text: [u8; 21],
+        len: i32,
+        left: [u8; 11],
+        right: [u8; 11],
+        mid: [u8; 11],
+        concat: [u8; 21],
+        insert: [u8; 21],
+        delete: [u8; 21],
+        replace: [u8; 21],
+        find: i32,
+        not_found: i32,
+    }
+    let mut main_type = MainType {
+        text: [0; 21],
+        len: 0,
+        left: [0; 11],
+        right: [0; 11],
+        mid: [0; 11],
+        concat: [0; 21],
+        insert: [0; 21],
+        delete: [0; 21],
+        replace: [0; 21],
+        find: 0,
+        not_found: 0,
+    };
+
+    let _: i32 = compile_and_run(src, &mut main_type);
+    let as_str = |bytes: &[u8]| {
+        let end = bytes.iter().position(|it| *it == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).to_string()
+    };
+    assert_eq!(11, main_type.len);
+    assert_eq!("hello", as_str(&main_type.left));
+    assert_eq!("world", as_str(&main_type.right));
+    assert_eq!("ell", as_str(&main_type.mid));
+    assert_eq!("hello, again", as_str(&main_type.concat));
+    assert_eq!("hello big world", as_str(&main_type.insert));
+    assert_eq!("hello", as_str(&main_type.delete));
+    assert_eq!("hello there", as_str(&main_type.replace));
+    assert_eq!(7, main_type.find);
+    assert_eq!(0, main_type.not_found);
+}
+
+#[test]
+fn string_function_results_are_truncated_to_the_target() {
+    let src = r#"
+        PROGRAM main
+            VAR
+                short : STRING[4];
+                wide : WSTRING[6];
+                wide_len : DINT;
+            END_VAR
+            short := CONCAT('abc', 'def');
+            wide := CONCAT("ab", MID("xcdx", 2, 2), "efgh");
+            wide_len := LEN(wide);
+        END_PROGRAM
+    "#;
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        short: [u8; 5],
+        wide: [u16; 7],
+        wide_len: i32,
+    }
+    let mut main_type = MainType {
+        short: [0; 5],
+        wide: [0; 7],
+        wide_len: 0,
+    };
+
+    let _: i32 = compile_and_run(src, &mut main_type);
+    assert_eq!("abcd\0".as_bytes(), &main_type.short);
+    assert_eq!("abcdef", String::from_utf16_lossy(&main_type.wide[..6]));
+    assert_eq!(6, main_type.wide_len);
+}
@@ -27,6 +27,31 @@ fn build_to_temp() {
     assert!(dir.path().join("libcopy.so").is_file());
 }
 
+#[test]
+fn build_toml_to_temp() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("json/build_to_temp.toml"),
+        "--target",
+        "x86_64-linux-gnu",
+        "--sysroot",
+        "sysroot",
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    build_with_subcommand(parameters).unwrap();
+
+    assert!(dir
+        .path()
+        .join("x86_64-linux-gnu")
+        .join("proj.so")
+        .is_file());
+    assert!(dir.path().join("libcopy.so").is_file());
+}
+
 #[test]
 fn build_with_separate_lib_folder() {
     let dir = tempfile::tempdir().unwrap();
@@ -109,6 +134,77 @@ fn build_for_multiple_targets_and_sysroots() {
         .is_file());
 }
 
+#[test]
+fn build_all_targets_of_the_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("json/targets_and_profiles.json"),
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    build_with_subcommand(parameters).unwrap();
+
+    assert!(dir
+        .path()
+        .join("aarch64-linux-gnu")
+        .join("proj.so")
+        .is_file());
+    assert!(dir
+        .path()
+        .join("x86_64-linux-gnu")
+        .join("proj.so")
+        .is_file());
+}
+
+#[test]
+fn build_selected_target_with_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("json/targets_and_profiles.json"),
+        "--profile",
+        "release",
+        "--target",
+        "arm",
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    build_with_subcommand(parameters).unwrap();
+
+    assert!(dir
+        .path()
+        .join("aarch64-linux-gnu")
+        .join("proj.so")
+        .is_file());
+    assert!(!dir.path().join("x86_64-linux-gnu").exists());
+}
+
+#[test]
+fn build_with_unknown_profile_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("json/targets_and_profiles.json"),
+        "--profile",
+        "debug",
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    let err = build_with_subcommand(parameters).unwrap_err();
+
+    assert_eq!(
+        err.get_message(),
+        "Unknown profile 'debug', the build description declares: release"
+    );
+}
+
 #[test]
 #[cfg_attr(target_os = "windows", ignore = "linker not available for Windows")]
 fn build_with_cc_linker() {
@@ -165,3 +261,77 @@ fn build_with_clang_linker_windows() {
 
     assert!(dir.path().join("clang_proj.so").is_file());
 }
+
+#[test]
+fn build_project_with_dependencies() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("workspace/machine/plc.json"),
+        "--target",
+        "x86_64-linux-gnu",
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    build_with_subcommand(parameters).unwrap();
+
+    //the dependency is built first and its interface is included by the project
+    assert!(dir.path().join("libshared.stif").is_file());
+    assert!(dir
+        .path()
+        .join("x86_64-linux-gnu")
+        .join("libshared.so")
+        .is_file());
+    assert!(dir
+        .path()
+        .join("x86_64-linux-gnu")
+        .join("machine.so")
+        .is_file());
+}
+
+#[test]
+fn the_standard_library_is_only_defined_by_the_built_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameters = CompileParameters::parse(&[
+        "rustyc",
+        "build",
+        &get_test_file("timers_workspace/plant/plc.json"),
+        "--target",
+        "x86_64-linux-gnu",
+        "--build-location",
+        dir.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    build_with_subcommand(parameters).unwrap();
+
+    let target = dir.path().join("x86_64-linux-gnu");
+    let dependency = std::fs::read_to_string(target.join("debounce.ir")).unwrap();
+    let project = std::fs::read_to_string(target.join("plant.ir")).unwrap();
+    //the dependency only declares what the project defines once
+    assert!(dependency.contains("declare void @TON("), "{}", dependency);
+    assert!(
+        !dependency.contains("define weak_odr void @TON("),
+        "{}",
+        dependency
+    );
+    assert!(!dependency.contains("@__retain_save"), "{}", dependency);
+    assert!(
+        project.contains("define weak_odr void @TON("),
+        "{}",
+        project
+    );
+    assert!(project.contains("define void @plant("), "{}", project);
+    //the project saves & restores the retained variables of its dependencies
+    assert!(
+        project.contains("define void @__retain_save("),
+        "{}",
+        project
+    );
+    assert!(
+        project.contains("@counter_instance = external global"),
+        "{}",
+        project
+    );
+}
@@ -26,3 +26,261 @@ fn ir_generation_full_pass() {
 
     assert_snapshot!(content);
 }
+
+#[test]
+fn separate_objects_are_written_next_to_the_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("out.ir");
+    build_with_params(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            get_test_file("multi/func.st").as_str(),
+            get_test_file("multi/prog.st").as_str(),
+            "--ir",
+            "--separate-objects",
+            "-o",
+            &output.to_string_lossy(),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert!(!output.exists());
+    let func = std::fs::read_to_string(dir.path().join("func.ir")).unwrap();
+    let prog = std::fs::read_to_string(dir.path().join("prog.ir")).unwrap();
+    assert!(func.contains("define i16 @main("));
+    assert!(func.contains("declare void @mainProg("));
+    assert!(prog.contains("define void @mainProg("));
+    assert!(!prog.contains("@main("));
+}
+
+#[test]
+fn header_is_written_for_the_compiled_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("out.ir");
+    let header = dir.path().join("out.h");
+    build_with_params(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            get_test_file("command_line.st").as_str(),
+            "-o",
+            output.to_str().unwrap(),
+            "--ir",
+            "--emit-header",
+            header.to_str().unwrap(),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    let content = std::fs::read_to_string(header).unwrap();
+    assert!(content.contains("#ifndef OUT_H_"), "{}", content);
+    assert!(
+        content.contains("int32_t myFunc(int32_t a, int32_t b, int32_t c);"),
+        "{}",
+        content
+    );
+    assert!(content.contains("#endif /* OUT_H_ */"), "{}", content);
+}
+
+#[test]
+fn imported_headers_can_be_included() {
+    let dir = tempfile::tempdir().unwrap();
+    let header = dir.path().join("io.h");
+    std::fs::write(
+        &header,
+        "
+        #include <stdint.h>
+        typedef struct { int32_t channel; double value; } Sample;
+        int32_t io_read(int32_t channel, Sample* sample);
+        ",
+    )
+    .unwrap();
+    let declarations = dir.path().join("io.st");
+    rusty::build_with_subcommand(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            "import-c",
+            header.to_str().unwrap(),
+            "-o",
+            declarations.to_str().unwrap(),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    //the imported declarations are included to call the C function
+    let program = dir.path().join("main.st");
+    std::fs::write(
+        &program,
+        "
+        FUNCTION main : DINT
+        VAR sample : Sample; END_VAR
+            main := io_read(3, sample);
+        END_FUNCTION
+        ",
+    )
+    .unwrap();
+    let output = dir.path().join("main.ir");
+    build_with_params(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            program.to_str().unwrap(),
+            "-i",
+            declarations.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+            "--ir",
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    let content = std::fs::read_to_string(output).unwrap();
+    assert!(content.contains("declare i32 @io_read("), "{}", content);
+}
+
+#[test]
+fn retain_map_is_written_for_separate_objects() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.st");
+    std::fs::write(&first, "VAR_GLOBAL RETAIN counter : DINT; END_VAR").unwrap();
+    let second = dir.path().join("second.st");
+    std::fs::write(
+        &second,
+        "PROGRAM mainProg VAR PERSISTENT limit : LREAL; END_VAR END_PROGRAM",
+    )
+    .unwrap();
+    let output = dir.path().join("out.ir");
+    let map = dir.path().join("retain.json");
+    build_with_params(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--ir",
+            "--separate-objects",
+            "-o",
+            output.to_str().unwrap(),
+            "--retain-map",
+            map.to_str().unwrap(),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    //the entry points are generated with the first file
+    let first = std::fs::read_to_string(dir.path().join("first.ir")).unwrap();
+    assert!(first.contains("section \".retain\""), "{}", first);
+    assert!(first.contains("define void @__retain_save("), "{}", first);
+    assert!(
+        first.contains("@mainProg_instance = external global"),
+        "{}",
+        first
+    );
+    let second = std::fs::read_to_string(dir.path().join("second.ir")).unwrap();
+    assert!(second.contains("section \".persistent\""), "{}", second);
+    assert!(!second.contains("@__retain_save("), "{}", second);
+
+    let map: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(map).unwrap()).unwrap();
+    assert_eq!(map["size"], 12);
+    assert_eq!(map["variables"][0]["name"], "counter");
+    assert_eq!(map["variables"][0]["kind"], "retain");
+    assert_eq!(map["variables"][1]["name"], "mainProg.limit");
+    assert_eq!(map["variables"][1]["symbol"], "mainProg_instance");
+    assert_eq!(map["variables"][1]["offset"], 4);
+    assert_eq!(map["variables"][1]["type"], "LREAL");
+    assert_eq!(map["variables"][1]["kind"], "persistent");
+}
+
+#[test]
+fn standard_library_objects_do_not_clash_with_user_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("timers.st");
+    std::fs::write(
+        &source,
+        "PROGRAM mainProg VAR t : TON; END_VAR t(IN := TRUE, PT := T#1s); END_PROGRAM",
+    )
+    .unwrap();
+    let output = dir.path().join("out.ir");
+    build_with_params(
+        rusty::cli::CompileParameters::parse(&[
+            "rustyc",
+            source.to_str().unwrap(),
+            "--ir",
+            "--separate-objects",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
+
+    let user = std::fs::read_to_string(dir.path().join("timers.ir")).unwrap();
+    let library = std::fs::read_to_string(dir.path().join("__stdlib_timers.ir")).unwrap();
+    assert!(user.contains("define void @mainProg("), "{}", user);
+    assert!(
+        library.contains("define weak_odr void @TON("),
+        "{}",
+        library
+    );
+}
+
+/// runs rustyc on a file with an unknown pragma, the header cannot be written into the
+/// directory it is given, so the build stops with an error after the compilation
+fn run_with_unwritable_header(error_format: &str) -> (bool, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("main.st");
+    std::fs::write(&source, "{foo}\nPROGRAM mainProg\nEND_PROGRAM").unwrap();
+    let output = dir.path().join("out.ir");
+    let result = std::process::Command::new(env!("CARGO_BIN_EXE_rustyc"))
+        .args([
+            source.to_str().unwrap(),
+            "--ir",
+            "-o",
+            output.to_str().unwrap(),
+            "--emit-header",
+            dir.path().to_str().unwrap(),
+            "--error-format",
+            error_format,
+        ])
+        .output()
+        .unwrap();
+    (
+        result.status.success(),
+        String::from_utf8(result.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn fatal_errors_are_reported_as_json() {
+    let (success, stderr) = run_with_unwritable_header("json");
+
+    assert!(!success);
+    let diagnostics = stderr
+        .lines()
+        .map(|it| serde_json::from_str::<serde_json::Value>(it).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 2, "{}", stderr);
+    assert_eq!(diagnostics[0]["code"], "E049");
+    assert_eq!(diagnostics[0]["severity"], "warning");
+    assert_eq!(diagnostics[1]["code"], "E001");
+    assert_eq!(diagnostics[1]["severity"], "error");
+    assert_eq!(diagnostics[1]["location"], serde_json::Value::Null);
+}
+
+#[test]
+fn fatal_errors_are_part_of_the_single_sarif_log() {
+    let (success, stderr) = run_with_unwritable_header("sarif");
+
+    assert!(!success);
+    //the whole output is one log
+    let log = serde_json::from_str::<serde_json::Value>(&stderr).unwrap();
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2, "{}", stderr);
+    assert_eq!(results[0]["ruleId"], "E049");
+    assert_eq!(results[0]["level"], "warning");
+    assert_eq!(results[1]["ruleId"], "E001");
+    assert_eq!(results[1]["level"], "error");
+}
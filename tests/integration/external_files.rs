@@ -3,7 +3,7 @@
 use std::{env, fs};
 
 use encoding_rs::Encoding;
-use rusty::{build_and_link, CompileOptions, ErrorFormat, FilePath};
+use rusty::{build_and_link, CompileOptions, FilePath};
 
 use crate::get_test_file;
 
@@ -18,11 +18,9 @@ fn compile_all(name: &str, encoding: Option<&'static Encoding>) {
         vec![],
         encoding,
         &CompileOptions {
-            build_location: None,
             format: rusty::FormatOption::IR,
             output: out.clone(),
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::default(),
+            ..Default::default()
         },
         vec![],
         None,
@@ -35,11 +33,9 @@ fn compile_all(name: &str, encoding: Option<&'static Encoding>) {
         vec![],
         encoding,
         &CompileOptions {
-            build_location: None,
             format: rusty::FormatOption::Bitcode,
             output: out.clone(),
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::default(),
+            ..Default::default()
         },
         vec![],
         None,
@@ -52,11 +48,9 @@ fn compile_all(name: &str, encoding: Option<&'static Encoding>) {
         vec![],
         encoding,
         &CompileOptions {
-            build_location: None,
             format: rusty::FormatOption::Shared,
             output: out.clone(),
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::default(),
+            ..Default::default()
         },
         vec![],
         None,
@@ -69,11 +63,9 @@ fn compile_all(name: &str, encoding: Option<&'static Encoding>) {
         vec![],
         encoding,
         &CompileOptions {
-            build_location: None,
             format: rusty::FormatOption::PIC,
             output: out.clone(),
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::default(),
+            ..Default::default()
         },
         vec![],
         None,
@@ -86,11 +78,9 @@ fn compile_all(name: &str, encoding: Option<&'static Encoding>) {
         vec![],
         encoding,
         &CompileOptions {
-            build_location: None,
             format: rusty::FormatOption::Static,
             output: out.clone(),
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::default(),
+            ..Default::default()
         },
         vec![],
         None,
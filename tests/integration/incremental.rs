@@ -0,0 +1,69 @@
+// Copyright (c) 2022 Ghaith Hachem and Mathias Rieder
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use rusty::{build_with_params, cli::CompileParameters};
+
+/// builds the given files into a shared object using the given cache directory
+fn build(files: &[&Path], output: &Path, cache_dir: &Path) {
+    let files = files
+        .iter()
+        .map(|it| it.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let mut args = vec!["rustyc".to_string()];
+    args.extend(files);
+    args.extend([
+        "--shared".to_string(),
+        "-o".to_string(),
+        output.to_string_lossy().to_string(),
+        "--cache-dir".to_string(),
+        cache_dir.to_string_lossy().to_string(),
+    ]);
+    build_with_params(CompileParameters::parse(&args).unwrap()).unwrap();
+}
+
+/// returns the content and modification time of every object file in the given directory
+fn get_objects(directory: &Path) -> BTreeMap<PathBuf, (Vec<u8>, SystemTime)> {
+    let mut objects = BTreeMap::new();
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            objects.append(&mut get_objects(&path));
+        } else if path.extension().map_or(false, |it| it == "o") {
+            let modified = fs::metadata(&path).unwrap().modified().unwrap();
+            objects.insert(path.clone(), (fs::read(&path).unwrap(), modified));
+        }
+    }
+    objects
+}
+
+#[test]
+fn unchanged_files_reuse_their_cached_objects() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().join("cache");
+    let output = dir.path().join("out.so");
+    let foo = dir.path().join("foo.st");
+    let bar = dir.path().join("bar.st");
+    fs::write(&foo, "FUNCTION foo : DINT foo := 1; END_FUNCTION").unwrap();
+    fs::write(&bar, "FUNCTION bar : DINT bar := foo(); END_FUNCTION").unwrap();
+
+    build(&[&foo, &bar], &output, &cache_dir);
+    let before = get_objects(&cache_dir);
+    assert_eq!(before.len(), 2);
+
+    //changing the body of foo does not affect bar
+    fs::write(&foo, "FUNCTION foo : DINT foo := 2; END_FUNCTION").unwrap();
+    build(&[&foo, &bar], &output, &cache_dir);
+    let after = get_objects(&cache_dir);
+    assert_eq!(after.len(), 2);
+
+    let unchanged = before
+        .iter()
+        .filter(|(path, object)| after.get(*path) == Some(object))
+        .count();
+    assert_eq!(unchanged, 1);
+}
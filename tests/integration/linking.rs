@@ -5,7 +5,7 @@ use inkwell::context::Context;
 use rusty::{
     build_and_link, compile_module,
     diagnostics::{Diagnostic, Diagnostician},
-    link, persist, CompileOptions, ErrorFormat, FilePath, FormatOption, LinkOptions, Target,
+    link, persist, CompileOptions, FilePath, FormatOption, LinkOptions, Target,
 };
 
 static TARGET: Option<&str> = Some("x86_64-linux-gnu");
@@ -32,11 +32,9 @@ fn link_as_shared_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out2.clone(),
             format: FormatOption::Shared,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -50,11 +48,9 @@ fn link_as_shared_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out1.clone(),
             format: FormatOption::Shared,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -94,11 +90,9 @@ fn link_as_pic_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out2.clone(),
             format: FormatOption::PIC,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -112,11 +106,9 @@ fn link_as_pic_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out1.clone(),
             format: FormatOption::PIC,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -155,11 +147,9 @@ fn link_as_static_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out2.clone(),
             format: FormatOption::Object,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -173,11 +163,9 @@ fn link_as_static_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out1.clone(),
             format: FormatOption::Static,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -217,11 +205,9 @@ fn link_as_relocatable_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out2.clone(),
             format: FormatOption::Object,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -235,11 +221,9 @@ fn link_as_relocatable_object() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: out1.clone(),
             format: FormatOption::Relocatable,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![TARGET.unwrap().into()],
         None,
@@ -313,11 +297,9 @@ fn link_to_a_relative_location_with_no_parent() {
         vec![],
         None,
         &CompileOptions {
-            build_location: None,
             output: "output.o".into(),
             format: FormatOption::Static,
-            optimization: rusty::OptimizationLevel::Default,
-            error_format: ErrorFormat::Rich,
+            ..Default::default()
         },
         vec![],
         None,
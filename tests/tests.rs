@@ -23,7 +23,10 @@ mod correctness {
     mod global_variables;
     mod initial_values;
     mod methods;
+    mod namespaces;
     mod pointers;
+    mod standard_function_blocks;
+    mod standard_functions;
     mod strings;
     mod sub_range_types;
     mod math_operators {
@@ -39,6 +42,7 @@ mod integration {
     mod build_description_tests;
     mod command_line_compile;
     mod external_files;
+    mod incremental;
     mod linking;
     mod multi_files;
 }